@@ -0,0 +1,72 @@
+//! Sequential vs parallel VLAN batch generation
+//!
+//! A single generator can hand out at most 4085 unique VLAN IDs, so the
+//! 10000-configuration batch is built from independent generators seeded per
+//! chunk, the same way for both variants.
+
+#[path = "_common/mod.rs"]
+mod bench_common;
+
+use bench_common::{ci_or_local, criterion_for_env};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use opnsense_config_faker::VlanConfig;
+use opnsense_config_faker::generator::vlan::VlanGenerator;
+use std::hint::black_box;
+
+/// Configurations generated by each seeded generator
+const CHUNK_SIZE: usize = 2500;
+
+/// Largest batch size benchmarked locally
+const CONFIG_COUNT: usize = 10000;
+
+fn generate_chunked(
+    count: usize,
+    generate: impl Fn(&mut VlanGenerator, usize) -> Vec<VlanConfig>,
+) -> Vec<VlanConfig> {
+    let mut configs = Vec::with_capacity(count);
+    let mut remaining = count;
+    let mut chunk = 0u64;
+    while remaining > 0 {
+        let size = remaining.min(CHUNK_SIZE);
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42 + chunk));
+        configs.extend(generate(&mut generator, size));
+        remaining -= size;
+        chunk += 1;
+    }
+    configs
+}
+
+fn bench_parallel_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_generation");
+
+    for &count in &ci_or_local(&[1000], &[1000, CONFIG_COUNT]) {
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &count,
+            |b, &count| {
+                b.iter(|| {
+                    black_box(generate_chunked(count, |generator, size| {
+                        generator.generate_batch(size).unwrap()
+                    }))
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("parallel", count), &count, |b, &count| {
+            b.iter(|| {
+                black_box(generate_chunked(count, |generator, size| {
+                    generator.generate_batch_parallel(size).unwrap()
+                }))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = criterion_for_env();
+    targets = bench_parallel_generation
+}
+criterion_main!(benches);
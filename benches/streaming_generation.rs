@@ -0,0 +1,112 @@
+#[path = "_common/mod.rs"]
+mod bench_common;
+
+use bench_common::{ci_or_local, criterion_for_env};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use opnsense_config_faker::Result;
+use opnsense_config_faker::generator::vlan::{VlanConfig, VlanGenerator};
+use opnsense_config_faker::io::csv::{try_write_csv_streaming, write_csv};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::NamedTempFile;
+
+/// Number of configurations used to compare batch and streaming output
+const CONFIG_COUNT: u16 = 10_000;
+
+/// System allocator wrapper that tracks current and peak heap usage
+struct PeakTrackingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakTrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakTrackingAllocator = PeakTrackingAllocator;
+
+/// Run `f` and return the peak heap growth it caused, in bytes
+fn measure_peak_allocation<F: FnOnce()>(f: F) -> usize {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    f();
+    PEAK.load(Ordering::Relaxed).saturating_sub(baseline)
+}
+
+/// VLAN IDs are unique per generator, so counts above the 4085 ID space are
+/// produced by chaining independently seeded generators in fixed-size chunks
+const CHUNK_SIZE: u16 = 2_500;
+
+/// Lazily yield `count` configurations across as many generators as needed
+fn config_stream(count: u16) -> impl Iterator<Item = Result<VlanConfig>> {
+    (0..count.div_ceil(CHUNK_SIZE)).flat_map(move |chunk| {
+        let chunk_len = CHUNK_SIZE.min(count - chunk * CHUNK_SIZE);
+        VlanGenerator::new_with_std_rng(Some(42 + u64::from(chunk)))
+            .into_config_iter(chunk_len, None)
+            .unwrap()
+    })
+}
+
+fn write_batch(count: u16, path: &std::path::Path) {
+    let configs: Vec<VlanConfig> = config_stream(count).collect::<Result<_>>().unwrap();
+    write_csv(&configs, path).unwrap();
+}
+
+fn write_streaming(count: u16, path: &std::path::Path) {
+    try_write_csv_streaming(config_stream(count), path).unwrap();
+}
+
+fn bench_streaming_generation(c: &mut Criterion) {
+    // Report peak allocation once per strategy; Criterion tracks timing
+    let temp_file = NamedTempFile::new().unwrap();
+    let batch_peak = measure_peak_allocation(|| write_batch(CONFIG_COUNT, temp_file.path()));
+    let streaming_peak =
+        measure_peak_allocation(|| write_streaming(CONFIG_COUNT, temp_file.path()));
+    println!(
+        "peak allocation for {CONFIG_COUNT} configs: batch = {} KiB, streaming = {} KiB",
+        batch_peak / 1024,
+        streaming_peak / 1024
+    );
+
+    let mut group = c.benchmark_group("streaming_generation");
+    group.sample_size(10);
+
+    for count in ci_or_local(&[1000], &[CONFIG_COUNT]) {
+        group.bench_with_input(BenchmarkId::new("batch", count), &count, |b, &count| {
+            b.iter(|| {
+                let temp_file = NamedTempFile::new().unwrap();
+                write_batch(black_box(count), temp_file.path());
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("streaming", count), &count, |b, &count| {
+            b.iter(|| {
+                let temp_file = NamedTempFile::new().unwrap();
+                write_streaming(black_box(count), temp_file.path());
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = criterion_for_env();
+    targets = bench_streaming_generation
+}
+criterion_main!(benches);
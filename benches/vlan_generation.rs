@@ -4,6 +4,10 @@
 use bench_common::{ci_or_local, criterion_for_env};
 use criterion::{Criterion, criterion_group, criterion_main};
 use opnsense_config_faker::generator::vlan::{VlanGenerator, generate_vlan_configurations};
+use opnsense_config_faker::utils::rfc1918::Rfc1918Class;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashSet;
 use std::hint::black_box;
 
 fn bench_vlan_generation(c: &mut Criterion) {
@@ -62,9 +66,48 @@ fn bench_vlan_generation(c: &mut Criterion) {
     );
 }
 
+/// Unique network generation keyed on strings, as before the packed `u32` set
+fn string_keyed_networks(count: usize, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut used = HashSet::new();
+    let mut networks = Vec::with_capacity(count);
+    while networks.len() < count {
+        let [a, b, c, _] = Rfc1918Class::ClassA
+            .generate_network(&mut rng)
+            .network()
+            .octets();
+        let key = format!("{a}.{b}.{c}.x");
+        if used.insert(key.clone()) {
+            networks.push(key);
+        }
+    }
+    networks
+}
+
+fn bench_network_uniqueness(c: &mut Criterion) {
+    let count = 10_000;
+    let mut group = c.benchmark_group(format!("unique_networks_{count}"));
+
+    group.bench_function("string_keys", |b| {
+        b.iter(|| black_box(string_keyed_networks(black_box(count), 42)))
+    });
+
+    group.bench_function("packed_u32_keys", |b| {
+        b.iter(|| {
+            let mut generator = VlanGenerator::new(Some(42));
+            let networks: Vec<String> = (0..black_box(count))
+                .map(|_| generator.generate_unique_ip_network(100).unwrap())
+                .collect();
+            black_box(networks)
+        })
+    });
+
+    group.finish();
+}
+
 criterion_group! {
     name = benches;
     config = criterion_for_env();
-    targets = bench_vlan_generation
+    targets = bench_vlan_generation, bench_network_uniqueness
 }
 criterion_main!(benches);
@@ -19,10 +19,7 @@ pub fn execute(args: CsvArgs) -> Result<()> {
 
     // Check if output file exists and handle force flag
     if args.output.exists() && !args.force {
-        return Err(crate::model::ConfigError::config(format!(
-            "Output file '{}' already exists. Use --force to overwrite.",
-            args.output.display()
-        )));
+        return Err(crate::model::ConfigError::file_exists(&args.output));
     }
 
     // Set up progress indicator
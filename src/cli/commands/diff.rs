@@ -0,0 +1,185 @@
+//! Diff command for comparing two generated CSV datasets
+//!
+//! VLANs are matched by ID. The report lists VLANs present in only one file
+//! and, for VLANs in both, every field whose value differs.
+
+use crate::cli::{DiffArgs, GlobalArgs};
+use crate::generator::VlanConfig;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// A field that differs between two VLANs with the same ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    /// Field name, e.g. `description`
+    pub field: &'static str,
+    /// Value in the first dataset
+    pub a: String,
+    /// Value in the second dataset
+    pub b: String,
+}
+
+/// A VLAN present in both datasets with different settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VlanChange {
+    pub vlan_id: u16,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two VLAN datasets, each list ordered by VLAN ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VlanDiff {
+    /// VLANs only in the first dataset
+    pub only_in_a: Vec<VlanConfig>,
+    /// VLANs only in the second dataset
+    pub only_in_b: Vec<VlanConfig>,
+    /// VLANs in both datasets whose network, description or WAN differ
+    pub changed: Vec<VlanChange>,
+}
+
+impl VlanDiff {
+    /// Whether the datasets hold the same VLANs
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two datasets by VLAN ID
+///
+/// When a dataset repeats a VLAN ID, its first occurrence is compared.
+pub fn diff_configs(a: &[VlanConfig], b: &[VlanConfig]) -> VlanDiff {
+    let by_id = |configs: &[VlanConfig]| {
+        let mut map = BTreeMap::new();
+        for config in configs {
+            map.entry(config.vlan_id).or_insert_with(|| config.clone());
+        }
+        map
+    };
+    let a = by_id(a);
+    let mut b = by_id(b);
+
+    let mut diff = VlanDiff::default();
+    for (vlan_id, config_a) in a {
+        let Some(config_b) = b.remove(&vlan_id) else {
+            diff.only_in_a.push(config_a);
+            continue;
+        };
+
+        let fields = [
+            ("ip_network", config_a.ip_network, config_b.ip_network),
+            ("description", config_a.description, config_b.description),
+            (
+                "wan_assignment",
+                config_a.wan_assignment.to_string(),
+                config_b.wan_assignment.to_string(),
+            ),
+        ];
+        let changes: Vec<FieldChange> = fields
+            .into_iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(field, a, b)| FieldChange { field, a, b })
+            .collect();
+        if !changes.is_empty() {
+            diff.changed.push(VlanChange { vlan_id, changes });
+        }
+    }
+    diff.only_in_b = b.into_values().collect();
+
+    diff
+}
+
+/// Execute the diff command
+pub fn execute(args: DiffArgs, global: &GlobalArgs) -> Result<()> {
+    let read = |path: &Path| {
+        crate::io::csv::read_csv_with_delimiter(path, args.csv_delimiter)
+            .with_context(|| format!("Failed to read CSV: {}", path.display()))
+    };
+    let diff = diff_configs(&read(&args.a)?, &read(&args.b)?);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    if !global.quiet {
+        println!(
+            "🔍 Comparing {} with {}",
+            args.a.display(),
+            args.b.display()
+        );
+        println!();
+    }
+    print!("{}", render_diff(&diff, &args.a, &args.b));
+
+    Ok(())
+}
+
+/// Render a human-readable diff summary
+fn render_diff(diff: &VlanDiff, a: &Path, b: &Path) -> String {
+    let mut out = String::new();
+    if diff.is_empty() {
+        out.push_str("✅ No differences found\n");
+        return out;
+    }
+
+    for (marker, path, configs) in [("➖", a, &diff.only_in_a), ("➕", b, &diff.only_in_b)] {
+        let _ = writeln!(
+            out,
+            "{marker} Only in {}: {}",
+            path.display(),
+            configs.len()
+        );
+        for config in configs {
+            let _ = writeln!(out, "   {config}");
+        }
+    }
+
+    let _ = writeln!(out, "✏️  Changed: {}", diff.changed.len());
+    for change in &diff.changed {
+        for field in &change.changes {
+            let _ = writeln!(
+                out,
+                "   VLAN {} {}: \"{}\" → \"{}\"",
+                change.vlan_id, field.field, field.a, field.b
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vlan_id: u16, network: &str, description: &str, wan: u8) -> VlanConfig {
+        VlanConfig::new(vlan_id, network.to_string(), description.to_string(), wan).unwrap()
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed() {
+        let a = vec![
+            config(100, "10.1.1.x", "IT VLAN 100", 1),
+            config(200, "10.1.2.x", "Sales VLAN 200", 2),
+            config(300, "10.1.3.x", "HR VLAN 300", 3),
+        ];
+        let b = vec![
+            config(100, "10.1.1.x", "IT VLAN 100", 1),
+            config(200, "10.1.9.x", "Sales VLAN 200", 3),
+            config(400, "10.1.4.x", "QA VLAN 400", 1),
+        ];
+
+        let diff = diff_configs(&a, &b);
+        assert_eq!(diff.only_in_a, [a[2].clone()]);
+        assert_eq!(diff.only_in_b, [b[2].clone()]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].vlan_id, 200);
+        let fields: Vec<_> = diff.changed[0].changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["ip_network", "wan_assignment"]);
+
+        assert!(diff_configs(&a, &a).is_empty());
+    }
+}
@@ -1,17 +1,47 @@
 //! Generate command implementation - unified CSV and XML generation
 
-use crate::cli::{GenerateArgs, GlobalArgs, OutputFormat};
-use crate::generator::vlan::generate_vlan_configurations;
-use crate::generator::{FirewallComplexity, generate_firewall_rules};
-use crate::io::csv::{read_csv, write_csv, write_firewall_rules_csv};
+use crate::cli::interactive::{Confirm, confirm_generation};
+use crate::cli::manifest::{Manifest, write_manifest};
+use crate::cli::render::{print_table, render_dry_run_summary, render_histogram};
+use crate::cli::{
+    ErrorFormat, GenerateArgs, GlobalArgs, MAX_UNIQUE_VLAN_IDS, NetworkClassArg, OutputFormat,
+};
+use crate::generator::departments::load_departments_file;
+use crate::generator::network_pool::load_networks_file;
+use crate::generator::network_pool::{allocate_from, available_subnets};
+use crate::generator::{
+    DhcpOptions, FirewallComplexity, FirewallRule, GenerationStats, NatConstraints, NatGenerator,
+    SortKey, VlanConfig, VlanGenerator, generate_aliases, generate_firewall_rules_with_policy,
+    generate_p2p_links_avoiding, shuffle_configs,
+};
+use crate::io::csv::{
+    append_csv_with_delimiter, is_gzip_path, read_csv_with_delimiter, read_csv_with_encoding,
+    try_write_csv_streaming_to, write_csv_comment, write_csv_to_with_delimiter,
+    write_firewall_rules_csv, write_p2p_links_csv,
+};
+use crate::io::json::write_json_to;
+use crate::io::jsonl::{try_write_jsonl_streaming, write_jsonl_streaming};
+use crate::io::limit::OutputBudget;
+use crate::io::toml::{read_toml, to_toml_string};
+use crate::io::yaml::{read_yaml, write_yaml_to};
+use crate::utils::mac::MacVendor;
+use crate::utils::progress::ProgressSink;
+use crate::utils::rate::{RateLimitedSink, RateLimiter};
+use crate::validate::ValidationEngine;
+use crate::xml::XMLEngine;
+use crate::xml::builder::OPNsenseConfigBuilder;
+use crate::xml::format::pretty_print;
 use crate::xml::template::XmlTemplate;
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use console::{Term, style};
 use indicatif::{ProgressBar, ProgressStyle};
+use ipnetwork::Ipv4Network;
 use std::env;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 /// Execute the generate command with global arguments
 pub fn execute_with_global(mut args: GenerateArgs, global: &GlobalArgs) -> Result<()> {
@@ -19,6 +49,20 @@ pub fn execute_with_global(mut args: GenerateArgs, global: &GlobalArgs) -> Resul
     if global.no_color {
         args.no_color = true;
     }
+    if args.no_color {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+
+    // Environment and preset defaults go in before anything reads or
+    // validates the options
+    args.apply_env_defaults()?;
+    args.apply_preset();
+
+    // A seed label stands in for an explicit seed
+    if let Some(label) = &args.seed_label {
+        args.seed = Some(crate::utils::seed::seed_from_label(label));
+    }
 
     // Apply global output if specified and not overridden
     if let Some(ref global_output) = global.output {
@@ -31,7 +75,7 @@ pub fn execute_with_global(mut args: GenerateArgs, global: &GlobalArgs) -> Resul
         }
     }
 
-    execute_internal(args, global)
+    execute_internal(args, global, &mut confirm_generation)
 }
 
 /// Execute the generate command (legacy function for backward compatibility)
@@ -40,14 +84,31 @@ pub fn execute(args: GenerateArgs) -> Result<()> {
     let global = GlobalArgs {
         quiet: false,
         no_color: args.no_color,
+        verbose: 0,
         output: None,
+        error_format: ErrorFormat::Text,
     };
 
     execute_with_global(args, &global)
 }
 
 /// Internal execution with global context
-fn execute_internal(args: GenerateArgs, global: &GlobalArgs) -> Result<()> {
+///
+/// `confirm` decides whether generated configurations are written when
+/// `--interactive` is set.
+fn execute_internal(
+    mut args: GenerateArgs,
+    global: &GlobalArgs,
+    confirm: &mut Confirm<'_>,
+) -> Result<()> {
+    resolve_fill_supernet(&mut args, global)?;
+
+    // Network lists are meant for piping, so no header
+    if args.networks_only {
+        resolve_seed(&mut args, global);
+        return execute_networks_only(&args, global);
+    }
+
     // Show header unless quiet
     if !global.quiet {
         println!(
@@ -60,7 +121,7 @@ fn execute_internal(args: GenerateArgs, global: &GlobalArgs) -> Result<()> {
     }
 
     // Handle interactive mode if requested
-    let args = if args.interactive {
+    let mut args = if args.interactive {
         handle_interactive_mode(args)?
     } else {
         args
@@ -74,11 +135,142 @@ fn execute_internal(args: GenerateArgs, global: &GlobalArgs) -> Result<()> {
         return Err(crate::model::ConfigError::invalid_parameter("count", &e).into());
     }
 
-    // Execute based on format
+    resolve_seed(&mut args, global);
+
+    // Execute based on format; --emit writes every format from one data set
     match args.format {
-        OutputFormat::Csv => execute_csv_generation(&args, global),
-        OutputFormat::Xml => execute_xml_generation(&args, global),
+        _ if args.emit.is_some() => execute_csv_generation(&args, global, confirm),
+        OutputFormat::Csv
+        | OutputFormat::Yaml
+        | OutputFormat::Toml
+        | OutputFormat::Json
+        | OutputFormat::Jsonl => execute_csv_generation(&args, global, confirm),
+        OutputFormat::Xml => execute_xml_generation(&args, global, confirm),
+    }
+}
+
+/// Run the generated configurations back through the validation engine
+/// when `--validate-after` is set
+fn validate_generated(
+    configs: &[VlanConfig],
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+) -> Result<()> {
+    if !args.validate_after {
+        return Ok(());
+    }
+
+    let issues = ValidationEngine::new()
+        .with_max_description_len(args.max_description_len)
+        .collect_issues(configs);
+    if !issues.is_empty() {
+        let report: String = issues
+            .iter()
+            .map(|issue| format!("\n  - {issue}"))
+            .collect();
+        return Err(crate::model::ConfigError::validation(format!(
+            "{} generated configurations failed validation with {} issue(s):{report}",
+            configs.len(),
+            issues.len()
+        ))
+        .into());
+    }
+
+    if !global.quiet {
+        println!("✅ Validated {} generated configurations", configs.len());
+    }
+    Ok(())
+}
+
+/// Pick and report a seed when none was given, so the run can be reproduced
+fn resolve_seed(args: &mut GenerateArgs, global: &GlobalArgs) {
+    if args.seed.is_none() && uses_seed(args) {
+        let seed = crate::utils::seed::random_seed();
+        args.seed = Some(seed);
+        if !global.quiet {
+            eprintln!("Using random seed: {seed}");
+        }
+    }
+}
+
+/// Whether the run draws anything from the seed
+///
+/// XML runs that load their VLANs from a file only use it to shuffle them
+/// and for firewall rules, point-to-point links and monolithic NAT entries.
+fn uses_seed(args: &GenerateArgs) -> bool {
+    let loads_vlans =
+        args.csv_file.is_some() || args.yaml_file.is_some() || args.toml_file.is_some();
+    !loads_vlans
+        || args.shuffle
+        || args.include_firewall_rules
+        || args.p2p_links.is_some()
+        || args.monolithic
+}
+
+/// Derive the count from the supernet size when `--fill-supernet` is set
+///
+/// Only the /24 networks still free count: those overlapping
+/// `--exclude-networks` and, with `--append`, those the existing file
+/// already uses are left out.
+fn resolve_fill_supernet(args: &mut GenerateArgs, global: &GlobalArgs) -> Result<()> {
+    let Some(supernet) = args.supernet.filter(|_| args.fill_supernet) else {
+        return Ok(());
+    };
+    // Rejects supernets too small to hold a /24
+    available_subnets(supernet, 24)?;
+    let mut generator = build_vlan_generator(args)?;
+    if let Some(output_file) = args
+        .output
+        .as_ref()
+        .filter(|path| args.append && path.exists())
+    {
+        let existing = read_csv_with_delimiter(output_file, args.csv_delimiter)
+            .with_context(|| format!("Failed to read existing CSV: {:?}", output_file))?;
+        generator.reserve_existing(&existing);
+    }
+    let available = generator.remaining_networks_estimate();
+    if available == 0 {
+        return Err(crate::model::ConfigError::resource_exhausted(format!(
+            "IP networks (every /24 network in supernet {supernet} is excluded or already used)"
+        ))
+        .into());
+    }
+    let count = match u16::try_from(available) {
+        Ok(count) if count <= MAX_UNIQUE_VLAN_IDS => count,
+        _ => {
+            if !global.quiet {
+                eprintln!(
+                    "⚠️  {supernet} has {available} free /24 networks; generating the maximum of {MAX_UNIQUE_VLAN_IDS}"
+                );
+            }
+            MAX_UNIQUE_VLAN_IDS
+        }
+    };
+    args.count = Some(count);
+    Ok(())
+}
+
+/// Write just the allocated network CIDRs, one per line
+fn execute_networks_only(args: &GenerateArgs, global: &GlobalArgs) -> Result<()> {
+    let mut generator = build_vlan_generator(args)?;
+    let networks = allocate_from(&mut generator, usize::from(args.vlan_count()))?;
+    let list: String = networks
+        .iter()
+        .map(|network| format!("{network}\n"))
+        .collect();
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, list)
+                .with_context(|| format!("Failed to write networks: {}", path.display()))?;
+            if !global.quiet {
+                println!("✅ Wrote {} networks to {}", networks.len(), path.display());
+            }
+        }
+        None => print!("{list}"),
     }
+
+    Ok(())
 }
 
 /// Handle interactive mode prompts for missing required arguments
@@ -86,14 +278,25 @@ fn handle_interactive_mode(mut args: GenerateArgs) -> Result<GenerateArgs> {
     let term = Term::stdout();
 
     match args.format {
-        OutputFormat::Csv => {
+        OutputFormat::Csv
+        | OutputFormat::Yaml
+        | OutputFormat::Toml
+        | OutputFormat::Json
+        | OutputFormat::Jsonl => {
             if args.output.is_none() {
-                println!("📝 CSV output file not specified.");
-                print!("Enter output filename (default: vlan_configs.csv): ");
+                let (label, default_file) = match args.format {
+                    OutputFormat::Yaml => ("YAML", "vlan_configs.yaml"),
+                    OutputFormat::Toml => ("TOML", "vlan_configs.toml"),
+                    OutputFormat::Json => ("JSON", "vlan_configs.json"),
+                    OutputFormat::Jsonl => ("JSONL", "vlan_configs.jsonl"),
+                    _ => ("CSV", "vlan_configs.csv"),
+                };
+                println!("📝 {label} output file not specified.");
+                print!("Enter output filename (default: {default_file}): ");
                 io::stdout().flush()?;
                 let input = term.read_line()?;
                 args.output = Some(if input.trim().is_empty() {
-                    PathBuf::from("vlan_configs.csv")
+                    PathBuf::from(default_file)
                 } else {
                     PathBuf::from(input.trim())
                 });
@@ -110,12 +313,16 @@ fn handle_interactive_mode(mut args: GenerateArgs) -> Result<GenerateArgs> {
                 }
             }
 
-            if args.csv_file.is_none() && args.count == 10 {
+            if args.csv_file.is_none()
+                && args.yaml_file.is_none()
+                && args.toml_file.is_none()
+                && args.count.is_none()
+            {
                 print!("Enter number of configurations to generate (default: 10): ");
                 io::stdout().flush()?;
                 let input = term.read_line()?;
                 if let Ok(count) = input.trim().parse::<u16>() {
-                    args.count = count;
+                    args.count = Some(count);
                 }
             }
         }
@@ -126,6 +333,90 @@ fn handle_interactive_mode(mut args: GenerateArgs) -> Result<GenerateArgs> {
 
 /// Validate arguments based on the selected format
 fn validate_arguments(args: &GenerateArgs) -> Result<()> {
+    // Streaming writes CSV rows or JSON lines directly; other formats are
+    // whole documents
+    if args.stream && !matches!(args.format, OutputFormat::Csv | OutputFormat::Jsonl) {
+        return Err(crate::model::ConfigError::invalid_parameter(
+            "stream",
+            "Streaming output is only supported for CSV and JSONL formats.",
+        )
+        .into());
+    }
+
+    // A merged document only exists for XML
+    if args.monolithic && !matches!(args.format, OutputFormat::Xml) {
+        return Err(crate::model::ConfigError::invalid_parameter(
+            "monolithic",
+            "Monolithic output is only supported for XML format.",
+        )
+        .into());
+    }
+
+    // Resuming regenerates the interrupted run, so it must be reproducible
+    if args.resume {
+        if !matches!(args.format, OutputFormat::Xml) {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "resume",
+                "Resuming is only supported for XML format.",
+            )
+            .into());
+        }
+        if args.seed.is_none() {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "resume",
+                "--resume needs the --seed of the interrupted run.",
+            )
+            .into());
+        }
+    }
+
+    if let Some(parents) = &args.parent_interfaces {
+        if parents.iter().any(|name| name.trim().is_empty()) {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "parent-interfaces",
+                "Parent interface names must not be empty.",
+            )
+            .into());
+        }
+    }
+
+    // Appending merges CSV rows, so the format and generation mode must fit
+    if args.append {
+        if !matches!(args.format, OutputFormat::Csv) {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "append",
+                "Appending is only supported for CSV format.",
+            )
+            .into());
+        }
+        if args.vlan_range.is_some() && args.count.is_none() {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "append",
+                "--append with --vlan-range requires --count to draw new VLAN IDs.",
+            )
+            .into());
+        }
+    }
+
+    // Emitted files are named after the output file's stem
+    if let Some(formats) = &args.emit {
+        if args.output.is_none() {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "output",
+                "Output file path is required for --emit. Use --output or -o to specify.",
+            )
+            .into());
+        }
+        if formats.iter().any(|f| matches!(f, OutputFormat::Xml)) && args.base_config.is_none() {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "base-config",
+                "Base configuration file is required to emit XML. Use --base-config or -b to specify.",
+            )
+            .into());
+        }
+        return Ok(());
+    }
+
     match args.format {
         OutputFormat::Csv => {
             // CSV format requires output file
@@ -137,6 +428,46 @@ fn validate_arguments(args: &GenerateArgs) -> Result<()> {
                 .into());
             }
         }
+        OutputFormat::Yaml => {
+            // YAML format requires output file
+            if args.output.is_none() {
+                return Err(crate::model::ConfigError::invalid_parameter(
+                    "output",
+                    "Output file path is required for YAML format. Use --output or -o to specify.",
+                )
+                .into());
+            }
+        }
+        OutputFormat::Toml => {
+            // TOML format requires output file
+            if args.output.is_none() {
+                return Err(crate::model::ConfigError::invalid_parameter(
+                    "output",
+                    "Output file path is required for TOML format. Use --output or -o to specify.",
+                )
+                .into());
+            }
+        }
+        OutputFormat::Json => {
+            // JSON format requires output file
+            if args.output.is_none() {
+                return Err(crate::model::ConfigError::invalid_parameter(
+                    "output",
+                    "Output file path is required for JSON format. Use --output or -o to specify.",
+                )
+                .into());
+            }
+        }
+        OutputFormat::Jsonl => {
+            // JSONL format requires output file
+            if args.output.is_none() {
+                return Err(crate::model::ConfigError::invalid_parameter(
+                    "output",
+                    "Output file path is required for JSONL format. Use --output or -o to specify.",
+                )
+                .into());
+            }
+        }
         OutputFormat::Xml => {
             // XML format requires base config
             if args.base_config.is_none() {
@@ -147,7 +478,7 @@ fn validate_arguments(args: &GenerateArgs) -> Result<()> {
             }
 
             // Either count or csv_file must be specified
-            if args.csv_file.is_none() && args.count == 0 {
+            if args.csv_file.is_none() && args.vlan_count() == 0 {
                 return Err(crate::model::ConfigError::invalid_parameter(
                     "count or csv-file",
                     "Either --count or --csv-file must be specified for XML generation.",
@@ -160,25 +491,176 @@ fn validate_arguments(args: &GenerateArgs) -> Result<()> {
     Ok(())
 }
 
-/// Execute CSV generation
-fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()> {
+/// Tell the user that interactive confirmation was declined
+fn report_cancelled(global: &GlobalArgs) {
+    if !global.quiet {
+        println!("🚫 Generation cancelled; no files written");
+    }
+}
+
+/// Build a VLAN generator configured from the generate arguments
+fn build_vlan_generator(args: &GenerateArgs) -> Result<VlanGenerator> {
+    let mut generator = VlanGenerator::new_with_std_rng(args.seed)
+        .with_description_template(args.description_template.clone());
+    if let Some(class) = args.network_class {
+        generator = generator.with_network_class(class.into());
+    }
+    if args.balanced_classes {
+        generator = generator.with_balanced_classes();
+    }
+    if let Some(supernet) = args.supernet {
+        generator = generator.with_supernet(supernet)?;
+    }
+    if let Some(path) = &args.networks_file {
+        let networks = load_networks_file(path)
+            .with_context(|| format!("Failed to load networks file: {:?}", path))?;
+        generator.use_network_pool(networks)?;
+        if args.shuffle_networks {
+            generator.shuffle_network_pool();
+        }
+    }
+    if let Some(exclude) = &args.exclude_networks {
+        let networks = crate::cli::parse_network_list(exclude)
+            .map_err(crate::model::ConfigError::validation)?;
+        generator.add_exclusions(&networks);
+    }
+    if let Some(path) = &args.departments_file {
+        let departments = load_departments_file(path)
+            .with_context(|| format!("Failed to load departments file: {:?}", path))?;
+        generator.set_departments(departments)?;
+    }
+    if let Some(policy) = args.dhcp_range {
+        generator = generator.with_dhcp_policy(policy)?;
+    }
+    if let Some(max_attempts) = args.max_attempts {
+        generator = generator.with_max_attempts(max_attempts)?;
+    }
+    if args.allow_duplicates {
+        generator = generator.with_allow_duplicates(true);
+    }
+    generator = generator.with_max_description_len(args.max_description_len);
+    Ok(generator)
+}
+
+/// Generate `--count` configurations, or the `--per-department` counts when given
+fn generate_counted(
+    generator: &mut VlanGenerator,
+    args: &GenerateArgs,
+    progress: &dyn ProgressSink,
+) -> crate::Result<Vec<VlanConfig>> {
+    if let Some(counts) = &args.per_department {
+        generator.generate_by_department(counts.clone(), Some(progress))
+    } else {
+        generator.generate_configurations(
+            args.vlan_count(),
+            args.wan_assignments.as_ref(),
+            Some(progress),
+        )
+    }
+}
+
+/// NAT protocol/port allow-list from `--nat-protocols` and `--nat-ports`
+fn nat_constraints(args: &GenerateArgs) -> NatConstraints {
+    NatConstraints {
+        protocols: args.nat_protocols.clone(),
+        ports: args.nat_ports.clone(),
+    }
+}
+
+/// DHCP settings from `--dhcp-dns`, `--no-gateway-dns` and `--mac-vendors`
+fn dhcp_options(args: &GenerateArgs) -> crate::Result<DhcpOptions> {
+    let mut options = DhcpOptions::default().with_gateway_dns(!args.no_gateway_dns);
+    if let Some(vendors) = mac_vendors(args) {
+        options = options.with_mac_vendors(vendors);
+    }
+    if let Some(servers) = &args.dhcp_dns {
+        options = options.with_dns_servers(servers.iter().map(ToString::to_string).collect())?;
+    }
+    Ok(options)
+}
+
+/// Hardware vendors from `--mac-vendors`
+fn mac_vendors(args: &GenerateArgs) -> Option<Vec<MacVendor>> {
+    args.mac_vendors
+        .as_ref()
+        .map(|vendors| vendors.iter().copied().map(MacVendor::from).collect())
+}
+
+/// Progress sink pacing generation to `--rate` items per second
+///
+/// Without `--rate`, or with `--quiet` where there is nothing to watch,
+/// updates pass straight through.
+fn rate_limited<'a>(
+    pb: &'a ProgressBar,
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+) -> RateLimitedSink<'a, ProgressBar> {
+    let limiter = args
+        .rate
+        .filter(|_| !global.quiet)
+        .map(RateLimiter::per_second);
+    RateLimitedSink::new(pb, limiter)
+}
+
+/// Execute CSV (or YAML/TOML) generation
+fn execute_csv_generation(
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+    confirm: &mut Confirm<'_>,
+) -> Result<()> {
     let output_file = args.output.as_ref().unwrap(); // Validated in validate_arguments
+    let targets = output_targets(args, output_file);
+    let labels = targets
+        .iter()
+        .map(|(format, _)| format_label(format))
+        .collect::<Vec<_>>()
+        .join(", ");
 
     if !global.quiet {
-        println!("📊 Generating CSV configuration data...");
+        println!("📊 Generating {labels} configuration data...");
     }
 
-    // Check if output file exists and handle force flag
-    if output_file.exists() && !args.force {
-        return Err(crate::model::ConfigError::config(format!(
-            "Output file '{}' already exists. Use --force to overwrite.",
-            output_file.display()
-        ))
-        .into());
+    // Check if output files exist and handle force flag; only CSV appends
+    for (format, path) in &targets {
+        let appends = args.append && matches!(format, OutputFormat::Csv);
+        if path.exists() && !args.force && !appends {
+            return Err(crate::model::ConfigError::file_exists(path).into());
+        }
+    }
+
+    // An emitted XML file is built on the base config, so check it up front
+    if targets
+        .iter()
+        .any(|(format, _)| matches!(format, OutputFormat::Xml))
+    {
+        let base_config = args.base_config.as_ref().unwrap(); // Validated in validate_arguments
+        let base_xml = fs::read_to_string(base_config)
+            .with_context(|| format!("Failed to read base config file: {:?}", base_config))?;
+        XMLEngine::validate_base_structure(&base_xml)
+            .with_context(|| format!("Invalid base config: {:?}", base_config))?;
+    }
+
+    if args.stream {
+        return execute_streaming_csv_generation(args, global, output_file);
+    }
+
+    // In append mode the existing rows' VLAN IDs and networks are reserved
+    let existing = if args.append && output_file.exists() {
+        read_csv_with_delimiter(output_file, args.csv_delimiter)
+            .with_context(|| format!("Failed to read existing CSV: {:?}", output_file))?
+    } else {
+        Vec::new()
+    };
+    if !global.quiet && !existing.is_empty() {
+        println!(
+            "📎 Appending to {} existing VLAN configurations",
+            existing.len()
+        );
     }
 
     // Generate VLAN configurations based on range or count
-    let (configs, pb) = if let Some(ref vlan_range_str) = args.vlan_range {
+    let started = Instant::now();
+    let (mut configs, pb) = if let Some(ref vlan_range_str) = args.vlan_range {
         // Parse VLAN ranges
         let vlan_ranges = crate::cli::parse_vlan_range(vlan_range_str)
             .map_err(crate::model::ConfigError::validation)?;
@@ -189,32 +671,42 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
             .sum();
 
         if !global.quiet {
-            println!(
-                "📋 Using VLAN ranges: {} (total: {} VLANs)",
-                vlan_range_str, total_vlans
-            );
+            if let Some(count) = args.count {
+                println!(
+                    "📋 Using VLAN ranges: {} (drawing {} of {} VLAN IDs)",
+                    vlan_range_str, count, total_vlans
+                );
+            } else {
+                println!(
+                    "📋 Using VLAN ranges: {} (total: {} VLANs)",
+                    vlan_range_str, total_vlans
+                );
+            }
         }
 
         // Set up progress indicator
         let pb = create_progress_bar(
-            total_vlans as u64,
+            args.count.map_or(total_vlans as u64, u64::from),
             "Generating VLAN configurations from ranges...",
             global.quiet,
         );
 
         // Generate from ranges
-        let configs = if args.wan_assignments.is_some() {
-            crate::generator::vlan::generate_vlan_configurations_from_ranges_with_wan(
-                &vlan_ranges,
-                args.seed,
-                args.wan_assignments.as_ref(),
-                Some(&pb),
-            )
+        let mut generator = build_vlan_generator(args)?;
+        generator.reserve_existing(&existing);
+        let configs = if let Some(count) = args.count {
+            generator.set_allowed_vlan_ids(&vlan_ranges).and_then(|()| {
+                generator.generate_configurations(
+                    count,
+                    args.wan_assignments.as_ref(),
+                    Some(&rate_limited(&pb, args, global)),
+                )
+            })
         } else {
-            crate::generator::vlan::generate_vlan_configurations_from_ranges(
+            generator.generate_configurations_from_ranges(
                 &vlan_ranges,
-                args.seed,
-                Some(&pb),
+                args.wan_assignments.as_ref(),
+                Some(&rate_limited(&pb, args, global)),
             )
         }
         .with_context(|| {
@@ -226,45 +718,118 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
 
         (configs, pb)
     } else {
+        let count = args.vlan_count();
+
         // Set up progress indicator
         let pb = create_progress_bar(
-            args.count as u64,
+            count as u64,
             "Generating VLAN configurations...",
             global.quiet,
         );
 
         // Generate VLAN configurations by count
-        let configs = if args.wan_assignments.is_some() {
-            crate::generator::vlan::generate_vlan_configurations_with_wan(
-                args.count,
-                args.seed,
-                args.wan_assignments.as_ref(),
-                Some(&pb),
-            )
-        } else {
-            generate_vlan_configurations(args.count, args.seed, Some(&pb))
-        }
-        .with_context(|| format!("Failed to generate {} VLAN configurations", args.count))?;
+        let mut generator = build_vlan_generator(args)?;
+        generator.reserve_existing(&existing);
+        let configs = generate_counted(&mut generator, args, &rate_limited(&pb, args, global))
+            .with_context(|| format!("Failed to generate {} VLAN configurations", count))?;
 
         (configs, pb)
     };
 
-    pb.set_message("Writing CSV file...");
+    if let Some(key) = args.sort {
+        SortKey::from(key).sort(&mut configs);
+    }
+    if let Some(seed) = args.seed.filter(|_| args.shuffle) {
+        shuffle_configs(&mut configs, seed);
+    }
+
+    let stats = GenerationStats::from_configs(&configs).with_duration(started.elapsed());
+
+    pb.suspend(|| validate_generated(&configs, args, global))?;
 
-    // Write to CSV file
-    write_csv(&configs, output_file)
-        .with_context(|| format!("Failed to write CSV to {:?}", output_file))?;
+    if args.preview {
+        pb.suspend(|| print_table(&configs));
+    }
+
+    if args.dry_run {
+        pb.finish_and_clear();
+        for (format, path) in &targets {
+            let content = render_configs(&configs, format, args)?;
+            print!(
+                "{}",
+                render_dry_run_summary(
+                    format.extension(),
+                    configs.len(),
+                    path,
+                    content.len() as u64
+                )
+            );
+        }
+        return Ok(());
+    }
 
+    if args.interactive && !pb.suspend(|| confirm(&configs))? {
+        pb.finish_and_clear();
+        report_cancelled(global);
+        return Ok(());
+    }
+
+    // Write each output file in its format
+    let mut budget = OutputBudget::new(args.max_output_bytes);
+    for (format, path) in &targets {
+        let label = format_label(format);
+        pb.set_message(format!("Writing {label} file..."));
+        match format {
+            OutputFormat::Csv if args.append => append_csv_with_delimiter(
+                &configs,
+                path,
+                args.csv_schema.into(),
+                args.csv_delimiter,
+            )
+            .with_context(|| format!("Failed to append CSV to {:?}", path))?,
+            OutputFormat::Csv => {
+                budget
+                    .write_file(path, |writer| {
+                        try_write_csv_streaming_to(
+                            configs.iter().cloned().map(Ok),
+                            writer,
+                            is_gzip_path(path),
+                            args.csv_schema.into(),
+                            args.csv_delimiter,
+                            csv_header_comment(args, configs.len()).as_deref(),
+                        )
+                    })
+                    .with_context(|| format!("Failed to write CSV to {:?}", path))?;
+            }
+            _ => budget
+                .write_file(path, |writer| {
+                    write_configs_to(&configs, format, args, writer)
+                })
+                .with_context(|| format!("Failed to write {label} to {:?}", path))?,
+        }
+    }
+
+    let outputs: Vec<&Path> = targets.iter().map(|(_, path)| path.as_path()).collect();
     pb.finish_with_message(format!(
-        "✅ Generated {} VLAN configurations in '{}'",
+        "✅ Generated {} VLAN configurations in {}",
         configs.len(),
-        output_file.display()
+        outputs
+            .iter()
+            .map(|path| format!("'{}'", path.display()))
+            .collect::<Vec<_>>()
+            .join(", ")
     ));
 
     if !global.quiet {
-        print_csv_summary(&configs, output_file);
+        print_csv_summary(&stats, &outputs);
+        if args.stats {
+            print_department_histogram(&stats);
+        }
     }
 
+    // Side files written below are recorded in the manifest too
+    let mut side_files: Vec<PathBuf> = Vec::new();
+
     // Generate VPN configurations if requested
     if let Some(vpn_count) = args.vpn_count {
         if !global.quiet {
@@ -306,9 +871,13 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         let nat_pb =
             create_progress_bar(nat_count as u64, "Generating NAT mappings...", global.quiet);
 
-        let nat_mappings =
-            crate::generator::nat::generate_nat_mappings(nat_count, args.seed, Some(&nat_pb))
-                .with_context(|| format!("Failed to generate {} NAT mappings", nat_count))?;
+        let nat_mappings = crate::generator::nat::generate_nat_mappings(
+            nat_count,
+            args.seed,
+            nat_constraints(args),
+            Some(&nat_pb),
+        )
+        .with_context(|| format!("Failed to generate {} NAT mappings", nat_count))?;
 
         nat_pb.finish_with_message(format!("✅ Generated {} NAT mappings", nat_mappings.len()));
 
@@ -329,10 +898,9 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         }
 
         // Parse complexity level
-        let complexity: FirewallComplexity =
-            args.firewall_rule_complexity.parse().map_err(|e| {
-                crate::model::ConfigError::validation(format!("Invalid firewall complexity: {}", e))
-            })?;
+        let complexity: FirewallComplexity = args.firewall_complexity().parse().map_err(|e| {
+            crate::model::ConfigError::validation(format!("Invalid firewall complexity: {}", e))
+        })?;
 
         // Generate firewall rules
         let firewall_pb = create_progress_bar(
@@ -340,12 +908,14 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
             "Generating firewall rules...",
             global.quiet,
         );
-        let firewall_rules = generate_firewall_rules(
+        let firewall_rules = generate_firewall_rules_with_policy(
             &configs,
             complexity,
             args.seed,
             Some(&firewall_pb),
             args.firewall_rules_per_vlan,
+            args.firewall_policy()
+                .context("Failed to load firewall profile")?,
         )?;
 
         firewall_pb.finish_with_message(format!(
@@ -354,16 +924,7 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         ));
 
         // Write firewall rules to separate CSV file
-        let stem = output_file
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| {
-                crate::model::ConfigError::invalid_parameter(
-                    "output",
-                    "Output file path must have a valid filename",
-                )
-            })?;
-        let firewall_output = output_file.with_file_name(format!("{stem}_firewall_rules.csv"));
+        let firewall_output = companion_path(output_file, "firewall_rules.csv")?;
 
         write_firewall_rules_csv(&firewall_rules, &firewall_output)
             .with_context(|| format!("Failed to write firewall rules to {:?}", firewall_output))?;
@@ -375,13 +936,268 @@ fn execute_csv_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
             );
             print_firewall_summary(&firewall_rules, &firewall_output);
         }
+        side_files.push(firewall_output);
+    }
+
+    if args.p2p_links.is_some() {
+        let p2p_output = companion_path(output_file, "p2p_links.csv")?;
+        write_p2p_links(args, global, &p2p_output, existing.iter().chain(&configs))?;
+        side_files.push(p2p_output);
+    }
+
+    let manifest_outputs: Vec<&Path> = outputs
+        .iter()
+        .copied()
+        .chain(side_files.iter().map(PathBuf::as_path))
+        .collect();
+    write_manifest_if_requested(args, global, configs.len(), &manifest_outputs)?;
+
+    Ok(())
+}
+
+/// Path next to `output_file` named `<stem>_<suffix>`
+fn companion_path(output_file: &Path, suffix: &str) -> Result<PathBuf> {
+    let stem = output_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| {
+            crate::model::ConfigError::invalid_parameter(
+                "output",
+                "Output file path must have a valid filename",
+            )
+        })?;
+    Ok(output_file.with_file_name(format!("{stem}_{suffix}")))
+}
+
+/// Generate the `--p2p-links` point-to-point links and write them as CSV
+///
+/// Links stay clear of the networks of `vlans` and of `--exclude-networks`.
+fn write_p2p_links<'a>(
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+    path: &Path,
+    vlans: impl IntoIterator<Item = &'a VlanConfig>,
+) -> Result<()> {
+    let Some(count) = args.p2p_links else {
+        return Ok(());
+    };
+
+    let mut reserved: Vec<Ipv4Network> = vlans
+        .into_iter()
+        .filter_map(|config| config.as_ipv4_network().ok())
+        .collect();
+    if let Some(exclude) = &args.exclude_networks {
+        let networks = crate::cli::parse_network_list(exclude)
+            .map_err(crate::model::ConfigError::validation)?;
+        reserved.extend(networks);
+    }
+    let links = generate_p2p_links_avoiding(count, args.seed, &reserved)
+        .with_context(|| format!("Failed to generate {count} point-to-point links"))?;
+    write_p2p_links_csv(&links, path)
+        .with_context(|| format!("Failed to write point-to-point links to {:?}", path))?;
+
+    if !global.quiet {
+        println!(
+            "🔌 {} point-to-point links written to: {}",
+            links.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Output formats and files for the CSV-style path
+///
+/// Without `--emit` this is the selected format at `--output`; with it, each
+/// emitted format goes to the output path with its extension swapped.
+fn output_targets(args: &GenerateArgs, output_file: &Path) -> Vec<(OutputFormat, PathBuf)> {
+    let Some(formats) = &args.emit else {
+        return vec![(args.format.clone(), output_file.to_path_buf())];
+    };
+
+    let mut targets: Vec<(OutputFormat, PathBuf)> = Vec::with_capacity(formats.len());
+    for format in formats {
+        let path = output_file.with_extension(format.extension());
+        if !targets.iter().any(|(_, existing)| *existing == path) {
+            targets.push((format.clone(), path));
+        }
+    }
+    targets
+}
+
+/// Human-readable label of an output format, e.g. "YAML"
+fn format_label(format: &OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Csv => "CSV",
+        OutputFormat::Xml => "XML",
+        OutputFormat::Yaml => "YAML",
+        OutputFormat::Toml => "TOML",
+        OutputFormat::Json => "JSON",
+        OutputFormat::Jsonl => "JSONL",
+    }
+}
+
+/// Render configurations in the given format in memory
+fn render_configs(
+    configs: &[VlanConfig],
+    format: &OutputFormat,
+    args: &GenerateArgs,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    write_configs_to(configs, format, args, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Write configurations in the given format to `writer`
+///
+/// XML places every VLAN in the `<vlans>` section of the base configuration.
+fn write_configs_to(
+    configs: &[VlanConfig],
+    format: &OutputFormat,
+    args: &GenerateArgs,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    match format {
+        OutputFormat::Csv => {
+            if let Some(comment) = csv_header_comment(args, configs.len()) {
+                write_csv_comment(&mut *writer, &comment)?;
+            }
+            write_csv_to_with_delimiter(
+                configs,
+                writer,
+                args.csv_schema.into(),
+                args.csv_delimiter,
+            )?
+        }
+        OutputFormat::Yaml => write_yaml_to(configs, writer)?,
+        OutputFormat::Json => write_json_to(configs, writer)?,
+        OutputFormat::Jsonl => {
+            write_jsonl_streaming(configs.iter().cloned(), writer)?;
+        }
+        OutputFormat::Toml => writer.write_all(to_toml_string(configs)?.as_bytes())?,
+        OutputFormat::Xml => {
+            let base_config = args.base_config.as_ref().unwrap(); // Validated in validate_arguments
+            let xml = OPNsenseConfigBuilder::with_template_file(base_config.clone())
+                .with_schema_version(args.opnsense_version)
+                .with_dhcp_options(dhcp_options(args)?)
+                .add_vlans(configs)
+                .build()
+                .with_context(|| format!("Failed to build XML from {:?}", base_config))?;
+            writer.write_all(format_xml(args, xml)?.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Reindent `xml` when `--pretty-xml` is set
+fn format_xml(args: &GenerateArgs, xml: String) -> Result<String> {
+    if args.pretty_xml {
+        Ok(pretty_print(&xml).context("Failed to pretty-print generated XML")?)
+    } else {
+        Ok(xml)
+    }
+}
+
+/// Comment block for the top of CSV output when `--csv-header-comment` is set
+fn csv_header_comment(args: &GenerateArgs, count: usize) -> Option<String> {
+    args.csv_header_comment.then(|| {
+        let seed = args
+            .seed
+            .map_or_else(|| "none".to_string(), |seed| seed.to_string());
+        format!(
+            "Generated by opnsense-config-faker v{}, seed={seed}, count={count}",
+            crate::VERSION
+        )
+    })
+}
+
+/// Execute CSV or JSONL generation by streaming configurations from the generator to disk
+fn execute_streaming_csv_generation(
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+    output_file: &Path,
+) -> Result<()> {
+    let count = args.vlan_count();
+    let mut generator = build_vlan_generator(args)?;
+    if let Some(ref vlan_range_str) = args.vlan_range {
+        if args.count.is_none() {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "stream",
+                "Streaming with --vlan-range requires --count.",
+            )
+            .into());
+        }
+        let vlan_ranges = crate::cli::parse_vlan_range(vlan_range_str)
+            .map_err(crate::model::ConfigError::validation)?;
+        generator.set_allowed_vlan_ids(&vlan_ranges)?;
+    }
+
+    let pb = create_progress_bar(
+        count as u64,
+        "Streaming VLAN configurations...",
+        global.quiet,
+    );
+
+    // Collect statistics while streaming so no configuration is retained
+    let started = Instant::now();
+    let mut stats = GenerationStats::default();
+    let progress = rate_limited(&pb, args, global);
+    let configs = generator
+        .into_config_iter(count, args.wan_assignments.clone())?
+        .inspect(|result| {
+            if let Ok(config) = result {
+                stats.record(config);
+                progress.inc(1);
+            }
+        });
+
+    let mut budget = OutputBudget::new(args.max_output_bytes);
+    let written = if matches!(args.format, OutputFormat::Jsonl) {
+        budget
+            .write_file(output_file, |writer| {
+                try_write_jsonl_streaming(configs, writer)
+            })
+            .with_context(|| format!("Failed to stream JSONL to {:?}", output_file))?
+    } else {
+        budget
+            .write_file(output_file, |writer| {
+                try_write_csv_streaming_to(
+                    configs,
+                    writer,
+                    is_gzip_path(output_file),
+                    args.csv_schema.into(),
+                    args.csv_delimiter,
+                    csv_header_comment(args, usize::from(count)).as_deref(),
+                )
+            })
+            .with_context(|| format!("Failed to stream CSV to {:?}", output_file))?
+    };
+
+    pb.finish_with_message(format!(
+        "✅ Streamed {} VLAN configurations to '{}'",
+        written,
+        output_file.display()
+    ));
+
+    if !global.quiet {
+        let stats = stats.with_duration(started.elapsed());
+        print_csv_summary(&stats, &[output_file]);
+        if args.stats {
+            print_department_histogram(&stats);
+        }
     }
 
+    write_manifest_if_requested(args, global, written, &[output_file])?;
+
     Ok(())
 }
 
 /// Execute XML generation
-fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()> {
+fn execute_xml_generation(
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+    confirm: &mut Confirm<'_>,
+) -> Result<()> {
     let base_config = args.base_config.as_ref().unwrap(); // Validated in validate_arguments
 
     if !global.quiet {
@@ -389,16 +1205,58 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
     }
 
     // Create output directory if it doesn't exist
-    if !args.output_dir.exists() {
+    if !args.dry_run && !args.output_dir.exists() {
         fs::create_dir_all(&args.output_dir)?;
     }
 
+    // VLANs already defined in the base config must not be generated again
+    let base_xml = fs::read_to_string(base_config)
+        .with_context(|| format!("Failed to read base config file: {:?}", base_config))?;
+    XMLEngine::validate_base_structure(&base_xml)
+        .with_context(|| format!("Invalid base config: {:?}", base_config))?;
+    let base_vlan_ids = XMLEngine::new()
+        .parse_template(base_xml.clone())
+        .and_then(|template| template.existing_vlan_ids())
+        .with_context(|| format!("Failed to read VLANs from base config: {:?}", base_config))?;
+    if !global.quiet && !base_vlan_ids.is_empty() {
+        println!(
+            "🔒 Base configuration defines {} VLANs; their IDs will not be reused",
+            base_vlan_ids.len()
+        );
+    }
+
+    // Files of an interrupted run are the first VLANs of the same seeded batch
+    let completed = if args.resume {
+        count_written_xml_files(args)?
+    } else {
+        0
+    };
+
     // Generate or load VLAN configurations
     let configs = if let Some(csv_file) = &args.csv_file {
         if !global.quiet {
             println!("📄 Loading configurations from CSV: {}", csv_file.display());
         }
-        read_csv(csv_file).with_context(|| format!("Failed to read CSV file: {:?}", csv_file))?
+        read_csv_with_encoding(csv_file, args.csv_delimiter, args.input_encoding.into())
+            .with_context(|| format!("Failed to read CSV file: {:?}", csv_file))?
+    } else if let Some(yaml_file) = &args.yaml_file {
+        if !global.quiet {
+            println!(
+                "📄 Loading configurations from YAML: {}",
+                yaml_file.display()
+            );
+        }
+        read_yaml(yaml_file)
+            .with_context(|| format!("Failed to read YAML file: {:?}", yaml_file))?
+    } else if let Some(toml_file) = &args.toml_file {
+        if !global.quiet {
+            println!(
+                "📄 Loading configurations from TOML: {}",
+                toml_file.display()
+            );
+        }
+        read_toml(toml_file)
+            .with_context(|| format!("Failed to read TOML file: {:?}", toml_file))?
     } else if let Some(ref vlan_range_str) = args.vlan_range {
         // Parse VLAN ranges
         let vlan_ranges = crate::cli::parse_vlan_range(vlan_range_str)
@@ -410,30 +1268,40 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
             .sum();
 
         if !global.quiet {
-            println!(
-                "📋 Using VLAN ranges: {} (total: {} VLANs)",
-                vlan_range_str, total_vlans
-            );
+            if let Some(count) = args.count {
+                println!(
+                    "📋 Using VLAN ranges: {} (drawing {} of {} VLAN IDs)",
+                    vlan_range_str, count, total_vlans
+                );
+            } else {
+                println!(
+                    "📋 Using VLAN ranges: {} (total: {} VLANs)",
+                    vlan_range_str, total_vlans
+                );
+            }
         }
 
         let pb = create_progress_bar(
-            total_vlans as u64,
+            args.count.map_or(total_vlans as u64, u64::from),
             "Generating configurations from ranges...",
             global.quiet,
         );
 
-        let configs = if args.wan_assignments.is_some() {
-            crate::generator::vlan::generate_vlan_configurations_from_ranges_with_wan(
-                &vlan_ranges,
-                args.seed,
-                args.wan_assignments.as_ref(),
-                Some(&pb),
-            )
+        let mut generator = build_vlan_generator(args)?;
+        generator.reserve_vlan_ids(&base_vlan_ids);
+        let configs = if let Some(count) = args.count {
+            generator.set_allowed_vlan_ids(&vlan_ranges).and_then(|()| {
+                generator.generate_configurations(
+                    count,
+                    args.wan_assignments.as_ref(),
+                    Some(&rate_limited(&pb, args, global)),
+                )
+            })
         } else {
-            crate::generator::vlan::generate_vlan_configurations_from_ranges(
+            generator.generate_configurations_from_ranges(
                 &vlan_ranges,
-                args.seed,
-                Some(&pb),
+                args.wan_assignments.as_ref(),
+                Some(&rate_limited(&pb, args, global)),
             )
         }
         .with_context(|| {
@@ -446,36 +1314,112 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         pb.finish_with_message("✅ Configurations generated from ranges");
         configs
     } else {
+        let count = args.vlan_count();
+        if completed > count {
+            return Err(crate::model::ConfigError::invalid_parameter(
+                "resume",
+                format!(
+                    "{completed} XML files already exist in {} but only {count} VLANs were requested",
+                    args.output_dir.display()
+                ),
+            )
+            .into());
+        }
         if !global.quiet {
-            println!("🔄 Generating {} VLAN configurations...", args.count);
+            if args.resume {
+                println!(
+                    "⏩ Resuming: {completed} of {count} VLAN files already written, generating {}",
+                    count - completed
+                );
+            } else {
+                println!("🔄 Generating {} VLAN configurations...", count);
+            }
         }
 
         let pb = create_progress_bar(
-            args.count as u64,
+            (count - completed) as u64,
             "Generating configurations...",
             global.quiet,
         );
 
-        let configs = if args.wan_assignments.is_some() {
-            crate::generator::vlan::generate_vlan_configurations_with_wan(
-                args.count,
-                args.seed,
+        let mut generator = build_vlan_generator(args)?;
+        generator.reserve_vlan_ids(&base_vlan_ids);
+        let progress = rate_limited(&pb, args, global);
+        let configs = if args.resume {
+            generator.generate_configurations_after(
+                completed,
+                count,
                 args.wan_assignments.as_ref(),
-                Some(&pb),
+                Some(&progress),
             )
         } else {
-            generate_vlan_configurations(args.count, args.seed, Some(&pb))
+            generate_counted(&mut generator, args, &progress)
         }
-        .with_context(|| format!("Failed to generate {} VLAN configurations", args.count))?;
+        .with_context(|| format!("Failed to generate {} VLAN configurations", count))?;
 
         pb.finish_with_message("✅ Configurations generated");
         configs
     };
 
+    // Loaded configurations take the DHCP range policy too
+    let mut configs = match args.dhcp_range {
+        Some(policy) => configs
+            .into_iter()
+            .map(|config| config.with_dhcp_policy(policy))
+            .collect::<crate::Result<Vec<_>>>()
+            .context("DHCP range does not fit the configured VLAN networks")?,
+        None => configs,
+    };
+    if let Some(key) = args.sort {
+        SortKey::from(key).sort(&mut configs);
+    }
+    if let Some(seed) = args.seed.filter(|_| args.shuffle) {
+        shuffle_configs(&mut configs, seed);
+    }
+
+    validate_generated(&configs, args, global)?;
+
+    if args.preview {
+        print_table(&configs);
+    }
+
+    if args.dry_run {
+        // Render every file in memory so the size reflects the real output
+        let template = XmlTemplate::new(base_xml)
+            .with_context(|| "Failed to create XML template from base configuration")?;
+        let mut estimated_bytes = 0u64;
+        for (index, config) in (usize::from(completed)..).zip(&configs) {
+            let output_xml = template.apply_configuration(
+                config,
+                args.firewall_nr,
+                args.opt_counter + index as u16,
+            )?;
+            estimated_bytes += output_xml.len() as u64;
+        }
+        print!(
+            "{}",
+            render_dry_run_summary(
+                &format_name(args),
+                configs.len(),
+                &args.output_dir,
+                estimated_bytes
+            )
+        );
+        return Ok(());
+    }
+
+    if args.interactive && !confirm(&configs)? {
+        report_cancelled(global);
+        return Ok(());
+    }
+
     if !global.quiet {
         println!("📝 Processing {} configurations...", configs.len());
     }
 
+    // Side files are recorded in the manifest next to the XML output
+    let mut side_files: Vec<PathBuf> = Vec::new();
+
     // Generate firewall rules if requested
     let firewall_rules = if args.include_firewall_rules {
         if !global.quiet {
@@ -483,10 +1427,9 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         }
 
         // Parse complexity level
-        let complexity: FirewallComplexity =
-            args.firewall_rule_complexity.parse().map_err(|e| {
-                crate::model::ConfigError::validation(format!("Invalid firewall complexity: {}", e))
-            })?;
+        let complexity: FirewallComplexity = args.firewall_complexity().parse().map_err(|e| {
+            crate::model::ConfigError::validation(format!("Invalid firewall complexity: {}", e))
+        })?;
 
         // Generate firewall rules
         let firewall_pb = create_progress_bar(
@@ -494,12 +1437,14 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
             "Generating firewall rules...",
             global.quiet,
         );
-        let rules = generate_firewall_rules(
+        let rules = generate_firewall_rules_with_policy(
             &configs,
             complexity,
             args.seed,
             Some(&firewall_pb),
             args.firewall_rules_per_vlan,
+            args.firewall_policy()
+                .context("Failed to load firewall profile")?,
         )?;
 
         firewall_pb.finish_with_message(format!("✅ Generated {} firewall rules", rules.len()));
@@ -512,15 +1457,33 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         if !global.quiet {
             println!("📄 Firewall rules CSV: {}", firewall_csv.display());
         }
+        side_files.push(firewall_csv);
 
         Some(rules)
     } else {
         None
     };
 
+    if args.p2p_links.is_some() {
+        let p2p_csv = args
+            .output_dir
+            .join(format!("firewall_{}_p2p_links.csv", args.firewall_nr));
+        write_p2p_links(args, global, &p2p_csv, &configs)?;
+        side_files.push(p2p_csv);
+    }
+
+    if args.monolithic {
+        return write_monolithic_xml(
+            args,
+            global,
+            base_xml,
+            &configs,
+            firewall_rules,
+            &side_files,
+        );
+    }
+
     // Load base XML template
-    let base_xml = fs::read_to_string(base_config)
-        .with_context(|| format!("Failed to read base config file: {:?}", base_config))?;
     let template = XmlTemplate::new(base_xml)
         .with_context(|| "Failed to create XML template from base configuration")?;
 
@@ -531,32 +1494,24 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         global.quiet,
     );
 
-    // Generate XML configurations
-    for (index, config) in configs.iter().enumerate() {
+    // Generate XML configurations; with --continue-on-error a failing VLAN
+    // is recorded and skipped instead of aborting the run
+    let mut budget = OutputBudget::new(args.max_output_bytes);
+    let mut written_files = Vec::with_capacity(configs.len());
+    let mut failures: Vec<(u16, anyhow::Error)> = Vec::new();
+    for (index, config) in (usize::from(completed)..).zip(&configs) {
         pb.set_message(format!("Processing VLAN {}", config.vlan_id));
 
-        // Generate XML for this configuration
-        let output_xml = template.apply_configuration(
-            config,
-            args.firewall_nr,
-            args.opt_counter + index as u16,
-        )?;
-
-        // Write output file
-        let output_file = args.output_dir.join(format!(
-            "firewall_{}_vlan_{}.xml",
-            args.firewall_nr, config.vlan_id
-        ));
-
-        if output_file.exists() && !args.force {
-            return Err(crate::model::ConfigError::config(format!(
-                "Output file '{}' already exists. Use --force to overwrite.",
-                output_file.display()
-            ))
-            .into());
+        match write_vlan_xml(args, &template, &mut budget, config, index) {
+            Ok(output_file) => written_files.push(output_file),
+            Err(e) if args.continue_on_error && !is_output_limit(&e) => {
+                if !global.json_errors() {
+                    pb.suspend(|| eprintln!("⚠️  Skipping VLAN {}: {e:#}", config.vlan_id));
+                }
+                failures.push((config.vlan_id, e));
+            }
+            Err(e) => return Err(e),
         }
-
-        fs::write(&output_file, output_xml)?;
         pb.inc(1);
     }
 
@@ -564,6 +1519,13 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
 
     if !global.quiet {
         print_xml_summary(&configs, &args.output_dir, args.firewall_nr);
+        if args.continue_on_error {
+            println!("  ✅ Written: {}", written_files.len());
+            println!("  ❌ Failed: {}", failures.len());
+        }
+        if args.stats {
+            print_department_histogram(&GenerationStats::from_configs(&configs));
+        }
     }
 
     // Print firewall summary if rules were generated
@@ -576,9 +1538,184 @@ fn execute_xml_generation(args: &GenerateArgs, global: &GlobalArgs) -> Result<()
         }
     }
 
+    let outputs: Vec<&Path> = written_files
+        .iter()
+        .chain(&side_files)
+        .map(PathBuf::as_path)
+        .collect();
+    write_manifest_if_requested(args, global, written_files.len(), &outputs)?;
+
+    if !failures.is_empty() {
+        let report: String = failures
+            .iter()
+            .map(|(vlan_id, e)| format!("\n  - VLAN {vlan_id}: {e:#}"))
+            .collect();
+        return Err(crate::model::ConfigError::config(format!(
+            "{} of {} VLAN configurations failed:{report}",
+            failures.len(),
+            configs.len()
+        ))
+        .into());
+    }
+
     Ok(())
 }
 
+/// Render one VLAN through the base template and write its XML file
+fn write_vlan_xml(
+    args: &GenerateArgs,
+    template: &XmlTemplate,
+    budget: &mut OutputBudget,
+    config: &VlanConfig,
+    index: usize,
+) -> Result<PathBuf> {
+    let output_xml = format_xml(
+        args,
+        template.apply_configuration(config, args.firewall_nr, args.opt_counter + index as u16)?,
+    )?;
+
+    let output_file = args.output_dir.join(args.xml_name_template.file_name(
+        args.firewall_nr,
+        config.vlan_id,
+        args.opt_counter + index as u16,
+        index,
+    ));
+
+    if output_file.exists() && !args.force {
+        return Err(crate::model::ConfigError::file_exists(&output_file).into());
+    }
+
+    budget.write_file(&output_file, |writer| -> Result<()> {
+        writer.write_all(output_xml.as_bytes())?;
+        Ok(())
+    })?;
+    Ok(output_file)
+}
+
+/// Whether an error is the `--max-output-bytes` limit, which ends the run
+/// even with --continue-on-error since every later file would fail too
+fn is_output_limit(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<crate::model::ConfigError>(),
+        Some(crate::model::ConfigError::ResourceExhausted { .. })
+    )
+}
+
+/// Count the files in `--output-dir` named by `--xml-name-template` for this
+/// firewall, i.e. the VLANs an interrupted run already wrote
+fn count_written_xml_files(args: &GenerateArgs) -> Result<u16> {
+    if !args.output_dir.exists() {
+        return Ok(0);
+    }
+    let mut count = 0u16;
+    for entry in fs::read_dir(&args.output_dir)
+        .with_context(|| format!("Failed to scan output directory {:?}", args.output_dir))?
+    {
+        let entry = entry?;
+        if entry.file_type()?.is_file()
+            && entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| args.xml_name_template.matches(name, args.firewall_nr))
+        {
+            count = count.saturating_add(1);
+        }
+    }
+    Ok(count)
+}
+
+/// Write VLANs, NAT, aliases and optional firewall rules into one XML document
+fn write_monolithic_xml(
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+    base_xml: String,
+    configs: &[VlanConfig],
+    firewall_rules: Option<Vec<FirewallRule>>,
+    side_files: &[PathBuf],
+) -> Result<()> {
+    let output_file = args
+        .output_dir
+        .join(format!("firewall_{}_config.xml", args.firewall_nr));
+    if output_file.exists() && !args.force {
+        return Err(crate::model::ConfigError::file_exists(&output_file).into());
+    }
+
+    let rules = firewall_rules.unwrap_or_default();
+    let nat = NatGenerator::new_with_seed(args.seed)
+        .with_constraints(nat_constraints(args))
+        .context("Invalid NAT constraints")?
+        .generate_for_vlans(configs)
+        .context("Failed to generate NAT port-forwards")?;
+    let aliases = generate_aliases(configs).context("Failed to generate firewall aliases")?;
+
+    let mut builder = OPNsenseConfigBuilder::with_template_content(base_xml)
+        .with_schema_version(args.opnsense_version)
+        .with_dhcp_options(dhcp_options(args)?);
+    if let Some(parents) = &args.parent_interfaces {
+        builder = builder.with_parent_interfaces(parents.clone());
+    }
+    let xml = builder
+        .build_complete(configs, &rules, &nat, &aliases)
+        .context("Failed to build monolithic XML configuration")?;
+    let xml = format_xml(args, xml)?;
+    OutputBudget::new(args.max_output_bytes).write_file(&output_file, |writer| -> Result<()> {
+        writer.write_all(xml.as_bytes())?;
+        Ok(())
+    })?;
+
+    if !global.quiet {
+        print_xml_summary(configs, &args.output_dir, args.firewall_nr);
+        if args.stats {
+            print_department_histogram(&GenerationStats::from_configs(configs));
+        }
+        println!("  📄 Configuration file: {}", output_file.display());
+        println!(
+            "  🔀 NAT port-forwards: {}, aliases: {}, filter rules: {}",
+            nat.len(),
+            aliases.len(),
+            rules.len()
+        );
+    }
+
+    let outputs: Vec<&Path> = std::iter::once(&output_file)
+        .chain(side_files)
+        .map(PathBuf::as_path)
+        .collect();
+    write_manifest_if_requested(args, global, configs.len(), &outputs)?;
+
+    Ok(())
+}
+
+/// Write the `--manifest` sidecar describing this run, if requested
+fn write_manifest_if_requested(
+    args: &GenerateArgs,
+    global: &GlobalArgs,
+    count: usize,
+    outputs: &[&Path],
+) -> Result<()> {
+    let Some(manifest_path) = &args.manifest else {
+        return Ok(());
+    };
+
+    let manifest = Manifest::new(&format_name(args), args.seed, count, outputs)
+        .with_context(|| "Failed to hash generated output for manifest")?;
+    write_manifest(&manifest, manifest_path)
+        .with_context(|| format!("Failed to write manifest to {:?}", manifest_path))?;
+
+    if !global.quiet {
+        println!("🧾 Manifest: {}", manifest_path.display());
+    }
+
+    Ok(())
+}
+
+/// Command-line name of the selected output format, e.g. "csv"
+fn format_name(args: &GenerateArgs) -> String {
+    args.format
+        .to_possible_value()
+        .map_or_else(String::new, |value| value.get_name().to_string())
+}
+
 /// Create a progress bar with consistent styling
 fn create_progress_bar(total: u64, message: &str, quiet: bool) -> ProgressBar {
     if quiet {
@@ -606,18 +1743,35 @@ fn create_progress_bar(total: u64, message: &str, quiet: bool) -> ProgressBar {
 }
 
 /// Print summary for CSV generation
-fn print_csv_summary(configs: &[crate::generator::vlan::VlanConfig], output_file: &Path) {
+fn print_csv_summary(stats: &GenerationStats, output_files: &[&Path]) {
     println!();
     println!("{}", style("Summary:").bold());
-    println!("  📊 Configurations: {}", configs.len());
-    println!("  📁 Output file: {}", output_file.display());
-    if !configs.is_empty() {
-        println!(
-            "  🏷️  VLAN IDs: {} - {}",
-            configs.iter().map(|c| c.vlan_id).min().unwrap_or(0),
-            configs.iter().map(|c| c.vlan_id).max().unwrap_or(0)
-        );
+    println!("  📊 Configurations: {}", stats.total);
+    for output_file in output_files {
+        println!("  📁 Output file: {}", output_file.display());
+    }
+    if let Some((min, max)) = stats.vlan_id_range {
+        println!("  🏷️  VLAN IDs: {} - {}", min, max);
     }
+    if !stats.networks_by_class.is_empty() {
+        let classes: Vec<String> = stats
+            .networks_by_class
+            .iter()
+            .map(|(class, count)| {
+                let name = NetworkClassArg::from(*class)
+                    .to_possible_value()
+                    .map_or_else(String::new, |value| value.get_name().to_string());
+                format!("{name} {count}")
+            })
+            .collect();
+        println!("  🌐 Networks: {}", classes.join(", "));
+    }
+}
+
+/// Print the `--stats` histogram of VLANs per department
+fn print_department_histogram(stats: &GenerationStats) {
+    println!("  🏢 VLANs per department:");
+    print!("{}", render_histogram(&stats.departments));
 }
 
 /// Print summary for XML generation
@@ -673,3 +1827,115 @@ fn print_firewall_summary(rules: &[crate::generator::FirewallRule], output_file:
         .len();
     println!("  🏷️  VLANs with rules: {}", vlan_count);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::interactive::confirm_generation_with;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+    use std::io::Cursor;
+
+    fn generate_args(extra: &[&str]) -> GenerateArgs {
+        let argv = ["faker", "generate", "--seed", "7"].iter().chain(extra);
+        match Cli::try_parse_from(argv).unwrap().command {
+            Commands::Generate(args) => *args,
+            _ => unreachable!(),
+        }
+    }
+
+    fn quiet() -> GlobalArgs {
+        GlobalArgs {
+            quiet: true,
+            no_color: true,
+            verbose: 0,
+            output: None,
+            error_format: ErrorFormat::Text,
+        }
+    }
+
+    #[test]
+    fn test_interactive_no_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv = dir.path().join("vlans.csv");
+        let xml_dir = dir.path().join("xml");
+        let base = dir.path().join("base.xml");
+        fs::write(&base, "<opnsense><interfaces/><vlans/></opnsense>").unwrap();
+        let mut prompts = 0;
+        let mut answer_no = |configs: &[VlanConfig]| {
+            prompts += 1;
+            confirm_generation_with(configs, 40, &mut Cursor::new("n\n"), &mut io::sink())
+        };
+
+        let args = generate_args(&[
+            "--interactive",
+            "--format",
+            "csv",
+            "--count",
+            "3",
+            "--output",
+            csv.to_str().unwrap(),
+        ]);
+        execute_internal(args, &quiet(), &mut answer_no).unwrap();
+        assert!(!csv.exists());
+
+        let args = generate_args(&[
+            "--interactive",
+            "--format",
+            "xml",
+            "--count",
+            "3",
+            "--base-config",
+            base.to_str().unwrap(),
+            "--output-dir",
+            xml_dir.to_str().unwrap(),
+        ]);
+        execute_internal(args, &quiet(), &mut answer_no).unwrap();
+        assert!(!xml_dir.exists() || fs::read_dir(&xml_dir).unwrap().next().is_none());
+
+        assert_eq!(prompts, 2);
+    }
+
+    #[test]
+    fn test_emit_append_only_appends_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv = dir.path().join("data.csv");
+        let json = dir.path().join("data.json");
+        let emit = || {
+            generate_args(&[
+                "--emit",
+                "csv,json",
+                "--count",
+                "4",
+                "--output",
+                csv.to_str().unwrap(),
+            ])
+        };
+        execute_internal(emit(), &quiet(), &mut confirm_generation).unwrap();
+        let written = fs::read_to_string(&json).unwrap();
+
+        // The CLI rejects --emit with --append, but library callers can set both
+        let mut args = emit();
+        args.append = true;
+        let error = execute_internal(args, &quiet(), &mut confirm_generation).unwrap_err();
+        assert!(error.to_string().contains("data.json"), "{error}");
+        assert_eq!(fs::read_to_string(&json).unwrap(), written);
+    }
+
+    #[test]
+    fn test_validate_after_reports_duplicates() {
+        let args = generate_args(&["--format", "csv", "--count", "3", "--validate-after"]);
+        let mut configs = VlanGenerator::new(Some(42)).generate_batch(3).unwrap();
+        validate_generated(&configs, &args, &quiet()).unwrap();
+
+        configs.push(configs[0].clone());
+        let error = validate_generated(&configs, &args, &quiet())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("failed validation with 2 issue(s)"),
+            "{error}"
+        );
+        assert!(error.contains("Duplicate VLAN ID"), "{error}");
+    }
+}
@@ -3,6 +3,8 @@
 pub mod completions;
 pub mod csv;
 pub mod deprecated;
+pub mod diff;
 pub mod generate;
 pub mod validate;
+pub mod verify;
 pub mod xml;
@@ -4,10 +4,12 @@
 //! ensuring consistency, correctness, and compliance with OPNsense standards.
 
 use crate::cli::{GlobalArgs, ValidateArgs, ValidationFormat};
+use crate::generator::VlanConfig;
 use crate::model::ConfigError;
 use crate::validate::ValidationEngine;
 use anyhow::{Context, Result};
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -19,7 +21,7 @@ pub fn execute_with_global(args: ValidateArgs, global: &GlobalArgs) -> Result<()
 
 /// Execute validation command
 pub fn execute(args: ValidateArgs, global: &GlobalArgs) -> Result<()> {
-    if !global.quiet {
+    if !global.quiet && !args.json {
         println!("🔍 OPNsense Config Faker - Validation Mode");
         println!();
     }
@@ -27,15 +29,41 @@ pub fn execute(args: ValidateArgs, global: &GlobalArgs) -> Result<()> {
     // Determine input format
     let format = determine_format(&args.input, &args.format)?;
 
-    if !global.quiet {
+    if !global.quiet && !args.json {
         println!("📂 Input: {}", args.input.display());
         println!("📝 Format: {:?}", format);
-        if args.verbose {
+        if global.verbose > 0 {
             println!("📊 Max errors: {}", args.max_errors);
         }
         println!();
     }
 
+    // Cross-file checks compare VLAN rows, which only CSV input provides
+    if args.against.is_some() && !matches!(format, ValidationFormat::Csv) {
+        return Err(ConfigError::invalid_parameter(
+            "against",
+            "--against is only supported for CSV input",
+        )
+        .into());
+    }
+    if args.json && !matches!(format, ValidationFormat::Csv) {
+        return Err(ConfigError::invalid_parameter(
+            "json",
+            "--json is only supported for CSV input",
+        )
+        .into());
+    }
+
+    // The JSON report owns stdout, so progress and per-error lines are silenced
+    let json_global = GlobalArgs {
+        quiet: true,
+        verbose: 0,
+        no_color: global.no_color,
+        output: global.output.clone(),
+        error_format: global.error_format,
+    };
+    let global = if args.json { &json_global } else { global };
+
     // Validate based on format
     match format {
         ValidationFormat::Csv => validate_csv(&args, global),
@@ -48,23 +76,61 @@ pub fn execute(args: ValidateArgs, global: &GlobalArgs) -> Result<()> {
     }
 }
 
+/// One problem found while validating, located by file line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportedError {
+    /// Line number in the input, counting the CSV header as line 1
+    pub line: usize,
+    /// What is wrong on that line
+    pub message: String,
+}
+
+/// Outcome of validating an input file, printed by `validate --json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    /// Whether no errors were found
+    pub valid: bool,
+    /// Every error found, up to `--max-errors`
+    pub errors: Vec<ReportedError>,
+    /// Number of configurations read from the input
+    pub count: usize,
+}
+
 /// Validate CSV configuration data
 fn validate_csv(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
-    let mut engine = ValidationEngine::new();
-    let mut error_count: u32 = 0;
+    let mut engine = ValidationEngine::new().with_max_description_len(args.max_description_len);
+    let mut report = ValidationReport::default();
 
     if !global.quiet {
         println!("📄 Reading CSV file: {}", args.input.display());
     }
 
-    // Use the existing CSV reader function with proper error chaining
-    let configs = crate::io::csv::read_csv(&args.input)
-        .with_context(|| format!("Failed to read CSV: {}", args.input.display()))?;
+    // Keep reading past bad rows so every problem is reported in one run
+    let (rows, mut row_errors) = crate::io::csv::read_csv_collect_errors_with_delimiter(
+        &args.input,
+        args.csv_delimiter,
+        args.input_encoding.into(),
+    )
+    .with_context(|| format!("Failed to read CSV: {}", args.input.display()))?;
+    report.count = rows.len();
+    row_errors.truncate(args.max_errors as usize);
+
+    if global.verbose > 0 || !global.quiet {
+        for row_error in &row_errors {
+            eprintln!("❌ Error at {row_error}");
+        }
+    }
+    report
+        .errors
+        .extend(row_errors.into_iter().map(|row_error| ReportedError {
+            line: row_error.line,
+            message: row_error.message,
+        }));
 
     if !global.quiet {
         println!(
             "✅ Successfully loaded {} configurations from CSV",
-            configs.len()
+            rows.len()
         );
     }
 
@@ -76,8 +142,8 @@ fn validate_csv(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
     };
 
     let mut valid_configs = Vec::new();
-    for (index, config) in configs.iter().enumerate() {
-        if error_count >= args.max_errors {
+    for (line, config) in &rows {
+        if report.errors.len() as u32 >= args.max_errors {
             if !global.quiet {
                 println!(
                     "⚠️  Reached maximum error limit ({}). Stopping validation.",
@@ -88,10 +154,13 @@ fn validate_csv(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
         }
 
         if let Err(e) = engine.validate_config(config) {
-            error_count += 1;
-            if args.verbose || !global.quiet {
-                eprintln!("❌ Error in configuration {}: {}", index + 1, e);
+            if global.verbose > 0 || !global.quiet {
+                eprintln!("❌ Error at line {line}: {e}");
             }
+            report.errors.push(ReportedError {
+                line: *line,
+                message: e.to_string(),
+            });
         } else {
             valid_configs.push(config.clone());
         }
@@ -101,6 +170,20 @@ fn validate_csv(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
 
     pb.finish_with_message("✅ Validation complete");
 
+    // Cross-file consistency against a second CSV
+    if let Some(against) = &args.against {
+        let limit = (args.max_errors as usize).saturating_sub(report.errors.len());
+        report
+            .errors
+            .extend(report_cross_conflicts(args, global, &rows, against, limit)?);
+    }
+
+    let error_count = report.errors.len() as u32;
+    report.valid = error_count == 0;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    }
+
     if !global.quiet {
         if error_count == 0 {
             println!("🎉 All configurations are valid!");
@@ -128,6 +211,68 @@ fn validate_csv(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
     Ok(())
 }
 
+/// Report VLAN ID and network overlap between the input and another CSV
+///
+/// Returns one error per conflict, up to `limit`, located on the input's
+/// line. `rows` pair each input configuration with its line; line numbers
+/// count the CSV header as line 1. A bad row in the other file fails the
+/// check.
+fn report_cross_conflicts(
+    args: &ValidateArgs,
+    global: &GlobalArgs,
+    rows: &[(usize, VlanConfig)],
+    against: &Path,
+    limit: usize,
+) -> Result<Vec<ReportedError>> {
+    if !global.quiet {
+        println!("📄 Checking for conflicts with: {}", against.display());
+    }
+
+    let (other_rows, other_errors) = crate::io::csv::read_csv_collect_errors_with_delimiter(
+        against,
+        args.csv_delimiter,
+        args.input_encoding.into(),
+    )
+    .with_context(|| format!("Failed to read CSV: {}", against.display()))?;
+    if let Some(row_error) = other_errors.first() {
+        return Err(ConfigError::validation(row_error.to_string()))
+            .with_context(|| format!("Failed to read CSV: {}", against.display()));
+    }
+
+    let (lines, configs): (Vec<usize>, Vec<VlanConfig>) = rows.iter().cloned().unzip();
+    let (other_lines, other): (Vec<usize>, Vec<VlanConfig>) = other_rows.into_iter().unzip();
+    let mut conflicts = ValidationEngine::find_cross_conflicts(&configs, &other);
+    if !global.quiet && conflicts.is_empty() {
+        println!("✅ No VLAN ID or network conflicts found");
+    }
+    conflicts.truncate(limit);
+
+    for conflict in &conflicts {
+        if global.verbose > 0 || !global.quiet {
+            eprintln!(
+                "❌ Conflict: {} ({} line {}, {} line {})",
+                conflict,
+                args.input.display(),
+                lines[conflict.index],
+                against.display(),
+                other_lines[conflict.other_index]
+            );
+        }
+    }
+
+    Ok(conflicts
+        .iter()
+        .map(|conflict| ReportedError {
+            line: lines[conflict.index],
+            message: format!(
+                "{conflict} ({} line {})",
+                against.display(),
+                other_lines[conflict.other_index]
+            ),
+        })
+        .collect())
+}
+
 /// Validate XML configuration data
 fn validate_xml(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
     if !global.quiet {
@@ -146,7 +291,6 @@ fn validate_xml(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
             Ok(quick_xml::events::Event::Eof) => break,
             Ok(_) => continue,
             Err(e) => {
-                eprintln!("❌ Invalid XML: {}", e);
                 return Err(
                     ConfigError::invalid_parameter("input", format!("Invalid XML: {}", e)).into(),
                 );
@@ -164,7 +308,14 @@ fn validate_xml(args: &ValidateArgs, global: &GlobalArgs) -> Result<()> {
 /// Determine input format from file extension or explicit format
 fn determine_format(input: &Path, format: &ValidationFormat) -> Result<ValidationFormat> {
     match format {
-        ValidationFormat::Auto => match input.extension().and_then(|ext| ext.to_str()) {
+        // Compressed inputs are detected by the extension before `.gz`
+        ValidationFormat::Auto => match input
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.strip_suffix(".gz").unwrap_or(name))
+            .and_then(|name| Path::new(name).extension())
+            .and_then(|ext| ext.to_str())
+        {
             Some("csv") => Ok(ValidationFormat::Csv),
             Some("xml") => Ok(ValidationFormat::Xml),
             _ => Err(ConfigError::invalid_parameter(
@@ -0,0 +1,46 @@
+//! Verify command for checking generated files against a manifest
+//!
+//! Each file is re-hashed and compared with the SHA-256 recorded by
+//! `generate --manifest`, so CI can detect stale or edited fixtures.
+
+use crate::cli::manifest::read_manifest;
+use crate::cli::{GlobalArgs, VerifyArgs};
+use anyhow::{Context, Result, bail};
+
+/// Execute the verify command
+pub fn execute(args: VerifyArgs, global: &GlobalArgs) -> Result<()> {
+    let manifest = read_manifest(&args.manifest)
+        .with_context(|| format!("Failed to read manifest: {}", args.manifest.display()))?;
+    let checks = manifest
+        .verify(&args.files)
+        .context("Failed to hash output files")?;
+
+    let mismatches = checks.iter().filter(|check| !check.matches()).count();
+    for check in &checks {
+        if check.matches() {
+            if !global.quiet {
+                println!("✅ {}", check.path.display());
+            }
+        } else if !global.json_errors() {
+            eprintln!(
+                "❌ {}: expected sha256 {}, found {}",
+                check.path.display(),
+                check.expected,
+                check.actual
+            );
+        }
+    }
+
+    if mismatches > 0 {
+        bail!(
+            "{mismatches} of {} files do not match {}",
+            checks.len(),
+            args.manifest.display()
+        );
+    }
+
+    if !global.quiet {
+        println!("🧾 All {} files match the manifest", checks.len());
+    }
+    Ok(())
+}
@@ -93,10 +93,7 @@ pub fn execute(args: XmlArgs) -> Result<()> {
         ));
 
         if output_file.exists() && !args.force {
-            return Err(crate::model::ConfigError::config(format!(
-                "Output file '{}' already exists. Use --force to overwrite.",
-                output_file.display()
-            )));
+            return Err(crate::model::ConfigError::file_exists(&output_file));
         }
 
         fs::write(&output_file, output_xml)?;
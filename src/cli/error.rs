@@ -1,5 +1,7 @@
 //! CLI-specific error types for enhanced error handling
 
+use crate::model::ConfigError;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// CLI-specific error types for command-line interface operations
@@ -80,6 +82,70 @@ pub fn progress<S: Into<String>>(message: S) -> Self {
     pub fn terminal<S: Into<String>>(message: S) -> Self {
         Self::TerminalError(message.into())
     }
+
+    /// Create an invalid argument error from a command-line parse failure
+    ///
+    /// Keeps clap's one-line message and drops its usage hint.
+    pub fn from_clap(error: &clap::Error) -> Self {
+        let rendered = error.render().to_string();
+        let message = rendered.lines().next().unwrap_or_default();
+        Self::invalid_argument(message.strip_prefix("error: ").unwrap_or(message))
+    }
+
+    /// Name of the variant, or of the wrapped [`ConfigError`] variant
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InvalidArgument(_) => "InvalidArgument",
+            Self::InteractiveModeError(_) => "InteractiveModeError",
+            Self::OutputError(_) => "OutputError",
+            Self::FileOperation { .. } => "FileOperation",
+            Self::ValidationFailed(_) => "ValidationFailed",
+            Self::ProgressError(_) => "ProgressError",
+            Self::TerminalError(_) => "TerminalError",
+            Self::Config(error) => error.kind(),
+        }
+    }
+}
+
+/// Machine-readable report of a failed command, written by
+/// `--error-format json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ErrorReport {
+    /// Message of the underlying error
+    pub error: String,
+    /// Variant of the underlying error, e.g. `Validation`; `Other` for
+    /// errors that are neither [`ConfigError`] nor [`CliError`]
+    pub kind: String,
+    /// Context added around the error, outermost first
+    pub context: Vec<String>,
+}
+
+impl ErrorReport {
+    /// Build a report from a command's error chain
+    ///
+    /// The first [`ConfigError`] or [`CliError`] in the chain is the
+    /// underlying error; the messages wrapped around it become the context.
+    /// Without one, the root cause is the underlying error.
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        let messages: Vec<String> = error.chain().map(ToString::to_string).collect();
+        let (index, kind) = error
+            .chain()
+            .enumerate()
+            .find_map(|(index, cause)| {
+                let kind = cause
+                    .downcast_ref::<ConfigError>()
+                    .map(ConfigError::kind)
+                    .or_else(|| cause.downcast_ref::<CliError>().map(CliError::kind))?;
+                Some((index, kind))
+            })
+            .unwrap_or((messages.len() - 1, "Other"));
+
+        Self {
+            error: messages[index].clone(),
+            kind: kind.to_string(),
+            context: messages[..index].to_vec(),
+        }
+    }
 }
 
 /// Result type alias for CLI operations
@@ -108,6 +174,36 @@ fn test_file_operation_error() {
         assert!(matches!(error, CliError::FileOperation { .. }));
     }
 
+    #[test]
+    fn test_cli_error_from_clap() {
+        use clap::Parser;
+
+        let error =
+            crate::cli::Cli::try_parse_from(["opnsense-config-faker", "generate", "--count", "0"])
+                .err()
+                .unwrap();
+        let report = ErrorReport::from_error(&CliError::from_clap(&error).into());
+        assert_eq!(report.kind, "InvalidArgument");
+        assert_eq!(
+            report.error,
+            "Invalid command-line argument: invalid value '0' for '--count <COUNT>': 0 is not in 1..=10000"
+        );
+    }
+
+    #[test]
+    fn test_error_report_finds_underlying_error() {
+        let error = anyhow::Error::from(ConfigError::validation("Invalid VLAN ID"))
+            .context("Failed to generate configurations");
+        let report = ErrorReport::from_error(&error);
+        assert_eq!(report.error, "Validation error: Invalid VLAN ID");
+        assert_eq!(report.kind, "Validation");
+        assert_eq!(report.context, ["Failed to generate configurations"]);
+
+        let report = ErrorReport::from_error(&anyhow::anyhow!("plain failure"));
+        assert_eq!(report.kind, "Other");
+        assert!(report.context.is_empty());
+    }
+
     #[test]
     fn test_config_error_conversion() {
         let config_error = crate::model::ConfigError::validation("Invalid VLAN ID");
@@ -0,0 +1,189 @@
+//! Interactive confirmation before generated configurations are written
+
+use crate::generator::VlanConfig;
+use console::{Term, style};
+use ipnetwork::Ipv4Network;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::net::Ipv4Addr;
+
+/// Prefix length used to group VLAN networks into blocks for the summary
+const BLOCK_PREFIX_LEN: u8 = 16;
+
+/// Decides whether generated configurations should be written
+pub type Confirm<'a> = dyn FnMut(&[VlanConfig]) -> io::Result<bool> + 'a;
+
+/// Render a summary of generated configurations for review
+///
+/// Lists the count, VLAN ID range, the /16 network blocks in use and the
+/// number of VLANs per department.
+pub fn render_summary(configs: &[VlanConfig]) -> String {
+    let mut blocks: BTreeMap<Ipv4Addr, usize> = BTreeMap::new();
+    let mut departments: BTreeMap<&str, usize> = BTreeMap::new();
+    for config in configs {
+        if let Ok(network) = config.as_ipv4_network() {
+            if let Ok(block) = Ipv4Network::new(network.network(), BLOCK_PREFIX_LEN) {
+                *blocks.entry(block.network()).or_default() += 1;
+            }
+        }
+        *departments.entry(config.department()).or_default() += 1;
+    }
+
+    let mut summary = String::new();
+    summary.push_str(&format!(
+        "{}\n",
+        style("Generation summary:").bold().for_stderr()
+    ));
+    summary.push_str(&format!("  📊 Configurations: {}\n", configs.len()));
+    let min = configs.iter().map(|c| c.vlan_id).min();
+    let max = configs.iter().map(|c| c.vlan_id).max();
+    if let (Some(min), Some(max)) = (min, max) {
+        summary.push_str(&format!("  🏷️  VLAN IDs: {min}-{max}\n"));
+    }
+    summary.push_str("  🌐 Network blocks:\n");
+    for (block, count) in &blocks {
+        let block = format!("{block}/{BLOCK_PREFIX_LEN}");
+        summary.push_str(&format!("      {block:<18} {count} VLANs\n"));
+    }
+    summary.push_str("  🏢 Departments:\n");
+    for (department, count) in &departments {
+        summary.push_str(&format!("      {department:<18} {count} VLANs\n"));
+    }
+    summary
+}
+
+/// Show a summary of the configurations and ask whether to write them
+///
+/// The summary is paged to the terminal height. Returns `true` without
+/// prompting when stdin is not a terminal, so piped and scripted runs are
+/// never blocked waiting for input.
+pub fn confirm_generation(configs: &[VlanConfig]) -> io::Result<bool> {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return Ok(true);
+    }
+    // Leave a row for the paging prompt
+    let (rows, _) = Term::stderr().size();
+    let page_lines = usize::from(rows).saturating_sub(1).max(1);
+    confirm_generation_with(configs, page_lines, &mut stdin.lock(), &mut io::stderr())
+}
+
+/// Prompt for confirmation using the given input and output streams
+///
+/// The summary is shown `page_lines` lines at a time; Enter shows the next
+/// page and "q" skips to the prompt. Only an explicit "y" or "yes"
+/// (case-insensitive) confirms; an empty line or end of input declines.
+pub fn confirm_generation_with<R: BufRead, W: Write>(
+    configs: &[VlanConfig],
+    page_lines: usize,
+    input: &mut R,
+    output: &mut W,
+) -> io::Result<bool> {
+    let summary = render_summary(configs);
+    let lines: Vec<&str> = summary.lines().collect();
+    let mut pages = lines.chunks(page_lines.max(1)).peekable();
+    while let Some(page) = pages.next() {
+        for line in page {
+            writeln!(output, "{line}")?;
+        }
+        if pages.peek().is_none() {
+            break;
+        }
+        write!(output, "-- more (Enter for next page, q to skip) --")?;
+        output.flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 || answer.trim().eq_ignore_ascii_case("q") {
+            break;
+        }
+    }
+
+    write!(output, "Write these configurations? [y/N]: ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::VlanGenerator;
+    use std::io::Cursor;
+
+    fn sample_configs() -> Vec<VlanConfig> {
+        vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(
+                250,
+                "10.1.9.x".to_string(),
+                "Customer Service VLAN 250".to_string(),
+                2,
+            )
+            .unwrap(),
+            VlanConfig::new(120, "172.16.4.x".to_string(), "IT VLAN 120".to_string(), 3).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_render_summary() {
+        let summary = render_summary(&sample_configs());
+        assert!(summary.contains("Configurations: 3"));
+        assert!(summary.contains("VLAN IDs: 100-250"));
+        assert!(summary.contains("10.1.0.0/16        2 VLANs"));
+        assert!(summary.contains("172.16.0.0/16      1 VLANs"));
+        assert!(summary.contains("IT                 2 VLANs"));
+        assert!(summary.contains("Customer Service   1 VLANs"));
+    }
+
+    #[test]
+    fn test_confirm_generation_declines_on_piped_no() {
+        let configs = VlanGenerator::new(Some(42)).generate_batch(5).unwrap();
+        let mut output = Vec::new();
+        let confirmed =
+            confirm_generation_with(&configs, 40, &mut Cursor::new("n\n"), &mut output).unwrap();
+        assert!(!confirmed);
+        assert!(String::from_utf8(output).unwrap().contains("[y/N]"));
+    }
+
+    #[test]
+    fn test_confirm_generation_answers() {
+        let configs = sample_configs();
+        let confirm = |input: &str| {
+            confirm_generation_with(&configs, 40, &mut Cursor::new(input), &mut io::sink()).unwrap()
+        };
+        assert!(confirm("y\n"));
+        assert!(confirm("YES\n"));
+        assert!(!confirm("\n"));
+        assert!(!confirm(""));
+        assert!(!confirm("maybe\n"));
+    }
+
+    #[test]
+    fn test_confirm_generation_pages_summary() {
+        let configs = sample_configs();
+        let lines = render_summary(&configs).lines().count();
+        let page = |input: &str| {
+            let mut output = Vec::new();
+            let confirmed =
+                confirm_generation_with(&configs, 3, &mut Cursor::new(input), &mut output).unwrap();
+            (confirmed, String::from_utf8(output).unwrap())
+        };
+
+        // Enter pages through the whole summary before the prompt
+        let enters = "\n".repeat(lines.div_ceil(3) - 1);
+        let (confirmed, output) = page(&format!("{enters}y\n"));
+        assert!(confirmed);
+        assert!(output.contains("Customer Service   1 VLANs"));
+        assert_eq!(output.matches("-- more").count(), lines.div_ceil(3) - 1);
+
+        // "q" skips the remaining pages
+        let (confirmed, output) = page("q\ny\n");
+        assert!(confirmed);
+        assert_eq!(output.matches("-- more").count(), 1);
+        assert!(!output.contains("Departments"));
+    }
+}
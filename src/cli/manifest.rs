@@ -0,0 +1,218 @@
+//! Reproducibility manifests written alongside generated output
+//!
+//! A manifest records how a set of files was generated (seed, count, format
+//! and tool version) together with a SHA-256 digest of each output file, so
+//! CI can regenerate fixtures and check whether the output still matches.
+
+use crate::Result;
+use crate::model::ConfigError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Description of a generation run and the files it produced
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Manifest {
+    /// Version of the tool that produced the output (`crate::VERSION`)
+    pub tool_version: String,
+    /// Output format name (`csv`, `xml`, ...)
+    pub format: String,
+    /// Random seed used for generation, if one was given
+    pub seed: Option<u64>,
+    /// Number of VLAN configurations generated
+    pub count: usize,
+    /// Generation time in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Digests of the generated output files
+    pub outputs: Vec<OutputDigest>,
+}
+
+/// SHA-256 digest of a single output file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OutputDigest {
+    /// Path of the output file as given on the command line
+    pub path: PathBuf,
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+}
+
+impl Manifest {
+    /// Build a manifest for the given output files, hashing each of them
+    pub fn new<P: AsRef<Path>>(
+        format: &str,
+        seed: Option<u64>,
+        count: usize,
+        outputs: &[P],
+    ) -> Result<Self> {
+        let outputs = outputs
+            .iter()
+            .map(|path| {
+                Ok(OutputDigest {
+                    path: path.as_ref().to_path_buf(),
+                    sha256: sha256_file(path)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            tool_version: crate::VERSION.to_string(),
+            format: format.to_string(),
+            seed,
+            count,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            outputs,
+        })
+    }
+
+    /// Digest recorded for a file, matched by path or else by file name
+    pub fn digest_for(&self, path: &Path) -> Option<&OutputDigest> {
+        self.outputs
+            .iter()
+            .find(|digest| digest.path == path)
+            .or_else(|| {
+                let name = path.file_name()?;
+                self.outputs
+                    .iter()
+                    .find(|digest| digest.path.file_name() == Some(name))
+            })
+    }
+
+    /// Re-hash files and compare them with the recorded digests
+    ///
+    /// With no files given, every output is checked at its recorded path.
+    /// A file that has no entry in the manifest is an error.
+    pub fn verify<P: AsRef<Path>>(&self, files: &[P]) -> Result<Vec<DigestCheck>> {
+        let check = |path: &Path, digest: &OutputDigest| {
+            Ok(DigestCheck {
+                path: path.to_path_buf(),
+                expected: digest.sha256.clone(),
+                actual: sha256_file(path)?,
+            })
+        };
+
+        if files.is_empty() {
+            return self
+                .outputs
+                .iter()
+                .map(|digest| check(&digest.path, digest))
+                .collect();
+        }
+
+        files
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                let digest = self.digest_for(path).ok_or_else(|| {
+                    ConfigError::validation(format!(
+                        "{} is not listed in the manifest",
+                        path.display()
+                    ))
+                })?;
+                check(path, digest)
+            })
+            .collect()
+    }
+}
+
+/// Recorded and recomputed digest of one output file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestCheck {
+    /// File that was hashed
+    pub path: PathBuf,
+    /// Digest recorded in the manifest
+    pub expected: String,
+    /// Digest of the file as it is now
+    pub actual: String,
+}
+
+impl DigestCheck {
+    /// Whether the file still matches the manifest
+    pub fn matches(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+/// Compute the lowercase hex SHA-256 digest of a file
+pub fn sha256_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Write a manifest as pretty-printed JSON
+pub fn write_manifest<P: AsRef<Path>>(manifest: &Manifest, path: P) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, manifest)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a manifest written by [`write_manifest`]
+pub fn read_manifest<P: AsRef<Path>>(path: P) -> Result<Manifest> {
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_sha256_file() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "abc").unwrap();
+        assert_eq!(
+            sha256_file(file.path()).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_write_manifest_round_trip() {
+        let output = NamedTempFile::new().unwrap();
+        std::fs::write(output.path(), "vlan_id\n100\n").unwrap();
+        let manifest = Manifest::new("csv", Some(42), 1, &[output.path()]).unwrap();
+        assert_eq!(manifest.tool_version, crate::VERSION);
+        assert_eq!(manifest.outputs[0].sha256.len(), 64);
+
+        let manifest_file = NamedTempFile::new().unwrap();
+        write_manifest(&manifest, manifest_file.path()).unwrap();
+        let read: Manifest =
+            serde_json::from_str(&std::fs::read_to_string(manifest_file.path()).unwrap()).unwrap();
+        assert_eq!(read, manifest);
+    }
+
+    #[test]
+    fn test_verify_detects_modified_output() {
+        let output = NamedTempFile::new().unwrap();
+        std::fs::write(output.path(), "vlan_id\n100\n").unwrap();
+        let manifest = Manifest::new("csv", Some(42), 1, &[output.path()]).unwrap();
+        let no_files: &[&Path] = &[];
+        assert!(manifest.verify(no_files).unwrap()[0].matches());
+        assert!(manifest.verify(&[output.path()]).unwrap()[0].matches());
+
+        std::fs::write(output.path(), "vlan_id\n101\n").unwrap();
+        assert!(!manifest.verify(&[output.path()]).unwrap()[0].matches());
+        assert!(manifest.verify(&[Path::new("unlisted.csv")]).is_err());
+    }
+}
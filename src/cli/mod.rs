@@ -1,15 +1,43 @@
 //! Command-line interface for OPNsense Config Faker
 
+use crate::generator::firewall::{DenyAction, FirewallPolicy, FirewallProfile};
+use crate::generator::p2p::MAX_P2P_LINKS;
+use crate::generator::vlan::{
+    DEFAULT_MAX_DESCRIPTION_LEN, DescriptionTemplate, DhcpRangePolicy, SortKey,
+};
+use crate::io::csv::{CsvSchema, InputEncoding};
+use crate::model::ConfigError;
+use crate::utils::mac::MacVendor;
+use crate::utils::rfc1918::Rfc1918Class;
+use crate::xml::{SchemaVersion, XmlNameTemplate};
 use clap::{Parser, Subcommand, ValueEnum};
+use ipnetwork::Ipv4Network;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
 use std::path::PathBuf;
 
 pub mod commands;
 pub mod error;
+pub mod interactive;
+pub mod manifest;
+pub mod render;
 
 /// Maximum number of unique VLAN IDs that can be generated
 /// VLAN IDs range from 10-4094, giving us 4085 unique values
 pub const MAX_UNIQUE_VLAN_IDS: u16 = 4085;
 
+/// Number of VLAN configurations generated when `--count` is not given
+pub const DEFAULT_VLAN_COUNT: u16 = 10;
+
+/// Firewall rule complexity used when `--firewall-rule-complexity` is not given
+pub const DEFAULT_FIREWALL_COMPLEXITY: &str = "intermediate";
+
+/// Environment variable supplying `--count` when it is not given
+pub const COUNT_ENV: &str = "OPNSENSE_FAKER_COUNT";
+
+/// Environment variable supplying `--seed` when it is not given
+pub const SEED_ENV: &str = "OPNSENSE_FAKER_SEED";
+
 /// OPNsense Config Faker - Generate realistic network configuration test data
 #[derive(Parser)]
 #[command(name = "opnsense-config-faker")]
@@ -28,15 +56,36 @@
   Generate XML from existing CSV:
     opnsense-config-faker generate --format xml --base-config config.xml --csv-file data.csv
 
+  Generate YAML configuration data:
+    opnsense-config-faker generate --count 25 --format yaml --output vlans.yaml
+
+  Generate XML from existing YAML:
+    opnsense-config-faker generate --format xml --base-config config.xml --yaml-file vlans.yaml
+
+  Generate TOML configuration data:
+    opnsense-config-faker generate --count 25 --format toml --output vlans.toml
+
+  Generate JSON configuration data:
+    opnsense-config-faker generate --count 25 --format json --output vlans.json
+
+  Write the same data set as CSV, JSON and XML:
+    opnsense-config-faker generate --count 25 --seed 42 --emit csv,json,xml --base-config config.xml --output fixtures.csv
+
   Generate configurations with firewall rules:
     opnsense-config-faker generate --count 25 --format csv --output config.csv --include-firewall-rules
 
   Generate advanced firewall rules:
     opnsense-config-faker generate --count 10 --format xml --base-config config.xml --include-firewall-rules --firewall-rule-complexity advanced
 
+  Stream a large CSV without buffering configurations in memory:
+    opnsense-config-faker generate --count 10000 --format csv --output large.csv --stream
+
   Generate from VLAN ranges:
     opnsense-config-faker generate --format csv --vlan-range "100-150,200-250" --output vlans.csv
 
+  Draw a fixed number of VLAN IDs from ranges:
+    opnsense-config-faker generate --format csv --vlan-range "100-199" --count 20 --output vlans.csv
+
   Generate with VPN configurations:
     opnsense-config-faker generate --count 10 --vpn-count 3 --format csv --output configs.csv
 
@@ -49,6 +98,27 @@
   Generate comprehensive configuration:
     opnsense-config-faker generate --vlan-range "100-120" --vpn-count 2 --nat-mappings 3 --wan-assignments multi --format csv --output complete.csv
 
+  Generate an exact number of VLANs per department:
+    opnsense-config-faker generate --per-department "IT=5,Sales=10,Finance=3" --format csv --output departments.csv
+
+  Use a custom DHCP pool for every VLAN:
+    opnsense-config-faker generate --count 10 --format xml --base-config config.xml --dhcp-range 50-250
+
+  Append more VLANs to an existing CSV without reusing IDs:
+    opnsense-config-faker generate --count 5 --format csv --output vlans.csv --append
+
+  Record how the output was generated for reproducible fixtures:
+    opnsense-config-faker generate --count 10 --seed 42 --format csv --output vlans.csv --manifest vlans.manifest.json
+
+  Reproduce the same data per environment with a named seed:
+    opnsense-config-faker generate --count 10 --seed-label staging --format csv --output staging.csv
+
+  Supply defaults for --seed, --count and --format from the environment:
+    OPNSENSE_FAKER_SEED=42 OPNSENSE_FAKER_COUNT=10 opnsense-config-faker generate --output vlans.csv
+
+  Check what would be generated without writing anything:
+    opnsense-config-faker generate --count 100 --format yaml --output vlans.yaml --dry-run
+
   Force overwrite existing files:
     opnsense-config-faker generate --count 10 --format csv --output test.csv --force
 
@@ -58,6 +128,7 @@
   Validate configuration data:
     opnsense-config-faker validate --input data.csv
     opnsense-config-faker validate --input config.xml --format xml
+    opnsense-config-faker validate --input site-a.csv --against site-b.csv
 
   Use global flags:
     opnsense-config-faker --quiet generate --count 10 --format csv
@@ -73,8 +144,8 @@ pub struct Cli {
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Generate network configuration data in CSV or XML format
-    Generate(GenerateArgs),
+    /// Generate network configuration data in CSV, XML, YAML, JSON or TOML format
+    Generate(Box<GenerateArgs>),
     /// Generate shell completions for the specified shell
     Completions {
         /// Shell to generate completions for
@@ -83,6 +154,10 @@ pub enum Commands {
     },
     /// Validate configuration data for consistency and correctness
     Validate(ValidateArgs),
+    /// Compare two CSV datasets and report added, removed and changed VLANs
+    Diff(DiffArgs),
+    /// Check generated files against the SHA-256 digests in a manifest
+    Verify(VerifyArgs),
     /// DEPRECATED: Use 'generate --format csv' instead
     #[command(hide = true)]
     Csv(CsvArgs),
@@ -94,7 +169,9 @@ pub enum Commands {
 /// Global flags available for all subcommands
 #[derive(Parser, Default)]
 pub struct GlobalArgs {
-    /// Suppress non-essential output (progress bars, summaries, etc.)
+    /// Suppress all non-error output (progress bars, summaries, info messages)
+    ///
+    /// Errors are still written to stderr and reflected in the exit code.
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
@@ -102,9 +179,40 @@ pub struct GlobalArgs {
     #[arg(long, global = true)]
     pub no_color: bool,
 
+    /// Log generator decisions to stderr: -v info, -vv debug, -vvv trace
+    ///
+    /// RUST_LOG overrides the level when set. Validation also prints more
+    /// detail with any verbosity.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
     /// Global output file or directory (overrides command-specific output)
     #[arg(short, long, global = true)]
     pub output: Option<PathBuf>,
+
+    /// How errors are reported on stderr when a command fails
+    ///
+    /// json implies --quiet and holds back per-item errors, so stderr carries
+    /// only the report; -v logging is still written there.
+    #[arg(long, value_enum, global = true, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+}
+
+impl GlobalArgs {
+    /// Whether stderr is kept for the `--error-format json` report alone
+    pub fn json_errors(&self) -> bool {
+        self.error_format == ErrorFormat::Json
+    }
+}
+
+/// Format of the error report written when a command fails
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Message followed by its chain of causes
+    #[default]
+    Text,
+    /// One JSON object with the error, its kind and the context around it
+    Json,
 }
 
 /// Output format for generated configurations
@@ -114,6 +222,28 @@ pub enum OutputFormat {
     Csv,
     /// Generate complete OPNsense XML configuration
     Xml,
+    /// Generate YAML file with VLAN configuration data
+    Yaml,
+    /// Generate TOML file with VLAN configuration data
+    Toml,
+    /// Generate JSON file with VLAN configuration data
+    Json,
+    /// Generate JSON Lines file with one VLAN configuration per line
+    Jsonl,
+}
+
+impl OutputFormat {
+    /// File extension used for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Xml => "xml",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Toml => "toml",
+            OutputFormat::Json => "json",
+            OutputFormat::Jsonl => "jsonl",
+        }
+    }
 }
 
 /// WAN assignment strategy for VLAN distribution
@@ -127,6 +257,213 @@ pub enum WanAssignmentStrategy {
     Balanced,
 }
 
+/// Named bundle of generation defaults for common scenarios
+///
+/// | Preset         | Networks | Firewall complexity | VLANs per department                              |
+/// |----------------|----------|---------------------|---------------------------------------------------|
+/// | `small-office` | Class C  | basic               | IT 2, Sales 2, Finance 1, HR 1 (6 total)          |
+/// | `enterprise`   | Class A  | advanced            | 11 departments, 190 total                         |
+/// | `lab`          | Class B  | intermediate        | Development 10, QA 10, Research 5, Training 5 (30) |
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+    /// A handful of Class C VLANs with basic firewall rules
+    SmallOffice,
+    /// Hundreds of Class A VLANs across many departments with advanced rules
+    Enterprise,
+    /// Class B VLANs for development and test teams
+    Lab,
+}
+
+impl Preset {
+    /// RFC 1918 class the preset draws networks from
+    pub fn network_class(self) -> NetworkClassArg {
+        match self {
+            Preset::SmallOffice => NetworkClassArg::ClassC,
+            Preset::Enterprise => NetworkClassArg::ClassA,
+            Preset::Lab => NetworkClassArg::ClassB,
+        }
+    }
+
+    /// Firewall rule complexity level the preset uses
+    pub fn firewall_complexity(self) -> &'static str {
+        match self {
+            Preset::SmallOffice => "basic",
+            Preset::Enterprise => "advanced",
+            Preset::Lab => "intermediate",
+        }
+    }
+
+    /// Number of VLANs per department
+    pub fn department_counts(self) -> HashMap<String, u16> {
+        let counts: &[(&str, u16)] = match self {
+            Preset::SmallOffice => &[("IT", 2), ("Sales", 2), ("Finance", 1), ("HR", 1)],
+            Preset::Enterprise => &[
+                ("Engineering", 40),
+                ("Sales", 30),
+                ("IT", 20),
+                ("Operations", 20),
+                ("Support", 20),
+                ("Finance", 15),
+                ("Marketing", 15),
+                ("HR", 10),
+                ("Security", 10),
+                ("Legal", 5),
+                ("Management", 5),
+            ],
+            Preset::Lab => &[
+                ("Development", 10),
+                ("QA", 10),
+                ("Research", 5),
+                ("Training", 5),
+            ],
+        };
+        counts
+            .iter()
+            .map(|(department, count)| (department.to_string(), *count))
+            .collect()
+    }
+}
+
+/// RFC 1918 address block to draw generated networks from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum NetworkClassArg {
+    /// 10.0.0.0/8
+    ClassA,
+    /// 172.16.0.0/12
+    ClassB,
+    /// 192.168.0.0/16
+    ClassC,
+    /// Weighted mix favouring Class A, then Class B, then Class C
+    Mixed,
+}
+
+impl From<NetworkClassArg> for Rfc1918Class {
+    fn from(class: NetworkClassArg) -> Self {
+        match class {
+            NetworkClassArg::ClassA => Rfc1918Class::ClassA,
+            NetworkClassArg::ClassB => Rfc1918Class::ClassB,
+            NetworkClassArg::ClassC => Rfc1918Class::ClassC,
+            NetworkClassArg::Mixed => Rfc1918Class::Mixed,
+        }
+    }
+}
+
+impl From<Rfc1918Class> for NetworkClassArg {
+    fn from(class: Rfc1918Class) -> Self {
+        match class {
+            Rfc1918Class::ClassA => NetworkClassArg::ClassA,
+            Rfc1918Class::ClassB => NetworkClassArg::ClassB,
+            Rfc1918Class::ClassC => NetworkClassArg::ClassC,
+            Rfc1918Class::Mixed => NetworkClassArg::Mixed,
+        }
+    }
+}
+
+/// Action taken by rules that deny traffic
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DenyActionArg {
+    /// Silently drop the packet
+    #[default]
+    Block,
+    /// Drop the packet and answer with a TCP RST or ICMP unreachable
+    Reject,
+}
+
+impl From<DenyActionArg> for DenyAction {
+    fn from(action: DenyActionArg) -> Self {
+        match action {
+            DenyActionArg::Block => DenyAction::Block,
+            DenyActionArg::Reject => DenyAction::Reject,
+        }
+    }
+}
+
+/// Hardware vendor whose registered OUIs can prefix generated MACs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum MacVendorArg {
+    /// Cisco Systems
+    Cisco,
+    /// Dell
+    Dell,
+    /// Apple
+    Apple,
+    /// Hewlett Packard
+    Hp,
+    /// Intel
+    Intel,
+    /// VMware virtual NICs
+    Vmware,
+}
+
+impl From<MacVendorArg> for MacVendor {
+    fn from(vendor: MacVendorArg) -> Self {
+        match vendor {
+            MacVendorArg::Cisco => MacVendor::Cisco,
+            MacVendorArg::Dell => MacVendor::Dell,
+            MacVendorArg::Apple => MacVendor::Apple,
+            MacVendorArg::Hp => MacVendor::Hp,
+            MacVendorArg::Intel => MacVendor::Intel,
+            MacVendorArg::Vmware => MacVendor::Vmware,
+        }
+    }
+}
+
+/// Column header set used for VLAN CSV files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum CsvSchemaArg {
+    /// `VLAN,IP Range,Beschreibung,WAN`, as written by the Python original
+    #[default]
+    Legacy,
+    /// `VLAN,IP Range,Description,WAN`
+    English,
+}
+
+impl From<CsvSchemaArg> for CsvSchema {
+    fn from(schema: CsvSchemaArg) -> Self {
+        match schema {
+            CsvSchemaArg::Legacy => CsvSchema::Legacy,
+            CsvSchemaArg::English => CsvSchema::English,
+        }
+    }
+}
+
+/// Character encoding of a VLAN CSV file being read
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputEncodingArg {
+    /// UTF-8; anything else is rejected with the offending byte offset
+    #[default]
+    Utf8,
+    /// ISO-8859-1, as exported by legacy spreadsheet tooling
+    Latin1,
+}
+
+impl From<InputEncodingArg> for InputEncoding {
+    fn from(encoding: InputEncodingArg) -> Self {
+        match encoding {
+            InputEncodingArg::Utf8 => InputEncoding::Utf8,
+            InputEncodingArg::Latin1 => InputEncoding::Latin1,
+        }
+    }
+}
+
+/// Field VLAN configurations are ordered by before being written
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SortKeyArg {
+    /// Ascending VLAN ID
+    VlanId,
+    /// Ascending network address, then VLAN ID
+    Network,
+}
+
+impl From<SortKeyArg> for SortKey {
+    fn from(key: SortKeyArg) -> Self {
+        match key {
+            SortKeyArg::VlanId => SortKey::VlanId,
+            SortKeyArg::Network => SortKey::Network,
+        }
+    }
+}
+
 /// Shell types for completion generation
 #[derive(Clone, Debug, ValueEnum)]
 pub enum Shell {
@@ -139,21 +476,44 @@ pub enum Shell {
 
 /// Arguments for the generate command
 #[derive(Parser)]
+#[command(group(clap::ArgGroup::new("schema_xml").args(["monolithic", "emit"]).multiple(true)))]
 pub struct GenerateArgs {
-    /// Output format (csv or xml)
-    #[arg(short = 'f', long = "format")]
-    #[arg(value_enum)]
+    /// Output format (csv, xml, yaml, json or toml)
+    #[arg(short = 'f', long = "format", required_unless_present_any = ["emit", "networks_only"])]
+    #[arg(value_enum, default_value = "csv", hide_default_value = true)]
+    #[arg(env = "OPNSENSE_FAKER_FORMAT")]
     pub format: OutputFormat,
 
-    /// Number of VLAN configurations to generate
+    /// Number of VLAN configurations to generate [default: 10]
     ///
     /// Note: For unique VLAN generation (XML format), maximum is 4085 due to
     /// VLAN ID range constraints (10-4094). CSV format may allow duplicates.
-    #[arg(short, long, default_value_t = 10)]
+    /// Combined with --vlan-range, IDs are drawn from the given ranges only.
+    /// Falls back to OPNSENSE_FAKER_COUNT unless an option it conflicts with
+    /// is given.
+    #[arg(short, long)]
     #[arg(value_parser = clap::value_parser!(u16).range(1..=10000))]
-    pub count: u16,
+    pub count: Option<u16>,
+
+    /// Start from a named scenario: small-office (6 Class C VLANs, basic
+    /// rules), enterprise (190 Class A VLANs, advanced rules) or lab (30
+    /// Class B VLANs, intermediate rules)
+    ///
+    /// The preset sets the network class, firewall rule complexity and
+    /// per-department VLAN counts. Any of those given explicitly, or options
+    /// they conflict with (such as --count or --supernet), win over the preset.
+    #[arg(long, value_enum)]
+    pub preset: Option<Preset>,
 
-    /// Output file path (for CSV format) or directory (for XML format)
+    /// Exact number of VLANs per department (e.g., "IT=5,Sales=10,Finance=3")
+    ///
+    /// The total number of VLANs is the sum of the counts.
+    #[arg(long, value_parser = parse_department_counts)]
+    #[arg(conflicts_with_all = ["count", "vlan_range", "csv_file", "yaml_file", "toml_file", "stream", "wan_assignments"])]
+    pub per_department: Option<HashMap<String, u16>>,
+
+    /// Output file path (for CSV/YAML/JSON/TOML format) or directory (for XML format);
+    /// CSV paths ending in .gz are gzip-compressed
     #[arg(long)]
     pub output: Option<PathBuf>,
 
@@ -169,6 +529,14 @@ pub struct GenerateArgs {
     #[arg(long, conflicts_with = "count")]
     pub csv_file: Option<PathBuf>,
 
+    /// Use existing YAML file for configuration data (XML format only)
+    #[arg(long, conflicts_with_all = ["count", "csv_file"])]
+    pub yaml_file: Option<PathBuf>,
+
+    /// Use existing TOML file for configuration data (XML format only)
+    #[arg(long, conflicts_with_all = ["count", "csv_file", "yaml_file"])]
+    pub toml_file: Option<PathBuf>,
+
     /// Firewall number for naming (used in filenames for XML format)
     #[arg(long, default_value_t = 1)]
     #[arg(value_parser = clap::value_parser!(u16).range(1..=999))]
@@ -179,24 +547,58 @@ pub struct GenerateArgs {
     #[arg(value_parser = clap::value_parser!(u16).range(1..=99))]
     pub opt_counter: u16,
 
+    /// File name template for generated XML files (XML format only), using
+    /// {firewall_nr}, {vlan_id}, {opt_counter} and {index} placeholders
+    #[arg(long, value_name = "TEMPLATE", default_value_t = XmlNameTemplate::default())]
+    pub xml_name_template: XmlNameTemplate,
+
+    /// Reindent generated XML with two spaces per level (XML format only)
+    #[arg(long)]
+    pub pretty_xml: bool,
+
     /// Force overwrite existing files
     #[arg(short = 'F', long)]
     pub force: bool,
 
-    /// Random seed for reproducible generation
+    /// Continue an interrupted XML run: count the files already written to
+    /// --output-dir and generate only the rest (needs the original --seed
+    /// and --count)
+    #[arg(long)]
+    #[arg(conflicts_with_all = [
+        "monolithic", "sort", "per_department", "vlan_range", "csv_file", "yaml_file",
+        "toml_file", "include_firewall_rules", "emit",
+    ])]
+    pub resume: bool,
+
+    /// Keep writing the remaining per-VLAN XML files when one fails, then
+    /// list the failures and exit non-zero (XML format only)
+    #[arg(long, conflicts_with = "monolithic")]
+    pub continue_on_error: bool,
+
+    /// Random seed for reproducible generation; when omitted a random seed is
+    /// chosen and printed to stderr so the run can be repeated
+    ///
+    /// Falls back to OPNSENSE_FAKER_SEED unless --seed-label is given.
     #[arg(long)]
     pub seed: Option<u64>,
 
+    /// Derive the random seed from a label (e.g., "staging" or "ci-run-7")
+    #[arg(long, conflicts_with = "seed")]
+    pub seed_label: Option<String>,
+
     /// Disable colored output (useful for scripts and CI)
     #[arg(long)]
     pub no_color: bool,
 
     /// Interactive mode - prompt for missing required arguments
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "stream")]
     pub interactive: bool,
 
     /// Include firewall rules in generated configurations
-    #[arg(long)]
+    ///
+    /// CSV, YAML and TOML output also writes the rules to
+    /// `<output>_firewall_rules.csv` next to the VLAN file.
+    #[arg(long, visible_alias = "with-firewall")]
     pub include_firewall_rules: bool,
 
     /// Number of firewall rules per VLAN (default: based on complexity level)
@@ -204,11 +606,25 @@ pub struct GenerateArgs {
     pub firewall_rules_per_vlan: Option<u16>,
 
     /// Firewall rule complexity level (basic, intermediate, advanced)
-    #[arg(long, default_value = "intermediate")]
-    pub firewall_rule_complexity: String,
+    /// [default: intermediate]
+    #[arg(long, visible_alias = "firewall-complexity")]
+    pub firewall_rule_complexity: Option<String>,
+
+    /// Action for generated deny rules: block drops silently, reject answers
+    /// with a TCP RST or ICMP unreachable
+    #[arg(long, value_enum, default_value = "block")]
+    pub deny_action: DenyActionArg,
+
+    /// JSON or TOML file of per-department rule toggles (VPN, social media,
+    /// file sharing, gaming) and application ports, replacing the built-in ones
+    #[arg(long, value_name = "FILE")]
+    pub firewall_profile: Option<PathBuf>,
 
     /// VLAN range specification (e.g., "100-150" or "10,20,30-40")
-    #[arg(long, conflicts_with = "count")]
+    ///
+    /// Without --count every ID in the ranges is generated; with --count that
+    /// many IDs are drawn at random from the ranges.
+    #[arg(long)]
     pub vlan_range: Option<String>,
 
     /// Number of VPN configurations to generate
@@ -219,19 +635,338 @@ pub struct GenerateArgs {
     #[arg(long)]
     pub nat_mappings: Option<u16>,
 
+    /// Comma-separated protocols NAT rules may use (TCP, UDP, Both, ICMP)
+    #[arg(long, value_delimiter = ',', value_name = "PROTOCOLS")]
+    pub nat_protocols: Vec<String>,
+
+    /// Comma-separated ports NAT rules may forward (e.g., "80,443")
+    #[arg(long, value_delimiter = ',', value_name = "PORTS")]
+    pub nat_ports: Vec<u16>,
+
+    /// Number of /31 point-to-point transit links to generate, written to
+    /// `<output>_p2p_links.csv` next to the VLAN file (XML format:
+    /// `firewall_<nr>_p2p_links.csv` in --output-dir)
+    #[arg(long, value_name = "N", conflicts_with_all = ["stream", "networks_only"])]
+    #[arg(value_parser = clap::value_parser!(u16).range(1..=MAX_P2P_LINKS as i64))]
+    pub p2p_links: Option<u16>,
+
     /// WAN assignment strategy for VLANs
     #[arg(long, value_enum)]
     pub wan_assignments: Option<WanAssignmentStrategy>,
+
+    /// Comma-separated CIDRs that generated networks must not overlap
+    /// (e.g., "10.1.0.0/16,10.2.5.0/24")
+    #[arg(long)]
+    pub exclude_networks: Option<String>,
+
+    /// RFC 1918 class to draw VLAN networks from (default: 10.0.0.0/8)
+    #[arg(long, value_enum)]
+    pub network_class: Option<NetworkClassArg>,
+
+    /// Draw networks from Class A, B and C in turn so every class is
+    /// represented (at least count/3 networks each)
+    #[arg(long, conflicts_with = "network_class")]
+    pub balanced_classes: bool,
+
+    /// Private supernet to draw every VLAN network from (e.g., "10.50.0.0/16");
+    /// fails if it holds fewer /24 networks than requested
+    #[arg(long, conflicts_with_all = ["network_class", "balanced_classes"])]
+    pub supernet: Option<Ipv4Network>,
+
+    /// Set the count to the number of /24 networks in --supernet that are
+    /// neither excluded nor already in the --append file, capped at the 4085
+    /// unique VLAN IDs
+    #[arg(long, requires = "supernet")]
+    #[arg(conflicts_with_all = ["count", "per_department", "vlan_range", "csv_file", "yaml_file", "toml_file"])]
+    pub fill_supernet: bool,
+
+    /// File of predetermined subnets, one CIDR per line (e.g. an IPAM
+    /// export), assigned to the VLANs in order instead of random networks;
+    /// fails if it lists fewer networks than requested
+    #[arg(long, conflicts_with_all = ["network_class", "balanced_classes", "supernet"])]
+    pub networks_file: Option<PathBuf>,
+
+    /// Assign the --networks-file subnets in random order
+    #[arg(long, requires = "networks_file")]
+    pub shuffle_networks: bool,
+
+    /// Random draws per unique VLAN ID or network before giving up; raise
+    /// for dense constraints such as narrow network classes or large exclusions
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub max_attempts: Option<usize>,
+
+    /// File of newline-separated department names used for VLAN descriptions
+    /// instead of the built-in list
+    #[arg(long)]
+    pub departments_file: Option<PathBuf>,
+
+    /// Format of generated VLAN descriptions, using {dept} and {id}
+    /// placeholders (e.g., "NYC {dept} VLAN {id}" or "{dept}-net-{id}")
+    #[arg(long, value_name = "TEMPLATE", default_value_t = DescriptionTemplate::default())]
+    pub description_template: DescriptionTemplate,
+
+    /// Comma-separated hardware vendors whose OUIs prefix static DHCP
+    /// reservation MACs (default: locally administered MACs)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "VENDORS")]
+    pub mac_vendors: Option<Vec<MacVendorArg>>,
+
+    /// Comma-separated DNS servers handed out by DHCP after the gateway
+    /// (default: 8.8.8.8,1.1.1.1)
+    #[arg(long, value_delimiter = ',', value_name = "ADDRESSES")]
+    pub dhcp_dns: Option<Vec<Ipv4Addr>>,
+
+    /// Do not hand out the VLAN gateway as the primary DHCP DNS server
+    #[arg(long)]
+    pub no_gateway_dns: bool,
+
+    /// DHCP pool as host offsets within each VLAN subnet (e.g., "50-250");
+    /// defaults to .100-.200
+    #[arg(long, value_name = "START-END")]
+    pub dhcp_range: Option<DhcpRangePolicy>,
+
+    /// CSV column headers: legacy (German "Beschreibung") or english
+    /// ("Description"); reading accepts either
+    #[arg(long, value_enum, default_value = "legacy")]
+    pub csv_schema: CsvSchemaArg,
+
+    /// Field delimiter for CSV output, and for CSV files read back with
+    /// --csv-file or --append (e.g. ";" for European Excel)
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_csv_delimiter)]
+    pub csv_delimiter: u8,
+
+    /// Character encoding of the file given with --csv-file
+    #[arg(long, value_enum, default_value_t = InputEncodingArg::Utf8, requires = "csv_file")]
+    pub input_encoding: InputEncodingArg,
+
+    /// Start CSV output with `#` comment lines naming the tool version, seed
+    /// and count; CSV files read back skip them (CSV format only)
+    #[arg(long, conflicts_with = "append")]
+    pub csv_header_comment: bool,
+
+    /// Stream configurations straight to the output file instead of
+    /// collecting them in memory first (CSV and JSONL formats only)
+    #[arg(long, conflicts_with_all = ["include_firewall_rules", "vpn_count", "nat_mappings"])]
+    pub stream: bool,
+
+    /// Append to an existing CSV output file, skipping VLAN IDs and networks
+    /// it already contains (CSV format only)
+    #[arg(long, conflicts_with = "stream")]
+    pub append: bool,
+
+    /// Abort once the output files of this run would exceed this many bytes
+    /// in total, removing the file being written
+    #[arg(long, value_name = "BYTES", conflicts_with = "append")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_output_bytes: Option<u64>,
+
+    /// Print a table of the generated VLAN configurations to stderr before
+    /// writing output
+    #[arg(long, conflicts_with = "stream")]
+    pub preview: bool,
+
+    /// Add a histogram of VLANs per department to the generation summary
+    #[arg(long)]
+    pub stats: bool,
+
+    /// Generate at most this many VLAN configurations per second so progress
+    /// bars visibly animate in demos; ignored with --quiet
+    #[arg(long, value_name = "PER_SECOND")]
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    pub rate: Option<u32>,
+
+    /// Validate arguments and generate in memory, then report the format,
+    /// count, target path and estimated size without writing any file
+    #[arg(long, conflicts_with = "stream")]
+    pub dry_run: bool,
+
+    /// Re-validate the generated configurations (unique VLAN IDs and
+    /// networks, no overlaps) and fail with a report if any check fails
+    #[arg(long, conflicts_with = "stream")]
+    pub validate_after: bool,
+
+    /// Longest VLAN description, in characters, that generated and
+    /// re-validated configurations may have
+    #[arg(long, value_name = "CHARS", default_value_t = DEFAULT_MAX_DESCRIPTION_LEN)]
+    #[arg(value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub max_description_len: usize,
+
+    /// Generate once and write each listed format to `<output-stem>.<ext>`
+    /// (e.g. "csv,json,xml"); XML needs --base-config with a <vlans> section
+    #[arg(long, value_enum, value_delimiter = ',')]
+    #[arg(conflicts_with_all = ["stream", "append", "csv_file", "yaml_file", "toml_file"])]
+    pub emit: Option<Vec<OutputFormat>>,
+
+    /// Write one merged XML file (VLANs, DHCP, NAT port-forwards, aliases and,
+    /// with --include-firewall-rules, filter rules) instead of one file per
+    /// VLAN (XML format only); the base config needs <vlans>, <nat>, <filter>
+    /// and <OPNsense><Firewall><Alias> sections
+    #[arg(long, conflicts_with_all = ["stream", "emit", "dry_run"])]
+    pub monolithic: bool,
+
+    /// Comma-separated physical NICs the VLANs are tagged on round-robin in
+    /// --monolithic output (default: em0,em1,em2,em3,igb0,igb1)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "NICS",
+        requires = "monolithic"
+    )]
+    pub parent_interfaces: Option<Vec<String>>,
+
+    /// OPNsense release whose XML element names are emitted, e.g. 24.1
+    /// (supported: 23.7, 24.1, 24.7); only --monolithic and --emit build
+    /// their XML from these elements, so it requires one of them
+    #[arg(long, value_name = "VERSION", default_value_t = SchemaVersion::latest())]
+    #[arg(requires = "schema_xml")]
+    pub opnsense_version: SchemaVersion,
+
+    /// Sort configurations before writing so the same set always produces
+    /// the same file
+    #[arg(long, value_enum, conflicts_with_all = ["stream", "networks_only"])]
+    pub sort: Option<SortKeyArg>,
+
+    /// Shuffle configurations before writing, in an order fixed by --seed,
+    /// to exercise consumers that must not assume any ordering
+    #[arg(long, conflicts_with_all = ["sort", "stream", "networks_only", "resume"])]
+    pub shuffle: bool,
+
+    /// Print only the generated network CIDRs, one per line, to --output or
+    /// stdout; honours the network class, supernet and exclusion options
+    #[arg(long)]
+    #[arg(conflicts_with_all = [
+        "stream", "append", "emit", "monolithic", "preview", "dry_run", "interactive",
+        "csv_file", "yaml_file", "toml_file", "per_department", "include_firewall_rules",
+    ])]
+    pub networks_only: bool,
+
+    /// Let VLAN IDs and networks repeat to produce deliberately conflicting
+    /// data; lifts the unique VLAN ID limit for non-XML formats
+    #[arg(long)]
+    pub allow_duplicates: bool,
+
+    /// Write a JSON manifest recording the seed, count, format, tool version
+    /// and SHA-256 of each output file
+    #[arg(long)]
+    pub manifest: Option<PathBuf>,
 }
 
 impl GenerateArgs {
+    /// Number of VLAN configurations to generate, falling back to the default
+    pub fn vlan_count(&self) -> u16 {
+        if let Some(counts) = &self.per_department {
+            return counts.values().sum();
+        }
+        self.count.unwrap_or(DEFAULT_VLAN_COUNT)
+    }
+
+    /// Firewall rule complexity level, falling back to the default
+    pub fn firewall_complexity(&self) -> &str {
+        self.firewall_rule_complexity
+            .as_deref()
+            .unwrap_or(DEFAULT_FIREWALL_COMPLEXITY)
+    }
+
+    /// Fill in the `--preset` defaults for options not given explicitly
+    ///
+    /// Department counts are only applied when no other option chooses how
+    /// many VLANs to generate or where they come from, and the network class
+    /// only when no other option chooses the address space.
+    pub fn apply_preset(&mut self) {
+        let Some(preset) = self.preset else {
+            return;
+        };
+
+        let count_chosen = self.count.is_some()
+            || self.per_department.is_some()
+            || self.vlan_range.is_some()
+            || self.csv_file.is_some()
+            || self.yaml_file.is_some()
+            || self.toml_file.is_some()
+            || self.stream
+            || self.wan_assignments.is_some()
+            || self.fill_supernet
+            || self.resume
+            || self.networks_only;
+        if !count_chosen {
+            self.per_department = Some(preset.department_counts());
+        }
+
+        let space_chosen = self.network_class.is_some()
+            || self.balanced_classes
+            || self.supernet.is_some()
+            || self.networks_file.is_some();
+        if !space_chosen {
+            self.network_class = Some(preset.network_class());
+        }
+
+        if self.firewall_rule_complexity.is_none() {
+            self.firewall_rule_complexity = Some(preset.firewall_complexity().to_string());
+        }
+    }
+
+    /// Fill `--count` and `--seed` from their environment variables
+    ///
+    /// Read after parsing rather than through clap's `env`, which treats an
+    /// environment value as a given flag and so rejects every option that
+    /// conflicts with it. A variable is ignored when the flag, or an option
+    /// it conflicts with, is on the command line.
+    pub fn apply_env_defaults(&mut self) -> crate::Result<()> {
+        let count_chosen = self.count.is_some()
+            || self.per_department.is_some()
+            || self.csv_file.is_some()
+            || self.yaml_file.is_some()
+            || self.toml_file.is_some()
+            || self.fill_supernet;
+        if !count_chosen {
+            if let Some(value) = env_var(COUNT_ENV)? {
+                let count = value
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|count| (1..=10000).contains(count))
+                    .ok_or_else(|| {
+                        ConfigError::invalid_parameter(
+                            "count",
+                            format!("{COUNT_ENV}={value} is not a number in 1..=10000"),
+                        )
+                    })?;
+                self.count = Some(count);
+            }
+        }
+
+        if self.seed.is_none() && self.seed_label.is_none() {
+            if let Some(value) = env_var(SEED_ENV)? {
+                let seed = value.parse::<u64>().map_err(|_| {
+                    ConfigError::invalid_parameter(
+                        "seed",
+                        format!("{SEED_ENV}={value} is not an unsigned 64-bit number"),
+                    )
+                })?;
+                self.seed = Some(seed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Firewall policy for generated rules, loading `--firewall-profile` if given
+    pub fn firewall_policy(&self) -> crate::Result<FirewallPolicy> {
+        let profile = match &self.firewall_profile {
+            Some(path) => FirewallProfile::load(path)?,
+            None => FirewallProfile::default(),
+        };
+        Ok(FirewallPolicy {
+            deny_action: self.deny_action.into(),
+            profile,
+        })
+    }
+
     /// Validate arguments after parsing, checking for VLAN ID constraints
     pub fn validate(&self) -> Result<(), String> {
         // For XML format, we require unique VLAN IDs, so check against maximum
-        if matches!(self.format, OutputFormat::Xml) && self.count > MAX_UNIQUE_VLAN_IDS {
+        if matches!(self.format, OutputFormat::Xml) && self.vlan_count() > MAX_UNIQUE_VLAN_IDS {
             return Err(format!(
                 "Cannot generate {} unique VLAN configurations. Maximum is {} for XML format due to VLAN ID range constraints (10-4094). Consider using CSV format if duplicates are acceptable, or reduce the count.",
-                self.count, MAX_UNIQUE_VLAN_IDS
+                self.vlan_count(),
+                MAX_UNIQUE_VLAN_IDS
             ));
         }
 
@@ -250,7 +985,10 @@ fn validate_vlan_range(&self, vlan_range: &str) -> Result<(), String> {
 
         let total_vlans: u32 = ranges.iter().map(|r| (r.1 - r.0 + 1) as u32).sum();
 
-        if matches!(self.format, OutputFormat::Xml) && total_vlans > MAX_UNIQUE_VLAN_IDS as u32 {
+        if matches!(self.format, OutputFormat::Xml)
+            && self.count.is_none()
+            && total_vlans > MAX_UNIQUE_VLAN_IDS as u32
+        {
             return Err(format!(
                 "VLAN range produces {} VLANs, but maximum is {} for XML format",
                 total_vlans, MAX_UNIQUE_VLAN_IDS
@@ -367,10 +1105,6 @@ pub struct ValidateArgs {
     #[arg(value_enum)]
     pub format: ValidationFormat,
 
-    /// Detailed validation output
-    #[arg(short, long)]
-    pub verbose: bool,
-
     /// Maximum number of errors to report before stopping
     #[arg(long, default_value_t = 100)]
     pub max_errors: u32,
@@ -378,6 +1112,57 @@ pub struct ValidateArgs {
     /// Output validation report to file
     #[arg(long)]
     pub report: Option<PathBuf>,
+
+    /// Second CSV file to check for VLAN ID and network overlap with the input
+    #[arg(long)]
+    pub against: Option<PathBuf>,
+
+    /// Longest VLAN description, in characters, that passes validation
+    #[arg(long, value_name = "CHARS", default_value_t = DEFAULT_MAX_DESCRIPTION_LEN)]
+    #[arg(value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub max_description_len: usize,
+
+    /// Print the result as JSON ({"valid", "errors": [{"line", "message"}],
+    /// "count"}) instead of human-readable text (CSV input only)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Character encoding of the CSV input
+    #[arg(long, value_enum, default_value_t = InputEncodingArg::Utf8)]
+    pub input_encoding: InputEncodingArg,
+
+    /// Field delimiter of the CSV input and the --against file (e.g. ";" for
+    /// files written with `generate --csv-delimiter ";"`)
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_csv_delimiter)]
+    pub csv_delimiter: u8,
+}
+
+/// Arguments for the diff command
+#[derive(Parser)]
+pub struct DiffArgs {
+    /// First CSV file (the baseline)
+    pub a: PathBuf,
+
+    /// Second CSV file to compare against the baseline
+    pub b: PathBuf,
+
+    /// Print the differences as JSON instead of a summary
+    #[arg(long)]
+    pub json: bool,
+
+    /// Field delimiter of both CSV files (e.g. ";")
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_csv_delimiter)]
+    pub csv_delimiter: u8,
+}
+
+/// Arguments for the verify command
+#[derive(Parser)]
+pub struct VerifyArgs {
+    /// Manifest written by `generate --manifest`
+    pub manifest: PathBuf,
+
+    /// Files to check; defaults to every output listed in the manifest
+    pub files: Vec<PathBuf>,
 }
 
 /// Validation input format
@@ -391,6 +1176,18 @@ pub enum ValidationFormat {
     Xml,
 }
 
+/// Value of an environment variable, `None` when it is unset
+fn env_var(name: &str) -> crate::Result<Option<String>> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(ConfigError::invalid_parameter(
+            name,
+            "environment variable is not valid Unicode",
+        )),
+    }
+}
+
 /// Parse VLAN range specification into individual ranges
 /// Supports formats like "100-150", "10,20,30-40", "100"
 pub fn parse_vlan_range(range_str: &str) -> Result<Vec<(u16, u16)>, String> {
@@ -452,10 +1249,179 @@ pub fn parse_vlan_range(range_str: &str) -> Result<Vec<(u16, u16)>, String> {
     Ok(ranges)
 }
 
+/// Parse a comma-separated list of IPv4 CIDRs such as "10.1.0.0/16,10.2.5.0/24"
+///
+/// Host bits are cleared, so "10.1.2.3/16" is read as 10.1.0.0/16.
+pub fn parse_network_list(list: &str) -> Result<Vec<Ipv4Network>, String> {
+    let mut networks = Vec::new();
+
+    for part in list.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        if !part.contains('/') {
+            return Err(format!("Network '{}' must be in CIDR notation", part));
+        }
+
+        let network: Ipv4Network = part
+            .parse()
+            .map_err(|e| format!("Invalid network '{}': {}", part, e))?;
+        let network = Ipv4Network::new(network.network(), network.prefix())
+            .map_err(|e| format!("Invalid network '{}': {}", part, e))?;
+        networks.push(network);
+    }
+
+    if networks.is_empty() {
+        return Err("No valid networks found".to_string());
+    }
+
+    Ok(networks)
+}
+
+/// Parse per-department VLAN counts like "IT=5,Sales=10,Finance=3"
+pub fn parse_department_counts(spec: &str) -> Result<HashMap<String, u16>, String> {
+    let mut counts = HashMap::new();
+    let mut total: u32 = 0;
+
+    for part in spec.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        let (name, count) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Entry '{}' must be in DEPARTMENT=COUNT form", part))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Entry '{}' has an empty department name", part));
+        }
+        let count: u16 = count
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid count in '{}': {}", part, e))?;
+        if count == 0 {
+            return Err(format!(
+                "Count for department '{}' must be at least 1",
+                name
+            ));
+        }
+        if counts.insert(name.to_string(), count).is_some() {
+            return Err(format!("Department '{}' is listed more than once", name));
+        }
+        total += u32::from(count);
+    }
+
+    if counts.is_empty() {
+        return Err("No department counts found".to_string());
+    }
+    if total > 10000 {
+        return Err(format!(
+            "Department counts add up to {}, maximum is 10000",
+            total
+        ));
+    }
+
+    Ok(counts)
+}
+
+/// Parse a CSV field delimiter, which must be a single byte
+pub fn parse_csv_delimiter(spec: &str) -> Result<u8, String> {
+    match spec.as_bytes() {
+        [b'"' | b'\n' | b'\r'] => Err(format!("{spec:?} cannot be used as a CSV delimiter")),
+        [byte] => Ok(*byte),
+        _ => Err(format!(
+            "CSV delimiter must be a single byte such as ',' or ';', got {spec:?}"
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_csv_delimiter() {
+        assert_eq!(parse_csv_delimiter(";"), Ok(b';'));
+        assert_eq!(parse_csv_delimiter("\t"), Ok(b'\t'));
+        assert!(parse_csv_delimiter("").is_err());
+        assert!(parse_csv_delimiter(";;").is_err());
+        assert!(parse_csv_delimiter("é").is_err());
+        assert!(parse_csv_delimiter("\"").is_err());
+    }
+
+    #[test]
+    fn test_preset_fills_only_unset_options() {
+        let parse = |extra: &[&str]| {
+            let argv = ["faker", "generate", "--format", "csv"].iter().chain(extra);
+            match Cli::try_parse_from(argv).unwrap().command {
+                Commands::Generate(mut args) => {
+                    args.apply_preset();
+                    args
+                }
+                _ => unreachable!(),
+            }
+        };
+
+        let args = parse(&["--preset", "small-office"]);
+        assert_eq!(args.network_class, Some(NetworkClassArg::ClassC));
+        assert_eq!(args.firewall_complexity(), "basic");
+        assert_eq!(args.vlan_count(), 6);
+
+        let args = parse(&[
+            "--preset",
+            "enterprise",
+            "--count",
+            "12",
+            "--network-class",
+            "class-b",
+            "--firewall-rule-complexity",
+            "basic",
+        ]);
+        assert!(args.per_department.is_none());
+        assert_eq!(args.vlan_count(), 12);
+        assert_eq!(args.network_class, Some(NetworkClassArg::ClassB));
+        assert_eq!(args.firewall_complexity(), "basic");
+
+        let args = parse(&[]);
+        assert!(args.network_class.is_none());
+        assert_eq!(args.firewall_complexity(), DEFAULT_FIREWALL_COMPLEXITY);
+    }
+
+    #[test]
+    fn test_parse_department_counts() {
+        let counts = parse_department_counts("IT=5, Sales=10,Finance=3").unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["IT"], 5);
+        assert_eq!(counts["Sales"], 10);
+        assert_eq!(counts["Finance"], 3);
+
+        assert!(parse_department_counts("IT").is_err());
+        assert!(parse_department_counts("IT=0").is_err());
+        assert!(parse_department_counts("IT=x").is_err());
+        assert!(parse_department_counts("IT=1,IT=2").is_err());
+        assert!(parse_department_counts("=3").is_err());
+        assert!(parse_department_counts("IT=6000,Sales=6000").is_err());
+        assert!(parse_department_counts(" , ").is_err());
+    }
+
+    #[test]
+    fn test_parse_network_list() {
+        let networks = parse_network_list("10.1.0.0/16, 10.2.5.7/24").unwrap();
+        assert_eq!(
+            networks,
+            [
+                "10.1.0.0/16".parse::<Ipv4Network>().unwrap(),
+                "10.2.5.0/24".parse::<Ipv4Network>().unwrap(),
+            ]
+        );
+
+        assert!(parse_network_list("10.1.0.0").is_err());
+        assert!(parse_network_list("10.1.0.0/33").is_err());
+        assert!(parse_network_list(" , ").is_err());
+    }
+
     #[test]
     fn test_parse_vlan_range() {
         // Test single VLAN
@@ -0,0 +1,238 @@
+//! Terminal rendering helpers for generated configurations
+
+use crate::generator::VlanConfig;
+use console::{Alignment, Style, Term, measure_text_width, pad_str, style};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Length of the longest histogram bar, in characters
+const HISTOGRAM_WIDTH: usize = 30;
+
+/// Column headers for the VLAN preview table
+const HEADERS: [&str; 5] = ["VLAN", "Network", "Prefix", "WAN", "Description"];
+
+/// Render VLAN configurations as a bordered, column-aligned table
+///
+/// Headers are bolded when colors are enabled for stderr; `console` disables
+/// styling automatically when `NO_COLOR` is set or stderr is not a terminal.
+pub fn render_table(configs: &[VlanConfig]) -> String {
+    let rows: Vec<[String; 5]> = configs
+        .iter()
+        .map(|config| {
+            [
+                config.vlan_id.to_string(),
+                config.ip_network.clone(),
+                format!("/{}", config.prefix_len),
+                format!("WAN{}", config.wan_assignment),
+                config.description.clone(),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADERS.map(measure_text_width);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(measure_text_width(cell));
+        }
+    }
+
+    let border = widths
+        .iter()
+        .map(|width| "-".repeat(width + 2))
+        .collect::<Vec<_>>()
+        .join("+");
+    let border = format!("+{border}+");
+    let header_style = Style::new().bold().for_stderr();
+
+    let mut table = String::new();
+    table.push_str(&border);
+    table.push('\n');
+    push_row(&mut table, &HEADERS, &widths, Some(&header_style));
+    table.push_str(&border);
+    table.push('\n');
+    for row in &rows {
+        push_row(&mut table, row, &widths, None);
+    }
+    table.push_str(&border);
+    table.push('\n');
+    table
+}
+
+/// Print VLAN configurations as a table to stderr
+///
+/// Stderr keeps the preview separate from any data written to stdout.
+pub fn print_table(configs: &[VlanConfig]) {
+    let table = render_table(configs);
+    // A closed stderr leaves nothing useful to report the failure to
+    let _ = Term::stderr().write_str(&table);
+}
+
+/// Render the `--dry-run` report of what would have been written
+///
+/// `estimated_bytes` is the size of the serialized output; for formats that
+/// write several files it is the total across all of them.
+pub fn render_dry_run_summary(
+    format: &str,
+    count: usize,
+    target: &Path,
+    estimated_bytes: u64,
+) -> String {
+    format!(
+        "{}\n  📄 Format: {format}\n  📊 Configurations: {count}\n  📁 Target: {}\n  📏 Estimated size: {} ({estimated_bytes} bytes)\n",
+        style("Dry run: no files written").bold(),
+        target.display(),
+        format_size(estimated_bytes),
+    )
+}
+
+/// Render counts as an indented text histogram, largest first
+///
+/// Each line holds the label, its count and a bar scaled to the largest
+/// count; every non-zero count gets at least one block.
+pub fn render_histogram(counts: &BTreeMap<String, usize>) -> String {
+    let mut entries: Vec<(&String, &usize)> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    let max = entries.first().map_or(0, |(_, count)| **count);
+    let label_width = entries
+        .iter()
+        .map(|(label, _)| measure_text_width(label))
+        .max()
+        .unwrap_or(0);
+    let count_width = max.to_string().len();
+
+    let mut output = String::new();
+    for (label, count) in entries {
+        let bar = (count * HISTOGRAM_WIDTH).div_ceil(max.max(1));
+        output.push_str(&format!(
+            "    {} {count:>count_width$} {}\n",
+            pad_str(label, label_width, Alignment::Left, None),
+            "█".repeat(bar)
+        ));
+    }
+    output
+}
+
+/// Format a byte count with a binary unit, e.g. "1.5 KiB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+fn push_row<S: AsRef<str>>(
+    table: &mut String,
+    cells: &[S],
+    widths: &[usize],
+    style: Option<&Style>,
+) {
+    table.push('|');
+    for (cell, width) in cells.iter().zip(widths) {
+        let padded = pad_str(cell.as_ref(), *width, Alignment::Left, None);
+        match style {
+            Some(style) => table.push_str(&format!(" {} |", style.apply_to(padded))),
+            None => table.push_str(&format!(" {padded} |")),
+        }
+    }
+    table.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::strip_ansi_codes;
+
+    fn sample_configs() -> Vec<VlanConfig> {
+        vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(
+                2048,
+                "172.16.40.x".to_string(),
+                "Engineering VLAN 2048".to_string(),
+                3,
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_render_histogram_scales_bars() {
+        let counts = BTreeMap::from([
+            ("IT".to_string(), 4),
+            ("Engineering".to_string(), 1),
+            ("Sales".to_string(), 2),
+        ]);
+        let histogram = render_histogram(&counts);
+        let lines: Vec<&str> = histogram.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("    IT          4 "), "{histogram}");
+        assert!(lines[0].ends_with(&"█".repeat(HISTOGRAM_WIDTH)));
+        assert!(lines[2].starts_with("    Engineering 1 "));
+        assert!(lines[2].ends_with(&format!(" {}", "█".repeat(8))));
+    }
+
+    #[test]
+    fn test_render_table_contains_each_vlan() {
+        let configs = sample_configs();
+        let table = strip_ansi_codes(&render_table(&configs)).into_owned();
+
+        for header in HEADERS {
+            assert!(table.contains(header), "missing header {header}");
+        }
+        for config in &configs {
+            assert!(table.contains(&config.vlan_id.to_string()));
+            assert!(table.contains(&config.ip_network));
+            assert!(table.contains(&config.description));
+        }
+    }
+
+    #[test]
+    fn test_render_table_columns_are_aligned() {
+        let table = strip_ansi_codes(&render_table(&sample_configs())).into_owned();
+        let widths: Vec<usize> = table.lines().map(measure_text_width).collect();
+
+        // 3 borders + header + 2 rows
+        assert_eq!(widths.len(), 6);
+        assert!(widths.iter().all(|width| *width == widths[0]));
+    }
+
+    #[test]
+    fn test_render_dry_run_summary() {
+        let summary = strip_ansi_codes(&render_dry_run_summary(
+            "csv",
+            25,
+            Path::new("out/vlans.csv"),
+            2048,
+        ))
+        .into_owned();
+        assert!(summary.contains("Format: csv"));
+        assert!(summary.contains("Configurations: 25"));
+        assert!(summary.contains("Target: out/vlans.csv"));
+        assert!(summary.contains("Estimated size: 2.0 KiB (2048 bytes)"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_render_table_empty() {
+        let table = render_table(&[]);
+        assert_eq!(strip_ansi_codes(&table).lines().count(), 4);
+    }
+}
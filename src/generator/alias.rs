@@ -0,0 +1,165 @@
+//! Firewall alias generation for VLAN networks and common services
+//!
+//! OPNsense aliases are named groups of hosts, networks or ports that rules
+//! reference instead of literal addresses.
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use crate::model::ConfigError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of entries an alias groups
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasType {
+    /// Individual IP addresses
+    Host,
+    /// Networks in CIDR notation
+    Network,
+    /// Port numbers or ranges
+    Port,
+}
+
+impl fmt::Display for AliasType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AliasType::Host => write!(f, "host"),
+            AliasType::Network => write!(f, "network"),
+            AliasType::Port => write!(f, "port"),
+        }
+    }
+}
+
+/// A named firewall alias
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Alias {
+    /// Alias name (letters, digits and underscores, as OPNsense requires)
+    pub name: String,
+    /// Kind of entries
+    pub alias_type: AliasType,
+    /// Addresses, networks or ports in the alias
+    pub entries: Vec<String>,
+    /// Human-readable description
+    pub description: String,
+}
+
+/// Port aliases for common services as (name, ports, description)
+const SERVICE_PORT_ALIASES: &[(&str, &[&str], &str)] = &[
+    ("WEB_PORTS", &["80", "443"], "Web traffic"),
+    (
+        "MAIL_PORTS",
+        &["25", "465", "587", "993"],
+        "Mail delivery and retrieval",
+    ),
+    ("DNS_PORTS", &["53", "853"], "DNS and DNS over TLS"),
+    (
+        "REMOTE_ADMIN_PORTS",
+        &["22", "3389"],
+        "SSH and RDP administration",
+    ),
+];
+
+/// Generate firewall aliases for a set of VLANs
+///
+/// Produces one network alias per VLAN (`VLAN100_NET`), a host alias of all
+/// VLAN gateways and port aliases for common services.
+pub fn generate_aliases(configs: &[VlanConfig]) -> Result<Vec<Alias>> {
+    let mut aliases = Vec::with_capacity(configs.len() + SERVICE_PORT_ALIASES.len() + 1);
+
+    for config in configs {
+        let network = config
+            .as_ipv4_network()
+            .map_err(|e| ConfigError::validation(e.to_string()))?;
+        aliases.push(Alias {
+            name: format!("VLAN{}_NET", config.vlan_id),
+            alias_type: AliasType::Network,
+            entries: vec![network.to_string()],
+            description: format!("{} network", config.description),
+        });
+    }
+
+    if !configs.is_empty() {
+        aliases.push(Alias {
+            name: "VLAN_GATEWAYS".to_string(),
+            alias_type: AliasType::Host,
+            entries: configs
+                .iter()
+                .map(VlanConfig::gateway_ip)
+                .collect::<Result<_>>()?,
+            description: "Gateway addresses of all VLANs".to_string(),
+        });
+    }
+
+    aliases.extend(
+        SERVICE_PORT_ALIASES
+            .iter()
+            .map(|(name, ports, description)| Alias {
+                name: (*name).to_string(),
+                alias_type: AliasType::Port,
+                entries: ports.iter().map(|port| (*port).to_string()).collect(),
+                description: (*description).to_string(),
+            }),
+    );
+
+    Ok(aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_alias_per_vlan() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new_with_prefix(
+                200,
+                "172.16.4.64/26".to_string(),
+                "Sales VLAN 200".to_string(),
+                2,
+                26,
+            )
+            .unwrap(),
+        ];
+
+        let aliases = generate_aliases(&configs).unwrap();
+        let networks: Vec<_> = aliases
+            .iter()
+            .filter(|alias| alias.alias_type == AliasType::Network)
+            .collect();
+        assert_eq!(networks.len(), configs.len());
+        assert_eq!(networks[0].name, "VLAN100_NET");
+        assert_eq!(networks[0].entries, ["10.1.2.0/24"]);
+        assert_eq!(networks[1].name, "VLAN200_NET");
+        assert_eq!(networks[1].entries, ["172.16.4.64/26"]);
+
+        let gateways = aliases
+            .iter()
+            .find(|alias| alias.alias_type == AliasType::Host)
+            .unwrap();
+        assert_eq!(gateways.entries, ["10.1.2.1", "172.16.4.65"]);
+
+        assert!(
+            aliases
+                .iter()
+                .any(|alias| alias.name == "WEB_PORTS" && alias.entries == ["80", "443"])
+        );
+    }
+
+    #[test]
+    fn test_alias_names_are_valid_identifiers() {
+        let configs = vec![
+            VlanConfig::new(4094, "10.9.9.x".to_string(), "HR VLAN 4094".to_string(), 3).unwrap(),
+        ];
+        for alias in generate_aliases(&configs).unwrap() {
+            assert!(alias.name.len() <= 32);
+            assert!(
+                alias
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            );
+        }
+    }
+}
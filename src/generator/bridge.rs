@@ -0,0 +1,84 @@
+//! Bridge interface generation for switch-like setups
+//!
+//! Bridging several VLAN interfaces lets an OPNsense box forward between
+//! them at layer 2, much like a managed switch.
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use crate::model::ConfigError;
+use serde::{Deserialize, Serialize};
+
+/// A bridge interface grouping VLAN interfaces
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Bridge {
+    /// Bridge interface name, e.g. `bridge0`
+    pub name: String,
+    /// Member interfaces, e.g. `vlan100`
+    pub members: Vec<String>,
+    /// Whether (rapid) spanning tree runs on the members
+    pub stp_enabled: bool,
+}
+
+/// Group VLAN interfaces into bridges of `group_size` members
+///
+/// Bridges are numbered from `bridge0` in VLAN order; a trailing group may
+/// have fewer members. Spanning tree is enabled on every bridge with more
+/// than one member, since only those can form a loop.
+pub fn generate_bridges(configs: &[VlanConfig], group_size: usize) -> Result<Vec<Bridge>> {
+    if group_size == 0 {
+        return Err(ConfigError::invalid_parameter(
+            "group_size",
+            "a bridge needs at least one member",
+        ));
+    }
+
+    Ok(configs
+        .chunks(group_size)
+        .enumerate()
+        .map(|(index, group)| Bridge {
+            name: format!("bridge{index}"),
+            members: group
+                .iter()
+                .map(|config| format!("vlan{}", config.vlan_id))
+                .collect(),
+            stp_enabled: group.len() > 1,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configs(count: u16) -> Vec<VlanConfig> {
+        (1..=count)
+            .map(|i| {
+                VlanConfig::new(
+                    i * 100,
+                    format!("10.1.{i}.x"),
+                    format!("IT VLAN {}", i * 100),
+                    1,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_six_vlans_in_groups_of_three() {
+        let bridges = generate_bridges(&configs(6), 3).unwrap();
+        assert_eq!(bridges.len(), 2);
+        assert!(bridges.iter().all(|bridge| bridge.members.len() == 3));
+        assert_eq!(bridges[1].name, "bridge1");
+        assert_eq!(bridges[1].members, ["vlan400", "vlan500", "vlan600"]);
+        assert!(bridges.iter().all(|bridge| bridge.stp_enabled));
+    }
+
+    #[test]
+    fn test_trailing_group_and_zero_group_size() {
+        let bridges = generate_bridges(&configs(3), 2).unwrap();
+        assert_eq!(bridges[1].members, ["vlan300"]);
+        assert!(!bridges[1].stp_enabled);
+        assert!(generate_bridges(&configs(3), 0).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! One-shot VLAN generation for library users
+//!
+//! [`GenerationBuilder`] gathers the options otherwise spread across
+//! [`VlanGenerator`] setters and the `generate_vlan_configurations*` free
+//! functions, then generates (and optionally validates) in a single call:
+//!
+//! ```
+//! use opnsense_config_faker::generator::GenerationBuilder;
+//! use opnsense_config_faker::utils::rfc1918::Rfc1918Class;
+//!
+//! let configs = GenerationBuilder::new()
+//!     .count(50)
+//!     .seed(42)
+//!     .network_class(Rfc1918Class::ClassC)
+//!     .validate(true)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(configs.len(), 50);
+//! ```
+
+use crate::Result;
+use crate::cli::{DEFAULT_VLAN_COUNT, WanAssignmentStrategy};
+use crate::generator::vlan::{DEFAULT_MAX_DESCRIPTION_LEN, DEFAULT_PREFIX_LEN};
+use crate::generator::{DescriptionTemplate, DhcpRangePolicy, VlanConfig, VlanGenerator};
+use crate::utils::progress::ProgressSink;
+use crate::utils::rfc1918::Rfc1918Class;
+use crate::validate::ValidationEngine;
+use ipnetwork::Ipv4Network;
+
+/// Fluent builder generating a batch of VLAN configurations
+pub struct GenerationBuilder<'a> {
+    count: u16,
+    seed: Option<u64>,
+    network_class: Option<Rfc1918Class>,
+    supernet: Option<Ipv4Network>,
+    prefix_len: u8,
+    dhcp_policy: Option<DhcpRangePolicy>,
+    description_template: Option<DescriptionTemplate>,
+    max_description_len: usize,
+    wan_strategy: Option<WanAssignmentStrategy>,
+    validate: bool,
+    progress: Option<&'a dyn ProgressSink>,
+}
+
+impl<'a> GenerationBuilder<'a> {
+    /// Start with the CLI defaults: 10 /24 VLANs from any private range,
+    /// random seed, no validation
+    pub fn new() -> Self {
+        Self {
+            count: DEFAULT_VLAN_COUNT,
+            seed: None,
+            network_class: None,
+            supernet: None,
+            prefix_len: DEFAULT_PREFIX_LEN,
+            dhcp_policy: None,
+            description_template: None,
+            max_description_len: DEFAULT_MAX_DESCRIPTION_LEN,
+            wan_strategy: None,
+            validate: false,
+            progress: None,
+        }
+    }
+
+    /// Number of VLAN configurations to generate
+    pub fn count(mut self, count: u16) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Seed for reproducible output
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Draw networks from one RFC 1918 class
+    pub fn network_class(mut self, class: Rfc1918Class) -> Self {
+        self.network_class = Some(class);
+        self
+    }
+
+    /// Draw every network from a private supernet
+    pub fn supernet(mut self, supernet: Ipv4Network) -> Self {
+        self.supernet = Some(supernet);
+        self
+    }
+
+    /// CIDR prefix length of the generated networks (8-30)
+    pub fn prefix_len(mut self, prefix_len: u8) -> Self {
+        self.prefix_len = prefix_len;
+        self
+    }
+
+    /// Explicit DHCP pool offsets for every VLAN
+    pub fn dhcp_policy(mut self, policy: DhcpRangePolicy) -> Self {
+        self.dhcp_policy = Some(policy);
+        self
+    }
+
+    /// Template for VLAN descriptions
+    pub fn description_template(mut self, template: DescriptionTemplate) -> Self {
+        self.description_template = Some(template);
+        self
+    }
+
+    /// Longest description, in characters, generation and validation accept
+    pub fn max_description_len(mut self, max_len: usize) -> Self {
+        self.max_description_len = max_len;
+        self
+    }
+
+    /// How VLANs are spread across WAN uplinks (random when unset)
+    pub fn wan_strategy(mut self, strategy: WanAssignmentStrategy) -> Self {
+        self.wan_strategy = Some(strategy);
+        self
+    }
+
+    /// Re-check the generated set with [`ValidationEngine`] before returning
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Report progress per generated configuration
+    pub fn progress(mut self, progress: &'a dyn ProgressSink) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Generate the configurations
+    ///
+    /// Invalid options (such as a public supernet or a prefix that does not
+    /// fit the class) are reported here rather than by the setters.
+    pub fn build(self) -> Result<Vec<VlanConfig>> {
+        let mut generator = VlanGenerator::new_with_std_rng(self.seed)
+            .with_max_description_len(self.max_description_len);
+        if let Some(template) = self.description_template {
+            generator = generator.with_description_template(template);
+        }
+        if let Some(class) = self.network_class {
+            generator = generator.with_network_class(class);
+        }
+        if let Some(supernet) = self.supernet {
+            generator = generator.with_supernet(supernet)?;
+        }
+        generator = generator.with_prefix_len(self.prefix_len)?;
+        if let Some(policy) = self.dhcp_policy {
+            generator = generator.with_dhcp_policy(policy)?;
+        }
+
+        let configs = generator.generate_configurations(
+            self.count,
+            self.wan_strategy.as_ref(),
+            self.progress,
+        )?;
+
+        if self.validate {
+            ValidationEngine::new()
+                .with_max_description_len(self.max_description_len)
+                .validate_configs(&configs)?;
+        }
+
+        Ok(configs)
+    }
+}
+
+impl Default for GenerationBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_matches_generator_without_validation() {
+        let configs = GenerationBuilder::new().count(20).seed(42).build().unwrap();
+        assert_eq!(configs.len(), 20);
+        assert_eq!(
+            configs,
+            VlanGenerator::new_with_std_rng(Some(42))
+                .generate_configurations(20, None, None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_with_validation_and_class() {
+        let configs = GenerationBuilder::new()
+            .count(50)
+            .seed(42)
+            .network_class(Rfc1918Class::ClassC)
+            .validate(true)
+            .build()
+            .unwrap();
+        assert_eq!(configs.len(), 50);
+        assert!(
+            configs
+                .iter()
+                .all(|config| config.ip_network.starts_with("192.168.")),
+            "{configs:?}"
+        );
+    }
+
+    #[test]
+    fn test_builder_reports_invalid_options() {
+        let public: Ipv4Network = "8.8.0.0/16".parse().unwrap();
+        assert!(GenerationBuilder::new().supernet(public).build().is_err());
+        assert!(GenerationBuilder::new().prefix_len(31).build().is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Captive portal zone generation for guest-facing VLANs
+//!
+//! Guest and training networks usually sit behind a captive portal that
+//! authenticates clients and expires their sessions.
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use crate::model::ConfigError;
+use serde::{Deserialize, Serialize};
+
+/// Description keywords that mark a VLAN as needing a captive portal
+const PORTAL_KEYWORDS: &[&str] = &["Guest", "Training"];
+
+/// A captive portal zone bound to a VLAN interface
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CaptivePortalZone {
+    /// Zone number, unique per firewall
+    pub zone_id: u16,
+    /// Interface the zone is bound to, e.g. `vlan200`
+    pub interface: String,
+    /// Maximum concurrent clients
+    pub max_clients: u32,
+    /// Minutes of inactivity before a client is logged out
+    pub idle_timeout: u32,
+    /// Minutes after which a client is logged out regardless of activity
+    pub hard_timeout: u32,
+}
+
+/// Whether a VLAN's description calls for a captive portal
+pub fn needs_captive_portal(config: &VlanConfig) -> bool {
+    PORTAL_KEYWORDS
+        .iter()
+        .any(|keyword| config.description.contains(keyword))
+}
+
+/// Generate captive portal zones for the guest and training VLANs
+///
+/// Zones are numbered from 0 in VLAN order. Client limits follow the subnet's
+/// usable host count; training sessions last a working day, guest sessions
+/// a few hours.
+pub fn generate_captive_portal_zones(configs: &[VlanConfig]) -> Result<Vec<CaptivePortalZone>> {
+    configs
+        .iter()
+        .filter(|config| needs_captive_portal(config))
+        .zip(0u16..)
+        .map(|(config, zone_id)| {
+            let network = config
+                .as_ipv4_network()
+                .map_err(|e| ConfigError::validation(e.to_string()))?;
+            // Leave out the network, gateway and broadcast addresses
+            let max_clients = network.size().saturating_sub(3);
+            let (idle_timeout, hard_timeout) = if config.description.contains("Training") {
+                (60, 480)
+            } else {
+                (30, 240)
+            };
+            Ok(CaptivePortalZone {
+                zone_id,
+                interface: format!("vlan{}", config.vlan_id),
+                max_clients,
+                idle_timeout,
+                hard_timeout,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guest_vlan_gets_zone() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Guest VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let zones = generate_captive_portal_zones(&configs).unwrap();
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].zone_id, 0);
+        assert_eq!(zones[0].interface, "vlan200");
+        assert_eq!(zones[0].max_clients, 253);
+        assert!(zones[0].idle_timeout < zones[0].hard_timeout);
+    }
+
+    #[test]
+    fn test_training_vlans_numbered_in_order() {
+        let configs = vec![
+            VlanConfig::new(
+                300,
+                "10.5.6.x".to_string(),
+                "Training VLAN 300".to_string(),
+                1,
+            )
+            .unwrap(),
+            VlanConfig::new_with_prefix(
+                400,
+                "10.7.8.0/26".to_string(),
+                "Guest VLAN 400".to_string(),
+                1,
+                26,
+            )
+            .unwrap(),
+        ];
+
+        let zones = generate_captive_portal_zones(&configs).unwrap();
+        let summary: Vec<_> = zones
+            .iter()
+            .map(|zone| (zone.zone_id, zone.interface.as_str(), zone.max_clients))
+            .collect();
+        assert_eq!(summary, [(0, "vlan300", 253), (1, "vlan400", 61)]);
+        assert_eq!(zones[0].hard_timeout, 480);
+    }
+}
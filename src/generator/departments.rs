@@ -1,5 +1,11 @@
 //! Department name constants for realistic VLAN naming
 
+use crate::Result;
+use crate::model::ConfigError;
+use std::fs;
+use std::io;
+use std::path::Path;
+
 /// Standard department names for generating realistic VLAN descriptions
 pub const DEPARTMENTS: &[&str] = &[
     "Sales",
@@ -24,6 +30,28 @@
     "Production",
 ];
 
+/// 802.1p priority for voice departments (IEEE 802.1Q "Voice")
+pub const VOICE_PCP: u8 = 5;
+
+/// 802.1p priority for video departments (IEEE 802.1Q "Video")
+pub const VIDEO_PCP: u8 = 4;
+
+/// 802.1p priority code point for a department's VLAN
+///
+/// Departments whose names mention voice or video traffic get the matching
+/// 802.1Q traffic class; everything else, guests included, is best effort (0).
+pub fn department_pcp(department: &str) -> u8 {
+    let name = department.to_ascii_lowercase();
+    let mentions = |keywords: &[&str]| keywords.iter().any(|keyword| name.contains(keyword));
+    if mentions(&["voice", "voip", "telephony", "phone"]) {
+        VOICE_PCP
+    } else if mentions(&["video", "camera", "cctv", "surveillance", "conferenc"]) {
+        VIDEO_PCP
+    } else {
+        0
+    }
+}
+
 /// Get a random department name using the provided RNG
 pub fn random_department<R: rand::Rng>(rng: &mut R) -> &'static str {
     DEPARTMENTS[rng.random_range(0..DEPARTMENTS.len())]
@@ -39,11 +67,43 @@ pub fn all_departments() -> &'static [&'static str] {
     DEPARTMENTS
 }
 
+/// Load newline-separated department names from a file
+///
+/// Surrounding whitespace and blank lines are ignored. A missing file or one
+/// without any names is an error.
+pub fn load_departments_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => ConfigError::ConfigNotFound {
+            path: path.display().to_string(),
+        },
+        _ => ConfigError::Io(e),
+    })?;
+
+    let departments: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if departments.is_empty() {
+        return Err(ConfigError::invalid_parameter(
+            "departments_file",
+            format!("{} contains no department names", path.display()),
+        ));
+    }
+
+    Ok(departments)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
 
     #[test]
     fn test_department_constants() {
@@ -70,6 +130,15 @@ fn test_all_departments() {
         assert_eq!(all, DEPARTMENTS);
     }
 
+    #[test]
+    fn test_department_pcp() {
+        assert_eq!(department_pcp("Voice"), VOICE_PCP);
+        assert_eq!(department_pcp("VoIP Phones"), VOICE_PCP);
+        assert_eq!(department_pcp("Video Conferencing"), VIDEO_PCP);
+        assert_eq!(department_pcp("Guest"), 0);
+        assert!(department_pcp("Voice") > department_pcp("Guest"));
+    }
+
     #[test]
     fn test_random_department() {
         let mut rng = ChaCha8Rng::seed_from_u64(42);
@@ -105,4 +174,29 @@ fn test_departments_are_valid() {
             assert!(dept.len() <= 50);
         }
     }
+
+    #[test]
+    fn test_load_departments_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "Platform\n\n  Data Science  \nFacilities").unwrap();
+
+        let departments = load_departments_file(file.path()).unwrap();
+        assert_eq!(departments, ["Platform", "Data Science", "Facilities"]);
+    }
+
+    #[test]
+    fn test_load_departments_file_empty() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "\n   \n").unwrap();
+
+        let err = load_departments_file(file.path()).unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidParameter { .. }));
+        assert!(err.to_string().contains("contains no department names"));
+    }
+
+    #[test]
+    fn test_load_departments_file_missing() {
+        let err = load_departments_file("/nonexistent/departments.txt").unwrap_err();
+        assert!(matches!(err, ConfigError::ConfigNotFound { .. }));
+    }
 }
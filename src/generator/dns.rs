@@ -0,0 +1,164 @@
+//! DNS record generation for VLAN gateways and static reservations
+//!
+//! Produces forward (A) and reverse (PTR) records that mirror the addressing
+//! already derived for each VLAN, suitable for loading into a test resolver.
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::Ipv4Addr;
+
+/// Default TTL in seconds for generated records
+pub const DEFAULT_TTL: u32 = 3600;
+
+/// Zone origin for the reverse (PTR) records of IPv4 addresses
+pub const REVERSE_ZONE_ORIGIN: &str = "in-addr.arpa.";
+
+/// DNS record types produced by the generator
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DnsRecordType {
+    /// IPv4 address record
+    A,
+    /// Reverse pointer record
+    Ptr,
+}
+
+impl fmt::Display for DnsRecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsRecordType::A => write!(f, "A"),
+            DnsRecordType::Ptr => write!(f, "PTR"),
+        }
+    }
+}
+
+/// A single DNS resource record with fully qualified names
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DnsRecord {
+    /// Fully qualified owner name (with trailing dot)
+    pub name: String,
+    /// Record type
+    pub record_type: DnsRecordType,
+    /// Record data: an IPv4 address for A, a fully qualified name for PTR
+    pub value: String,
+    /// Time to live in seconds
+    pub ttl: u32,
+}
+
+impl DnsRecord {
+    /// Create an A record
+    pub fn a(name: String, address: String) -> Self {
+        Self {
+            name,
+            record_type: DnsRecordType::A,
+            value: address,
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Create the PTR record pointing `address` back at `target`
+    pub fn ptr(address: Ipv4Addr, target: String) -> Self {
+        Self {
+            name: reverse_name(address),
+            record_type: DnsRecordType::Ptr,
+            value: target,
+            ttl: DEFAULT_TTL,
+        }
+    }
+}
+
+/// Build the `in-addr.arpa.` owner name for an IPv4 address
+pub fn reverse_name(address: Ipv4Addr) -> String {
+    let [a, b, c, d] = address.octets();
+    format!("{d}.{c}.{b}.{a}.{REVERSE_ZONE_ORIGIN}")
+}
+
+/// Generate A and PTR records for each VLAN's gateway and static reservations
+///
+/// Names live under the VLAN's DHCP domain, e.g. `gw.vlan100.it.company.local.`
+/// for the gateway and `server-it-01.vlan100.it.company.local.` for a
+/// reservation.
+pub fn generate_dns_records(configs: &[VlanConfig]) -> Result<Vec<DnsRecord>> {
+    let mut records = Vec::new();
+
+    for config in configs {
+        let zone = format!("vlan{}.{}.", config.vlan_id, config.dhcp_domain_name());
+
+        let mut hosts = vec![(format!("gw.{zone}"), config.gateway_ip()?)];
+        hosts.extend(
+            config
+                .static_reservations(&[])?
+                .into_iter()
+                .map(|reservation| {
+                    (
+                        format!("{}.{zone}", reservation.hostname),
+                        reservation.ip_addr,
+                    )
+                }),
+        );
+
+        for (name, address) in hosts {
+            let ip: Ipv4Addr = address.parse().map_err(|e| {
+                crate::model::ConfigError::validation(format!(
+                    "Invalid address {address} for {name}: {e}"
+                ))
+            })?;
+            records.push(DnsRecord::a(name.clone(), address));
+            records.push(DnsRecord::ptr(ip, name));
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_name() {
+        assert_eq!(
+            reverse_name(Ipv4Addr::new(10, 1, 2, 10)),
+            "10.2.1.10.in-addr.arpa."
+        );
+    }
+
+    #[test]
+    fn test_a_and_ptr_for_each_static_reservation() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Sales VLAN 200".to_string(), 2).unwrap(),
+        ];
+        let records = generate_dns_records(&configs).unwrap();
+
+        for config in &configs {
+            for reservation in config.static_reservations(&[]).unwrap() {
+                let a = records
+                    .iter()
+                    .find(|r| r.record_type == DnsRecordType::A && r.value == reservation.ip_addr)
+                    .expect("A record for reservation");
+                assert!(a.name.starts_with(&reservation.hostname));
+
+                let ptr_name = reverse_name(reservation.ip_addr.parse().unwrap());
+                let ptr = records
+                    .iter()
+                    .find(|r| r.record_type == DnsRecordType::Ptr && r.name == ptr_name)
+                    .expect("PTR record for reservation");
+                assert_eq!(ptr.value, a.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gateway_records() {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        let records = generate_dns_records(&[config]).unwrap();
+
+        assert_eq!(records[0].name, "gw.vlan100.it.company.local.");
+        assert_eq!(records[0].value, "10.1.2.1");
+        assert_eq!(records[1].name, "1.2.1.10.in-addr.arpa.");
+        assert_eq!(records[1].value, "gw.vlan100.it.company.local.");
+    }
+}
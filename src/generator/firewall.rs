@@ -2,12 +2,57 @@
 
 use crate::Result;
 use crate::model::ConfigError;
+use crate::utils::progress::ProgressSink;
 use fake::Fake;
-use indicatif::ProgressBar;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// IP version a firewall rule applies to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpVersion {
+    /// IPv4 (`inet` in OPNsense)
+    #[default]
+    #[serde(rename = "inet")]
+    V4,
+    /// IPv6 (`inet6` in OPNsense)
+    #[serde(rename = "inet6")]
+    V6,
+}
+
+impl fmt::Display for IpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpVersion::V4 => write!(f, "inet"),
+            IpVersion::V6 => write!(f, "inet6"),
+        }
+    }
+}
+
+/// ICMP types a rule may match, with the pf name OPNsense stores in
+/// `<icmptype>`
+pub const ICMP_TYPES: &[(&str, &str)] = &[
+    ("echo-request", "echoreq"),
+    ("echo-reply", "echorep"),
+    ("destination-unreachable", "unreach"),
+    ("time-exceeded", "timex"),
+    ("parameter-problem", "paramprob"),
+];
+
+/// ICMP types allowed by generated diagnostics rules
+const DIAGNOSTIC_ICMP_TYPES: [&str; 2] = ["echo-request", "destination-unreachable"];
+
+/// pf name of an ICMP type, e.g. `echoreq` for `echo-request`
+pub fn icmp_type_pf_name(icmp_type: &str) -> Option<&'static str> {
+    ICMP_TYPES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(icmp_type))
+        .map(|(_, pf_name)| *pf_name)
+}
 
 /// Firewall rule configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -47,6 +92,15 @@ pub struct FirewallRule {
 
     /// Interface this rule applies to
     pub interface: String,
+
+    /// IP version the rule matches
+    #[serde(default)]
+    pub ip_version: IpVersion,
+
+    /// ICMP type matched by an ICMP rule (e.g. `echo-request`); `None`
+    /// matches every type and is the only value other protocols accept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icmp_type: Option<String>,
 }
 
 impl FirewallRule {
@@ -90,7 +144,7 @@ pub fn new(
         }
 
         // Validate protocol
-        let valid_protocols = ["tcp", "udp", "icmp", "any"];
+        let valid_protocols = ["tcp", "udp", "icmp", "icmpv6", "any"];
         if !valid_protocols.contains(&protocol.to_lowercase().as_str()) {
             return Err(ConfigError::validation(format!(
                 "Invalid protocol '{}'. Must be one of: {:?}",
@@ -121,9 +175,61 @@ pub fn new(
             vlan_id,
             priority,
             interface,
+            ip_version: IpVersion::V4,
+            icmp_type: None,
         })
     }
 
+    /// Set the ICMP type, checking that this is an ICMP rule
+    pub fn with_icmp_type(mut self, icmp_type: impl Into<String>) -> Result<Self> {
+        self.icmp_type = Some(icmp_type.into());
+        self.validate_icmp_type()?;
+        Ok(self)
+    }
+
+    /// Reject an ICMP type on non-ICMP rules and unknown ICMP types
+    fn validate_icmp_type(&self) -> Result<()> {
+        let Some(icmp_type) = &self.icmp_type else {
+            return Ok(());
+        };
+        if !self.protocol.eq_ignore_ascii_case("icmp") {
+            return Err(ConfigError::validation(format!(
+                "ICMP type '{icmp_type}' cannot be used in a '{}' rule",
+                self.protocol
+            )));
+        }
+        if icmp_type_pf_name(icmp_type).is_none() {
+            let known: Vec<&str> = ICMP_TYPES.iter().map(|(name, _)| *name).collect();
+            return Err(ConfigError::validation(format!(
+                "Unknown ICMP type '{icmp_type}'. Must be one of: {known:?}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Set the IP version, checking that the protocol belongs to it
+    pub fn with_ip_version(mut self, ip_version: IpVersion) -> Result<Self> {
+        self.ip_version = ip_version;
+        self.validate_ip_version()?;
+        Ok(self)
+    }
+
+    /// Reject ICMP on IPv6 rules and ICMPv6 on IPv4 rules
+    fn validate_ip_version(&self) -> Result<()> {
+        let expected = match self.protocol.to_lowercase().as_str() {
+            "icmp" => IpVersion::V4,
+            "icmpv6" => IpVersion::V6,
+            _ => return Ok(()),
+        };
+        if self.ip_version != expected {
+            return Err(ConfigError::validation(format!(
+                "Protocol '{}' cannot be used in an {} rule",
+                self.protocol, self.ip_version
+            )));
+        }
+        Ok(())
+    }
+
     /// Validate the firewall rule configuration
     pub fn validate(&self) -> Result<()> {
         // Re-run validation logic
@@ -141,7 +247,8 @@ pub fn validate(&self) -> Result<()> {
             self.priority,
             self.interface.clone(),
         )?;
-        Ok(())
+        self.validate_ip_version()?;
+        self.validate_icmp_type()
     }
 }
 
@@ -180,6 +287,155 @@ fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
     }
 }
 
+/// Action taken by rules that deny traffic
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DenyAction {
+    /// Silently drop the packet
+    #[default]
+    Block,
+    /// Drop the packet and answer with a TCP RST or ICMP unreachable
+    Reject,
+}
+
+impl DenyAction {
+    /// Rule action string for this deny action
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DenyAction::Block => "block",
+            DenyAction::Reject => "reject",
+        }
+    }
+}
+
+/// Site-wide choices applied to every generated rule
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirewallPolicy {
+    /// Action used by every deny rule
+    pub deny_action: DenyAction,
+    /// Per-department toggles for the department-specific rules
+    pub profile: FirewallProfile,
+}
+
+/// Department-specific rule choices
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DepartmentRules {
+    /// Pass VPN traffic (OpenVPN, IPsec) instead of blocking it
+    pub allow_vpn: bool,
+    /// Block social media instead of passing it
+    pub block_social_media: bool,
+    /// Pass file sharing (FTP, SSH, SMB) instead of blocking it
+    pub allow_file_sharing: bool,
+    /// Block gaming traffic instead of passing and logging it
+    pub block_gaming: bool,
+    /// Ports of the application access rule, e.g. `"1433,3306"` or `"any"`
+    pub app_ports: String,
+}
+
+impl Default for DepartmentRules {
+    fn default() -> Self {
+        Self {
+            allow_vpn: false,
+            block_social_media: false,
+            allow_file_sharing: false,
+            block_gaming: false,
+            app_ports: "any".to_string(),
+        }
+    }
+}
+
+/// Rule choices for departments whose name contains `name`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepartmentProfile {
+    /// Case-insensitive fragment of the department name, e.g. `"IT"`
+    pub name: String,
+    /// Rules applied to matching departments
+    #[serde(flatten)]
+    pub rules: DepartmentRules,
+}
+
+/// Per-department firewall policy, loadable from JSON or TOML
+///
+/// Entries are checked in order and the first whose name occurs in the
+/// department wins; departments without a match use `default`. The default
+/// profile reproduces the built-in department policies.
+///
+/// ```toml
+/// [[departments]]
+/// name = "IT"
+/// allow_vpn = false
+/// app_ports = "22,443"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallProfile {
+    /// Department entries in match order
+    #[serde(default)]
+    pub departments: Vec<DepartmentProfile>,
+    /// Rules for departments no entry matches
+    #[serde(default)]
+    pub default: DepartmentRules,
+}
+
+impl Default for FirewallProfile {
+    fn default() -> Self {
+        const ADMIN_PORTS: &str = "22,23,3389,5900,8080,8443"; // SSH, Telnet, RDP, VNC, Web management
+        const MAIL_PORTS: &str = "25,587,465,143,993"; // SMTP, IMAP
+        const DATABASE_PORTS: &str = "1433,3306,5432";
+
+        // Only IT may use file sharing
+        let department = |name: &str, allow_vpn, block_social_media, block_gaming, ports: &str| {
+            DepartmentProfile {
+                name: name.to_string(),
+                rules: DepartmentRules {
+                    allow_vpn,
+                    block_social_media,
+                    allow_file_sharing: name == "it",
+                    block_gaming,
+                    app_ports: ports.to_string(),
+                },
+            }
+        };
+        Self {
+            // name, allow VPN, block social media, block gaming, app ports
+            departments: vec![
+                department("it", true, false, false, ADMIN_PORTS),
+                department("engineering", true, false, false, ADMIN_PORTS),
+                department("sales", true, false, false, MAIL_PORTS),
+                department("marketing", false, false, false, MAIL_PORTS),
+                department("finance", false, true, true, DATABASE_PORTS),
+                department("hr", false, true, true, DATABASE_PORTS),
+                department("legal", false, true, true, "any"),
+                department("executive", false, false, true, "any"),
+            ],
+            default: DepartmentRules::default(),
+        }
+    }
+}
+
+impl FirewallProfile {
+    /// Load a profile from a `.toml` file, or JSON for any other extension
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let profile = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)?
+        } else {
+            serde_json::from_str(&content)?
+        };
+        Ok(profile)
+    }
+
+    /// Rules for a department, matched case-insensitively
+    pub fn rules_for(&self, department: &str) -> &DepartmentRules {
+        let department = department.to_lowercase();
+        self.departments
+            .iter()
+            .find(|entry| department.contains(&entry.name.to_lowercase()))
+            .map_or(&self.default, |entry| &entry.rules)
+    }
+}
+
 /// Firewall rule generator
 pub struct FirewallGenerator {
     /// Random number generator for future randomized rule generation
@@ -191,6 +447,7 @@ pub struct FirewallGenerator {
     rng: ChaCha8Rng,
     rule_counter: u16,
     used_rule_ids: HashSet<String>,
+    policy: FirewallPolicy,
 }
 
 impl FirewallGenerator {
@@ -205,9 +462,16 @@ pub fn new(seed: Option<u64>) -> Self {
             rng,
             rule_counter: 1,
             used_rule_ids: HashSet::new(),
+            policy: FirewallPolicy::default(),
         }
     }
 
+    /// Apply a firewall policy to all rules generated from now on
+    pub fn with_policy(mut self, policy: FirewallPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Generate firewall rules for a specific VLAN
     pub fn generate_vlan_rules(
         &mut self,
@@ -312,7 +576,6 @@ fn generate_intermediate_rules(
         vlan_network: &str,
         department: &str,
     ) -> Result<Vec<FirewallRule>> {
-        let dept_lower = department.to_lowercase();
         let mut rules = Vec::with_capacity(4);
 
         // Rule 4: Allow NTP time synchronization
@@ -332,20 +595,26 @@ fn generate_intermediate_rules(
         )?);
 
         // Rule 5: Allow ICMP for network diagnostics
-        rules.push(FirewallRule::new(
-            self.generate_rule_id(),
-            vlan_network.to_string(),
-            "any".to_string(),
-            "icmp".to_string(),
-            "any".to_string(),
-            "pass".to_string(),
-            "out".to_string(),
-            generate_rule_description(&mut self.rng, department, "Allow", "ICMP diagnostics"),
-            false, // Don't log ICMP traffic
-            Some(vlan_id),
-            0, // Will be set later
-            format!("vlan{}", vlan_id),
-        )?);
+        let icmp_type = *DIAGNOSTIC_ICMP_TYPES
+            .choose(&mut self.rng)
+            .expect("diagnostic ICMP types are not empty");
+        rules.push(
+            FirewallRule::new(
+                self.generate_rule_id(),
+                vlan_network.to_string(),
+                "any".to_string(),
+                "icmp".to_string(),
+                "any".to_string(),
+                "pass".to_string(),
+                "out".to_string(),
+                generate_rule_description(&mut self.rng, department, "Allow", "ICMP diagnostics"),
+                false, // Don't log ICMP traffic
+                Some(vlan_id),
+                0, // Will be set later
+                format!("vlan{}", vlan_id),
+            )?
+            .with_icmp_type(icmp_type)?,
+        );
 
         // Rule 6: Block common attack ports
         rules.push(FirewallRule::new(
@@ -354,7 +623,7 @@ fn generate_intermediate_rules(
             vlan_network.to_string(),
             "tcp".to_string(),
             "22,23,3389".to_string(), // SSH, Telnet, RDP
-            "block".to_string(),
+            self.policy.deny_action.as_str().to_string(),
             "in".to_string(),
             generate_rule_description(&mut self.rng, department, "Block", "remote access attempts"),
             true,
@@ -364,7 +633,7 @@ fn generate_intermediate_rules(
         )?);
 
         // Rule 7: Allow specific application ports based on department
-        let app_ports = self.get_department_ports(&dept_lower);
+        let app_ports = self.policy.profile.rules_for(department).app_ports.clone();
         rules.push(FirewallRule::new(
             self.generate_rule_id(),
             vlan_network.to_string(),
@@ -390,7 +659,7 @@ fn generate_advanced_rules(
         vlan_network: &str,
         department: &str,
     ) -> Result<Vec<FirewallRule>> {
-        let dept_lower = department.to_lowercase();
+        let profile = self.policy.profile.rules_for(department).clone();
         let mut rules = Vec::with_capacity(8);
 
         // Rule 8: Rate limiting for web traffic
@@ -416,7 +685,7 @@ fn generate_advanced_rules(
             "any".to_string(),
             "tcp".to_string(),
             "6881:6889,51413".to_string(), // BitTorrent ports
-            "block".to_string(),
+            self.policy.deny_action.as_str().to_string(),
             "out".to_string(),
             generate_rule_description(&mut self.rng, department, "Block", "P2P traffic"),
             true,
@@ -425,8 +694,13 @@ fn generate_advanced_rules(
             format!("vlan{}", vlan_id),
         )?);
 
+        // Rules 10-13 are department-specific. Departments outside a policy
+        // get a rule that repeats the verdict they would get anyway (the
+        // default deny, or the web access pass), so every VLAN receives the
+        // full set without opening or closing any traffic.
+
         // Rule 10: Allow VPN access for specific departments
-        if self.should_allow_vpn(&dept_lower) {
+        if profile.allow_vpn {
             rules.push(FirewallRule::new(
                 self.generate_rule_id(),
                 vlan_network.to_string(),
@@ -441,17 +715,32 @@ fn generate_advanced_rules(
                 0, // Will be set later
                 format!("vlan{}", vlan_id),
             )?);
+        } else {
+            rules.push(FirewallRule::new(
+                self.generate_rule_id(),
+                vlan_network.to_string(),
+                "any".to_string(),
+                "udp".to_string(),
+                "1194,500,4500".to_string(), // OpenVPN, IPSec
+                self.policy.deny_action.as_str().to_string(),
+                "out".to_string(),
+                generate_rule_description(&mut self.rng, department, "Default deny", "VPN access"),
+                true,
+                Some(vlan_id),
+                0, // Will be set later
+                format!("vlan{}", vlan_id),
+            )?);
         }
 
         // Rule 11: Block social media for certain departments
-        if self.should_block_social_media(&dept_lower) {
+        if profile.block_social_media {
             rules.push(FirewallRule::new(
                 self.generate_rule_id(),
                 vlan_network.to_string(),
                 "any".to_string(),
                 "tcp".to_string(),
                 "443".to_string(),
-                "block".to_string(),
+                self.policy.deny_action.as_str().to_string(),
                 "out".to_string(),
                 generate_rule_description(
                     &mut self.rng,
@@ -464,10 +753,25 @@ fn generate_advanced_rules(
                 0, // Will be set later
                 format!("vlan{}", vlan_id),
             )?);
+        } else {
+            rules.push(FirewallRule::new(
+                self.generate_rule_id(),
+                vlan_network.to_string(),
+                "any".to_string(),
+                "tcp".to_string(),
+                "443".to_string(), // Already passed by rule 3
+                "pass".to_string(),
+                "out".to_string(),
+                generate_rule_description(&mut self.rng, department, "Allow", "HTTPS access"),
+                true,
+                Some(vlan_id),
+                0, // Will be set later
+                format!("vlan{}", vlan_id),
+            )?);
         }
 
         // Rule 12: Allow file sharing for IT department
-        if dept_lower.contains("it") {
+        if profile.allow_file_sharing {
             rules.push(FirewallRule::new(
                 self.generate_rule_id(),
                 vlan_network.to_string(),
@@ -482,17 +786,37 @@ fn generate_advanced_rules(
                 0, // Will be set later
                 format!("vlan{}", vlan_id),
             )?);
+        } else {
+            rules.push(FirewallRule::new(
+                self.generate_rule_id(),
+                vlan_network.to_string(),
+                "any".to_string(),
+                "tcp".to_string(),
+                "21,22,445,139".to_string(), // FTP, SSH, SMB
+                self.policy.deny_action.as_str().to_string(),
+                "out".to_string(),
+                generate_rule_description(
+                    &mut self.rng,
+                    department,
+                    "Default deny",
+                    "file sharing",
+                ),
+                true,
+                Some(vlan_id),
+                0, // Will be set later
+                format!("vlan{}", vlan_id),
+            )?);
         }
 
         // Rule 13: Block gaming traffic for business departments
-        if self.should_block_gaming(&dept_lower) {
+        if profile.block_gaming {
             rules.push(FirewallRule::new(
                 self.generate_rule_id(),
                 vlan_network.to_string(),
                 "any".to_string(),
                 "tcp".to_string(),
                 "27015:27018,25565,25575".to_string(), // Common gaming ports
-                "block".to_string(),
+                self.policy.deny_action.as_str().to_string(),
                 "out".to_string(),
                 generate_rule_description(&mut self.rng, department, "Block", "gaming traffic"),
                 true,
@@ -500,6 +824,26 @@ fn generate_advanced_rules(
                 0, // Will be set later
                 format!("vlan{}", vlan_id),
             )?);
+        } else {
+            rules.push(FirewallRule::new(
+                self.generate_rule_id(),
+                vlan_network.to_string(),
+                "any".to_string(),
+                "tcp".to_string(),
+                "27015:27018,25565,25575".to_string(), // Common gaming ports
+                self.policy.deny_action.as_str().to_string(),
+                "out".to_string(),
+                generate_rule_description(
+                    &mut self.rng,
+                    department,
+                    "Default deny",
+                    "gaming traffic",
+                ),
+                true,
+                Some(vlan_id),
+                0, // Will be set later
+                format!("vlan{}", vlan_id),
+            )?);
         }
 
         // Rule 14: Allow monitoring and management traffic
@@ -525,7 +869,7 @@ fn generate_advanced_rules(
             "any".to_string(),
             "any".to_string(),
             "any".to_string(),
-            "block".to_string(),
+            self.policy.deny_action.as_str().to_string(),
             "out".to_string(),
             generate_rule_description(
                 &mut self.rng,
@@ -542,6 +886,73 @@ fn generate_advanced_rules(
         Ok(rules)
     }
 
+    /// Generate the essential IPv6 rules for a VLAN
+    ///
+    /// IPv6 needs ICMPv6 for neighbor discovery and path MTU discovery,
+    /// DHCPv6 for address assignment and router advertisements so hosts
+    /// learn their prefix and default route. `vlan_network` is the VLAN's
+    /// IPv6 prefix, e.g. `fd00:0:0:64::/64`.
+    pub fn generate_ipv6_basic_rules(
+        &mut self,
+        vlan_id: u16,
+        vlan_network: &str,
+        department: &str,
+    ) -> Result<Vec<FirewallRule>> {
+        // (source, destination, protocol, ports, direction, log, subject)
+        let specs = [
+            (
+                "any",
+                vlan_network,
+                "icmpv6",
+                "any",
+                "in",
+                false,
+                "ICMPv6 neighbor discovery",
+            ),
+            (
+                vlan_network,
+                "any",
+                "udp",
+                "546,547",
+                "in",
+                false,
+                "DHCPv6 address assignment",
+            ),
+            (
+                "any",
+                "ff02::1",
+                "icmpv6",
+                "any",
+                "out",
+                false,
+                "router advertisements",
+            ),
+        ];
+
+        let mut rules = Vec::with_capacity(specs.len());
+        for (priority, (source, destination, protocol, ports, direction, log, subject)) in
+            (1u16..).zip(specs)
+        {
+            let rule = FirewallRule::new(
+                self.generate_rule_id(),
+                source.to_string(),
+                destination.to_string(),
+                protocol.to_string(),
+                ports.to_string(),
+                "pass".to_string(),
+                direction.to_string(),
+                generate_rule_description(&mut self.rng, department, "Allow", subject),
+                log,
+                Some(vlan_id),
+                priority,
+                format!("vlan{}", vlan_id),
+            )?;
+            rules.push(rule.with_ip_version(IpVersion::V6)?);
+        }
+
+        Ok(rules)
+    }
+
     /// Generate a unique rule ID
     fn generate_rule_id(&mut self) -> String {
         loop {
@@ -554,39 +965,6 @@ fn generate_rule_id(&mut self) -> String {
             }
         }
     }
-
-    /// Get department-specific application ports
-    fn get_department_ports(&self, dept_lower: &str) -> String {
-        if dept_lower.contains("it") || dept_lower.contains("engineering") {
-            "22,23,3389,5900,8080,8443".to_string() // SSH, Telnet, RDP, VNC, Web management
-        } else if dept_lower.contains("sales") || dept_lower.contains("marketing") {
-            "25,587,465,143,993".to_string() // SMTP, IMAP
-        } else if dept_lower.contains("finance") || dept_lower.contains("hr") {
-            "1433,3306,5432".to_string() // Database ports
-        } else {
-            "any".to_string()
-        }
-    }
-
-    /// Determine if VPN access should be allowed for this department
-    fn should_allow_vpn(&self, dept_lower: &str) -> bool {
-        dept_lower.contains("it")
-            || dept_lower.contains("engineering")
-            || dept_lower.contains("sales")
-    }
-
-    /// Determine if social media should be blocked for this department
-    fn should_block_social_media(&self, dept_lower: &str) -> bool {
-        dept_lower.contains("finance") || dept_lower.contains("hr") || dept_lower.contains("legal")
-    }
-
-    /// Determine if gaming traffic should be blocked for this department
-    fn should_block_gaming(&self, dept_lower: &str) -> bool {
-        dept_lower.contains("finance")
-            || dept_lower.contains("hr")
-            || dept_lower.contains("legal")
-            || dept_lower.contains("executive")
-    }
 }
 
 /// Generate firewall rules for multiple VLANs
@@ -594,10 +972,29 @@ pub fn generate_firewall_rules(
     vlan_configs: &[crate::generator::VlanConfig],
     complexity: FirewallComplexity,
     seed: Option<u64>,
-    progress_bar: Option<&ProgressBar>,
+    progress_bar: Option<&dyn ProgressSink>,
+    firewall_rules_per_vlan: Option<u16>,
+) -> Result<Vec<FirewallRule>> {
+    generate_firewall_rules_with_policy(
+        vlan_configs,
+        complexity,
+        seed,
+        progress_bar,
+        firewall_rules_per_vlan,
+        FirewallPolicy::default(),
+    )
+}
+
+/// Generate firewall rules for multiple VLANs under the given policy
+pub fn generate_firewall_rules_with_policy(
+    vlan_configs: &[crate::generator::VlanConfig],
+    complexity: FirewallComplexity,
+    seed: Option<u64>,
+    progress_bar: Option<&dyn ProgressSink>,
     firewall_rules_per_vlan: Option<u16>,
+    policy: FirewallPolicy,
 ) -> Result<Vec<FirewallRule>> {
-    let mut generator = FirewallGenerator::new(seed);
+    let mut generator = FirewallGenerator::new(seed).with_policy(policy);
     let rules_estimate = vlan_configs.len() * complexity.rules_per_vlan() as usize;
     let mut all_rules = Vec::with_capacity(rules_estimate);
 
@@ -611,7 +1008,7 @@ pub fn generate_firewall_rules(
         })?;
 
         if let Some(pb) = progress_bar {
-            pb.set_message(format!(
+            pb.set_message(&format!(
                 "Generating firewall rules for VLAN {}",
                 vlan_config.vlan_id
             ));
@@ -859,6 +1256,44 @@ fn test_firewall_generator_advanced() {
         assert!(rules.len() >= 10); // Advanced should generate more rules
     }
 
+    #[test]
+    fn test_advanced_rules_complete_for_every_department() {
+        let mut generator = FirewallGenerator::new(Some(12345));
+
+        for department in ["IT", "Legal", "Marketing", "Operations"] {
+            let rules = generator
+                .generate_vlan_rules(
+                    100,
+                    "192.168.100.0/24",
+                    FirewallComplexity::Advanced,
+                    department,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(
+                rules.len(),
+                FirewallComplexity::Advanced.rules_per_vlan() as usize,
+                "{department}"
+            );
+
+            // Padding rules never open traffic the profile doesn't allow
+            let profile = generator.policy.profile.rules_for(department).clone();
+            for rule in &rules {
+                match rule.ports.as_str() {
+                    "1194,500,4500" => assert_eq!(rule.action == "pass", profile.allow_vpn),
+                    "21,22,445,139" => {
+                        assert_eq!(rule.action == "pass", profile.allow_file_sharing)
+                    }
+                    "27015:27018,25565,25575" => assert_ne!(rule.action, "pass"),
+                    _ => {}
+                }
+                if !profile.block_social_media {
+                    assert!(!rule.description.contains("social media"), "{rule:?}");
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_firewall_generator_multiple_vlans() {
         let mut generator = FirewallGenerator::new(Some(12345));
@@ -939,6 +1374,163 @@ fn test_firewall_generator_deterministic() {
         }
     }
 
+    #[test]
+    fn test_reject_policy_applies_to_every_deny_rule() {
+        let policy = FirewallPolicy {
+            deny_action: DenyAction::Reject,
+            ..Default::default()
+        };
+        let mut blocking = FirewallGenerator::new(Some(12345));
+        let mut rejecting = FirewallGenerator::new(Some(12345)).with_policy(policy);
+
+        for department in ["IT", "Sales", "Guest"] {
+            let blocked = blocking
+                .generate_vlan_rules(
+                    100,
+                    "10.1.2.x",
+                    FirewallComplexity::Advanced,
+                    department,
+                    None,
+                )
+                .unwrap();
+            let rejected = rejecting
+                .generate_vlan_rules(
+                    100,
+                    "10.1.2.x",
+                    FirewallComplexity::Advanced,
+                    department,
+                    None,
+                )
+                .unwrap();
+
+            assert!(rejected.iter().all(|rule| rule.action != "block"));
+            assert_eq!(blocked.len(), rejected.len());
+            for (blocked, rejected) in blocked.iter().zip(&rejected) {
+                let expected = if blocked.action == "block" {
+                    "reject"
+                } else {
+                    blocked.action.as_str()
+                };
+                assert_eq!(rejected.action, expected);
+            }
+            assert!(rejected.iter().any(|rule| rule.action == "reject"));
+        }
+    }
+
+    #[test]
+    fn test_firewall_profile_blocks_vpn_for_it() {
+        let mut file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            br#"{"departments": [{"name": "IT", "allow_vpn": false, "app_ports": "443"}]}"#,
+        )
+        .unwrap();
+        let profile = FirewallProfile::load(file.path()).unwrap();
+        assert!(!profile.rules_for("it").allow_vpn);
+        assert_eq!(profile.rules_for("Sales"), &DepartmentRules::default());
+
+        let is_vpn = |rule: &FirewallRule| rule.ports == "1194,500,4500";
+        let policy = FirewallPolicy {
+            profile,
+            ..Default::default()
+        };
+        let rules = FirewallGenerator::new(Some(7))
+            .with_policy(policy)
+            .generate_vlan_rules(100, "10.1.2.x", FirewallComplexity::Advanced, "IT", None)
+            .unwrap();
+        let vpn = rules.iter().find(|rule| is_vpn(rule)).unwrap();
+        assert_eq!(vpn.action, "block");
+        assert!(rules.iter().any(|rule| rule.ports == "443"));
+
+        // The built-in profile still lets IT use VPNs
+        let rules = FirewallGenerator::new(Some(7))
+            .generate_vlan_rules(100, "10.1.2.x", FirewallComplexity::Advanced, "IT", None)
+            .unwrap();
+        assert_eq!(
+            rules.iter().find(|rule| is_vpn(rule)).unwrap().action,
+            "pass"
+        );
+    }
+
+    #[test]
+    fn test_ipv6_basic_rules() {
+        let mut generator = FirewallGenerator::new(Some(12345));
+        let rules = generator
+            .generate_ipv6_basic_rules(100, "fd00:0:0:64::/64", "IT")
+            .unwrap();
+
+        assert!(rules.iter().all(|rule| rule.ip_version == IpVersion::V6));
+        assert!(
+            rules
+                .iter()
+                .any(|rule| rule.protocol == "icmpv6" && rule.action == "pass")
+        );
+        assert!(rules.iter().any(|rule| rule.ports == "546,547"));
+        for rule in &rules {
+            rule.validate().unwrap();
+        }
+
+        let priorities: Vec<u16> = rules.iter().map(|rule| rule.priority).collect();
+        assert_eq!(priorities, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_ip_version_must_match_icmp_protocol() {
+        let rule = |protocol: &str| {
+            FirewallRule::new(
+                "rule_0001".to_string(),
+                "any".to_string(),
+                "any".to_string(),
+                protocol.to_string(),
+                "any".to_string(),
+                "pass".to_string(),
+                "in".to_string(),
+                "Test".to_string(),
+                false,
+                None,
+                1,
+                "lan".to_string(),
+            )
+            .unwrap()
+        };
+
+        assert!(rule("icmp").with_ip_version(IpVersion::V6).is_err());
+        assert!(rule("icmpv6").validate().is_err());
+        assert!(rule("icmpv6").with_ip_version(IpVersion::V6).is_ok());
+        assert!(rule("tcp").with_ip_version(IpVersion::V6).is_ok());
+    }
+
+    #[test]
+    fn test_icmp_type_only_on_icmp_rules() {
+        let mut generator = FirewallGenerator::new(Some(42));
+        let rules = generator
+            .generate_vlan_rules(100, "10.1.2.0/24", FirewallComplexity::Advanced, "IT", None)
+            .unwrap();
+
+        let icmp: Vec<_> = rules.iter().filter(|r| r.protocol == "icmp").collect();
+        assert!(!icmp.is_empty());
+        for rule in icmp {
+            let icmp_type = rule.icmp_type.as_deref().unwrap();
+            assert!(DIAGNOSTIC_ICMP_TYPES.contains(&icmp_type), "{icmp_type}");
+        }
+        let tcp: Vec<_> = rules.iter().filter(|r| r.protocol == "tcp").collect();
+        assert!(!tcp.is_empty());
+        assert!(tcp.iter().all(|rule| rule.icmp_type.is_none()));
+
+        let mut tcp_rule = tcp[0].clone();
+        tcp_rule.icmp_type = Some("echo-request".to_string());
+        assert!(tcp_rule.validate().is_err());
+        assert!(
+            rules
+                .iter()
+                .find(|r| r.protocol == "icmp")
+                .unwrap()
+                .clone()
+                .with_icmp_type("redirect")
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_department_extraction() {
         let mut rng = ChaCha8Rng::seed_from_u64(12345);
@@ -1165,6 +1757,10 @@ fn test_generate_firewall_rules_with_invalid_vlan_config() {
             ip_network: "192.168.100.x".to_string(),
             description: "Invalid_VLAN".to_string(),
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let vlan_configs = vec![invalid_vlan];
@@ -1193,6 +1789,10 @@ fn test_generate_firewall_rules_with_invalid_network_format() {
             ip_network: "invalid.network.format".to_string(), // Invalid format
             description: "Invalid_Network_VLAN".to_string(),
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let vlan_configs = vec![invalid_vlan];
@@ -1221,6 +1821,10 @@ fn test_generate_firewall_rules_with_empty_description() {
             ip_network: "192.168.100.x".to_string(),
             description: "".to_string(), // Empty description
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let vlan_configs = vec![invalid_vlan];
@@ -1249,6 +1853,10 @@ fn test_generate_firewall_rules_with_invalid_wan_assignment() {
             ip_network: "192.168.100.x".to_string(),
             description: "Test_VLAN".to_string(),
             wan_assignment: 5, // Invalid WAN assignment > 3
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let vlan_configs = vec![invalid_vlan];
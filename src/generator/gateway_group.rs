@@ -0,0 +1,116 @@
+//! Gateway group generation for multi-WAN setups
+//!
+//! OPNsense policy routing sends traffic to a gateway group rather than a
+//! single gateway. Members on a lower tier are preferred; members sharing a
+//! tier share the load.
+
+use crate::generator::wan::WanInterface;
+use serde::{Deserialize, Serialize};
+
+/// Highest tier OPNsense accepts for a gateway group member
+pub const MAX_GATEWAY_TIER: u8 = 5;
+
+/// Condition under which a gateway group stops using a member
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GatewayTrigger {
+    /// The member's monitor stops answering
+    MemberDown,
+    /// The member exceeds its packet loss threshold
+    PacketLoss,
+    /// The member exceeds its latency threshold
+    HighLatency,
+    /// The member exceeds either threshold
+    PacketLossOrHighLatency,
+}
+
+impl GatewayTrigger {
+    /// Value written to `<trigger>`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GatewayTrigger::MemberDown => "down",
+            GatewayTrigger::PacketLoss => "downloss",
+            GatewayTrigger::HighLatency => "downlatency",
+            GatewayTrigger::PacketLossOrHighLatency => "downlosslatency",
+        }
+    }
+}
+
+/// A named group of WAN gateways with a tier per member
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GatewayGroup {
+    /// Group name referenced by firewall rules, e.g. `WAN_Failover`
+    pub name: String,
+    /// Gateway names (`WAN_GW`, ...) and their tiers (1 is preferred)
+    pub members: Vec<(String, u8)>,
+    /// When a member is taken out of the group
+    pub trigger: GatewayTrigger,
+}
+
+/// Assemble WAN gateways into a failover and a load-balance group
+///
+/// The failover group tiers the gateways by monitor priority, preferred
+/// first (tiers beyond [`MAX_GATEWAY_TIER`] share the last tier) and drops a
+/// member on packet loss or high latency. The load-balance group puts every
+/// gateway on tier 1 and only drops a member that is down. Fewer than two
+/// WANs give no groups.
+pub fn generate_gateway_groups(wans: &[WanInterface]) -> Vec<GatewayGroup> {
+    if wans.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut by_priority: Vec<&WanInterface> = wans.iter().collect();
+    by_priority.sort_by_key(|wan| (wan.monitor.as_ref().map_or(u8::MAX, |m| m.priority), wan.id));
+
+    let failover = GatewayGroup {
+        name: "WAN_Failover".to_string(),
+        members: (1u8..)
+            .zip(by_priority)
+            .map(|(tier, wan)| (wan.gateway_name(), tier.min(MAX_GATEWAY_TIER)))
+            .collect(),
+        trigger: GatewayTrigger::PacketLossOrHighLatency,
+    };
+    let load_balance = GatewayGroup {
+        name: "WAN_LoadBalance".to_string(),
+        members: wans.iter().map(|wan| (wan.gateway_name(), 1)).collect(),
+        trigger: GatewayTrigger::MemberDown,
+    };
+
+    vec![failover, load_balance]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::wan::generate_wan_interfaces;
+
+    #[test]
+    fn test_failover_and_load_balance_groups() {
+        let mut wans = generate_wan_interfaces(3, Some(42)).unwrap();
+        // Make wan3 the preferred uplink
+        wans[2].monitor.as_mut().unwrap().priority = 1;
+
+        let groups = generate_gateway_groups(&wans);
+        assert_eq!(groups.len(), 2);
+
+        let failover = &groups[0];
+        assert_eq!(failover.trigger, GatewayTrigger::PacketLossOrHighLatency);
+        assert_eq!(
+            failover.members,
+            [
+                ("WAN3_GW".to_string(), 1),
+                ("WAN_GW".to_string(), 2),
+                ("WAN2_GW".to_string(), 3),
+            ]
+        );
+
+        let load_balance = &groups[1];
+        assert_eq!(load_balance.members.len(), 3);
+        assert!(load_balance.members.iter().all(|(_, tier)| *tier == 1));
+    }
+
+    #[test]
+    fn test_single_wan_has_no_groups() {
+        let wans = generate_wan_interfaces(1, Some(42)).unwrap();
+        assert!(generate_gateway_groups(&wans).is_empty());
+    }
+}
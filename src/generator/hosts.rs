@@ -0,0 +1,265 @@
+//! Host inventory generation for endpoint simulation within VLANs
+//!
+//! Fills a VLAN's usable address range with plausible workstations,
+//! printers and servers, each with a unique MAC and an OS fitting its role.
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use crate::model::ConfigError;
+use crate::utils::mac;
+use rand::Rng;
+use rand::seq::index;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::net::Ipv4Addr;
+
+/// Role a host plays on the network
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum HostRole {
+    /// End-user desktop or laptop
+    Workstation,
+    /// Network printer or multifunction device
+    Printer,
+    /// Departmental server
+    Server,
+}
+
+impl HostRole {
+    /// Short hostname prefix for the role
+    fn hostname_prefix(self) -> &'static str {
+        match self {
+            HostRole::Workstation => "ws",
+            HostRole::Printer => "prn",
+            HostRole::Server => "srv",
+        }
+    }
+
+    /// Operating systems seen for the role, with relative weights
+    fn os_weights(self) -> &'static [(OsType, u32)] {
+        match self {
+            HostRole::Workstation => &[
+                (OsType::Windows, 60),
+                (OsType::MacOs, 25),
+                (OsType::Linux, 10),
+                (OsType::ChromeOs, 5),
+            ],
+            HostRole::Printer => &[(OsType::Embedded, 1)],
+            HostRole::Server => &[
+                (OsType::Linux, 70),
+                (OsType::Windows, 25),
+                (OsType::FreeBsd, 5),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for HostRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostRole::Workstation => write!(f, "workstation"),
+            HostRole::Printer => write!(f, "printer"),
+            HostRole::Server => write!(f, "server"),
+        }
+    }
+}
+
+/// Operating system family of a host
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OsType {
+    Windows,
+    MacOs,
+    Linux,
+    ChromeOs,
+    FreeBsd,
+    /// Vendor firmware on appliances such as printers
+    Embedded,
+}
+
+impl fmt::Display for OsType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OsType::Windows => write!(f, "Windows"),
+            OsType::MacOs => write!(f, "macOS"),
+            OsType::Linux => write!(f, "Linux"),
+            OsType::ChromeOs => write!(f, "ChromeOS"),
+            OsType::FreeBsd => write!(f, "FreeBSD"),
+            OsType::Embedded => write!(f, "Embedded"),
+        }
+    }
+}
+
+/// A single host on a VLAN
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Host {
+    /// Hostname such as `ws-it-001`
+    pub hostname: String,
+    /// MAC address in aa:bb:cc:dd:ee:ff format
+    pub mac: String,
+    /// IPv4 address within the VLAN subnet
+    pub ip: String,
+    /// Operating system family
+    pub os_type: OsType,
+    /// Role on the network
+    pub role: HostRole,
+}
+
+/// Relative weights of host roles in a typical office VLAN
+const ROLE_WEIGHTS: &[(HostRole, u32)] = &[
+    (HostRole::Workstation, 80),
+    (HostRole::Server, 15),
+    (HostRole::Printer, 5),
+];
+
+/// Pick an item from a weighted table
+fn pick_weighted<T: Copy, R: Rng + ?Sized>(rng: &mut R, table: &[(T, u32)]) -> T {
+    let total: u32 = table.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.random_range(0..total);
+    for (item, weight) in table {
+        if roll < *weight {
+            return *item;
+        }
+        roll -= weight;
+    }
+    table[table.len() - 1].0
+}
+
+/// Generate `count` hosts spread over a VLAN's usable address range
+///
+/// Addresses exclude the network, gateway (.1) and broadcast addresses and
+/// are returned in ascending order. Hostnames are numbered per role and
+/// department, e.g. `ws-it-001` and `srv-it-001`.
+pub fn generate_hosts_for_vlan<R: Rng + ?Sized>(
+    config: &VlanConfig,
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<Host>> {
+    let network = config
+        .as_ipv4_network()
+        .map_err(|e| ConfigError::validation(e.to_string()))?;
+    let base = u32::from(network.network());
+    // Usable host offsets run from 2 (after the gateway) to size - 2
+    let usable = (network.size() as usize).saturating_sub(3);
+    if count > usable {
+        return Err(ConfigError::resource_exhausted(format!(
+            "host addresses in {network}: requested {count}, only {usable} available"
+        )));
+    }
+
+    let department = config.department_label();
+
+    let mut offsets = index::sample(rng, usable, count).into_vec();
+    offsets.sort_unstable();
+
+    let mut sequence: HashMap<HostRole, u32> = HashMap::new();
+    let mut used_macs = HashSet::with_capacity(count);
+    let hosts = offsets
+        .into_iter()
+        .map(|offset| {
+            let role = pick_weighted(rng, ROLE_WEIGHTS);
+            let os_type = pick_weighted(rng, role.os_weights());
+            let number = sequence.entry(role).or_default();
+            *number += 1;
+            let mac = loop {
+                let mac = mac::generate_mac(rng, None);
+                if used_macs.insert(mac.clone()) {
+                    break mac;
+                }
+            };
+            Host {
+                hostname: format!("{}-{department}-{number:03}", role.hostname_prefix()),
+                mac,
+                ip: Ipv4Addr::from(base + 2 + offset as u32).to_string(),
+                os_type,
+                role,
+            }
+        })
+        .collect();
+
+    Ok(hosts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ipnetwork::Ipv4Network;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_hosts_are_unique_and_within_subnet() {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let hosts = generate_hosts_for_vlan(&config, 200, &mut rng).unwrap();
+        assert_eq!(hosts.len(), 200);
+
+        let subnet: Ipv4Network = "10.1.2.0/24".parse().unwrap();
+        let mut ips = HashSet::new();
+        for host in &hosts {
+            let ip: Ipv4Addr = host.ip.parse().unwrap();
+            assert!(subnet.contains(ip), "{ip} outside {subnet}");
+            assert_ne!(ip, subnet.network());
+            assert_ne!(ip, Ipv4Addr::new(10, 1, 2, 1));
+            assert_ne!(ip, subnet.broadcast());
+            assert!(ips.insert(ip), "duplicate IP {ip}");
+        }
+
+        let macs: HashSet<_> = hosts.iter().map(|h| &h.mac).collect();
+        assert_eq!(macs.len(), hosts.len());
+        let hostnames: HashSet<_> = hosts.iter().map(|h| &h.hostname).collect();
+        assert_eq!(hostnames.len(), hosts.len());
+    }
+
+    #[test]
+    fn test_hosts_fill_small_subnet() {
+        let config = VlanConfig::new_with_prefix(
+            100,
+            "10.1.2.64/29".to_string(),
+            "Sales VLAN 100".to_string(),
+            1,
+            29,
+        )
+        .unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let hosts = generate_hosts_for_vlan(&config, 5, &mut rng).unwrap();
+        let ips: Vec<_> = hosts.iter().map(|h| h.ip.as_str()).collect();
+        assert_eq!(
+            ips,
+            [
+                "10.1.2.66",
+                "10.1.2.67",
+                "10.1.2.68",
+                "10.1.2.69",
+                "10.1.2.70"
+            ]
+        );
+
+        assert!(generate_hosts_for_vlan(&config, 6, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_host_os_matches_role() {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for host in generate_hosts_for_vlan(&config, 250, &mut rng).unwrap() {
+            match host.role {
+                HostRole::Printer => assert_eq!(host.os_type, OsType::Embedded),
+                HostRole::Workstation => {
+                    assert!(!matches!(host.os_type, OsType::Embedded | OsType::FreeBsd))
+                }
+                HostRole::Server => assert!(!matches!(
+                    host.os_type,
+                    OsType::Embedded | OsType::MacOs | OsType::ChromeOs
+                )),
+            }
+            assert!(
+                host.hostname
+                    .starts_with(&format!("{}-it-", host.role.hostname_prefix()))
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Remote syslog target generation
+//!
+//! OPNsense can forward its logs to remote collectors. Each generated target
+//! points at a plausible collector host and uses the standard port for its
+//! transport: 514 for plain UDP or TCP and 6514 for syslog over TLS.
+
+use crate::Result;
+use crate::model::ConfigError;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Collector host name prefixes, combined with a number and [`LOG_DOMAIN`]
+const HOST_PREFIXES: [&str; 6] = ["syslog", "logs", "siem", "graylog", "loghost", "collector"];
+
+/// Domain of the generated collector hosts (RFC 2606 reserved)
+const LOG_DOMAIN: &str = "example.com";
+
+/// Highest number appended to a host name prefix
+const HOSTS_PER_PREFIX: usize = 99;
+
+/// Maximum number of syslog targets that can be generated
+pub const MAX_SYSLOG_TARGETS: usize = HOST_PREFIXES.len() * HOSTS_PER_PREFIX;
+
+/// Syslog facilities a target may forward
+pub const SYSLOG_FACILITIES: [&str; 14] = [
+    "kern", "user", "mail", "daemon", "auth", "syslog", "local0", "local1", "local2", "local3",
+    "local4", "local5", "local6", "local7",
+];
+
+/// Transport used to reach a syslog collector
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogTransport {
+    /// Plain UDP (RFC 5426)
+    Udp,
+    /// Plain TCP (RFC 6587)
+    Tcp,
+    /// TLS over TCP (RFC 5425)
+    Tls,
+}
+
+impl SyslogTransport {
+    /// Value written to `<transport>` (IPv4 variants)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyslogTransport::Udp => "udp4",
+            SyslogTransport::Tcp => "tcp4",
+            SyslogTransport::Tls => "tls4",
+        }
+    }
+
+    /// IANA-assigned port for the transport
+    pub fn default_port(&self) -> u16 {
+        match self {
+            SyslogTransport::Udp | SyslogTransport::Tcp => 514,
+            SyslogTransport::Tls => 6514,
+        }
+    }
+}
+
+/// A remote collector OPNsense forwards logs to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyslogTarget {
+    /// Collector host name, e.g. `siem01.example.com`
+    pub host: String,
+    /// Collector port
+    pub port: u16,
+    /// Transport to the collector
+    pub transport: SyslogTransport,
+    /// Facilities forwarded to the collector, e.g. `auth`, `local0`
+    pub facilities: Vec<String>,
+}
+
+/// Generate `count` syslog targets with distinct hosts
+///
+/// Most targets use UDP, as most collectors do; the rest use TLS or TCP.
+/// Each forwards one to three facilities on its transport's standard port.
+pub fn generate_syslog_targets(count: u16, seed: Option<u64>) -> Result<Vec<SyslogTarget>> {
+    if usize::from(count) > MAX_SYSLOG_TARGETS {
+        return Err(ConfigError::resource_exhausted(format!(
+            "syslog targets (requested {count}, maximum {MAX_SYSLOG_TARGETS})"
+        )));
+    }
+
+    let mut rng: Box<dyn RngCore> = if let Some(seed) = seed {
+        Box::new(StdRng::seed_from_u64(seed))
+    } else {
+        Box::new(StdRng::from_rng(&mut rand::rng()))
+    };
+
+    let hosts = (0..MAX_SYSLOG_TARGETS).choose_multiple(&mut rng, usize::from(count));
+    Ok(hosts
+        .into_iter()
+        .map(|host| {
+            let prefix = HOST_PREFIXES[host % HOST_PREFIXES.len()];
+            let number = host / HOST_PREFIXES.len() + 1;
+            let transport = match rng.random_range(0..10) {
+                0..6 => SyslogTransport::Udp,
+                6..9 => SyslogTransport::Tls,
+                _ => SyslogTransport::Tcp,
+            };
+            let facility_count = rng.random_range(1..=3);
+            let mut facilities: Vec<String> = SYSLOG_FACILITIES
+                .choose_multiple(&mut rng, facility_count)
+                .map(|facility| facility.to_string())
+                .collect();
+            facilities.sort();
+
+            SyslogTarget {
+                host: format!("{prefix}{number:02}.{LOG_DOMAIN}"),
+                port: transport.default_port(),
+                transport,
+                facilities,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_two_targets_have_distinct_hosts_and_valid_transports() {
+        let targets = generate_syslog_targets(2, Some(42)).unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_ne!(targets[0].host, targets[1].host);
+
+        for target in &targets {
+            assert!(["udp4", "tcp4", "tls4"].contains(&target.transport.as_str()));
+            assert_eq!(target.port, target.transport.default_port());
+            assert!((1..=3).contains(&target.facilities.len()));
+            assert!(
+                target
+                    .facilities
+                    .iter()
+                    .all(|facility| SYSLOG_FACILITIES.contains(&facility.as_str()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_hosts_stay_distinct_up_to_the_maximum() {
+        let targets = generate_syslog_targets(MAX_SYSLOG_TARGETS as u16, Some(7)).unwrap();
+        let hosts: HashSet<_> = targets.iter().map(|target| &target.host).collect();
+        assert_eq!(hosts.len(), MAX_SYSLOG_TARGETS);
+        assert!(generate_syslog_targets(MAX_SYSLOG_TARGETS as u16 + 1, Some(7)).is_err());
+    }
+}
@@ -1,14 +1,49 @@
 //! Data generation modules for network configurations
 
+pub mod alias;
+pub mod bridge;
+pub mod builder;
+pub mod captive_portal;
 pub mod departments;
+pub mod dns;
 pub mod firewall;
+pub mod gateway_group;
+pub mod hosts;
+pub mod logging;
 pub mod nat;
+pub mod network_pool;
+pub mod p2p;
 pub mod performance;
+pub mod shaper;
+pub mod stats;
+pub mod users;
 pub mod vlan;
 pub mod vpn;
+pub mod wan;
 
-pub use firewall::{FirewallComplexity, FirewallGenerator, FirewallRule, generate_firewall_rules};
-pub use nat::{NatGenerator, NatMapping, NatRuleType, generate_nat_mappings};
+pub use alias::{Alias, AliasType, generate_aliases};
+pub use bridge::{Bridge, generate_bridges};
+pub use builder::GenerationBuilder;
+pub use captive_portal::{CaptivePortalZone, generate_captive_portal_zones, needs_captive_portal};
+pub use dns::{DnsRecord, DnsRecordType, generate_dns_records};
+pub use firewall::{
+    DenyAction, DepartmentProfile, DepartmentRules, FirewallComplexity, FirewallGenerator,
+    FirewallPolicy, FirewallProfile, FirewallRule, IpVersion, generate_firewall_rules,
+    generate_firewall_rules_with_policy,
+};
+pub use gateway_group::{GatewayGroup, GatewayTrigger, generate_gateway_groups};
+pub use hosts::{Host, HostRole, OsType, generate_hosts_for_vlan};
+pub use logging::{SyslogTarget, SyslogTransport, generate_syslog_targets};
+pub use nat::{NatConstraints, NatGenerator, NatMapping, NatRuleType, generate_nat_mappings};
+pub use network_pool::allocate_networks;
+pub use p2p::{PointToPointLink, generate_p2p_links, generate_p2p_links_avoiding};
 pub use performance::{PerformanceMetrics, PerformantConfigGenerator};
-pub use vlan::{VlanConfig, VlanGenerator};
-pub use vpn::{VpnConfig, VpnGenerator, VpnType, generate_vpn_configurations};
+pub use shaper::{Scheduler, ShaperPipe, ShaperQueue, TrafficShaper, generate_shapers};
+pub use stats::GenerationStats;
+pub use users::{Group, User, UserAccounts, UserScope, generate_users};
+pub use vlan::{
+    DescriptionTemplate, DhcpOptions, DhcpRangePolicy, SortKey, VlanConfig, VlanGenerator,
+    VlanGeneratorIter, shuffle_configs,
+};
+pub use vpn::{VpnConfig, VpnGenerator, VpnType, WireGuardPeer, generate_vpn_configurations};
+pub use wan::{WanInterface, WanMonitor, generate_wan_interfaces};
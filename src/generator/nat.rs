@@ -3,11 +3,12 @@
 //! This module provides functionality to generate realistic NAT (Network Address Translation)
 //! mappings including port forwarding, source NAT, and destination NAT rules.
 
+use crate::generator::VlanConfig;
 use crate::model::ConfigError;
+use crate::utils::seed::{random_short_id, random_uuid};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use uuid::Uuid;
 
 /// Result type for NAT generation operations
 pub type NatResult<T> = Result<T, ConfigError>;
@@ -64,6 +65,7 @@ impl NatMapping {
     /// Create a new NAT mapping with validation
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        id: String,
         rule_type: NatRuleType,
         name: String,
         source: String,
@@ -79,7 +81,7 @@ pub fn new(
         vlan_id: Option<u16>,
     ) -> NatResult<Self> {
         let mapping = Self {
-            id: Uuid::new_v4().to_string(),
+            id,
             rule_type,
             name,
             source,
@@ -183,11 +185,76 @@ fn is_valid_port_range(&self, port_range: &str) -> bool {
     }
 }
 
+/// Protocols of the generated rules that carry ports
+const PORT_PROTOCOLS: &[&str] = &["TCP", "UDP", "Both"];
+
+/// Allow-list restricting the protocols and ports of generated NAT rules
+///
+/// An empty list leaves that dimension unrestricted. Port restrictions apply
+/// to destination and target ports, so with ports set the generator only
+/// picks rule types and protocols that carry ports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NatConstraints {
+    /// Allowed protocols: TCP, UDP, Both or ICMP, case-insensitive
+    pub protocols: Vec<String>,
+    /// Allowed destination and target ports
+    pub ports: Vec<u16>,
+}
+
+impl NatConstraints {
+    /// Whether neither protocols nor ports are restricted
+    pub fn is_unrestricted(&self) -> bool {
+        self.protocols.is_empty() && self.ports.is_empty()
+    }
+
+    /// Spell protocols the way [`NatMapping`] does and drop duplicates
+    fn normalized(self) -> NatResult<Self> {
+        let mut protocols: Vec<String> = Vec::with_capacity(self.protocols.len());
+        for protocol in &self.protocols {
+            let name = match protocol.trim().to_ascii_lowercase().as_str() {
+                "tcp" => "TCP",
+                "udp" => "UDP",
+                "both" | "tcp/udp" => "Both",
+                "icmp" => "ICMP",
+                _ => {
+                    return Err(ConfigError::invalid_parameter(
+                        "nat_protocols",
+                        format!("'{protocol}' is not one of TCP, UDP, Both or ICMP"),
+                    ));
+                }
+            };
+            if !protocols.iter().any(|p| p == name) {
+                protocols.push(name.to_string());
+            }
+        }
+
+        let mut ports = self.ports;
+        if let Some(port) = ports.iter().find(|&&port| port == 0) {
+            return Err(ConfigError::invalid_parameter(
+                "nat_ports",
+                format!("port {port} is not a valid service port"),
+            ));
+        }
+        let mut seen = HashSet::new();
+        ports.retain(|port| seen.insert(*port));
+
+        if !ports.is_empty() && !protocols.is_empty() && protocols.iter().all(|p| p == "ICMP") {
+            return Err(ConfigError::invalid_parameter(
+                "nat_ports",
+                "ICMP rules carry no ports; allow TCP, UDP or Both alongside ports",
+            ));
+        }
+
+        Ok(Self { protocols, ports })
+    }
+}
+
 /// NAT mapping generator with realistic configurations
 pub struct NatGenerator {
     rng: Box<dyn RngCore>,
     used_names: HashSet<String>,
     used_external_ports: HashSet<u16>,
+    constraints: NatConstraints,
 }
 
 impl NatGenerator {
@@ -208,9 +275,19 @@ pub fn new_with_seed(seed: Option<u64>) -> Self {
             rng,
             used_names: HashSet::new(),
             used_external_ports: HashSet::new(),
+            constraints: NatConstraints::default(),
         }
     }
 
+    /// Only generate rules allowed by `constraints`
+    ///
+    /// External ports stay unique while the port allow-list has unused
+    /// entries; once it is used up, port-forwards reuse allowed ports.
+    pub fn with_constraints(mut self, constraints: NatConstraints) -> NatResult<Self> {
+        self.constraints = constraints.normalized()?;
+        Ok(self)
+    }
+
     /// Generate a single NAT mapping
     pub fn generate_single(&mut self, rule_type: Option<NatRuleType>) -> NatResult<NatMapping> {
         let rule_type = rule_type.unwrap_or_else(|| self.random_nat_type());
@@ -229,7 +306,9 @@ pub fn generate_single(&mut self, rule_type: Option<NatRuleType>) -> NatResult<N
             None
         };
 
-        NatMapping::new(
+        let id = random_uuid(&mut self.rng).to_string();
+        let mapping = NatMapping::new(
+            id,
             rule_type,
             name,
             source,
@@ -243,7 +322,8 @@ pub fn generate_single(&mut self, rule_type: Option<NatRuleType>) -> NatResult<N
             enabled,
             log,
             vlan_id,
-        )
+        )?;
+        Ok(mapping)
     }
 
     /// Generate multiple NAT mappings
@@ -258,8 +338,62 @@ pub fn generate_batch(&mut self, count: u16) -> NatResult<Vec<NatMapping>> {
         Ok(mappings)
     }
 
+    /// Generate port-forwards to the static DHCP reservation hosts of `configs`
+    ///
+    /// Each reservation gets one WAN port-forward with a unique external port,
+    /// targeting the reservation's IP and tagged with its VLAN ID. VLANs whose
+    /// subnets are too small to hold reservations contribute no rules.
+    pub fn generate_for_vlans(&mut self, configs: &[VlanConfig]) -> NatResult<Vec<NatMapping>> {
+        let mut mappings = Vec::new();
+
+        for config in configs {
+            for reservation in config.static_reservations(&[])? {
+                let name = format!(
+                    "Port-Forward-VLAN{}-{}",
+                    config.vlan_id, reservation.hostname
+                );
+                // An ICMP-only allow-list leaves nothing to port-forward
+                let Some(protocol) = self.random_port_protocol() else {
+                    return Ok(Vec::new());
+                };
+                self.used_names.insert(name.clone());
+                let external_port = self.generate_unique_external_port()?;
+                let target_port = self.generate_service_port();
+
+                let id = random_uuid(&mut self.rng).to_string();
+                let mapping = NatMapping::new(
+                    id,
+                    NatRuleType::PortForward,
+                    name,
+                    "any".to_string(),
+                    "any".to_string(),
+                    "any".to_string(),
+                    external_port.to_string(),
+                    protocol,
+                    "WAN".to_string(),
+                    reservation.ip_addr,
+                    target_port,
+                    true,
+                    self.rng.random_bool(0.3),
+                    Some(config.vlan_id),
+                )?;
+                mappings.push(mapping);
+            }
+        }
+
+        Ok(mappings)
+    }
+
     /// Generate a random NAT rule type
     fn random_nat_type(&mut self) -> NatRuleType {
+        if !self.constraints.ports.is_empty() {
+            // Only these rule types carry destination ports
+            return if self.rng.random_bool(0.5) {
+                NatRuleType::PortForward
+            } else {
+                NatRuleType::DestinationNat
+            };
+        }
         match self.rng.random_range(0..5) {
             0 => NatRuleType::PortForward,
             1 => NatRuleType::SourceNat,
@@ -329,12 +463,21 @@ fn generate_unique_name(&mut self, rule_type: &NatRuleType) -> String {
                 NatRuleType::OneToOneNat => "1to1-NAT",
                 NatRuleType::OutboundNat => "Outbound",
             },
-            Uuid::new_v4().to_string().split('-').next().unwrap()
+            random_short_id(&mut self.rng)
         )
     }
 
     /// Generate random protocol
     fn random_protocol(&mut self) -> String {
+        if !self.constraints.ports.is_empty() {
+            // Constraints are validated to allow a protocol that carries ports
+            return self
+                .random_port_protocol()
+                .unwrap_or_else(|| "TCP".to_string());
+        }
+        if let Some(protocol) = self.constraints.protocols.choose(&mut self.rng) {
+            return protocol.clone();
+        }
         match self.rng.random_range(0..4) {
             0 => "TCP",
             1 => "UDP",
@@ -344,6 +487,24 @@ fn random_protocol(&mut self) -> String {
         .to_string()
     }
 
+    /// Generate a random protocol that carries ports, if any is allowed
+    fn random_port_protocol(&mut self) -> Option<String> {
+        if self.constraints.protocols.is_empty() {
+            return Some(
+                PORT_PROTOCOLS[self.rng.random_range(0..PORT_PROTOCOLS.len())].to_string(),
+            );
+        }
+        let allowed: Vec<&String> = self
+            .constraints
+            .protocols
+            .iter()
+            .filter(|protocol| protocol.as_str() != "ICMP")
+            .collect();
+        allowed
+            .choose(&mut self.rng)
+            .map(|protocol| protocol.to_string())
+    }
+
     /// Generate source address and port based on rule type
     fn generate_source(&mut self, rule_type: &NatRuleType) -> (String, String) {
         match rule_type {
@@ -458,6 +619,25 @@ fn generate_unique_external_port(&mut self) -> NatResult<u16> {
         const COMMON_PORTS: &[u16] = &[80, 443, 22, 21, 25, 53, 110, 143, 993, 995, 3389, 5900];
         const MAX_ATTEMPTS: usize = 100;
 
+        if !self.constraints.ports.is_empty() {
+            let unused = self
+                .constraints
+                .ports
+                .iter()
+                .copied()
+                .find(|port| !self.used_external_ports.contains(port));
+            let port = match unused {
+                Some(port) => port,
+                None => *self
+                    .constraints
+                    .ports
+                    .choose(&mut self.rng)
+                    .expect("port allow-list is not empty"),
+            };
+            self.used_external_ports.insert(port);
+            return Ok(port);
+        }
+
         // Try common ports first
         for &port in COMMON_PORTS {
             if self.used_external_ports.insert(port) {
@@ -502,6 +682,10 @@ fn generate_service_port(&mut self) -> String {
             ("8443", "HTTPS Alt"),
         ];
 
+        if let Some(port) = self.constraints.ports.choose(&mut self.rng) {
+            return port.to_string();
+        }
+
         if self.rng.random_bool(0.8) {
             // Use common service port
             let (port, _service) = common_services[self.rng.random_range(0..common_services.len())];
@@ -523,9 +707,10 @@ fn default() -> Self {
 pub fn generate_nat_mappings(
     count: u16,
     seed: Option<u64>,
-    progress_bar: Option<&indicatif::ProgressBar>,
+    constraints: NatConstraints,
+    progress_bar: Option<&dyn crate::utils::progress::ProgressSink>,
 ) -> NatResult<Vec<NatMapping>> {
-    let mut generator = NatGenerator::new_with_seed(seed);
+    let mut generator = NatGenerator::new_with_seed(seed).with_constraints(constraints)?;
     let mut mappings = Vec::with_capacity(count as usize);
 
     for i in 0..count {
@@ -547,6 +732,7 @@ mod tests {
     #[test]
     fn test_nat_mapping_creation() {
         let mapping = NatMapping::new(
+            "nat-1".to_string(),
             NatRuleType::PortForward,
             "Web-Server-Forward".to_string(),
             "any".to_string(),
@@ -572,6 +758,7 @@ fn test_nat_mapping_creation() {
     #[test]
     fn test_nat_mapping_validation_invalid_protocol() {
         let mapping = NatMapping::new(
+            "nat-1".to_string(),
             NatRuleType::PortForward,
             "Test-Forward".to_string(),
             "any".to_string(),
@@ -599,6 +786,7 @@ fn test_nat_mapping_validation_invalid_protocol() {
     #[test]
     fn test_nat_mapping_validation_invalid_vlan() {
         let mapping = NatMapping::new(
+            "nat-1".to_string(),
             NatRuleType::PortForward,
             "Test-Forward".to_string(),
             "any".to_string(),
@@ -655,6 +843,83 @@ fn test_nat_generator_batch() {
         }
     }
 
+    #[test]
+    fn test_generate_for_vlans_targets_reservations() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.1.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.1.2.x".to_string(), "Sales VLAN 200".to_string(), 2).unwrap(),
+            VlanConfig::new(300, "10.1.3.x".to_string(), "HR VLAN 300".to_string(), 3).unwrap(),
+        ];
+        let mappings = NatGenerator::new_with_seed(Some(42))
+            .generate_for_vlans(&configs)
+            .unwrap();
+
+        let expected: usize = configs
+            .iter()
+            .map(|config| config.static_reservations(&[]).unwrap().len())
+            .sum();
+        assert_eq!(mappings.len(), expected);
+
+        for mapping in &mappings {
+            assert_eq!(mapping.rule_type, NatRuleType::PortForward);
+            let config = configs
+                .iter()
+                .find(|config| Some(config.vlan_id) == mapping.vlan_id)
+                .expect("mapping is tagged with one of the VLANs");
+            let reservation_ips: Vec<String> = config
+                .static_reservations(&[])
+                .unwrap()
+                .into_iter()
+                .map(|reservation| reservation.ip_addr)
+                .collect();
+            assert!(
+                reservation_ips.contains(&mapping.target_ip),
+                "{} is not a reservation of VLAN {}",
+                mapping.target_ip,
+                config.vlan_id
+            );
+        }
+
+        let ports: HashSet<_> = mappings.iter().map(|m| &m.destination_port).collect();
+        assert_eq!(ports.len(), mappings.len());
+    }
+
+    #[test]
+    fn test_constraints_restrict_protocol_and_port() {
+        let constraints = NatConstraints {
+            protocols: vec!["tcp".to_string()],
+            ports: vec![443],
+        };
+        let mappings = generate_nat_mappings(50, Some(42), constraints.clone(), None).unwrap();
+        assert_eq!(mappings.len(), 50);
+        for mapping in &mappings {
+            assert_eq!(mapping.protocol, "TCP", "{}", mapping.name);
+            assert_eq!(mapping.destination_port, "443", "{}", mapping.name);
+            assert_eq!(mapping.target_port, "443", "{}", mapping.name);
+        }
+
+        let configs = vec![
+            VlanConfig::new(100, "10.1.1.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+        ];
+        let forwards = NatGenerator::new_with_seed(Some(42))
+            .with_constraints(constraints)
+            .unwrap()
+            .generate_for_vlans(&configs)
+            .unwrap();
+        assert!(!forwards.is_empty());
+        assert!(
+            forwards
+                .iter()
+                .all(|m| m.protocol == "TCP" && m.target_port == "443")
+        );
+
+        let bad = NatConstraints {
+            protocols: vec!["sctp".to_string()],
+            ports: Vec::new(),
+        };
+        assert!(NatGenerator::new().with_constraints(bad).is_err());
+    }
+
     #[test]
     fn test_port_validation() {
         let mapping = NatMapping {
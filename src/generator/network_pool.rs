@@ -0,0 +1,155 @@
+//! Allocation of unique private networks without full VLAN configurations
+//!
+//! Useful when only address space is needed, e.g. to seed IPAM or another
+//! tool with subnets. Networks are claimed with the same uniqueness rules as
+//! [`VlanGenerator`], so a pool never repeats or overlaps a network.
+
+use crate::Result;
+use crate::generator::VlanGenerator;
+use crate::model::ConfigError;
+use crate::utils::rfc1918::Rfc1918Class;
+use ipnetwork::Ipv4Network;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Allocate `count` unique /24 networks from an RFC 1918 class
+///
+/// The same seed always yields the same networks. Fails with
+/// `ResourceExhausted` when the class cannot supply `count` networks.
+pub fn allocate_networks(
+    count: usize,
+    class: Rfc1918Class,
+    seed: Option<u64>,
+) -> Result<Vec<Ipv4Network>> {
+    let mut generator = VlanGenerator::new_with_std_rng(seed).with_network_class(class);
+    allocate_from(&mut generator, count)
+}
+
+/// Allocate `count` networks from a configured generator
+///
+/// Honours the generator's class, supernet, prefix length, exclusions and
+/// retry budget, and never returns a network it has handed out before.
+pub fn allocate_from(generator: &mut VlanGenerator, count: usize) -> Result<Vec<Ipv4Network>> {
+    let max_attempts = generator.max_attempts();
+    (0..count)
+        .map(|_| generator.generate_unique_network(max_attempts))
+        .collect()
+}
+
+/// Number of `/prefix` subnets that fit in `supernet`
+///
+/// Fails when `prefix` is shorter than the supernet's own prefix or longer
+/// than /30, the smallest network a VLAN can use.
+pub fn available_subnets(supernet: Ipv4Network, prefix: u8) -> Result<usize> {
+    if prefix < supernet.prefix() || prefix > 30 {
+        return Err(ConfigError::invalid_parameter(
+            "prefix",
+            format!(
+                "/{prefix} subnets do not fit in supernet {supernet} (expected /{}-/30)",
+                supernet.prefix()
+            ),
+        ));
+    }
+    Ok(1usize << (prefix - supernet.prefix()))
+}
+
+/// Load predetermined networks, one CIDR per line, e.g. from an IPAM export
+///
+/// Blank lines and lines starting with `#` are skipped.
+pub fn load_networks_file<P: AsRef<Path>>(path: P) -> Result<Vec<Ipv4Network>> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => ConfigError::ConfigNotFound {
+            path: path.display().to_string(),
+        },
+        _ => ConfigError::Io(e),
+    })?;
+
+    let networks = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            line.parse::<Ipv4Network>().map_err(|e| {
+                ConfigError::invalid_parameter(
+                    "networks_file",
+                    format!(
+                        "{}:{line_number}: '{line}' is not a CIDR: {e}",
+                        path.display()
+                    ),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if networks.is_empty() {
+        return Err(ConfigError::invalid_parameter(
+            "networks_file",
+            format!("{} contains no networks", path.display()),
+        ));
+    }
+
+    Ok(networks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rfc1918::is_rfc1918_network;
+    use std::collections::HashSet;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_networks_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "# IPAM export\n10.20.0.0/24\n\n172.16.5.64/26").unwrap();
+        let networks = load_networks_file(file.path()).unwrap();
+        assert_eq!(
+            networks,
+            [
+                "10.20.0.0/24".parse().unwrap(),
+                "172.16.5.64/26".parse().unwrap()
+            ]
+        );
+
+        writeln!(file, "not-a-network").unwrap();
+        let error = load_networks_file(file.path()).unwrap_err().to_string();
+        assert!(error.contains(":5:"), "{error}");
+    }
+
+    #[test]
+    fn test_available_subnets() {
+        let supernet: Ipv4Network = "10.50.0.0/22".parse().unwrap();
+        assert_eq!(available_subnets(supernet, 24).unwrap(), 4);
+        assert_eq!(available_subnets(supernet, 22).unwrap(), 1);
+        assert_eq!(
+            available_subnets("10.0.0.0/8".parse().unwrap(), 24).unwrap(),
+            65536
+        );
+        assert!(available_subnets(supernet, 21).is_err());
+        assert!(available_subnets(supernet, 31).is_err());
+    }
+
+    #[test]
+    fn test_allocate_networks_unique_private_and_disjoint() {
+        let networks = allocate_networks(50, Rfc1918Class::Mixed, Some(42)).unwrap();
+        assert_eq!(networks.len(), 50);
+
+        let unique: HashSet<_> = networks.iter().collect();
+        assert_eq!(unique.len(), 50);
+        assert!(networks.iter().all(is_rfc1918_network));
+        for (i, a) in networks.iter().enumerate() {
+            for b in &networks[i + 1..] {
+                assert!(!a.overlaps(*b), "{a} overlaps {b}");
+            }
+        }
+
+        assert_eq!(
+            networks,
+            allocate_networks(50, Rfc1918Class::Mixed, Some(42)).unwrap()
+        );
+    }
+}
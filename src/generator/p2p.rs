@@ -0,0 +1,157 @@
+//! Point-to-point link addressing for transit and tunnel interfaces
+//!
+//! Each link gets its own /31 (RFC 3021), so both addresses are usable hosts.
+//! Links are carved from 10.0.0.0/16. Random Class A VLAN networks start at
+//! 10.1.0.0, but a supernet, networks file or short prefix can still place
+//! VLANs in that block, so [`generate_p2p_links_avoiding`] skips every /31
+//! overlapping the networks it is given.
+
+use crate::Result;
+use crate::model::ConfigError;
+use ipnetwork::Ipv4Network;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// Block the /31 link subnets are carved from
+const TRANSIT_BLOCK: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 0);
+
+/// Prefix length of the transit block
+const TRANSIT_BLOCK_PREFIX: u8 = 16;
+
+/// Prefix length of every link subnet
+pub const P2P_PREFIX: u8 = 31;
+
+/// Maximum number of links that fit in the transit block
+pub const MAX_P2P_LINKS: usize = 1 << (P2P_PREFIX - TRANSIT_BLOCK_PREFIX);
+
+/// The two ends of a /31 point-to-point link
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PointToPointLink {
+    /// Address on this side, the lower address of the /31
+    pub local_ip: String,
+    /// Address of the far end, the higher address of the /31
+    pub peer_ip: String,
+    /// Prefix length of the link subnet
+    pub prefix: u8,
+}
+
+/// Generate `count` links on distinct /31 subnets
+pub fn generate_p2p_links(count: u16, seed: Option<u64>) -> Result<Vec<PointToPointLink>> {
+    generate_p2p_links_avoiding(count, seed, &[])
+}
+
+/// Generate `count` links on distinct /31 subnets overlapping none of
+/// `reserved`, e.g. the VLAN networks and excluded ranges of the same run
+pub fn generate_p2p_links_avoiding(
+    count: u16,
+    seed: Option<u64>,
+    reserved: &[Ipv4Network],
+) -> Result<Vec<PointToPointLink>> {
+    if usize::from(count) > MAX_P2P_LINKS {
+        return Err(ConfigError::resource_exhausted(format!(
+            "point-to-point links (requested {count}, maximum {MAX_P2P_LINKS})"
+        )));
+    }
+
+    let block = u32::from(TRANSIT_BLOCK);
+    let last = block + (MAX_P2P_LINKS as u32 * 2 - 1);
+    let mut taken = vec![false; MAX_P2P_LINKS];
+    for network in reserved {
+        let start = u32::from(network.network()).max(block);
+        let end = u32::from(network.broadcast()).min(last);
+        if start <= end {
+            let (first, last) = ((start - block) / 2, (end - block) / 2);
+            taken[first as usize..=last as usize].fill(true);
+        }
+    }
+    let free: Vec<u32> = (0..MAX_P2P_LINKS as u32)
+        .filter(|&subnet| !taken[subnet as usize])
+        .collect();
+    if usize::from(count) > free.len() {
+        return Err(ConfigError::resource_exhausted(format!(
+            "point-to-point links (requested {count}, but only {} /31 subnets of {TRANSIT_BLOCK}/{TRANSIT_BLOCK_PREFIX} are clear of VLAN and excluded networks)",
+            free.len()
+        )));
+    }
+
+    let mut rng: Box<dyn RngCore> = if let Some(seed) = seed {
+        Box::new(StdRng::seed_from_u64(seed))
+    } else {
+        Box::new(StdRng::from_rng(&mut rand::rng()))
+    };
+
+    Ok(free
+        .into_iter()
+        .choose_multiple(&mut rng, usize::from(count))
+        .into_iter()
+        .map(|subnet| {
+            let network = block + subnet * 2;
+            PointToPointLink {
+                local_ip: Ipv4Addr::from(network).to_string(),
+                peer_ip: Ipv4Addr::from(network + 1).to_string(),
+                prefix: P2P_PREFIX,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rfc1918::is_rfc1918_addr;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_five_links_are_unique_adjacent_pairs() {
+        let links = generate_p2p_links(5, Some(42)).unwrap();
+        assert_eq!(links.len(), 5);
+
+        let mut addresses = HashSet::new();
+        for link in &links {
+            let local: Ipv4Addr = link.local_ip.parse().unwrap();
+            let peer: Ipv4Addr = link.peer_ip.parse().unwrap();
+            assert_eq!(link.prefix, P2P_PREFIX);
+            assert_eq!(local.octets()[..3], peer.octets()[..3]);
+            assert_eq!(local.octets()[3] + 1, peer.octets()[3]);
+            assert!(is_rfc1918_addr(local) && is_rfc1918_addr(peer));
+            assert!(addresses.insert(local) && addresses.insert(peer));
+        }
+    }
+
+    #[test]
+    fn test_links_avoid_reserved_networks() {
+        // Leave a single /24 of the transit block free
+        let reserved: Vec<Ipv4Network> = vec![
+            "10.0.0.0/17".parse().unwrap(),
+            "10.0.128.0/18".parse().unwrap(),
+            "10.0.192.0/19".parse().unwrap(),
+            "10.0.224.0/20".parse().unwrap(),
+            "10.0.240.0/21".parse().unwrap(),
+            "10.0.248.0/22".parse().unwrap(),
+            "10.0.252.0/23".parse().unwrap(),
+            "10.0.254.0/24".parse().unwrap(),
+            "192.168.1.0/24".parse().unwrap(),
+        ];
+        let links = generate_p2p_links_avoiding(128, Some(42), &reserved).unwrap();
+        assert_eq!(links.len(), 128);
+        assert!(
+            links
+                .iter()
+                .all(|link| link.local_ip.starts_with("10.0.255."))
+        );
+
+        assert!(generate_p2p_links_avoiding(129, Some(42), &reserved).is_err());
+
+        // Without overlap the seeded links are unchanged
+        assert_eq!(
+            generate_p2p_links_avoiding(5, Some(42), &reserved[8..]).unwrap(),
+            generate_p2p_links(5, Some(42)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_link_count_is_capped() {
+        assert!(generate_p2p_links(MAX_P2P_LINKS as u16 + 1, Some(42)).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Traffic shaper generation for per-VLAN QoS
+//!
+//! Each VLAN gets a pipe capping its bandwidth and a queue inside that pipe.
+//! Bandwidth tiers follow the department in the VLAN description, so
+//! engineering networks get more headroom than guest networks.
+
+use crate::generator::VlanConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Packet scheduler used inside a pipe
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Scheduler {
+    /// First in, first out
+    Fifo,
+    /// Quick fair queueing
+    Qfq,
+    /// Fair queueing with controlled delay, suited to many small flows
+    FqCodel,
+}
+
+impl fmt::Display for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scheduler::Fifo => write!(f, "fifo"),
+            Scheduler::Qfq => write!(f, "qfq"),
+            Scheduler::FqCodel => write!(f, "fq_codel"),
+        }
+    }
+}
+
+/// A shaper pipe limiting a VLAN's total bandwidth
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ShaperPipe {
+    /// Pipe number, starting at 1
+    pub pipe_id: u16,
+    /// Bandwidth cap in Mbit/s
+    pub bandwidth_mbit: u32,
+    /// Scheduler for traffic within the pipe
+    pub scheduler: Scheduler,
+    /// Human-readable description
+    pub description: String,
+}
+
+/// A shaper queue sharing a pipe's bandwidth
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ShaperQueue {
+    /// Pipe the queue belongs to
+    pub pipe_id: u16,
+    /// Share of the pipe relative to other queues (1-100)
+    pub weight: u8,
+    /// Scheduling priority, 1 (highest) to 5 (lowest)
+    pub priority: u8,
+    /// Human-readable description
+    pub description: String,
+}
+
+/// Pipes and queues generated for a set of VLANs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrafficShaper {
+    pub pipes: Vec<ShaperPipe>,
+    pub queues: Vec<ShaperQueue>,
+}
+
+/// Bandwidth tier as (Mbit/s, scheduler, queue weight, priority)
+type Tier = (u32, Scheduler, u8, u8);
+
+/// Tiers keyed by the department names they apply to
+const DEPARTMENT_TIERS: &[(&[&str], Tier)] = &[
+    (
+        &["IT", "Engineering", "Development", "Security"],
+        (1000, Scheduler::Qfq, 80, 1),
+    ),
+    (
+        &["Management", "Finance", "Research", "Operations"],
+        (500, Scheduler::Qfq, 60, 2),
+    ),
+    (&["Guest", "Training"], (50, Scheduler::FqCodel, 10, 5)),
+];
+
+/// Tier for departments not listed in [`DEPARTMENT_TIERS`]
+const DEFAULT_TIER: Tier = (200, Scheduler::Fifo, 40, 3);
+
+/// Bandwidth tier for a VLAN, from the department named in its description
+fn tier_for(config: &VlanConfig) -> Tier {
+    let department = config.department();
+    DEPARTMENT_TIERS
+        .iter()
+        .find(|(departments, _)| departments.contains(&department))
+        .map_or(DEFAULT_TIER, |(_, tier)| *tier)
+}
+
+/// Generate one pipe and one queue per VLAN
+pub fn generate_shapers(configs: &[VlanConfig]) -> TrafficShaper {
+    let mut shaper = TrafficShaper::default();
+
+    for (config, pipe_id) in configs.iter().zip(1u16..) {
+        let (bandwidth_mbit, scheduler, weight, priority) = tier_for(config);
+        shaper.pipes.push(ShaperPipe {
+            pipe_id,
+            bandwidth_mbit,
+            scheduler,
+            description: format!("{} bandwidth", config.description),
+        });
+        shaper.queues.push(ShaperQueue {
+            pipe_id,
+            weight,
+            priority,
+            description: format!("{} traffic", config.description),
+        });
+    }
+
+    shaper
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_pipe_per_vlan_with_department_tiers() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Guest VLAN 200".to_string(), 2).unwrap(),
+            VlanConfig::new(300, "10.5.6.x".to_string(), "Sales VLAN 300".to_string(), 3).unwrap(),
+        ];
+
+        let shaper = generate_shapers(&configs);
+        assert_eq!(shaper.pipes.len(), configs.len());
+        assert_eq!(shaper.queues.len(), configs.len());
+
+        let pipe_ids: Vec<_> = shaper.pipes.iter().map(|pipe| pipe.pipe_id).collect();
+        assert_eq!(pipe_ids, [1, 2, 3]);
+        for (pipe, queue) in shaper.pipes.iter().zip(&shaper.queues) {
+            assert_eq!(pipe.pipe_id, queue.pipe_id);
+        }
+
+        let it = &shaper.pipes[0];
+        let guest = &shaper.pipes[1];
+        assert_ne!(it.bandwidth_mbit, guest.bandwidth_mbit);
+        assert!(it.bandwidth_mbit > guest.bandwidth_mbit);
+        assert_eq!(guest.scheduler, Scheduler::FqCodel);
+        assert!(shaper.queues[0].priority < shaper.queues[1].priority);
+        assert_eq!(shaper.pipes[2].bandwidth_mbit, DEFAULT_TIER.0);
+
+        // Templated descriptions map to the same tier
+        let templated =
+            VlanConfig::new(400, "10.7.8.x".to_string(), "IT_400".to_string(), 1).unwrap();
+        assert_eq!(tier_for(&templated), tier_for(&configs[0]));
+    }
+}
@@ -0,0 +1,97 @@
+//! Structured statistics about generated VLAN configurations
+//!
+//! The CLI renders its summary from these, and tests or downstream tools can
+//! inspect them directly instead of parsing output.
+
+use crate::generator::VlanConfig;
+use crate::utils::rfc1918::Rfc1918Class;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+/// Summary of a generated set of VLAN configurations
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GenerationStats {
+    /// Number of configurations generated
+    pub total: usize,
+    /// Number of distinct VLAN IDs
+    pub unique_vlan_ids: usize,
+    /// Networks per RFC 1918 class
+    pub networks_by_class: BTreeMap<Rfc1918Class, usize>,
+    /// Configurations per department
+    pub departments: BTreeMap<String, usize>,
+    /// Lowest and highest VLAN ID, if any configurations were generated
+    pub vlan_id_range: Option<(u16, u16)>,
+    /// Wall-clock generation time in milliseconds
+    pub duration_ms: u64,
+    #[serde(skip)]
+    seen_vlan_ids: HashSet<u16>,
+}
+
+impl GenerationStats {
+    /// Collect statistics for a finished set of configurations
+    pub fn from_configs(configs: &[VlanConfig]) -> Self {
+        let mut stats = Self::default();
+        for config in configs {
+            stats.record(config);
+        }
+        stats
+    }
+
+    /// Add one configuration, e.g. while streaming
+    pub fn record(&mut self, config: &VlanConfig) {
+        self.total += 1;
+        if self.seen_vlan_ids.insert(config.vlan_id) {
+            self.unique_vlan_ids += 1;
+        }
+        if let Some(class) = config
+            .as_ipv4_network()
+            .ok()
+            .and_then(|network| Rfc1918Class::of_network(&network))
+        {
+            *self.networks_by_class.entry(class).or_default() += 1;
+        }
+        *self
+            .departments
+            .entry(config.department().to_string())
+            .or_default() += 1;
+        self.vlan_id_range = Some(match self.vlan_id_range {
+            Some((min, max)) => (min.min(config.vlan_id), max.max(config.vlan_id)),
+            None => (config.vlan_id, config.vlan_id),
+        });
+    }
+
+    /// Set the time the generation took
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_count_classes_and_departments() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "172.16.4.x".to_string(), "IT VLAN 200".to_string(), 2).unwrap(),
+            VlanConfig::new(
+                300,
+                "192.168.5.x".to_string(),
+                "Sales VLAN 300".to_string(),
+                3,
+            )
+            .unwrap(),
+        ];
+
+        let stats = GenerationStats::from_configs(&configs);
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.unique_vlan_ids, 3);
+        assert_eq!(stats.vlan_id_range, Some((100, 300)));
+        assert_eq!(stats.departments["IT"], 2);
+        assert_eq!(stats.departments["Sales"], 1);
+        assert_eq!(stats.networks_by_class[&Rfc1918Class::ClassB], 1);
+    }
+}
@@ -0,0 +1,213 @@
+//! Local user and group accounts for authentication testing
+//!
+//! Groups come from a fixed set of roles with OPNsense page privileges.
+//! Users get realistic names and belong to one or two of those groups.
+//! User and group IDs start at 2000, where OPNsense numbers local accounts.
+
+use fake::Fake;
+use fake::faker::name::en::{FirstName, LastName};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// First UID and GID OPNsense assigns to local accounts
+pub const FIRST_ACCOUNT_ID: u32 = 2000;
+
+/// Roles generated users are drawn into, as (name, description, privileges)
+const GROUP_ROLES: &[(&str, &str, &[&str])] = &[
+    (
+        "netops",
+        "Network Operators",
+        &[
+            "page-interfaces",
+            "page-firewall-rules",
+            "page-firewall-nat-portforward",
+        ],
+    ),
+    (
+        "helpdesk",
+        "Helpdesk",
+        &["page-dashboard-all", "page-diagnostics-ping"],
+    ),
+    (
+        "auditors",
+        "Security Auditors",
+        &["page-status-systemlogs", "page-diagnostics-logs-firewall"],
+    ),
+    ("vpnusers", "VPN Users", &["user-config-readonly"]),
+];
+
+/// Whether an account is built into OPNsense or created locally
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserScope {
+    /// Built-in account such as `root`
+    System,
+    /// Locally created account
+    User,
+}
+
+impl fmt::Display for UserScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserScope::System => write!(f, "system"),
+            UserScope::User => write!(f, "user"),
+        }
+    }
+}
+
+/// A group granting its members OPNsense page privileges
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Group {
+    /// Group name
+    pub name: String,
+    /// Human-readable description
+    pub description: String,
+    /// Group ID
+    pub gid: u32,
+    /// Privileges such as `page-firewall-rules`
+    pub privileges: Vec<String>,
+}
+
+/// A local user account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct User {
+    /// Login name, unique across the generated users
+    pub username: String,
+    /// Display name
+    pub full_name: String,
+    /// User ID
+    pub uid: u32,
+    /// Names of the groups the user belongs to
+    pub groups: Vec<String>,
+    /// Account scope
+    pub scope: UserScope,
+}
+
+/// Generated users together with the groups they reference
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserAccounts {
+    /// Built-in role groups the users are spread over
+    pub groups: Vec<Group>,
+    /// Locally created users, each a member of one or two groups
+    pub users: Vec<User>,
+}
+
+impl UserAccounts {
+    /// UIDs of the users belonging to a group
+    pub fn member_uids(&self, group: &str) -> Vec<u32> {
+        self.users
+            .iter()
+            .filter(|user| user.groups.iter().any(|name| name == group))
+            .map(|user| user.uid)
+            .collect()
+    }
+}
+
+/// Generate `count` users spread over the built-in role groups
+///
+/// Usernames are the first initial and last name, lowercased, with a number
+/// appended when that name is already taken.
+pub fn generate_users(count: u16, seed: Option<u64>) -> UserAccounts {
+    let mut rng: Box<dyn RngCore> = if let Some(seed) = seed {
+        Box::new(StdRng::seed_from_u64(seed))
+    } else {
+        Box::new(StdRng::from_rng(&mut rand::rng()))
+    };
+
+    let groups: Vec<Group> = GROUP_ROLES
+        .iter()
+        .zip(FIRST_ACCOUNT_ID..)
+        .map(|((name, description, privileges), gid)| Group {
+            name: name.to_string(),
+            description: description.to_string(),
+            gid,
+            privileges: privileges.iter().map(|p| p.to_string()).collect(),
+        })
+        .collect();
+
+    let mut taken = HashSet::new();
+    let users = (FIRST_ACCOUNT_ID..)
+        .take(usize::from(count))
+        .map(|uid| {
+            let first: String = FirstName().fake_with_rng(&mut rng);
+            let last: String = LastName().fake_with_rng(&mut rng);
+            let username = unique_username(&first, &last, &mut taken);
+
+            let membership = if rng.random_bool(0.25) { 2 } else { 1 };
+            let groups = groups
+                .choose_multiple(&mut rng, membership)
+                .map(|group| group.name.clone())
+                .collect();
+
+            User {
+                username,
+                full_name: format!("{first} {last}"),
+                uid,
+                groups,
+                scope: UserScope::User,
+            }
+        })
+        .collect();
+
+    UserAccounts { groups, users }
+}
+
+/// Build `jdoe` from "John Doe", numbering repeats `jdoe2`, `jdoe3`, ...
+fn unique_username(first: &str, last: &str, taken: &mut HashSet<String>) -> String {
+    let base: String = first
+        .chars()
+        .take(1)
+        .chain(last.chars())
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let base = if base.is_empty() {
+        "user".to_string()
+    } else {
+        base
+    };
+
+    let mut username = base.clone();
+    let mut suffix = 2;
+    while !taken.insert(username.clone()) {
+        username = format!("{base}{suffix}");
+        suffix += 1;
+    }
+    username
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_users_unique_with_existing_groups() {
+        let accounts = generate_users(10, Some(42));
+        assert_eq!(accounts.users.len(), 10);
+
+        let usernames: HashSet<_> = accounts.users.iter().map(|u| &u.username).collect();
+        assert_eq!(usernames.len(), 10);
+
+        let group_names: HashSet<_> = accounts.groups.iter().map(|g| &g.name).collect();
+        for user in &accounts.users {
+            assert!(!user.groups.is_empty(), "{} has no group", user.username);
+            for group in &user.groups {
+                assert!(group_names.contains(group), "unknown group {group}");
+            }
+        }
+
+        assert_eq!(accounts, generate_users(10, Some(42)));
+    }
+
+    #[test]
+    fn test_unique_username_numbers_repeats() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_username("John", "Doe", &mut taken), "jdoe");
+        assert_eq!(unique_username("Jane", "Doe", &mut taken), "jdoe2");
+        assert_eq!(unique_username("José", "O'Neil", &mut taken), "joneil");
+    }
+}
@@ -2,15 +2,84 @@
 
 use crate::Result;
 use crate::generator::departments;
+use crate::generator::stats::GenerationStats;
 use crate::model::{ConfigError, VlanError, VlanResult};
-use crate::utils::rfc1918;
-use indicatif::ProgressBar;
+use crate::utils::mac::{self, MacVendor, OuiPool};
+use crate::utils::placeholder::PlaceholderTemplate;
+use crate::utils::progress::ProgressSink;
+use crate::utils::rfc1918::{self, Rfc1918Class};
+use crate::utils::seed::random_seed;
 use ipnetwork::Ipv4Network;
 use rand::prelude::*;
 use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+use std::time::Instant;
+use tracing::{debug, trace};
+
+/// Default CIDR prefix length for VLAN networks
+pub const DEFAULT_PREFIX_LEN: u8 = 24;
+
+/// Department names used for descriptions by the legacy generation path
+const LEGACY_DEPARTMENTS: &[&str] = &[
+    "Sales",
+    "IT",
+    "HR",
+    "Finance",
+    "Marketing",
+    "Operations",
+    "Engineering",
+    "Support",
+    "Legal",
+    "Procurement",
+    "Security",
+    "Development",
+    "QA",
+    "Research",
+    "Training",
+    "Management",
+];
+
+/// Default number of random draws per unique VLAN ID or network before giving up
+pub const DEFAULT_MAX_ATTEMPTS: usize = 1000;
+
+/// Valid CIDR prefix lengths for VLAN networks
+pub const PREFIX_LEN_RANGE: std::ops::RangeInclusive<u8> = 8..=30;
+
+/// Offset of the first usable host, used as the gateway address
+const FIRST_USABLE_HOST_OFFSET: u32 = 1;
+
+/// Highest 802.1p priority code point (3 bits)
+pub const MAX_PCP: u8 = 7;
+
+/// Longest VLAN description, in characters, accepted by default
+pub const DEFAULT_MAX_DESCRIPTION_LEN: usize = 63;
+
+fn default_prefix_len() -> u8 {
+    DEFAULT_PREFIX_LEN
+}
+
+/// Public resolvers handed out after the gateway when no DNS servers are set
+const PUBLIC_DNS_SERVERS: [&str; 2] = [
+    "8.8.8.8", // Google DNS
+    "1.1.1.1", // Cloudflare DNS
+];
+
+/// Infer the prefix length from an IP network string.
+///
+/// CIDR strings such as "10.1.2.64/26" yield their suffix, while the legacy
+/// "10.1.2.x" format and unparsable suffixes fall back to /24.
+pub fn infer_prefix_len(ip_network: &str) -> u8 {
+    ip_network
+        .rsplit_once('/')
+        .and_then(|(_, prefix)| prefix.parse().ok())
+        .unwrap_or(DEFAULT_PREFIX_LEN)
+}
 
 /// Static DHCP reservation mapping MAC address to IP
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -48,6 +117,239 @@ pub struct DhcpServerConfig {
     pub static_reservations: Vec<StaticReservation>,
 }
 
+impl DhcpServerConfig {
+    /// Check that no static reservation collides with the pool or gateway
+    ///
+    /// A reserved IP inside `[range_start, range_end]` could also be leased
+    /// dynamically, and one equal to the gateway would shadow the router.
+    /// Every address, DNS servers included, must be IPv4.
+    pub fn validate(&self) -> Result<()> {
+        let parse = |field: &str, value: &str| {
+            value.parse::<Ipv4Addr>().map_err(|_| {
+                ConfigError::validation(format!("DHCP {field} '{value}' is not an IPv4 address"))
+            })
+        };
+        let range_start = parse("range start", &self.range_start)?;
+        let range_end = parse("range end", &self.range_end)?;
+        let gateway = parse("gateway", &self.gateway)?;
+        for dns_server in &self.dns_servers {
+            parse("DNS server", dns_server)?;
+        }
+
+        for reservation in &self.static_reservations {
+            let ip = parse("reservation", &reservation.ip_addr)?;
+            if (range_start..=range_end).contains(&ip) {
+                return Err(ConfigError::validation(format!(
+                    "static reservation {ip} ({}) lies inside the DHCP pool {range_start}-{range_end}",
+                    reservation.hostname
+                )));
+            }
+            if ip == gateway {
+                return Err(ConfigError::validation(format!(
+                    "static reservation {ip} ({}) collides with the gateway",
+                    reservation.hostname
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// DHCP settings shared by every VLAN's server instead of stored per VLAN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DhcpOptions {
+    /// DNS servers handed out after the gateway; the public Google and
+    /// Cloudflare resolvers when empty
+    pub dns_servers: Vec<String>,
+    /// Whether each VLAN's gateway is handed out as its primary DNS server
+    pub gateway_dns: bool,
+    /// Vendors whose OUIs prefix static reservation MACs; locally
+    /// administered MACs when empty
+    pub mac_vendors: Vec<MacVendor>,
+}
+
+impl Default for DhcpOptions {
+    fn default() -> Self {
+        Self {
+            dns_servers: Vec::new(),
+            gateway_dns: true,
+            mac_vendors: Vec::new(),
+        }
+    }
+}
+
+impl DhcpOptions {
+    /// Hand out these DNS servers instead of the public resolvers
+    pub fn with_dns_servers(mut self, servers: Vec<String>) -> Result<Self> {
+        for server in &servers {
+            if server.parse::<Ipv4Addr>().is_err() {
+                return Err(ConfigError::invalid_parameter(
+                    "dns_servers",
+                    format!("'{server}' is not an IPv4 address"),
+                ));
+            }
+        }
+        self.dns_servers = servers;
+        Ok(self)
+    }
+
+    /// Choose whether the gateway is the primary DNS server
+    pub fn with_gateway_dns(mut self, gateway_dns: bool) -> Self {
+        self.gateway_dns = gateway_dns;
+        self
+    }
+
+    /// Draw static reservation MACs from the given vendors' OUIs
+    pub fn with_mac_vendors(mut self, vendors: Vec<MacVendor>) -> Self {
+        self.mac_vendors = vendors;
+        self
+    }
+}
+
+/// Host offsets bounding the DHCP pool within each VLAN subnet
+///
+/// Offsets are relative to the network address, so `50-250` on
+/// 10.1.2.0/24 yields a pool of 10.1.2.50-10.1.2.250.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DhcpRangePolicy {
+    /// Offset of the first pool address
+    pub start_offset: u8,
+    /// Offset of the last pool address
+    pub end_offset: u8,
+}
+
+impl DhcpRangePolicy {
+    /// Create a policy, requiring `start_offset < end_offset`
+    pub fn new(start_offset: u8, end_offset: u8) -> Result<Self> {
+        if start_offset >= end_offset {
+            return Err(ConfigError::invalid_parameter(
+                "dhcp_range",
+                format!("start offset {start_offset} must be less than end offset {end_offset}"),
+            ));
+        }
+        Ok(Self {
+            start_offset,
+            end_offset,
+        })
+    }
+
+    /// Check that the pool fits the usable hosts of a subnet with `prefix_len`
+    ///
+    /// The network address, gateway (.1) and broadcast address are excluded.
+    pub fn validate_for_prefix(&self, prefix_len: u8) -> Result<()> {
+        let size = 1u32.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0);
+        let last_usable = size.saturating_sub(2);
+        if self.start_offset < 2 || u32::from(self.end_offset) > last_usable {
+            return Err(ConfigError::invalid_parameter(
+                "dhcp_range",
+                format!(
+                    "{self} does not fit the usable host range 2-{last_usable} of a /{prefix_len} subnet"
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DhcpRangePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start_offset, self.end_offset)
+    }
+}
+
+impl FromStr for DhcpRangePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Invalid DHCP range '{s}', expected START-END (e.g. 50-250)"))?;
+        let parse = |part: &str| {
+            part.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("Invalid DHCP range offset '{part}', expected 0-255"))
+        };
+        Self::new(parse(start)?, parse(end)?).map_err(|e| e.to_string())
+    }
+}
+
+/// Format of generated VLAN descriptions
+///
+/// `{dept}` is replaced with the department name and `{id}` with the VLAN
+/// ID. The default, `{dept} VLAN {id}`, matches the Python original.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptionTemplate(PlaceholderTemplate);
+
+impl DescriptionTemplate {
+    /// Description for a VLAN of `department` with the given ID
+    pub fn render(&self, department: &str, vlan_id: u16) -> String {
+        self.0.render(|name| match name {
+            "dept" => department.to_string(),
+            _ => vlan_id.to_string(),
+        })
+    }
+}
+
+impl Default for DescriptionTemplate {
+    fn default() -> Self {
+        "{dept} VLAN {id}"
+            .parse()
+            .expect("default template is valid")
+    }
+}
+
+impl fmt::Display for DescriptionTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for DescriptionTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err("Description template cannot be empty".to_string());
+        }
+        PlaceholderTemplate::parse(s, &["dept", "id"]).map(Self)
+    }
+}
+
+/// Field VLAN configurations are ordered by before being written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Ascending VLAN ID
+    VlanId,
+    /// Ascending network address, then VLAN ID
+    Network,
+}
+
+impl SortKey {
+    /// Sort configurations in place; the sort is stable
+    ///
+    /// Networks compare numerically, so `10.2.0.x` precedes `10.10.0.x`.
+    pub fn sort(self, configs: &mut [VlanConfig]) {
+        match self {
+            SortKey::VlanId => configs.sort_by_key(|config| config.vlan_id),
+            SortKey::Network => configs.sort_by_cached_key(|config| {
+                let address = config
+                    .as_ipv4_network()
+                    .ok()
+                    .map(|network| u32::from(network.network()));
+                (address, config.vlan_id)
+            }),
+        }
+    }
+}
+
+/// Shuffle configurations in place, reproducibly for a given seed
+///
+/// The same seed and input always give the same order, so shuffled output
+/// can be regenerated exactly.
+pub fn shuffle_configs(configs: &mut [VlanConfig], seed: u64) {
+    configs.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+}
+
 /// VLAN configuration structure matching Python implementation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct VlanConfig {
@@ -62,11 +364,66 @@ pub struct VlanConfig {
 
     /// WAN assignment (1-3 for multi-WAN scenarios)
     pub wan_assignment: u8,
+
+    /// CIDR prefix length of the VLAN network (8-30, /24 by default)
+    #[serde(default = "default_prefix_len")]
+    pub prefix_len: u8,
+
+    /// Explicit DHCP pool offsets; the scaled .100-.200 layout when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dhcp_policy: Option<DhcpRangePolicy>,
+
+    /// IEEE 802.1ad service tag (S-VLAN) for QinQ stacking; `vlan_id` is
+    /// then the customer tag (C-VLAN)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outer_vlan_id: Option<u16>,
+
+    /// 802.1p priority code point (0-7); voice and video departments get
+    /// higher priorities, everything else is best effort (0)
+    #[serde(default)]
+    pub pcp: u8,
 }
 
 impl VlanConfig {
+    /// Private helper to validate the prefix length range
+    fn validate_prefix_len(prefix_len: u8) -> Result<()> {
+        if !PREFIX_LEN_RANGE.contains(&prefix_len) {
+            return Err(ConfigError::validation(format!(
+                "Prefix length /{prefix_len} is outside valid range /8-/30"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validate a QinQ service tag range
+    ///
+    /// Shared with the XML generator and [`crate::validate`] so every path
+    /// reports the same range and message.
+    pub(crate) fn validate_outer_vlan_id(outer_vlan_id: u16) -> Result<()> {
+        if !(10..=4094).contains(&outer_vlan_id) {
+            return Err(ConfigError::validation(format!(
+                "Outer VLAN ID {outer_vlan_id} is outside valid range 10-4094"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Private helper to validate the 802.1p priority code point
+    fn validate_pcp(pcp: u8) -> Result<()> {
+        if pcp > MAX_PCP {
+            return Err(ConfigError::validation(format!(
+                "PCP {pcp} is outside valid range 0-{MAX_PCP}"
+            )));
+        }
+        Ok(())
+    }
+
     /// Private helper to validate IP network format strictly
-    fn validate_ip_format_strict(ip_network: &str) -> Result<()> {
+    fn validate_ip_format_strict(ip_network: &str, prefix_len: u8) -> Result<()> {
+        if prefix_len != DEFAULT_PREFIX_LEN {
+            return Self::validate_cidr_format(ip_network, prefix_len);
+        }
+
         // Validate IP network format - must be either "x.x.x.x" or "x.x.x.0/24" format
         let is_x_format = ip_network.ends_with(".x") && ip_network.matches('.').count() == 3;
         let is_cidr_format = ip_network.ends_with(".0/24") && ip_network.matches('.').count() == 3;
@@ -99,12 +456,55 @@ fn validate_ip_format_strict(ip_network: &str) -> Result<()> {
         Ok(())
     }
 
+    /// Private helper to validate a CIDR network string for non-/24 prefixes
+    fn validate_cidr_format(ip_network: &str, prefix_len: u8) -> Result<()> {
+        let network: Ipv4Network = ip_network.parse().map_err(|_| {
+            ConfigError::validation(format!(
+                "IP network '{ip_network}' does not match expected format (should be CIDR with /{prefix_len})"
+            ))
+        })?;
+
+        if !ip_network.contains('/') || network.prefix() != prefix_len {
+            return Err(ConfigError::validation(format!(
+                "IP network '{ip_network}' does not match prefix length /{prefix_len}"
+            )));
+        }
+
+        if network.ip() != network.network() {
+            return Err(ConfigError::validation(format!(
+                "IP network '{ip_network}' is not aligned to a /{prefix_len} boundary"
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Create a new VLAN configuration
     pub fn new(
         vlan_id: u16,
         ip_network: String,
         description: String,
         wan_assignment: u8,
+    ) -> Result<Self> {
+        Self::new_with_prefix(
+            vlan_id,
+            ip_network,
+            description,
+            wan_assignment,
+            DEFAULT_PREFIX_LEN,
+        )
+    }
+
+    /// Create a new VLAN configuration with an explicit CIDR prefix length
+    ///
+    /// Prefixes other than /24 require the network in CIDR notation, e.g.
+    /// "10.1.2.64/26".
+    pub fn new_with_prefix(
+        vlan_id: u16,
+        ip_network: String,
+        description: String,
+        wan_assignment: u8,
+        prefix_len: u8,
     ) -> Result<Self> {
         // Validate VLAN ID range
         if !(10..=4094).contains(&vlan_id) {
@@ -120,14 +520,19 @@ pub fn new(
             )));
         }
 
-        // Validate IP network format using helper function
-        Self::validate_ip_format_strict(&ip_network)?;
+        // Validate prefix length and IP network format using helper functions
+        Self::validate_prefix_len(prefix_len)?;
+        Self::validate_ip_format_strict(&ip_network, prefix_len)?;
 
         Ok(Self {
             vlan_id,
             ip_network,
             description,
             wan_assignment,
+            prefix_len,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         })
     }
 
@@ -149,45 +554,72 @@ pub fn new_with_network(
             return Err(VlanError::InvalidWanAssignment(wan));
         }
 
+        // Validate prefix length
+        let prefix_len = network.prefix();
+        if !PREFIX_LEN_RANGE.contains(&prefix_len) {
+            return Err(VlanError::InvalidPrefixLength(prefix_len));
+        }
+
         // Validate RFC 1918 compliance
         if !rfc1918::is_rfc1918_network(&network) {
             return Err(VlanError::NonRfc1918Network(network.to_string()));
         }
 
-        // Convert network to string format for compatibility
-        let ip_network = format!(
-            "{}.x",
-            network
-                .network()
-                .octets()
-                .iter()
-                .take(3)
-                .map(|octet| octet.to_string())
-                .collect::<Vec<_>>()
-                .join(".")
-        );
+        // Convert network to string format for compatibility; /24 keeps the
+        // legacy "x.x.x.x" form, other prefixes use CIDR notation
+        let ip_network = if prefix_len == DEFAULT_PREFIX_LEN {
+            format!(
+                "{}.x",
+                network
+                    .network()
+                    .octets()
+                    .iter()
+                    .take(3)
+                    .map(|octet| octet.to_string())
+                    .collect::<Vec<_>>()
+                    .join(".")
+            )
+        } else {
+            format!("{}/{}", network.network(), prefix_len)
+        };
 
         Ok(Self {
             vlan_id,
             ip_network,
             description,
             wan_assignment: wan,
+            prefix_len,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         })
     }
 
     /// Get the network as an Ipv4Network if possible
     pub fn as_ipv4_network(&self) -> VlanResult<Ipv4Network> {
-        let base = self
-            .ip_network
-            .strip_suffix(".x")
-            .or_else(|| self.ip_network.strip_suffix(".0/24"))
-            .ok_or_else(|| {
-                VlanError::network_parsing(format!(
-                    "Cannot parse network format: {}",
-                    self.ip_network
-                ))
-            })?;
-        rfc1918::validate_rfc1918_network_string(&format!("{base}.0/24"))
+        let cidr = if self.prefix_len == DEFAULT_PREFIX_LEN {
+            let base = self
+                .ip_network
+                .strip_suffix(".x")
+                .or_else(|| self.ip_network.strip_suffix(".0/24"))
+                .ok_or_else(|| {
+                    VlanError::network_parsing(format!(
+                        "Cannot parse network format: {}",
+                        self.ip_network
+                    ))
+                })?;
+            format!("{base}.0/24")
+        } else {
+            self.ip_network.clone()
+        };
+        let network = rfc1918::validate_rfc1918_network_string(&cidr)?;
+        if network.prefix() != self.prefix_len || network.ip() != network.network() {
+            return Err(VlanError::network_parsing(format!(
+                "Network {} does not match prefix length /{}",
+                self.ip_network, self.prefix_len
+            )));
+        }
+        Ok(network)
     }
 
     /// Validate that this configuration is RFC 1918 compliant
@@ -195,8 +627,41 @@ pub fn validate_rfc1918(&self) -> VlanResult<()> {
         self.as_ipv4_network().map(|_| ())
     }
 
+    /// Check a description is non-empty, at most `max_len` characters and
+    /// free of control characters and angle brackets, which break XML
+    pub fn validate_description(description: &str, max_len: usize) -> Result<()> {
+        if description.trim().is_empty() {
+            return Err(ConfigError::validation("VLAN description cannot be empty"));
+        }
+
+        let len = description.chars().count();
+        if len > max_len {
+            return Err(ConfigError::validation(format!(
+                "VLAN description '{description}' is {len} characters long, maximum is {max_len}"
+            )));
+        }
+
+        if let Some(c) = description
+            .chars()
+            .find(|c| c.is_control() || matches!(c, '<' | '>'))
+        {
+            return Err(ConfigError::validation(format!(
+                "VLAN description {description:?} contains disallowed character {c:?}"
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Comprehensive validation of VLAN configuration
+    ///
+    /// Descriptions are limited to [`DEFAULT_MAX_DESCRIPTION_LEN`] characters.
     pub fn validate(&self) -> Result<()> {
+        self.validate_with_max_description_len(DEFAULT_MAX_DESCRIPTION_LEN)
+    }
+
+    /// Comprehensive validation with a custom description length limit
+    pub fn validate_with_max_description_len(&self, max_description_len: usize) -> Result<()> {
         // Validate VLAN ID range
         if !(10..=4094).contains(&self.vlan_id) {
             return Err(ConfigError::validation(format!(
@@ -213,14 +678,19 @@ pub fn validate(&self) -> Result<()> {
             )));
         }
 
-        // Validate IP network format using helper function
-        Self::validate_ip_format_strict(&self.ip_network)?;
-
-        // Validate description is not empty (including whitespace-only)
-        if self.description.trim().is_empty() {
-            return Err(ConfigError::validation("VLAN description cannot be empty"));
+        // Validate the QinQ service tag
+        if let Some(outer) = self.outer_vlan_id {
+            Self::validate_outer_vlan_id(outer)?;
         }
 
+        Self::validate_pcp(self.pcp)?;
+
+        // Validate prefix length and IP network format using helper functions
+        Self::validate_prefix_len(self.prefix_len)?;
+        Self::validate_ip_format_strict(&self.ip_network, self.prefix_len)?;
+
+        Self::validate_description(&self.description, max_description_len)?;
+
         // Validate RFC 1918 compliance
         if let Err(e) = self.validate_rfc1918() {
             return Err(ConfigError::validation(format!(
@@ -232,45 +702,131 @@ pub fn validate(&self) -> Result<()> {
         Ok(())
     }
 
-    /// Get the subnet mask for this VLAN (always /24 for compatibility)
-    pub fn subnet_mask(&self) -> &'static str {
-        "255.255.255.0"
+    /// Get the subnet mask for this VLAN in dotted-decimal notation
+    pub fn subnet_mask(&self) -> String {
+        let prefix = u32::from(self.prefix_len.min(32));
+        let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+        Ipv4Addr::from(mask).to_string()
     }
 
-    /// Extract the three-octet base prefix from the IP network string.
+    /// Resolve the network address and size of the subnet.
     ///
-    /// Handles both "10.1.2.x" and "10.1.2.0/24" formats, returning "10.1.2".
-    fn network_base(&self) -> Result<&str> {
-        self.ip_network
-            .strip_suffix(".x")
-            .or_else(|| self.ip_network.strip_suffix(".0/24"))
-            .ok_or_else(|| {
-                ConfigError::validation(format!(
-                    "Cannot parse base from IP network: {}",
-                    self.ip_network
-                ))
-            })
+    /// Handles "10.1.2.x", "10.1.2.0/24" and CIDR forms such as "10.1.2.64/26".
+    fn network_bounds(&self) -> Result<(u32, u32)> {
+        let parse_error = || {
+            ConfigError::validation(format!(
+                "Cannot parse base from IP network: {}",
+                self.ip_network
+            ))
+        };
+
+        Self::validate_prefix_len(self.prefix_len)?;
+        let cidr = match self.ip_network.strip_suffix(".x") {
+            Some(base) => format!("{base}.0/{}", self.prefix_len),
+            None => self.ip_network.clone(),
+        };
+        let network: Ipv4Network = cidr.parse().map_err(|_| parse_error())?;
+        if network.prefix() != self.prefix_len || network.ip() != network.network() {
+            return Err(parse_error());
+        }
+
+        Ok((u32::from(network.network()), 1u32 << (32 - self.prefix_len)))
+    }
+
+    /// Get the usable host address at `offset` from the network address
+    ///
+    /// Offset 0 is the network address and the last offset the broadcast
+    /// address; neither can be handed to a host, so both are rejected.
+    fn host_ip(&self, offset: u32) -> Result<String> {
+        let (network, size) = self.network_bounds()?;
+        if offset == 0 || offset >= size - 1 {
+            return Err(ConfigError::validation(format!(
+                "Offset {offset} is not a usable host address in {} (usable offsets are 1-{})",
+                self.ip_network,
+                size - 2
+            )));
+        }
+        Ok(Ipv4Addr::from(network + offset).to_string())
+    }
+
+    /// Stack this VLAN inside an 802.1ad service tag (QinQ)
+    pub fn with_outer_vlan_id(mut self, outer_vlan_id: u16) -> Result<Self> {
+        Self::validate_outer_vlan_id(outer_vlan_id)?;
+        self.outer_vlan_id = Some(outer_vlan_id);
+        Ok(self)
+    }
+
+    /// Tag this VLAN's traffic with an 802.1p priority code point (0-7)
+    pub fn with_pcp(mut self, pcp: u8) -> Result<Self> {
+        Self::validate_pcp(pcp)?;
+        self.pcp = pcp;
+        Ok(self)
+    }
+
+    /// Apply an explicit DHCP range policy, checking it fits this subnet
+    pub fn with_dhcp_policy(mut self, policy: DhcpRangePolicy) -> Result<Self> {
+        policy.validate_for_prefix(self.prefix_len)?;
+        self.dhcp_policy = Some(policy);
+        Ok(self)
     }
 
-    /// Get the gateway IP address (network + 1)
+    /// Offsets of the DHCP pool within the subnet.
+    ///
+    /// Uses the configured policy when present; otherwise scales the classic
+    /// .100-.200 layout of a /24 to the subnet size and clamps it to the
+    /// usable host range after the gateway.
+    fn dhcp_range_offsets(&self) -> Result<(u32, u32)> {
+        let (_, size) = self.network_bounds()?;
+        if let Some(policy) = self.dhcp_policy {
+            policy.validate_for_prefix(self.prefix_len)?;
+            return Ok((u32::from(policy.start_offset), u32::from(policy.end_offset)));
+        }
+        let last_usable = size - 2;
+        let scale = |octet: u64| (u64::from(size) * octet / 256) as u32;
+        let start = scale(100).clamp(2, last_usable);
+        let end = scale(200).clamp(start, last_usable);
+        Ok((start, end))
+    }
+
+    /// Get the gateway IP address, the first usable host of the subnet
     pub fn gateway_ip(&self) -> Result<String> {
-        Ok(format!("{}.1", self.network_base()?))
+        self.host_ip(FIRST_USABLE_HOST_OFFSET)
     }
 
     /// Get the DHCP range start IP
     pub fn dhcp_range_start(&self) -> Result<String> {
-        Ok(format!("{}.100", self.network_base()?))
+        self.host_ip(self.dhcp_range_offsets()?.0)
     }
 
     /// Get the DHCP range end IP
     pub fn dhcp_range_end(&self) -> Result<String> {
-        Ok(format!("{}.200", self.network_base()?))
+        self.host_ip(self.dhcp_range_offsets()?.1)
     }
 
     /// Get the DHCP lease time based on department type (in seconds)
+    ///
+    /// A [`Self::department`] naming a built-in department picks the lease,
+    /// which covers multi-word names such as `Customer Service`. Otherwise
+    /// the description is split into words at every character that is not a
+    /// letter or digit, and the first word naming a built-in department picks
+    /// it. This finds the department wherever a description template puts
+    /// it, so `NYC IT VLAN 100` and `IT_100` both get the IT lease.
+    /// Descriptions without a built-in department, such as those from a
+    /// custom departments file, get the default 8-hour lease.
     pub fn dhcp_lease_time(&self) -> u32 {
+        Self::department_lease_time(self.department())
+            .or_else(|| {
+                self.description
+                    .split(|c: char| !c.is_alphanumeric())
+                    .find_map(Self::department_lease_time)
+            })
+            .unwrap_or(28800)
+    }
+
+    /// Lease time for a built-in department name (in seconds)
+    fn department_lease_time(department: &str) -> Option<u32> {
         // Determine lease time based on department characteristics
-        match self.description.split(' ').next().unwrap_or("Unknown") {
+        let lease = match department {
             // Corporate departments - longer lease times (24 hours)
             "IT" | "Finance" | "Accounting" | "Legal" | "Management" => 86400,
             // Production environments - medium lease times (12 hours)
@@ -281,9 +837,9 @@ pub fn dhcp_lease_time(&self) -> u32 {
             "HR" | "Procurement" | "Logistics" => 14400,
             // Security-sensitive - short lease times (6 hours) for easier tracking
             "Security" => 21600,
-            // Default for unknown departments (8 hours)
-            _ => 28800,
-        }
+            _ => return None,
+        };
+        Some(lease)
     }
 
     /// Get the maximum DHCP lease time (typically 2x the default lease time)
@@ -291,29 +847,69 @@ pub fn dhcp_max_lease_time(&self) -> u32 {
         self.dhcp_lease_time() * 2
     }
 
-    /// Get the DHCP domain name based on department context
-    pub fn dhcp_domain_name(&self) -> String {
+    /// Department named in the description, without the VLAN ID
+    ///
+    /// Strips the default " VLAN <id>" suffix, or a VLAN ID that a
+    /// description template put at either end, set off by spaces or
+    /// punctuation (`IT_100`, `100-IT`). Descriptions that follow neither
+    /// pattern are returned whole.
+    pub fn department(&self) -> &str {
+        if let Some(department) = self
+            .description
+            .strip_suffix(&format!(" VLAN {}", self.vlan_id))
+        {
+            return department;
+        }
+
+        let id = self.vlan_id.to_string();
+        let is_separator = |c: char| c.is_whitespace() || c.is_ascii_punctuation();
         let department = self
             .description
-            .split(' ')
-            .next()
-            .unwrap_or("unknown")
-            .to_lowercase();
-        format!("{}.company.local", department)
+            .strip_suffix(id.as_str())
+            .filter(|rest| rest.ends_with(is_separator))
+            .map(|rest| rest.trim_end_matches(is_separator))
+            .or_else(|| {
+                self.description
+                    .strip_prefix(id.as_str())
+                    .filter(|rest| rest.starts_with(is_separator))
+                    .map(|rest| rest.trim_start_matches(is_separator))
+            });
+        match department {
+            Some(department) if !department.is_empty() => department,
+            _ => &self.description,
+        }
+    }
+
+    /// Lowercase first word of the department, for host and domain names
+    pub(crate) fn department_label(&self) -> String {
+        match self.department().split(' ').next() {
+            Some(word) if !word.is_empty() => word.to_lowercase(),
+            _ => "unknown".to_string(),
+        }
+    }
+
+    /// Get the DHCP domain name based on department context
+    pub fn dhcp_domain_name(&self) -> String {
+        format!("{}.company.local", self.department_label())
     }
 
-    /// Get DNS servers list (gateway + reliable public DNS)
-    pub fn dhcp_dns_servers(&self) -> Result<Vec<String>> {
+    /// Get DNS servers list (gateway + configured or reliable public DNS)
+    pub fn dhcp_dns_servers(&self, options: &DhcpOptions) -> Result<Vec<String>> {
         let mut dns_servers = Vec::with_capacity(3);
 
         // Add gateway as primary DNS
-        if let Ok(gateway) = self.gateway_ip() {
+        if options.gateway_dns
+            && let Ok(gateway) = self.gateway_ip()
+        {
             dns_servers.push(gateway);
         }
 
-        // Add reliable public DNS servers as secondary
-        dns_servers.push("8.8.8.8".to_string()); // Google DNS
-        dns_servers.push("1.1.1.1".to_string()); // Cloudflare DNS
+        // Add configured DNS servers, or reliable public ones, as secondary
+        if options.dns_servers.is_empty() {
+            dns_servers.extend(PUBLIC_DNS_SERVERS.map(String::from));
+        } else {
+            dns_servers.extend(options.dns_servers.iter().cloned());
+        }
 
         Ok(dns_servers)
     }
@@ -328,134 +924,648 @@ pub fn dhcp_ntp_servers(&self) -> Vec<String> {
     }
 
     /// Generate static DHCP reservations with realistic MAC-IP mappings
-    pub fn static_reservations(&self) -> Result<Vec<StaticReservation>> {
-        let mut reservations = Vec::with_capacity(2);
-
-        // Get base network for IP assignments
-        let base = self.network_base()?;
-
-        // Generate department-specific static reservations
-        let department = self
-            .description
-            .split(' ')
-            .next()
-            .unwrap_or("unknown")
-            .to_lowercase();
-        match department.as_str() {
-            "it" | "engineering" | "development" => {
+    ///
+    /// MACs are drawn from an RNG seeded by the VLAN ID and network (see
+    /// [`mac::mac_seed`]), not from the generator's RNG, so they are unique
+    /// per config and identical whether the config was just generated or
+    /// loaded back from CSV, JSON, YAML or TOML. Each device gets an OUI from
+    /// `vendors`, or a locally administered prefix when it is empty.
+    pub fn static_reservations(&self, vendors: &[MacVendor]) -> Result<Vec<StaticReservation>> {
+        // Generate department-specific static reservations as
+        // (host offset, hostname) candidates
+        let department = self.department_label();
+        let candidates = match department.as_str() {
+            "it" | "engineering" | "development" => vec![
                 // IT departments typically have servers and network equipment
-                reservations.push(StaticReservation {
-                    mac: format!("aa:bb:cc:dd:ee:{:02x}", self.vlan_id % 256),
-                    ip_addr: format!("{}.10", base),
-                    hostname: format!("server-{}-01", department),
-                });
-                reservations.push(StaticReservation {
-                    mac: format!("aa:bb:cc:dd:ef:{:02x}", self.vlan_id % 256),
-                    ip_addr: format!("{}.11", base),
-                    hostname: format!("printer-{}-01", department),
-                });
-            }
-            "finance" | "accounting" | "legal" => {
+                (10, format!("server-{}-01", department)),
+                (11, format!("printer-{}-01", department)),
+            ],
+            "finance" | "accounting" | "legal" => vec![
                 // Finance departments typically have specialized workstations
-                reservations.push(StaticReservation {
-                    mac: format!("aa:bb:cc:dd:f0:{:02x}", self.vlan_id % 256),
-                    ip_addr: format!("{}.15", base),
-                    hostname: format!("workstation-{}-01", department),
-                });
-            }
-            "sales" | "marketing" => {
+                (15, format!("workstation-{}-01", department)),
+            ],
+            "sales" | "marketing" => vec![
                 // Sales departments typically have presentation equipment
-                reservations.push(StaticReservation {
-                    mac: format!("aa:bb:cc:dd:f1:{:02x}", self.vlan_id % 256),
-                    ip_addr: format!("{}.20", base),
-                    hostname: format!("display-{}-01", department),
-                });
-            }
-            _ => {
+                (20, format!("display-{}-01", department)),
+            ],
+            _ => vec![
                 // Default reservation for other departments
-                reservations.push(StaticReservation {
-                    mac: format!("aa:bb:cc:dd:f2:{:02x}", self.vlan_id % 256),
-                    ip_addr: format!("{}.25", base),
-                    hostname: format!("device-{}-01", department),
-                });
-            }
-        }
+                (25, format!("device-{}-01", department)),
+            ],
+        };
 
-        Ok(reservations)
+        // Reservations live between the gateway and the DHCP pool; subnets
+        // too small to hold an offset skip that reservation
+        let (dhcp_start, _) = self.dhcp_range_offsets()?;
+        let mut rng = ChaCha8Rng::seed_from_u64(mac::mac_seed(self.vlan_id, &self.ip_network));
+        let oui_pool = OuiPool::from_vendors(vendors);
+        let mut used_macs = HashSet::new();
+        candidates
+            .into_iter()
+            .filter(|(offset, _)| *offset > 1 && *offset < dhcp_start)
+            .map(|(offset, hostname)| {
+                let mac = loop {
+                    let mac = oui_pool.generate_mac(&mut rng);
+                    if used_macs.insert(mac.clone()) {
+                        break mac;
+                    }
+                };
+                Ok(StaticReservation {
+                    mac,
+                    ip_addr: self.host_ip(offset)?,
+                    hostname,
+                })
+            })
+            .collect()
     }
 
     /// Generate complete DHCP server configuration
-    pub fn dhcp_server_config(&self) -> Result<DhcpServerConfig> {
-        Ok(DhcpServerConfig {
+    pub fn dhcp_server_config(&self, options: &DhcpOptions) -> Result<DhcpServerConfig> {
+        let config = DhcpServerConfig {
             enabled: true,
             range_start: self.dhcp_range_start()?,
             range_end: self.dhcp_range_end()?,
             lease_time: self.dhcp_lease_time(),
             max_lease_time: self.dhcp_max_lease_time(),
-            dns_servers: self.dhcp_dns_servers()?,
+            dns_servers: self.dhcp_dns_servers(options)?,
             domain_name: self.dhcp_domain_name(),
             gateway: self.gateway_ip()?,
             ntp_servers: self.dhcp_ntp_servers(),
-            static_reservations: self.static_reservations()?,
-        })
+            static_reservations: self.static_reservations(&options.mac_vendors)?,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl fmt::Display for VlanConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VLAN {}  {}  /{}  WAN{}  \"{}\"",
+            self.vlan_id, self.ip_network, self.prefix_len, self.wan_assignment, self.description
+        )
+    }
+}
+
+/// Address ranges of the networks a generator has handed out or reserved
+///
+/// Ranges are `(network, broadcast)` pairs keyed by their start and never
+/// overlap: reserving a network that overlaps others merges them. A network
+/// therefore overlaps a claimed one exactly when the last range starting at
+/// or before its broadcast address reaches its network address.
+#[derive(Debug, Default)]
+struct ClaimedNetworks {
+    ranges: BTreeMap<u32, u32>,
+    count: usize,
+}
+
+impl ClaimedNetworks {
+    fn bounds(network: Ipv4Network) -> (u32, u32) {
+        (network.network().into(), network.broadcast().into())
+    }
+
+    /// Whether `network` shares any address with a claimed network
+    fn overlaps(&self, network: Ipv4Network) -> bool {
+        let (start, end) = Self::bounds(network);
+        self.ranges
+            .range(..=end)
+            .next_back()
+            .is_some_and(|(_, &claimed_end)| claimed_end >= start)
+    }
+
+    /// Claim `network` unless it overlaps a claimed one, returning whether it
+    /// was claimed
+    fn claim(&mut self, network: Ipv4Network) -> bool {
+        if self.overlaps(network) {
+            return false;
+        }
+        self.reserve(network);
+        true
+    }
+
+    /// Mark `network` as used, merging it with any claimed network it overlaps
+    fn reserve(&mut self, network: Ipv4Network) {
+        let (mut start, mut end) = Self::bounds(network);
+        let overlapping: Vec<(u32, u32)> = self
+            .ranges
+            .range(..=end)
+            .rev()
+            .take_while(|&(_, &claimed_end)| claimed_end >= start)
+            .map(|(&claimed_start, &claimed_end)| (claimed_start, claimed_end))
+            .collect();
+        for (claimed_start, claimed_end) in overlapping {
+            self.ranges.remove(&claimed_start);
+            start = start.min(claimed_start);
+            end = end.max(claimed_end);
+        }
+        self.ranges.insert(start, end);
+        self.count += 1;
+    }
+
+    /// Number of networks claimed or reserved
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Claimed address ranges in ascending order
+    fn ranges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.ranges.iter().map(|(&start, &end)| (start, end))
     }
 }
 
 /// VLAN configuration generator with enhanced RFC 1918 compliance
 pub struct VlanGenerator {
     rng: Box<dyn RngCore>,
+    seed: u64,
     used_vlan_ids: HashSet<u16>,
-    used_networks: HashSet<String>,
+    /// Network addresses already handed out, packed as `u32`
+    used_networks: ClaimedNetworks,
+    prefix_len: u8,
+    allowed_vlan_ids: Option<Vec<u16>>,
+    network_class: Option<Rfc1918Class>,
+    departments: Option<Vec<String>>,
+    excluded_networks: Vec<Ipv4Network>,
+    dhcp_policy: Option<DhcpRangePolicy>,
+    allow_duplicates: bool,
+    balanced_classes: bool,
+    class_cursor: usize,
+    supernet: Option<Ipv4Network>,
+    network_pool: Option<VecDeque<Ipv4Network>>,
+    description_template: DescriptionTemplate,
+    max_description_len: usize,
+    max_attempts: usize,
 }
 
 impl VlanGenerator {
     /// Create a new generator with optional seed using ChaCha8Rng
+    ///
+    /// Without a seed a random one is chosen; see [`Self::effective_seed`].
     pub fn new(seed: Option<u64>) -> Self {
-        let rng: Box<dyn RngCore> = if let Some(seed) = seed {
-            Box::new(ChaCha8Rng::seed_from_u64(seed))
-        } else {
-            Box::new(ChaCha8Rng::from_rng(&mut rand::rng()))
-        };
+        let seed = seed.unwrap_or_else(random_seed);
+        Self::with_rng(Box::new(ChaCha8Rng::seed_from_u64(seed)), seed)
+    }
+
+    /// Create a new generator with StdRng for compatibility
+    ///
+    /// Without a seed a random one is chosen; see [`Self::effective_seed`].
+    pub fn new_with_std_rng(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(random_seed);
+        Self::with_rng(Box::new(StdRng::seed_from_u64(seed)), seed)
+    }
 
+    fn with_rng(rng: Box<dyn RngCore>, seed: u64) -> Self {
         Self {
             rng,
+            seed,
             used_vlan_ids: HashSet::new(),
-            used_networks: HashSet::new(),
+            used_networks: ClaimedNetworks::default(),
+            prefix_len: DEFAULT_PREFIX_LEN,
+            allowed_vlan_ids: None,
+            network_class: None,
+            departments: None,
+            excluded_networks: Vec::new(),
+            dhcp_policy: None,
+            allow_duplicates: false,
+            balanced_classes: false,
+            class_cursor: 0,
+            supernet: None,
+            network_pool: None,
+            description_template: DescriptionTemplate::default(),
+            max_description_len: DEFAULT_MAX_DESCRIPTION_LEN,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
         }
     }
 
-    /// Create a new generator with StdRng for compatibility
-    pub fn new_with_std_rng(seed: Option<u64>) -> Self {
-        let rng: Box<dyn RngCore> = if let Some(seed) = seed {
-            Box::new(StdRng::seed_from_u64(seed))
+    /// Set the CIDR prefix length used for generated networks (8-30)
+    pub fn with_prefix_len(mut self, prefix_len: u8) -> Result<Self> {
+        if !PREFIX_LEN_RANGE.contains(&prefix_len) {
+            return Err(ConfigError::invalid_parameter(
+                "prefix_len",
+                format!("/{prefix_len} is outside valid range /8-/30"),
+            ));
+        }
+        self.prefix_len = prefix_len;
+        Ok(self)
+    }
+
+    /// CIDR prefix length used for generated networks
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Use explicit DHCP pool offsets for every generated VLAN
+    ///
+    /// The policy must fit the usable host range of the generator's prefix
+    /// length, so call this after `with_prefix_len`.
+    pub fn with_dhcp_policy(mut self, policy: DhcpRangePolicy) -> Result<Self> {
+        policy.validate_for_prefix(self.prefix_len)?;
+        self.dhcp_policy = Some(policy);
+        Ok(self)
+    }
+
+    /// Let VLAN IDs and networks repeat, e.g. to test conflict detection
+    ///
+    /// Draws are no longer checked against earlier ones, so the count is not
+    /// limited by the size of the VLAN ID range or network class. Exclusions
+    /// still apply.
+    pub fn with_allow_duplicates(mut self, allow_duplicates: bool) -> Self {
+        self.allow_duplicates = allow_duplicates;
+        self
+    }
+
+    /// Reject generated descriptions longer than `max_len` characters
+    ///
+    /// Defaults to [`DEFAULT_MAX_DESCRIPTION_LEN`]. Descriptions are also
+    /// checked for control characters and angle brackets.
+    pub fn with_max_description_len(mut self, max_len: usize) -> Self {
+        self.max_description_len = max_len;
+        self
+    }
+
+    /// Set how many random draws are made for each unique VLAN ID or
+    /// network before generation fails with `ResourceExhausted`
+    ///
+    /// Dense constraints such as narrow classes or large exclusions may need
+    /// more than the default of [`DEFAULT_MAX_ATTEMPTS`].
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Result<Self> {
+        if max_attempts == 0 {
+            return Err(ConfigError::invalid_parameter(
+                "max_attempts",
+                "at least one attempt is required",
+            ));
+        }
+        self.max_attempts = max_attempts;
+        Ok(self)
+    }
+
+    /// Seed the generator's RNG was created from
+    ///
+    /// When no seed was given this is the randomly chosen one, so passing it
+    /// back in reproduces the same output.
+    pub fn effective_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Random draws made per unique VLAN ID or network
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Draw generated networks from a specific RFC 1918 class
+    ///
+    /// Without a class, `generate_single` uses 10.0.0.0/8 and
+    /// `generate_single_enhanced` uses a weighted mix of all classes.
+    pub fn with_network_class(mut self, class: Rfc1918Class) -> Self {
+        self.network_class = Some(class);
+        self
+    }
+
+    /// Draw networks from Class A, B and C in turn
+    ///
+    /// Every run of three networks covers all classes, so a batch of `n`
+    /// networks holds at least `n / 3` from each. If a class has no unique
+    /// network left, the next class in the rotation is used instead.
+    /// Overrides `with_network_class`.
+    pub fn with_balanced_classes(mut self) -> Self {
+        self.balanced_classes = true;
+        self
+    }
+
+    /// Draw every generated network from inside an RFC 1918 supernet
+    ///
+    /// Overrides `with_network_class` and `with_balanced_classes`. Generation
+    /// fails with `ResourceExhausted` once the supernet has no free subnet of
+    /// the generator's prefix length left.
+    pub fn with_supernet(mut self, supernet: Ipv4Network) -> Result<Self> {
+        if !rfc1918::is_rfc1918_network(&supernet) {
+            return Err(ConfigError::invalid_parameter(
+                "supernet",
+                format!("{supernet} is not an RFC 1918 private network"),
+            ));
+        }
+        let supernet = Ipv4Network::new(supernet.network(), supernet.prefix())
+            .map_err(|e| ConfigError::invalid_parameter("supernet", e.to_string()))?;
+        self.supernet = Some(supernet);
+        Ok(self)
+    }
+
+    /// Assign networks from a predetermined list instead of generating them
+    ///
+    /// VLANs take the networks in list order, each keeping its own prefix
+    /// length. Overrides the class, supernet and prefix length settings.
+    /// Networks must be RFC 1918, listed once and must not overlap, so
+    /// `10.1.0.0/16` and `10.1.2.0/24` cannot both be listed; generating more
+    /// VLANs than the pool holds fails with `ResourceExhausted`.
+    pub fn use_network_pool(&mut self, networks: Vec<Ipv4Network>) -> Result<()> {
+        let mut seen = HashSet::new();
+        let mut pool = VecDeque::with_capacity(networks.len());
+        for network in networks {
+            let network = Ipv4Network::new(network.network(), network.prefix())
+                .map_err(|e| ConfigError::invalid_parameter("network_pool", e.to_string()))?;
+            if !rfc1918::is_rfc1918_network(&network) {
+                return Err(ConfigError::invalid_parameter(
+                    "network_pool",
+                    format!("{network} is not an RFC 1918 private network"),
+                ));
+            }
+            if !PREFIX_LEN_RANGE.contains(&network.prefix()) {
+                return Err(ConfigError::invalid_parameter(
+                    "network_pool",
+                    format!("{network} has a prefix outside /8-/30"),
+                ));
+            }
+            if !seen.insert(network) {
+                return Err(ConfigError::invalid_parameter(
+                    "network_pool",
+                    format!("{network} is listed more than once"),
+                ));
+            }
+            pool.push_back(network);
+        }
+
+        // Sorted by start address, a network overlaps an earlier one exactly
+        // when it starts before the furthest end seen so far
+        let mut sorted: Vec<Ipv4Network> = pool.iter().copied().collect();
+        sorted.sort_unstable_by_key(|network| {
+            (
+                u32::from(network.network()),
+                Reverse(u32::from(network.broadcast())),
+            )
+        });
+        let mut widest: Option<Ipv4Network> = None;
+        for network in sorted {
+            match widest {
+                Some(widest) if network.network() <= widest.broadcast() => {
+                    return Err(ConfigError::invalid_parameter(
+                        "network_pool",
+                        format!("{network} overlaps {widest}"),
+                    ));
+                }
+                _ => widest = Some(network),
+            }
+        }
+
+        self.network_pool = Some(pool);
+        Ok(())
+    }
+
+    /// Shuffle the remaining network pool with the generator's RNG
+    pub fn shuffle_network_pool(&mut self) {
+        if let Some(pool) = &mut self.network_pool {
+            pool.make_contiguous().shuffle(&mut self.rng);
+        }
+    }
+
+    /// Next pool network that is neither used nor excluded
+    ///
+    /// `None` without a pool, `Some(None)` once the pool is used up.
+    fn take_pool_network(&mut self) -> Option<Option<Ipv4Network>> {
+        self.network_pool.as_ref()?;
+        while let Some(network) = self.network_pool.as_mut()?.pop_front() {
+            if self.is_excluded(network) {
+                debug!(%network, "pool network is excluded, skipping");
+                continue;
+            }
+            if self.used_networks.claim(network) || self.allow_duplicates {
+                return Some(Some(network));
+            }
+            debug!(%network, "pool network already in use, skipping");
+        }
+        Some(None)
+    }
+
+    /// Format generated descriptions with a `{dept}`/`{id}` template
+    pub fn with_description_template(mut self, template: DescriptionTemplate) -> Self {
+        self.description_template = template;
+        self
+    }
+
+    /// Restrict generated VLAN IDs to the given inclusive ranges
+    ///
+    /// IDs already handed out by this generator are excluded from the pool.
+    pub fn set_allowed_vlan_ids(&mut self, ranges: &[(u16, u16)]) -> Result<()> {
+        let mut pool = Vec::new();
+        let mut seen = HashSet::new();
+        for &(start, end) in ranges {
+            if start > end || start < 10 || end > 4094 {
+                return Err(ConfigError::invalid_parameter(
+                    "vlan_range",
+                    format!("Range {start}-{end} is outside valid range 10-4094"),
+                ));
+            }
+            pool.extend(
+                (start..=end).filter(|id| !self.used_vlan_ids.contains(id) && seen.insert(*id)),
+            );
+        }
+
+        self.allowed_vlan_ids = Some(pool);
+        Ok(())
+    }
+
+    /// Replace the built-in department list used for VLAN descriptions
+    ///
+    /// Names are trimmed and blank entries dropped; at least one must remain.
+    pub fn set_departments(&mut self, departments: Vec<String>) -> Result<()> {
+        let departments: Vec<String> = departments
+            .into_iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if departments.is_empty() {
+            return Err(ConfigError::invalid_parameter(
+                "departments",
+                "at least one department name is required",
+            ));
+        }
+
+        self.departments = Some(departments);
+        Ok(())
+    }
+
+    /// Never generate networks that overlap any of the given ranges
+    pub fn add_exclusions(&mut self, networks: &[Ipv4Network]) {
+        self.excluded_networks.extend_from_slice(networks);
+    }
+
+    /// Whether a candidate network overlaps an excluded range
+    fn is_excluded(&self, network: Ipv4Network) -> bool {
+        self.excluded_networks
+            .iter()
+            .any(|excluded| excluded.overlaps(network))
+    }
+
+    /// Mark the VLAN IDs and networks of existing configurations as used
+    ///
+    /// Subsequent generation will not reuse them, which lets new configurations
+    /// be appended to an existing data set without collisions.
+    pub fn reserve_existing(&mut self, configs: &[VlanConfig]) {
+        for config in configs {
+            if let Ok(network) = config.as_ipv4_network() {
+                self.used_networks.reserve(network);
+            }
+        }
+
+        let vlan_ids: Vec<u16> = configs.iter().map(|config| config.vlan_id).collect();
+        self.reserve_vlan_ids(&vlan_ids);
+    }
+
+    /// Mark VLAN IDs as already in use, e.g. those defined in a base config
+    pub fn reserve_vlan_ids(&mut self, vlan_ids: &[u16]) {
+        self.used_vlan_ids.extend(vlan_ids);
+
+        if let Some(pool) = &mut self.allowed_vlan_ids {
+            pool.retain(|id| !self.used_vlan_ids.contains(id));
+        }
+    }
+
+    /// Number of VLAN IDs still available in the allowed pool, if one is set
+    pub fn remaining_allowed_vlan_ids(&self) -> Option<usize> {
+        self.allowed_vlan_ids.as_ref().map(Vec::len)
+    }
+
+    /// Number of unique VLAN IDs the generator can still hand out
+    ///
+    /// Counts the allowed pool when `--vlan-range` style ranges are set, and
+    /// the unused IDs of 10-4094 otherwise.
+    pub fn remaining_vlan_ids(&self) -> usize {
+        self.remaining_allowed_vlan_ids().unwrap_or_else(|| {
+            (10..=4094u16)
+                .filter(|vlan_id| !self.used_vlan_ids.contains(vlan_id))
+                .count()
+        })
+    }
+
+    /// Rough number of unique networks the generator can still hand out
+    ///
+    /// Exact for a network pool, and for a supernet, whose excluded and
+    /// already used subnets are subtracted. For RFC 1918 classes it is the
+    /// number of networks of the configured prefix length the class draws
+    /// from, less those already used; excluded networks are not subtracted.
+    pub fn remaining_networks_estimate(&self) -> usize {
+        if let Some(pool) = &self.network_pool {
+            return pool.len();
+        }
+        if let Some(supernet) = self.supernet {
+            return usize::try_from(self.free_supernet_networks(supernet)).unwrap_or(usize::MAX);
+        }
+
+        let capacity = if self.balanced_classes {
+            [
+                Rfc1918Class::ClassA,
+                Rfc1918Class::ClassB,
+                Rfc1918Class::ClassC,
+            ]
+            .into_iter()
+            .map(|class| class_network_capacity(class, self.prefix_len))
+            .sum()
         } else {
-            Box::new(StdRng::from_rng(&mut rand::rng()))
+            class_network_capacity(
+                self.network_class.unwrap_or(Rfc1918Class::ClassA),
+                self.prefix_len,
+            )
         };
+        usize::try_from(capacity.saturating_sub(self.used_networks.len() as u64))
+            .unwrap_or(usize::MAX)
+    }
 
-        Self {
-            rng,
-            used_vlan_ids: HashSet::new(),
-            used_networks: HashSet::new(),
+    /// Fail early when an allowed VLAN ID pool cannot satisfy `count` configurations
+    fn ensure_vlan_id_capacity(&self, count: usize) -> Result<()> {
+        match self.remaining_allowed_vlan_ids() {
+            Some(available) if count > available && !self.allow_duplicates => {
+                Err(ConfigError::resource_exhausted(format!(
+                    "VLAN IDs (requested {count}, but only {available} available in allowed ranges)"
+                )))
+            }
+            _ => self.ensure_network_capacity(count),
+        }
+    }
+
+    /// Number of subnets of the generator's prefix length inside the supernet
+    fn supernet_capacity(&self) -> Option<u64> {
+        self.supernet.map(|supernet| {
+            self.prefix_len
+                .checked_sub(supernet.prefix())
+                .map_or(0, |bits| 1u64 << bits)
+        })
+    }
+
+    /// Subnets of the supernet that are neither excluded nor already used
+    fn free_supernet_networks(&self, supernet: Ipv4Network) -> u64 {
+        let Some(capacity) = self.supernet_capacity() else {
+            return 0;
+        };
+        let first = u64::from(u32::from(supernet.network()));
+        let last = u64::from(u32::from(supernet.broadcast()));
+        let block_size = 1u64 << (32 - self.prefix_len);
+
+        // Subnet index ranges touched by an exclusion or a used network,
+        // counted once each
+        let mut blocked_ranges: Vec<(u64, u64)> = self
+            .excluded_networks
+            .iter()
+            .map(|&excluded| ClaimedNetworks::bounds(excluded))
+            .chain(self.used_networks.ranges())
+            .filter_map(|(start, end)| {
+                let start = u64::from(start).max(first);
+                let end = u64::from(end).min(last);
+                (start <= end).then(|| ((start - first) / block_size, (end - first) / block_size))
+            })
+            .collect();
+        blocked_ranges.sort_unstable();
+        let mut blocked = 0;
+        let mut next_index = 0;
+        for (start, end) in blocked_ranges {
+            let start = start.max(next_index);
+            if start <= end {
+                blocked += end - start + 1;
+                next_index = end + 1;
+            }
+        }
+
+        capacity.saturating_sub(blocked)
+    }
+
+    /// Fail early when the supernet cannot hold `count` distinct networks
+    fn ensure_network_capacity(&self, count: usize) -> Result<()> {
+        if let Some(pool) = &self.network_pool {
+            if count > pool.len() {
+                return Err(ConfigError::resource_exhausted(format!(
+                    "IP networks (requested {count}, but the network pool only holds {})",
+                    pool.len()
+                )));
+            }
+        }
+        if self.allow_duplicates {
+            return Ok(());
+        }
+        match (self.supernet, self.supernet_capacity()) {
+            (Some(supernet), Some(available)) if count as u64 > available => {
+                Err(ConfigError::resource_exhausted(format!(
+                    "IP networks (requested {count}, but only {available} /{} networks fit in supernet {supernet})",
+                    self.prefix_len
+                )))
+            }
+            _ => Ok(()),
         }
     }
 
     /// Generate a single VLAN configuration
     pub fn generate_single(&mut self) -> Result<VlanConfig> {
-        const MAX_ATTEMPTS: usize = 1000;
-
         // Generate unique VLAN ID
-        let vlan_id = self.generate_unique_vlan_id(MAX_ATTEMPTS)?;
+        let vlan_id = self.generate_unique_vlan_id(self.max_attempts)?;
 
         // Generate unique IP network
-        let ip_network = self.generate_unique_ip_network(MAX_ATTEMPTS)?;
+        let ip_network = self.generate_unique_ip_network(self.max_attempts)?;
 
-        // Generate description using new department constants
-        let description = self.generate_description(vlan_id);
+        // Pick the department the description and priority derive from
+        let department = self.random_department();
 
         // Generate WAN assignment
         let wan_assignment = self.rng.random_range(1..=3);
 
-        VlanConfig::new(vlan_id, ip_network, description, wan_assignment)
+        self.build_config(vlan_id, ip_network, &department, wan_assignment)
     }
 
     /// Generate WAN assignment based on strategy
@@ -486,27 +1596,58 @@ pub fn generate_wan_assignment(
         }
     }
 
+    /// Build a VLAN configuration for a department with the generator's
+    /// description template, prefix and DHCP policy
+    fn build_config(
+        &self,
+        vlan_id: u16,
+        ip_network: String,
+        department: &str,
+        wan_assignment: u8,
+    ) -> Result<VlanConfig> {
+        let description = self.description_template.render(department, vlan_id);
+        VlanConfig::validate_description(&description, self.max_description_len)?;
+        // Pool networks keep their own prefix, so it is read off the network
+        let prefix_len = infer_prefix_len(&ip_network);
+        let mut config = VlanConfig::new_with_prefix(
+            vlan_id,
+            ip_network,
+            description,
+            wan_assignment,
+            prefix_len,
+        )?;
+        config.dhcp_policy = self.dhcp_policy;
+        config.pcp = departments::department_pcp(department);
+        Ok(config)
+    }
+
     /// Generate a single VLAN configuration with enhanced validation
     pub fn generate_single_enhanced(&mut self) -> VlanResult<VlanConfig> {
-        const MAX_ATTEMPTS: usize = 1000;
-
         // Generate unique VLAN ID
-        let vlan_id = self.generate_unique_vlan_id_enhanced(MAX_ATTEMPTS)?;
+        let vlan_id = self.generate_unique_vlan_id_enhanced(self.max_attempts)?;
 
         // Generate unique RFC 1918 network
-        let network = self.generate_unique_rfc1918_network(MAX_ATTEMPTS)?;
+        let network = self.generate_unique_rfc1918_network(self.max_attempts)?;
 
         // Generate description using new department constants
-        let description = self.generate_description_enhanced(vlan_id);
+        let department = self.random_department_enhanced();
+        let description = self.description_template.render(&department, vlan_id);
+        VlanConfig::validate_description(&description, self.max_description_len)
+            .map_err(|e| VlanError::validation(e.to_string()))?;
 
         // Generate WAN assignment
         let wan_assignment = Some(self.rng.random_range(1..=3));
 
-        VlanConfig::new_with_network(vlan_id, network, description, wan_assignment)
+        let mut config =
+            VlanConfig::new_with_network(vlan_id, network, description, wan_assignment)?;
+        config.dhcp_policy = self.dhcp_policy;
+        config.pcp = departments::department_pcp(&department);
+        Ok(config)
     }
 
     /// Generate a batch of VLAN configurations
     pub fn generate_batch(&mut self, count: usize) -> Result<Vec<VlanConfig>> {
+        self.ensure_vlan_id_capacity(count)?;
         let mut configs = Vec::with_capacity(count);
 
         for _ in 0..count {
@@ -517,6 +1658,259 @@ pub fn generate_batch(&mut self, count: usize) -> Result<Vec<VlanConfig>> {
         Ok(configs)
     }
 
+    /// Generate a batch of VLAN configurations along with their statistics
+    pub fn generate_batch_with_stats(
+        &mut self,
+        count: usize,
+    ) -> Result<(Vec<VlanConfig>, GenerationStats)> {
+        let started = Instant::now();
+        let configs = self.generate_batch(count)?;
+        let stats = GenerationStats::from_configs(&configs).with_duration(started.elapsed());
+        Ok((configs, stats))
+    }
+
+    /// Generate a batch of VLAN configurations, building them in parallel
+    ///
+    /// VLAN IDs, networks, WAN assignments and a per-configuration seed are
+    /// reserved up front in a single sequential pass, which keeps them unique
+    /// and makes the result depend only on the generator's seed. Descriptions
+    /// and configuration validation then run on the rayon thread pool. The
+    /// output is deterministic but differs from [`Self::generate_batch`] for
+    /// the same seed, since descriptions come from the per-configuration seeds.
+    #[cfg(feature = "rayon")]
+    pub fn generate_batch_parallel(&mut self, count: usize) -> Result<Vec<VlanConfig>> {
+        use rayon::prelude::*;
+
+        self.ensure_vlan_id_capacity(count)?;
+
+        let mut reserved = Vec::with_capacity(count);
+        for _ in 0..count {
+            let vlan_id = self.generate_unique_vlan_id(self.max_attempts)?;
+            let ip_network = self.generate_unique_ip_network(self.max_attempts)?;
+            let wan_assignment: u8 = self.rng.random_range(1..=3);
+            let seed: u64 = self.rng.random();
+            reserved.push((vlan_id, ip_network, wan_assignment, seed));
+        }
+
+        let departments: Vec<&str> = match &self.departments {
+            Some(departments) => departments.iter().map(String::as_str).collect(),
+            None => LEGACY_DEPARTMENTS.to_vec(),
+        };
+        let dhcp_policy = self.dhcp_policy;
+        let max_description_len = self.max_description_len;
+        let description_template = &self.description_template;
+
+        reserved
+            .into_par_iter()
+            .map(|(vlan_id, ip_network, wan_assignment, seed)| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let department = departments[rng.random_range(0..departments.len())];
+                let description = description_template.render(department, vlan_id);
+                VlanConfig::validate_description(&description, max_description_len)?;
+                let prefix_len = infer_prefix_len(&ip_network);
+                let mut config = VlanConfig::new_with_prefix(
+                    vlan_id,
+                    ip_network,
+                    description,
+                    wan_assignment,
+                    prefix_len,
+                )?;
+                config.dhcp_policy = dhcp_policy;
+                config.pcp = departments::department_pcp(department);
+                Ok(config)
+            })
+            .collect()
+    }
+
+    /// Generate `count` configurations, assigning WANs with the given strategy
+    pub fn generate_configurations(
+        &mut self,
+        count: u16,
+        wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
+        progress_bar: Option<&dyn ProgressSink>,
+    ) -> Result<Vec<VlanConfig>> {
+        self.generate_configurations_after(0, count, wan_strategy, progress_bar)
+    }
+
+    /// Generate configurations `skip..count` of a `count`-sized batch
+    ///
+    /// The first `skip` configurations are drawn and discarded, so a seeded
+    /// generator returns exactly the tail of what
+    /// [`generate_configurations`](Self::generate_configurations) would for
+    /// the same seed and count. Used to resume interrupted runs.
+    pub fn generate_configurations_after(
+        &mut self,
+        skip: u16,
+        count: u16,
+        wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
+        progress_bar: Option<&dyn ProgressSink>,
+    ) -> Result<Vec<VlanConfig>> {
+        if skip > count {
+            return Err(ConfigError::invalid_parameter(
+                "skip",
+                format!("cannot skip {skip} of {count} configurations"),
+            ));
+        }
+        self.ensure_vlan_id_capacity(count as usize)?;
+        for i in 0..skip {
+            self.generate_configuration_at(i as usize, count as usize, wan_strategy)?;
+        }
+
+        let mut configs = Vec::with_capacity((count - skip) as usize);
+        for i in skip..count {
+            let config =
+                self.generate_configuration_at(i as usize, count as usize, wan_strategy)?;
+            configs.push(config);
+
+            if let Some(pb) = progress_bar {
+                pb.set_position((i - skip) as u64 + 1);
+            }
+        }
+
+        Ok(configs)
+    }
+
+    /// Generate `count` configurations, handing each to `sink` as it is made
+    ///
+    /// Produces the same configurations as
+    /// [`generate_configurations`](Self::generate_configurations) without
+    /// collecting them, so callers can push each one into their own data
+    /// structure or network call. Stops at the first error from generation
+    /// or from `sink`, and returns the number of configurations delivered.
+    pub fn generate_into<F>(&mut self, count: u16, mut sink: F) -> Result<usize>
+    where
+        F: FnMut(VlanConfig) -> Result<()>,
+    {
+        self.ensure_vlan_id_capacity(count as usize)?;
+        for i in 0..count {
+            sink(self.generate_configuration_at(i as usize, count as usize, None)?)?;
+        }
+        Ok(count as usize)
+    }
+
+    /// Generate exactly the requested number of VLANs for each department
+    ///
+    /// Departments are processed in name order so a seeded generator yields
+    /// the same configurations regardless of map iteration order. VLAN IDs and
+    /// networks stay unique across all departments. Progress is reported
+    /// after every configuration, as in
+    /// [`generate_configurations`](Self::generate_configurations).
+    pub fn generate_by_department(
+        &mut self,
+        counts: HashMap<String, u16>,
+        progress_bar: Option<&dyn ProgressSink>,
+    ) -> Result<Vec<VlanConfig>> {
+        let mut departments: Vec<(String, u16)> = counts.into_iter().collect();
+        departments.sort();
+
+        let total: usize = departments.iter().map(|(_, count)| *count as usize).sum();
+        if total == 0 {
+            return Err(ConfigError::invalid_parameter(
+                "per_department",
+                "at least one VLAN must be requested",
+            ));
+        }
+        self.ensure_vlan_id_capacity(total)?;
+
+        let mut configs = Vec::with_capacity(total);
+        for (department, count) in &departments {
+            for _ in 0..*count {
+                let vlan_id = self.generate_unique_vlan_id(self.max_attempts)?;
+                let ip_network = self.generate_unique_ip_network(self.max_attempts)?;
+                let wan_assignment = self.rng.random_range(1..=3);
+
+                configs.push(self.build_config(vlan_id, ip_network, department, wan_assignment)?);
+
+                if let Some(pb) = progress_bar {
+                    pb.set_position(configs.len() as u64);
+                }
+            }
+        }
+
+        Ok(configs)
+    }
+
+    /// Turn the generator into a lazy iterator over `count` configurations
+    ///
+    /// Configurations are produced one at a time so callers can stream them to
+    /// disk without holding the whole batch in memory.
+    pub fn into_config_iter(
+        self,
+        count: u16,
+        wan_strategy: Option<crate::cli::WanAssignmentStrategy>,
+    ) -> Result<VlanGeneratorIter> {
+        self.ensure_vlan_id_capacity(count as usize)?;
+        Ok(VlanGeneratorIter {
+            generator: self,
+            wan_strategy,
+            index: 0,
+            total: count as usize,
+        })
+    }
+
+    /// Generate the configuration at position `index` of a `total`-sized batch
+    fn generate_configuration_at(
+        &mut self,
+        index: usize,
+        total: usize,
+        wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
+    ) -> Result<VlanConfig> {
+        let vlan_id = self.generate_unique_vlan_id(self.max_attempts)?;
+        let ip_network = self.generate_unique_ip_network(self.max_attempts)?;
+        let department = self.random_department();
+
+        // Generate WAN assignment based on strategy (random when unset)
+        let wan_assignment = self.generate_wan_assignment(wan_strategy, Some(index), Some(total));
+
+        self.build_config(vlan_id, ip_network, &department, wan_assignment)
+    }
+
+    /// Generate one configuration for every VLAN ID in the given ranges
+    pub fn generate_configurations_from_ranges(
+        &mut self,
+        vlan_ranges: &[(u16, u16)],
+        wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
+        progress_bar: Option<&dyn ProgressSink>,
+    ) -> Result<Vec<VlanConfig>> {
+        // Calculate total number of VLANs for progress tracking and pre-allocation
+        let total_vlans: u32 = vlan_ranges
+            .iter()
+            .map(|(start, end)| u32::from(*end) - u32::from(*start) + 1)
+            .sum();
+        self.ensure_network_capacity(total_vlans as usize)?;
+        let mut configs = Vec::with_capacity(total_vlans as usize);
+        let mut vlan_index = 0usize;
+
+        for (start, end) in vlan_ranges {
+            for vlan_id in *start..=*end {
+                // Skip IDs that were reserved before generation started
+                if !self.used_vlan_ids.insert(vlan_id) {
+                    continue;
+                }
+
+                let ip_network = self.generate_unique_ip_network(self.max_attempts)?;
+                let department = self.random_department();
+
+                // Generate WAN assignment based on strategy (random when unset)
+                let wan_assignment = self.generate_wan_assignment(
+                    wan_strategy,
+                    Some(vlan_index),
+                    Some(total_vlans as usize),
+                );
+
+                let config = self.build_config(vlan_id, ip_network, &department, wan_assignment)?;
+                configs.push(config);
+
+                vlan_index += 1;
+                if let Some(pb) = progress_bar {
+                    pb.set_position(vlan_index as u64);
+                }
+            }
+        }
+
+        Ok(configs)
+    }
+
     /// Generate a batch of VLAN configurations with enhanced validation
     pub fn generate_batch_enhanced(&mut self, count: usize) -> VlanResult<Vec<VlanConfig>> {
         let mut configs = Vec::with_capacity(count);
@@ -531,18 +1925,51 @@ pub fn generate_batch_enhanced(&mut self, count: usize) -> VlanResult<Vec<VlanCo
 
     /// Generate unique VLAN ID
     fn generate_unique_vlan_id(&mut self, max_attempts: usize) -> Result<u16> {
-        for _ in 0..max_attempts {
+        if let Some(pool) = self.allowed_vlan_ids.as_mut() {
+            if pool.is_empty() {
+                return Err(ConfigError::resource_exhausted(
+                    "VLAN IDs in allowed ranges",
+                ));
+            }
+            let index = self.rng.random_range(0..pool.len());
+            let vlan_id = if self.allow_duplicates {
+                pool[index]
+            } else {
+                pool.swap_remove(index)
+            };
+            self.used_vlan_ids.insert(vlan_id);
+            return Ok(vlan_id);
+        }
+
+        if self.allow_duplicates {
+            let vlan_id = self.rng.random_range(10..=4094);
+            self.used_vlan_ids.insert(vlan_id);
+            return Ok(vlan_id);
+        }
+
+        for attempt in 1..=max_attempts {
             let vlan_id = self.rng.random_range(10..=4094);
             if self.used_vlan_ids.insert(vlan_id) {
+                trace!(vlan_id, attempt, "claimed VLAN ID");
                 return Ok(vlan_id);
             }
+            debug!(vlan_id, attempt, "VLAN ID already in use, retrying");
         }
 
-        Err(ConfigError::resource_exhausted("VLAN IDs"))
+        debug!(max_attempts, "no free VLAN ID found");
+        Err(ConfigError::resource_exhausted(format!(
+            "VLAN IDs (gave up after {max_attempts} attempts)"
+        )))
     }
 
     /// Generate unique VLAN ID with enhanced error handling
     fn generate_unique_vlan_id_enhanced(&mut self, max_attempts: usize) -> VlanResult<u16> {
+        if self.allowed_vlan_ids.is_some() || self.allow_duplicates {
+            return self
+                .generate_unique_vlan_id(max_attempts)
+                .map_err(|_| VlanError::VlanIdExhausted);
+        }
+
         for _ in 0..max_attempts {
             let vlan_id = self.rng.random_range(10..=4094);
             if self.used_vlan_ids.insert(vlan_id) {
@@ -553,101 +1980,302 @@ fn generate_unique_vlan_id_enhanced(&mut self, max_attempts: usize) -> VlanResul
         Err(VlanError::VlanIdExhausted)
     }
 
+    /// Classes to try, in order, for the next generated network
+    ///
+    /// Balanced generation rotates through Class A, B and C; otherwise the
+    /// configured class (or `default`) is the only candidate.
+    fn next_network_classes(&mut self, default: Rfc1918Class) -> Vec<Rfc1918Class> {
+        if !self.balanced_classes {
+            return vec![self.network_class.unwrap_or(default)];
+        }
+
+        let mut classes = vec![
+            Rfc1918Class::ClassA,
+            Rfc1918Class::ClassB,
+            Rfc1918Class::ClassC,
+        ];
+        let start = self.class_cursor % classes.len();
+        classes.rotate_left(start);
+        self.class_cursor += 1;
+        classes
+    }
+
     /// Generate unique IP network
+    ///
+    /// Uniqueness is tracked on the packed network address, so the string
+    /// form is only built once a network has been claimed.
     pub fn generate_unique_ip_network(&mut self, max_attempts: usize) -> Result<String> {
-        for _ in 0..max_attempts {
-            // Generate Class A private network (10.0.0.0/8)
-            let second_octet = self.rng.random_range(1..=254);
-            let third_octet = self.rng.random_range(1..=254);
-            let network = format!("10.{second_octet}.{third_octet}.x");
+        self.generate_unique_network(max_attempts)
+            .map(|network| self.network_key(network))
+    }
+
+    /// Claim a network no earlier call has returned
+    ///
+    /// Honours the configured class, supernet, prefix length and exclusions,
+    /// defaulting to Class A private networks (10.0.0.0/8).
+    pub(crate) fn generate_unique_network(&mut self, max_attempts: usize) -> Result<Ipv4Network> {
+        if let Some(network) = self.take_pool_network() {
+            return network
+                .ok_or_else(|| ConfigError::resource_exhausted("IP networks in network pool"));
+        }
+
+        if let Some(supernet) = self.supernet {
+            return self.claim_supernet_network(supernet).ok_or_else(|| {
+                ConfigError::resource_exhausted(format!("IP networks in supernet {supernet}"))
+            });
+        }
+
+        let mut attempts = 0;
+        let classes = self.next_network_classes(Rfc1918Class::ClassA);
+        for class in self.fitting_classes(classes)? {
+            for _ in 0..max_attempts {
+                attempts += 1;
+                let network = class.generate_network(&mut self.rng);
+                let network = self.resize_network(network);
+                if self.is_excluded(network) {
+                    debug!(%network, attempt = attempts, "network is excluded, retrying");
+                    continue;
+                }
 
-            if self.used_networks.insert(network.clone()) {
-                return Ok(network);
+                if self.used_networks.claim(network) || self.allow_duplicates {
+                    trace!(%network, attempt = attempts, "claimed network");
+                    return Ok(network);
+                }
+                debug!(%network, attempt = attempts, "network already in use, retrying");
             }
+            debug!(?class, max_attempts, "no free network found in class");
         }
 
-        Err(ConfigError::resource_exhausted("IP networks"))
+        let resource = if self.excluded_networks.is_empty() {
+            "IP networks"
+        } else {
+            "IP networks outside excluded ranges"
+        };
+        Err(ConfigError::resource_exhausted(format!(
+            "{resource} (gave up after {attempts} attempts)"
+        )))
     }
 
     /// Generate unique RFC 1918 network using ipnetwork types
     fn generate_unique_rfc1918_network(&mut self, max_attempts: usize) -> VlanResult<Ipv4Network> {
-        for _ in 0..max_attempts {
-            // Prefer Class A networks for larger address space
-            let network = if self.rng.random_bool(0.8) {
-                rfc1918::generate_random_class_a_network(&mut self.rng)
-            } else if self.rng.random_bool(0.6) {
-                rfc1918::generate_random_class_b_network(&mut self.rng)
-            } else {
-                rfc1918::generate_random_class_c_network(&mut self.rng)
-            };
+        if let Some(network) = self.take_pool_network() {
+            return network.ok_or(VlanError::NetworkExhausted);
+        }
+
+        if let Some(supernet) = self.supernet {
+            return self
+                .claim_supernet_network(supernet)
+                .ok_or(VlanError::NetworkExhausted);
+        }
 
-            let network_key = network.to_string();
-            if self.used_networks.insert(network_key) {
-                return Ok(network);
+        let classes = self.next_network_classes(Rfc1918Class::Mixed);
+        let classes = self
+            .fitting_classes(classes)
+            .map_err(|e| VlanError::validation(e.to_string()))?;
+        for class in classes {
+            for _ in 0..max_attempts {
+                let network = class.generate_network(&mut self.rng);
+                let network = self.resize_network(network);
+                if self.is_excluded(network) {
+                    continue;
+                }
+
+                if self.used_networks.claim(network) || self.allow_duplicates {
+                    return Ok(network);
+                }
             }
         }
 
         Err(VlanError::NetworkExhausted)
     }
 
+    /// Network string as stored in `VlanConfig::ip_network`
+    ///
+    /// /24 networks use the legacy `a.b.c.x` form, other prefixes CIDR.
+    fn network_key(&self, network: Ipv4Network) -> String {
+        if network.prefix() == DEFAULT_PREFIX_LEN {
+            let [a, b, c, _] = network.network().octets();
+            format!("{a}.{b}.{c}.x")
+        } else {
+            network.to_string()
+        }
+    }
+
+    /// Reserve a free subnet of the supernet, or `None` when it is full
+    ///
+    /// The scan starts at a random subnet and wraps around, so every free
+    /// subnet is found no matter how full the supernet is.
+    fn claim_supernet_network(&mut self, supernet: Ipv4Network) -> Option<Ipv4Network> {
+        let capacity = self.supernet_capacity().filter(|&capacity| capacity > 0)?;
+        let base = u64::from(u32::from(supernet.network()));
+        let block_size = 1u64 << (32 - self.prefix_len);
+        let start = self.rng.random_range(0..capacity);
+
+        for offset in 0..capacity {
+            let index = (start + offset) % capacity;
+            let address = Ipv4Addr::from((base + index * block_size) as u32);
+            let network = Ipv4Network::new(address, self.prefix_len)
+                .expect("prefix length validated by with_prefix_len");
+            if self.is_excluded(network) {
+                continue;
+            }
+
+            if !self.used_networks.claim(network) && !self.allow_duplicates {
+                continue;
+            }
+            return Some(network);
+        }
+
+        None
+    }
+
+    /// Drop the classes the prefix length doesn't fit
+    ///
+    /// Balanced rotation skips such classes; only when none is left is the
+    /// prefix length rejected.
+    fn fitting_classes(&self, classes: Vec<Rfc1918Class>) -> Result<Vec<Rfc1918Class>> {
+        let Some(&first) = classes.first() else {
+            return Ok(classes);
+        };
+        let fitting: Vec<_> = classes
+            .into_iter()
+            .filter(|&class| self.prefix_len >= class.min_prefix_len())
+            .collect();
+        if fitting.is_empty() {
+            self.ensure_prefix_fits_class(first)?;
+        }
+        Ok(fitting)
+    }
+
+    /// Reject prefix lengths whose networks would spill outside the class block
+    fn ensure_prefix_fits_class(&self, class: Rfc1918Class) -> Result<()> {
+        if self.prefix_len < class.min_prefix_len() {
+            return Err(ConfigError::invalid_parameter(
+                "prefix_len",
+                format!(
+                    "/{} is too short for {:?} networks (minimum /{})",
+                    self.prefix_len,
+                    class,
+                    class.min_prefix_len()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resize a generated /24 network to the configured prefix length.
+    ///
+    /// Shorter prefixes mask down to the enclosing network, longer prefixes
+    /// pick a random aligned block inside the /24.
+    fn resize_network(&mut self, network: Ipv4Network) -> Ipv4Network {
+        let base = u32::from(network.network());
+        let address = match self.prefix_len {
+            DEFAULT_PREFIX_LEN => return network,
+            prefix if prefix > DEFAULT_PREFIX_LEN => {
+                let block_size = 1u32 << (32 - prefix);
+                base + self.rng.random_range(0..256 / block_size) * block_size
+            }
+            prefix => base & (u32::MAX << (32 - prefix)),
+        };
+        Ipv4Network::new(Ipv4Addr::from(address), self.prefix_len)
+            .expect("prefix length validated by with_prefix_len")
+    }
+
     /// Generate department-based description using legacy constants
     pub fn generate_description(&mut self, vlan_id: u16) -> String {
-        const DEPARTMENTS: &[&str] = &[
-            "Sales",
-            "IT",
-            "HR",
-            "Finance",
-            "Marketing",
-            "Operations",
-            "Engineering",
-            "Support",
-            "Legal",
-            "Procurement",
-            "Security",
-            "Development",
-            "QA",
-            "Research",
-            "Training",
-            "Management",
-        ];
+        let department = self.random_department();
+        self.description_template.render(&department, vlan_id)
+    }
 
-        let department = DEPARTMENTS[self.rng.random_range(0..DEPARTMENTS.len())];
-        format!("{department} VLAN {vlan_id}")
+    /// Pick a custom department, or one of the legacy constants
+    fn random_department(&mut self) -> String {
+        if let Some(departments) = &self.departments {
+            return departments[self.rng.random_range(0..departments.len())].clone();
+        }
+
+        LEGACY_DEPARTMENTS[self.rng.random_range(0..LEGACY_DEPARTMENTS.len())].to_string()
     }
 
-    /// Generate department-based description using new constants
-    fn generate_description_enhanced(&mut self, vlan_id: u16) -> String {
-        let department = departments::random_department(&mut self.rng);
-        format!("{department} VLAN {vlan_id}")
+    /// Pick a custom department, or one of the new constants
+    fn random_department_enhanced(&mut self) -> String {
+        if self.departments.is_some() {
+            return self.random_department();
+        }
+
+        departments::random_department(&mut self.rng).to_string()
     }
 }
 
-/// Generate multiple VLAN configurations using legacy StdRng for compatibility
-pub fn generate_vlan_configurations(
-    count: u16,
-    seed: Option<u64>,
-    progress_bar: Option<&ProgressBar>,
-) -> Result<Vec<VlanConfig>> {
-    let mut generator = VlanGenerator::new_with_std_rng(seed);
-    let mut configs = Vec::with_capacity(count as usize);
+/// Lazy iterator over generated VLAN configurations
+///
+/// Created by [`VlanGenerator::into_config_iter`]. Iteration stops after the
+/// requested count or the first error.
+pub struct VlanGeneratorIter {
+    generator: VlanGenerator,
+    wan_strategy: Option<crate::cli::WanAssignmentStrategy>,
+    index: usize,
+    total: usize,
+}
 
-    for i in 0..count {
-        let config = generator.generate_single()?;
-        configs.push(config);
+impl Iterator for VlanGeneratorIter {
+    type Item = Result<VlanConfig>;
 
-        if let Some(pb) = progress_bar {
-            pb.set_position(i as u64 + 1);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.total {
+            return None;
         }
+
+        let result = self.generator.generate_configuration_at(
+            self.index,
+            self.total,
+            self.wan_strategy.as_ref(),
+        );
+        self.index = if result.is_ok() {
+            self.index + 1
+        } else {
+            self.total
+        };
+        Some(result)
     }
 
-    Ok(configs)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.total - self.index))
+    }
+}
+
+/// Number of `prefix_len` networks a class draws from
+///
+/// The class generators draw /24 networks with the second and third octets
+/// (third only, for Class C) in 1-254; other prefix lengths split or merge
+/// those /24s.
+fn class_network_capacity(class: Rfc1918Class, prefix_len: u8) -> u64 {
+    let slash_24s: u64 = match class {
+        Rfc1918Class::ClassA => 254 * 254,
+        Rfc1918Class::ClassB => 16 * 254,
+        Rfc1918Class::ClassC => 254,
+        Rfc1918Class::Mixed => 254 * 254 + 16 * 254 + 254,
+    };
+    if prefix_len >= DEFAULT_PREFIX_LEN {
+        slash_24s << (prefix_len - DEFAULT_PREFIX_LEN)
+    } else {
+        (slash_24s >> (DEFAULT_PREFIX_LEN - prefix_len)).max(1)
+    }
+}
+
+/// Generate multiple VLAN configurations using legacy StdRng for compatibility
+pub fn generate_vlan_configurations(
+    count: u16,
+    seed: Option<u64>,
+    progress_bar: Option<&dyn ProgressSink>,
+) -> Result<Vec<VlanConfig>> {
+    VlanGenerator::new_with_std_rng(seed).generate_configurations(count, None, progress_bar)
 }
 
 /// Generate multiple VLAN configurations using enhanced ChaCha8Rng
 pub fn generate_vlan_configurations_enhanced(
     count: u16,
     seed: Option<u64>,
-    progress_bar: Option<&ProgressBar>,
+    progress_bar: Option<&dyn ProgressSink>,
 ) -> VlanResult<Vec<VlanConfig>> {
     let mut generator = VlanGenerator::new(seed);
     let mut configs = Vec::with_capacity(count as usize);
@@ -668,40 +2296,13 @@ pub fn generate_vlan_configurations_enhanced(
 pub fn generate_vlan_configurations_from_ranges(
     vlan_ranges: &[(u16, u16)],
     seed: Option<u64>,
-    progress_bar: Option<&ProgressBar>,
+    progress_bar: Option<&dyn ProgressSink>,
 ) -> Result<Vec<VlanConfig>> {
-    let mut generator = VlanGenerator::new_with_std_rng(seed);
-
-    // Calculate total number of VLANs for progress tracking and pre-allocation
-    let total_vlans: u32 = vlan_ranges
-        .iter()
-        .map(|(start, end)| u32::from(*end) - u32::from(*start) + 1)
-        .sum();
-    let mut configs = Vec::with_capacity(total_vlans as usize);
-    let mut processed = 0u64;
-
-    for (start, end) in vlan_ranges {
-        for vlan_id in *start..=*end {
-            // Generate unique IP network
-            let ip_network = generator.generate_unique_ip_network(1000)?;
-
-            // Generate description
-            let description = generator.generate_description(vlan_id);
-
-            // Generate WAN assignment
-            let wan_assignment = generator.rng.random_range(1..=3);
-
-            let config = VlanConfig::new(vlan_id, ip_network, description, wan_assignment)?;
-            configs.push(config);
-
-            processed += 1;
-            if let Some(pb) = progress_bar {
-                pb.set_position(processed);
-            }
-        }
-    }
-
-    Ok(configs)
+    VlanGenerator::new_with_std_rng(seed).generate_configurations_from_ranges(
+        vlan_ranges,
+        None,
+        progress_bar,
+    )
 }
 
 /// Generate VLAN configurations from specified ranges with WAN assignment strategy
@@ -709,46 +2310,13 @@ pub fn generate_vlan_configurations_from_ranges_with_wan(
     vlan_ranges: &[(u16, u16)],
     seed: Option<u64>,
     wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
-    progress_bar: Option<&ProgressBar>,
+    progress_bar: Option<&dyn ProgressSink>,
 ) -> Result<Vec<VlanConfig>> {
-    let mut generator = VlanGenerator::new_with_std_rng(seed);
-
-    // Calculate total number of VLANs for progress tracking and pre-allocation
-    let total_vlans: u32 = vlan_ranges
-        .iter()
-        .map(|(start, end)| u32::from(*end) - u32::from(*start) + 1)
-        .sum();
-    let mut configs = Vec::with_capacity(total_vlans as usize);
-    let mut processed = 0u64;
-    let mut vlan_index = 0usize;
-
-    for (start, end) in vlan_ranges {
-        for vlan_id in *start..=*end {
-            // Generate unique IP network
-            let ip_network = generator.generate_unique_ip_network(1000)?;
-
-            // Generate description
-            let description = generator.generate_description(vlan_id);
-
-            // Generate WAN assignment based on strategy
-            let wan_assignment = generator.generate_wan_assignment(
-                wan_strategy,
-                Some(vlan_index),
-                Some(total_vlans as usize),
-            );
-
-            let config = VlanConfig::new(vlan_id, ip_network, description, wan_assignment)?;
-            configs.push(config);
-
-            processed += 1;
-            vlan_index += 1;
-            if let Some(pb) = progress_bar {
-                pb.set_position(processed);
-            }
-        }
-    }
-
-    Ok(configs)
+    VlanGenerator::new_with_std_rng(seed).generate_configurations_from_ranges(
+        vlan_ranges,
+        wan_strategy,
+        progress_bar,
+    )
 }
 
 /// Generate VLAN configurations by count with WAN assignment strategy
@@ -756,29 +2324,22 @@ pub fn generate_vlan_configurations_with_wan(
     count: u16,
     seed: Option<u64>,
     wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
-    progress_bar: Option<&ProgressBar>,
+    progress_bar: Option<&dyn ProgressSink>,
 ) -> Result<Vec<VlanConfig>> {
-    let mut generator = VlanGenerator::new_with_std_rng(seed);
-    let mut configs = Vec::with_capacity(count as usize);
-
-    for i in 0..count {
-        let vlan_id = generator.generate_unique_vlan_id(1000)?;
-        let ip_network = generator.generate_unique_ip_network(1000)?;
-        let description = generator.generate_description(vlan_id);
-
-        // Generate WAN assignment based on strategy
-        let wan_assignment =
-            generator.generate_wan_assignment(wan_strategy, Some(i as usize), Some(count as usize));
-
-        let config = VlanConfig::new(vlan_id, ip_network, description, wan_assignment)?;
-        configs.push(config);
-
-        if let Some(pb) = progress_bar {
-            pb.set_position(i as u64 + 1);
-        }
-    }
+    VlanGenerator::new_with_std_rng(seed).generate_configurations(count, wan_strategy, progress_bar)
+}
 
-    Ok(configs)
+/// Generate `count` VLAN configurations with IDs drawn only from the given ranges
+pub fn generate_vlan_configurations_in_ranges(
+    count: u16,
+    vlan_ranges: &[(u16, u16)],
+    seed: Option<u64>,
+    wan_strategy: Option<&crate::cli::WanAssignmentStrategy>,
+    progress_bar: Option<&dyn ProgressSink>,
+) -> Result<Vec<VlanConfig>> {
+    let mut generator = VlanGenerator::new_with_std_rng(seed);
+    generator.set_allowed_vlan_ids(vlan_ranges)?;
+    generator.generate_configurations(count, wan_strategy, progress_bar)
 }
 
 #[cfg(test)]
@@ -1012,6 +2573,113 @@ fn test_dhcp_range_end_derives_from_cidr_format() {
         assert_eq!(config2.dhcp_range_end().unwrap(), "192.168.50.200");
     }
 
+    #[test]
+    fn test_dhcp_range_policy_sets_pool_bounds() {
+        let policy = DhcpRangePolicy::new(20, 240).unwrap();
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "Test".to_string(), 1)
+            .unwrap()
+            .with_dhcp_policy(policy)
+            .unwrap();
+        assert_eq!(config.dhcp_range_start().unwrap(), "10.1.2.20");
+        assert_eq!(config.dhcp_range_end().unwrap(), "10.1.2.240");
+
+        let mut generator = VlanGenerator::new(Some(42))
+            .with_dhcp_policy(policy)
+            .unwrap();
+        for config in generator.generate_batch(10).unwrap() {
+            assert!(config.dhcp_range_start().unwrap().ends_with(".20"));
+            assert!(config.dhcp_range_end().unwrap().ends_with(".240"));
+        }
+    }
+
+    #[test]
+    fn test_outer_vlan_id_validation() {
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "Test".to_string(), 1).unwrap();
+        assert!(config.clone().with_outer_vlan_id(9).is_err());
+        assert!(config.clone().with_outer_vlan_id(4095).is_err());
+
+        let stacked = config.with_outer_vlan_id(200).unwrap();
+        assert_eq!(stacked.outer_vlan_id, Some(200));
+        assert!(stacked.validate().is_ok());
+
+        let mut invalid = stacked;
+        invalid.outer_vlan_id = Some(5000);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_dhcp_range_policy_validation() {
+        assert!(DhcpRangePolicy::new(200, 100).is_err());
+        assert!(DhcpRangePolicy::new(100, 100).is_err());
+
+        // Offsets must avoid the network address, gateway and broadcast
+        assert!(
+            DhcpRangePolicy::new(1, 100)
+                .unwrap()
+                .validate_for_prefix(24)
+                .is_err()
+        );
+        assert!(
+            DhcpRangePolicy::new(2, 254)
+                .unwrap()
+                .validate_for_prefix(24)
+                .is_ok()
+        );
+        assert!(
+            DhcpRangePolicy::new(2, 255)
+                .unwrap()
+                .validate_for_prefix(24)
+                .is_err()
+        );
+
+        // A /26 only has 64 addresses
+        let policy = DhcpRangePolicy::new(20, 240).unwrap();
+        assert!(
+            VlanGenerator::new(Some(42))
+                .with_prefix_len(26)
+                .unwrap()
+                .with_dhcp_policy(policy)
+                .is_err()
+        );
+        let config =
+            VlanConfig::new_with_prefix(100, "10.1.2.64/26".to_string(), "Test".to_string(), 1, 26)
+                .unwrap();
+        assert!(config.with_dhcp_policy(policy).is_err());
+    }
+
+    #[test]
+    fn test_dhcp_range_policy_from_str() {
+        let policy: DhcpRangePolicy = "50-250".parse().unwrap();
+        assert_eq!(policy, DhcpRangePolicy::new(50, 250).unwrap());
+        assert_eq!(policy.to_string(), "50-250");
+
+        assert!("50".parse::<DhcpRangePolicy>().is_err());
+        assert!("50-300".parse::<DhcpRangePolicy>().is_err());
+        assert!("250-50".parse::<DhcpRangePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_slash_30_gateway_and_dhcp_stay_within_usable_hosts() {
+        // 10.1.2.0 is the network and 10.1.2.3 the broadcast address
+        let config = VlanConfig::new_with_prefix(
+            100,
+            "10.1.2.0/30".to_string(),
+            "IT VLAN 100".to_string(),
+            1,
+            30,
+        )
+        .unwrap();
+
+        assert_eq!(config.gateway_ip().unwrap(), "10.1.2.1");
+        let usable = ["10.1.2.1", "10.1.2.2"];
+        assert!(usable.contains(&config.dhcp_range_start().unwrap().as_str()));
+        assert!(usable.contains(&config.dhcp_range_end().unwrap().as_str()));
+
+        assert!(config.host_ip(0).is_err());
+        assert!(config.host_ip(3).is_err());
+        assert_eq!(config.host_ip(2).unwrap(), "10.1.2.2");
+    }
+
     #[test]
     fn test_gateway_dhcp_methods_fail_when_network_invalid() {
         // Create a config with invalid network by bypassing validation
@@ -1025,7 +2693,7 @@ fn test_gateway_dhcp_methods_fail_when_network_invalid() {
         assert!(config.dhcp_range_start().is_err());
         assert!(config.dhcp_range_end().is_err());
 
-        // All methods delegate to network_base(), which returns a unified error
+        // All methods delegate to network_bounds(), which returns a unified error
         let gateway_error = config.gateway_ip().unwrap_err();
         assert!(
             gateway_error
@@ -1161,45 +2829,425 @@ fn test_vlan_config_creation_with_boundary_values() {
             )
             .unwrap();
 
-            assert_eq!(config.vlan_id, vlan_id);
-            assert_eq!(config.ip_network, network);
-            assert_eq!(config.wan_assignment, wan);
+            assert_eq!(config.vlan_id, vlan_id);
+            assert_eq!(config.ip_network, network);
+            assert_eq!(config.wan_assignment, wan);
+
+            // Verify derived addresses work
+            assert!(config.gateway_ip().is_ok());
+            assert!(config.dhcp_range_start().is_ok());
+            assert!(config.dhcp_range_end().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_vlan_generator_with_different_seeds() {
+        // Test that different seeds produce different (but valid) results
+        let mut gen1 = VlanGenerator::new(Some(12345));
+        let mut gen2 = VlanGenerator::new(Some(67890));
+
+        let config1 = gen1.generate_single().unwrap();
+        let config2 = gen2.generate_single().unwrap();
+
+        // Both should be valid but likely different
+        assert!((10..=4094).contains(&config1.vlan_id));
+        assert!((10..=4094).contains(&config2.vlan_id));
+        assert!((1..=3).contains(&config1.wan_assignment));
+        assert!((1..=3).contains(&config2.wan_assignment));
+
+        // With different seeds, results should usually be different
+        // (Not guaranteed, but extremely likely with good seeds)
+    }
+
+    #[test]
+    fn test_subnet_mask_is_always_24() {
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "Test".to_string(), 1).unwrap();
+        assert_eq!(config.subnet_mask(), "255.255.255.0");
+
+        // Should be consistent regardless of config parameters
+        let config2 =
+            VlanConfig::new(4094, "192.168.1.0/24".to_string(), "Other".to_string(), 3).unwrap();
+        assert_eq!(config2.subnet_mask(), "255.255.255.0");
+    }
+
+    #[test]
+    fn test_subnet_mask_follows_prefix_len() {
+        let config =
+            VlanConfig::new_with_prefix(100, "10.1.2.64/26".to_string(), "Test".to_string(), 1, 26)
+                .unwrap();
+        assert_eq!(config.subnet_mask(), "255.255.255.192");
+
+        let config =
+            VlanConfig::new_with_prefix(100, "10.4.0.0/16".to_string(), "Test".to_string(), 1, 16)
+                .unwrap();
+        assert_eq!(config.subnet_mask(), "255.255.0.0");
+    }
+
+    #[test]
+    fn test_display_format() {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        assert_eq!(
+            config.to_string(),
+            "VLAN 100  10.1.2.x  /24  WAN1  \"IT VLAN 100\""
+        );
+    }
+
+    #[test]
+    fn test_new_with_prefix_rejects_out_of_range_prefix() {
+        for prefix_len in [0, 7, 31, 32] {
+            let result = VlanConfig::new_with_prefix(
+                100,
+                format!("10.1.2.0/{prefix_len}"),
+                "Test".to_string(),
+                1,
+                prefix_len,
+            );
+            let error_msg = result.unwrap_err().to_string();
+            assert!(error_msg.contains(&format!(
+                "Prefix length /{prefix_len} is outside valid range /8-/30"
+            )));
+        }
+    }
+
+    #[test]
+    fn test_new_with_prefix_requires_matching_aligned_cidr() {
+        // Legacy .x format only describes /24 networks
+        assert!(
+            VlanConfig::new_with_prefix(100, "10.1.2.x".to_string(), "Test".to_string(), 1, 26)
+                .is_err()
+        );
+        // Prefix in the string must match the requested prefix
+        assert!(
+            VlanConfig::new_with_prefix(100, "10.1.2.0/25".to_string(), "Test".to_string(), 1, 26)
+                .is_err()
+        );
+        // Host bits must be zero
+        assert!(
+            VlanConfig::new_with_prefix(100, "10.1.2.32/26".to_string(), "Test".to_string(), 1, 26)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_addresses_respect_usable_host_range() {
+        let config = VlanConfig::new_with_prefix(
+            100,
+            "10.1.2.64/26".to_string(),
+            "IT VLAN 100".to_string(),
+            1,
+            26,
+        )
+        .unwrap();
+        assert_eq!(config.gateway_ip().unwrap(), "10.1.2.65");
+        assert_eq!(config.dhcp_range_start().unwrap(), "10.1.2.89");
+        assert_eq!(config.dhcp_range_end().unwrap(), "10.1.2.114");
+        assert!(config.validate().is_ok());
+
+        // Reservations at .10/.11 fit below the DHCP pool
+        let reservations = config.static_reservations(&[]).unwrap();
+        assert_eq!(reservations.len(), 2);
+        assert_eq!(reservations[0].ip_addr, "10.1.2.74");
+
+        // A /30 only has two usable hosts: gateway and a single lease
+        let config = VlanConfig::new_with_prefix(
+            100,
+            "10.1.2.4/30".to_string(),
+            "Sales VLAN 100".to_string(),
+            1,
+            30,
+        )
+        .unwrap();
+        assert_eq!(config.gateway_ip().unwrap(), "10.1.2.5");
+        assert_eq!(config.dhcp_range_start().unwrap(), "10.1.2.6");
+        assert_eq!(config.dhcp_range_end().unwrap(), "10.1.2.6");
+        assert!(config.static_reservations(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generator_with_prefix_len() {
+        let mut generator = VlanGenerator::new(Some(42)).with_prefix_len(27).unwrap();
+        assert_eq!(generator.prefix_len(), 27);
+
+        let configs = generator.generate_batch(20).unwrap();
+        for config in &configs {
+            assert_eq!(config.prefix_len, 27);
+            assert!(config.ip_network.ends_with("/27"));
+            assert!(config.validate().is_ok());
+        }
+
+        let configs = generator.generate_batch_enhanced(20).unwrap();
+        for config in &configs {
+            assert_eq!(config.as_ipv4_network().unwrap().prefix(), 27);
+        }
+
+        assert!(VlanGenerator::new(Some(42)).with_prefix_len(31).is_err());
+        assert!(VlanGenerator::new(Some(42)).with_prefix_len(7).is_err());
+    }
+
+    #[test]
+    fn test_infer_prefix_len() {
+        assert_eq!(infer_prefix_len("10.1.2.x"), 24);
+        assert_eq!(infer_prefix_len("10.1.2.0/24"), 24);
+        assert_eq!(infer_prefix_len("10.1.2.64/26"), 26);
+        assert_eq!(infer_prefix_len("10.1.2.0/abc"), 24);
+    }
+
+    #[test]
+    fn test_network_class_c_only_yields_192_168() {
+        let mut generator =
+            VlanGenerator::new_with_std_rng(Some(42)).with_network_class(Rfc1918Class::ClassC);
+        let configs = generator.generate_configurations(50, None, None).unwrap();
+        for config in &configs {
+            assert!(
+                config.ip_network.starts_with("192.168."),
+                "unexpected network {}",
+                config.ip_network
+            );
+            assert!(config.validate().is_ok());
+        }
+
+        let mut generator = VlanGenerator::new(Some(42)).with_network_class(Rfc1918Class::ClassC);
+        for config in generator.generate_batch_enhanced(50).unwrap() {
+            assert_eq!(
+                config.as_ipv4_network().unwrap().network().octets()[..2],
+                [192, 168]
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_batch_with_stats_matches_configs() {
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42)).with_balanced_classes();
+        let (configs, stats) = generator.generate_batch_with_stats(12).unwrap();
+
+        assert_eq!(stats.total, configs.len());
+        assert_eq!(stats.unique_vlan_ids, configs.len());
+        assert_eq!(
+            stats.networks_by_class.values().sum::<usize>(),
+            configs.len()
+        );
+        assert_eq!(stats.networks_by_class.len(), 3);
+        assert_eq!(stats.departments.values().sum::<usize>(), configs.len());
+    }
+
+    #[test]
+    fn test_balanced_classes_cover_every_class() {
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42)).with_balanced_classes();
+        let configs = generator.generate_configurations(9, None, None).unwrap();
+
+        let mut per_class = [0usize; 3];
+        for config in &configs {
+            match config.ip_network.split('.').next().unwrap() {
+                "10" => per_class[0] += 1,
+                "172" => per_class[1] += 1,
+                "192" => per_class[2] += 1,
+                other => panic!("unexpected network {other}"),
+            }
+        }
+        assert!(per_class.iter().all(|&count| count >= 3), "{per_class:?}");
+    }
+
+    #[test]
+    fn test_balanced_classes_skip_classes_the_prefix_does_not_fit() {
+        // A /14 fits Class A and B but not Class C (minimum /16)
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42))
+            .with_balanced_classes()
+            .with_prefix_len(14)
+            .unwrap();
+        let configs = generator.generate_configurations(6, None, None).unwrap();
+        assert_eq!(configs.len(), 6);
+        assert!(
+            configs
+                .iter()
+                .all(|config| !config.ip_network.starts_with("192.168.")),
+            "{configs:?}"
+        );
+
+        // A single class the prefix doesn't fit is still rejected
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42))
+            .with_network_class(Rfc1918Class::ClassC)
+            .with_prefix_len(14)
+            .unwrap();
+        assert!(generator.generate_configurations(1, None, None).is_err());
+    }
+
+    #[test]
+    fn test_description_template() {
+        let template: DescriptionTemplate = "{dept}_{id}".parse().unwrap();
+        let mut generator =
+            VlanGenerator::new_with_std_rng(Some(42)).with_description_template(template);
+        for config in generator.generate_batch(20).unwrap() {
+            let (department, id) = config.description.split_once('_').unwrap();
+            assert!(LEGACY_DEPARTMENTS.contains(&department));
+            assert_eq!(id, config.vlan_id.to_string());
+        }
+
+        assert!("{dept} {vlan}".parse::<DescriptionTemplate>().is_err());
+        assert_eq!(
+            DescriptionTemplate::default().render("IT", 100),
+            "IT VLAN 100"
+        );
+    }
+
+    #[test]
+    fn test_dhcp_lease_time_finds_department_anywhere() {
+        let lease = |description: &str| {
+            VlanConfig::new(100, "10.1.2.x".to_string(), description.to_string(), 1)
+                .unwrap()
+                .dhcp_lease_time()
+        };
+        assert_eq!(lease("IT VLAN 100"), 86400);
+        assert_eq!(lease("IT_100"), 86400);
+        assert_eq!(lease("NYC Security VLAN 100"), 21600);
+        assert_eq!(lease("100-net-HR"), 14400);
+        assert_eq!(lease("Guest VLAN 100"), 28800);
+
+        // Multi-word departments are matched before the description is split
+        assert_eq!(
+            VlanConfig::department_lease_time("Customer Service"),
+            Some(28800)
+        );
+        assert_eq!(lease("Customer Service VLAN 100"), 28800);
+        assert_eq!(lease("Customer Service_100"), 28800);
+    }
+
+    #[test]
+    fn test_max_attempts_budget() {
+        // Class C yields only 254 distinct /24 networks, so filling it takes many draws
+        let generate = |max_attempts| {
+            VlanGenerator::new_with_std_rng(Some(42))
+                .with_network_class(Rfc1918Class::ClassC)
+                .with_max_attempts(max_attempts)
+                .unwrap()
+                .generate_configurations(254, None, None)
+        };
+
+        let err = generate(5).unwrap_err();
+        assert!(matches!(err, ConfigError::ResourceExhausted { .. }));
+        assert!(err.to_string().contains("after 5 attempts"), "{err}");
+
+        let configs = generate(100_000).unwrap();
+        assert_eq!(configs.len(), 254);
+
+        assert!(VlanGenerator::new(None).with_max_attempts(0).is_err());
+    }
+
+    #[test]
+    fn test_supernet_keeps_networks_inside() {
+        let supernet: Ipv4Network = "10.50.0.0/16".parse().unwrap();
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42))
+            .with_supernet(supernet)
+            .unwrap();
+        for config in generator.generate_batch(50).unwrap() {
+            assert!(supernet.contains(config.as_ipv4_network().unwrap().network()));
+        }
+
+        let mut generator = VlanGenerator::new(Some(42))
+            .with_supernet(supernet)
+            .unwrap();
+        for config in generator.generate_batch_enhanced(10).unwrap() {
+            assert!(supernet.contains(config.as_ipv4_network().unwrap().network()));
+        }
+    }
+
+    #[test]
+    fn test_supernet_exhaustion() {
+        let supernet: Ipv4Network = "10.50.0.0/24".parse().unwrap();
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42))
+            .with_supernet(supernet)
+            .unwrap();
+        let result = generator.generate_configurations(2, None, None);
+        assert!(matches!(result, Err(ConfigError::ResourceExhausted { .. })));
+
+        let configs = generator.generate_configurations(1, None, None).unwrap();
+        assert_eq!(configs[0].ip_network, "10.50.0.x");
+        assert!(matches!(
+            generator.generate_single(),
+            Err(ConfigError::ResourceExhausted { .. })
+        ));
+
+        assert!(
+            VlanGenerator::new(Some(42))
+                .with_supernet("8.8.0.0/16".parse().unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_network_class_rejects_prefix_wider_than_class() {
+        let mut generator = VlanGenerator::new(Some(42))
+            .with_network_class(Rfc1918Class::ClassC)
+            .with_prefix_len(12)
+            .unwrap();
+        assert!(matches!(
+            generator.generate_single(),
+            Err(ConfigError::InvalidParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_config_iter_matches_batch_generation() {
+        let batch = VlanGenerator::new_with_std_rng(Some(42))
+            .generate_configurations(25, None, None)
+            .unwrap();
+        let streamed: Vec<VlanConfig> = VlanGenerator::new_with_std_rng(Some(42))
+            .into_config_iter(25, None)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(batch, streamed);
+    }
 
-            // Verify derived addresses work
-            assert!(config.gateway_ip().is_ok());
-            assert!(config.dhcp_range_start().is_ok());
-            assert!(config.dhcp_range_end().is_ok());
-        }
+    #[test]
+    fn test_config_iter_checks_capacity_up_front() {
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.set_allowed_vlan_ids(&[(100, 102)]).unwrap();
+        assert!(matches!(
+            generator.into_config_iter(4, None),
+            Err(ConfigError::ResourceExhausted { .. })
+        ));
     }
 
     #[test]
-    fn test_vlan_generator_with_different_seeds() {
-        // Test that different seeds produce different (but valid) results
-        let mut gen1 = VlanGenerator::new(Some(12345));
-        let mut gen2 = VlanGenerator::new(Some(67890));
+    fn test_allowed_vlan_ids_narrow_range_fills_pool() {
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.set_allowed_vlan_ids(&[(100, 105)]).unwrap();
+        assert_eq!(generator.remaining_allowed_vlan_ids(), Some(6));
+
+        let configs = generator.generate_batch(6).unwrap();
+        let mut ids: Vec<u16> = configs.iter().map(|c| c.vlan_id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![100, 101, 102, 103, 104, 105]);
+        assert_eq!(generator.remaining_allowed_vlan_ids(), Some(0));
+    }
 
-        let config1 = gen1.generate_single().unwrap();
-        let config2 = gen2.generate_single().unwrap();
+    #[test]
+    fn test_allowed_vlan_ids_exhaustion() {
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.set_allowed_vlan_ids(&[(100, 105)]).unwrap();
 
-        // Both should be valid but likely different
-        assert!((10..=4094).contains(&config1.vlan_id));
-        assert!((10..=4094).contains(&config2.vlan_id));
-        assert!((1..=3).contains(&config1.wan_assignment));
-        assert!((1..=3).contains(&config2.wan_assignment));
+        let result = generator.generate_batch(7);
+        assert!(matches!(result, Err(ConfigError::ResourceExhausted { .. })));
 
-        // With different seeds, results should usually be different
-        // (Not guaranteed, but extremely likely with good seeds)
+        let result = generate_vlan_configurations_in_ranges(7, &[(100, 105)], Some(42), None, None);
+        assert!(matches!(result, Err(ConfigError::ResourceExhausted { .. })));
     }
 
     #[test]
-    fn test_subnet_mask_is_always_24() {
-        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "Test".to_string(), 1).unwrap();
-        assert_eq!(config.subnet_mask(), "255.255.255.0");
+    fn test_allowed_vlan_ids_multiple_ranges() {
+        let ranges = [(10, 10), (20, 30), (25, 40)];
+        let configs = generate_vlan_configurations_in_ranges(22, &ranges, Some(7), None, None)
+            .expect("overlapping ranges should deduplicate to 22 IDs");
+        assert!(
+            configs
+                .iter()
+                .all(|c| c.vlan_id == 10 || (20..=40).contains(&c.vlan_id))
+        );
 
-        // Should be consistent regardless of config parameters
-        let config2 =
-            VlanConfig::new(4094, "192.168.1.0/24".to_string(), "Other".to_string(), 3).unwrap();
-        assert_eq!(config2.subnet_mask(), "255.255.255.0");
+        let mut generator = VlanGenerator::new(Some(42));
+        assert!(generator.set_allowed_vlan_ids(&[(5, 20)]).is_err());
     }
 
     #[test]
@@ -1471,6 +3519,10 @@ fn test_vlan_config_validate_invalid_vlan_id() {
             ip_network: "192.168.100.x".to_string(),
             description: "Test_VLAN".to_string(),
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let result = invalid_config.validate();
@@ -1486,6 +3538,10 @@ fn test_vlan_config_validate_invalid_wan_assignment() {
             ip_network: "192.168.100.x".to_string(),
             description: "Test_VLAN".to_string(),
             wan_assignment: 5, // Invalid WAN assignment > 3
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let result = invalid_config.validate();
@@ -1501,6 +3557,10 @@ fn test_vlan_config_validate_invalid_network_format() {
             ip_network: "invalid.network.format".to_string(), // Invalid format
             description: "Test_VLAN".to_string(),
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let result = invalid_config.validate();
@@ -1516,6 +3576,10 @@ fn test_vlan_config_validate_empty_description() {
             ip_network: "192.168.100.x".to_string(),
             description: "".to_string(), // Empty description
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let result = invalid_config.validate();
@@ -1524,6 +3588,36 @@ fn test_vlan_config_validate_empty_description() {
         assert!(error_msg.contains("cannot be empty"));
     }
 
+    #[test]
+    fn test_vlan_config_validate_long_description() {
+        let mut config = VlanConfig::new(100, "10.1.2.x".to_string(), "x".repeat(63), 1).unwrap();
+        assert!(config.validate().is_ok());
+
+        config.description.push('x');
+        let error_msg = config.validate().unwrap_err().to_string();
+        assert!(
+            error_msg.contains("64 characters long, maximum is 63"),
+            "{error_msg}"
+        );
+        assert!(config.validate_with_max_description_len(64).is_ok());
+    }
+
+    #[test]
+    fn test_vlan_config_validate_description_characters() {
+        for description in ["Sales <VLAN 100>", "Sales\tVLAN", "Sales VLAN\n"] {
+            let config =
+                VlanConfig::new(100, "10.1.2.x".to_string(), description.to_string(), 1).unwrap();
+            let error_msg = config.validate().unwrap_err().to_string();
+            assert!(error_msg.contains("disallowed character"), "{error_msg}");
+        }
+    }
+
+    #[test]
+    fn test_generator_rejects_descriptions_over_limit() {
+        let mut generator = VlanGenerator::new(Some(42)).with_max_description_len(5);
+        assert!(generator.generate_batch(1).is_err());
+    }
+
     #[test]
     fn test_vlan_config_validate_cidr_format() {
         let valid_config = VlanConfig {
@@ -1531,6 +3625,10 @@ fn test_vlan_config_validate_cidr_format() {
             ip_network: "192.168.100.0/24".to_string(), // CIDR format
             description: "Test_VLAN".to_string(),
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         assert!(valid_config.validate().is_ok());
@@ -1543,6 +3641,10 @@ fn test_vlan_config_validate_invalid_octet_structure() {
             ip_network: "192.168..x".to_string(), // Invalid octet structure
             description: "Test_VLAN".to_string(),
             wan_assignment: 1,
+            prefix_len: 24,
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
         };
 
         let result = invalid_config.validate();
@@ -1589,10 +3691,34 @@ fn test_dhcp_domain_name_department_specific() {
         assert_eq!(sales_config.dhcp_domain_name(), "sales.company.local");
     }
 
+    #[test]
+    fn test_department_from_templated_descriptions() {
+        let config = |description: &str| {
+            VlanConfig::new(100, "10.1.2.x".to_string(), description.to_string(), 1).unwrap()
+        };
+
+        for description in [
+            "Customer Service VLAN 100",
+            "Customer Service_100",
+            "100 - Customer Service",
+        ] {
+            let config = config(description);
+            assert_eq!(config.department(), "Customer Service", "{description}");
+            assert_eq!(config.dhcp_domain_name(), "customer.company.local");
+        }
+
+        // An ID not set off from the name is part of the description
+        assert_eq!(config("Lab100").department(), "Lab100");
+        assert_eq!(config("100").department(), "100");
+
+        let reservations = config("IT_100").static_reservations(&[]).unwrap();
+        assert_eq!(reservations[0].hostname, "server-it-01");
+    }
+
     #[test]
     fn test_dhcp_dns_servers() {
         let config = VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
-        let dns_servers = config.dhcp_dns_servers().unwrap();
+        let dns_servers = config.dhcp_dns_servers(&DhcpOptions::default()).unwrap();
 
         assert!(dns_servers.len() >= 3);
         assert_eq!(dns_servers[0], "10.1.2.1"); // Gateway as primary
@@ -1600,6 +3726,30 @@ fn test_dhcp_dns_servers() {
         assert!(dns_servers.contains(&"1.1.1.1".to_string())); // Cloudflare DNS
     }
 
+    #[test]
+    fn test_custom_dhcp_dns_servers_replace_public_ones() {
+        let custom = vec!["10.0.0.53".to_string(), "10.0.0.54".to_string()];
+        let options = DhcpOptions::default()
+            .with_dns_servers(custom.clone())
+            .unwrap();
+        let config = VlanGenerator::new(Some(42)).generate_single().unwrap();
+        let dns_servers = config.dhcp_server_config(&options).unwrap().dns_servers;
+
+        assert_eq!(dns_servers[0], config.gateway_ip().unwrap());
+        assert_eq!(dns_servers[1..], custom);
+        assert!(!dns_servers.contains(&"8.8.8.8".to_string()));
+        assert!(!dns_servers.contains(&"1.1.1.1".to_string()));
+
+        let options = options.with_gateway_dns(false);
+        assert_eq!(config.dhcp_dns_servers(&options).unwrap(), custom);
+
+        assert!(
+            DhcpOptions::default()
+                .with_dns_servers(vec!["dns.example.com".to_string()])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_dhcp_ntp_servers() {
         let config = VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
@@ -1615,7 +3765,7 @@ fn test_dhcp_ntp_servers() {
     fn test_static_reservations_department_specific() {
         let it_config =
             VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
-        let reservations = it_config.static_reservations().unwrap();
+        let reservations = it_config.static_reservations(&[]).unwrap();
 
         assert!(reservations.len() >= 2); // IT should have server and printer
         assert!(reservations.iter().any(|r| r.hostname.contains("server")));
@@ -1623,7 +3773,7 @@ fn test_static_reservations_department_specific() {
 
         let finance_config =
             VlanConfig::new(200, "10.1.3.x".to_string(), "Finance 200".to_string(), 1).unwrap();
-        let finance_reservations = finance_config.static_reservations().unwrap();
+        let finance_reservations = finance_config.static_reservations(&[]).unwrap();
 
         assert!(!finance_reservations.is_empty());
         assert!(
@@ -1633,10 +3783,530 @@ fn test_static_reservations_department_specific() {
         );
     }
 
+    #[test]
+    fn test_allow_duplicates_repeats_vlan_ids() {
+        let mut generator = VlanGenerator::new(Some(42)).with_allow_duplicates(true);
+        generator.set_allowed_vlan_ids(&[(100, 101)]).unwrap();
+        let configs = generator.generate_configurations(5, None, None).unwrap();
+        assert_eq!(configs.len(), 5);
+
+        let unique: HashSet<_> = configs.iter().map(|config| config.vlan_id).collect();
+        assert!(unique.len() < configs.len());
+        assert!(unique.iter().all(|id| (100..=101).contains(id)));
+
+        let mut strict = VlanGenerator::new(Some(42));
+        strict.set_allowed_vlan_ids(&[(100, 101)]).unwrap();
+        assert!(strict.generate_configurations(5, None, None).is_err());
+    }
+
+    #[test]
+    fn test_static_reservation_macs_use_selected_vendors() {
+        let vendors = vec![MacVendor::Cisco, MacVendor::Apple];
+        let prefixes: Vec<String> = vendors
+            .iter()
+            .flat_map(|vendor| vendor.ouis())
+            .map(|oui| format!("{:02x}:{:02x}:{:02x}:", oui[0], oui[1], oui[2]))
+            .collect();
+
+        let configs = VlanGenerator::new(Some(42)).generate_batch(20).unwrap();
+        let macs: Vec<String> = configs
+            .iter()
+            .flat_map(|config| config.static_reservations(&vendors).unwrap())
+            .map(|reservation| reservation.mac)
+            .collect();
+        assert!(!macs.is_empty());
+        for mac in &macs {
+            assert!(
+                prefixes.iter().any(|prefix| mac.starts_with(prefix)),
+                "{mac}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_static_reservation_macs_distinct_across_vlans() {
+        // VLAN IDs 256 apart used to share the same MAC suffix
+        let vlan_100 =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
+        let vlan_356 =
+            VlanConfig::new(356, "10.1.2.x".to_string(), "IT 356".to_string(), 1).unwrap();
+
+        let macs: Vec<String> = [&vlan_100, &vlan_356]
+            .iter()
+            .flat_map(|config| config.static_reservations(&[]).unwrap())
+            .map(|reservation| reservation.mac)
+            .collect();
+        assert_eq!(macs.len(), 4);
+        assert_eq!(macs.iter().collect::<HashSet<_>>().len(), macs.len());
+
+        // Same config always yields the same MACs
+        assert_eq!(
+            vlan_100.static_reservations(&[]).unwrap(),
+            vlan_100.static_reservations(&[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_departments_used_for_descriptions() {
+        let custom = vec![
+            "Platform".to_string(),
+            "Facilities".to_string(),
+            "Studio".to_string(),
+        ];
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.set_departments(custom.clone()).unwrap();
+
+        let configs = generator.generate_batch(30).unwrap();
+        for config in &configs {
+            let department = config.description.split(" VLAN ").next().unwrap();
+            assert!(custom.iter().any(|name| name == department));
+            // Unknown departments fall back to the default 8-hour lease
+            assert_eq!(config.dhcp_lease_time(), 28800);
+        }
+    }
+
+    #[test]
+    fn test_remaining_ids_and_networks_decrease_with_generation() {
+        let mut generator = VlanGenerator::new(Some(42));
+        let vlan_ids_before = generator.remaining_vlan_ids();
+        let networks_before = generator.remaining_networks_estimate();
+        assert_eq!(vlan_ids_before, crate::cli::MAX_UNIQUE_VLAN_IDS as usize);
+        assert_eq!(networks_before, 254 * 254);
+
+        generator.generate_configurations(100, None, None).unwrap();
+        assert_eq!(generator.remaining_vlan_ids(), vlan_ids_before - 100);
+        assert_eq!(
+            generator.remaining_networks_estimate(),
+            networks_before - 100
+        );
+
+        let mut class_c = VlanGenerator::new(Some(42)).with_network_class(Rfc1918Class::ClassC);
+        class_c.generate_configurations(100, None, None).unwrap();
+        assert_eq!(class_c.remaining_networks_estimate(), 254 - 100);
+
+        let mut ranged = VlanGenerator::new(Some(42));
+        ranged.set_allowed_vlan_ids(&[(100, 299)]).unwrap();
+        ranged.generate_configurations(100, None, None).unwrap();
+        assert_eq!(ranged.remaining_vlan_ids(), 100);
+
+        // A supernet counts only the subnets still free
+        let supernet: Ipv4Network = "10.50.0.0/22".parse().unwrap();
+        let mut within = VlanGenerator::new(Some(42))
+            .with_supernet(supernet)
+            .unwrap();
+        within.add_exclusions(&[
+            "10.50.1.0/24".parse().unwrap(),
+            "10.50.0.0/23".parse().unwrap(),
+        ]);
+        within.reserve_existing(&[
+            VlanConfig::new(100, "10.50.3.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.60.0.x".to_string(), "IT VLAN 200".to_string(), 1).unwrap(),
+        ]);
+        assert_eq!(within.remaining_networks_estimate(), 1);
+        within.generate_configurations(1, None, None).unwrap();
+        assert_eq!(within.remaining_networks_estimate(), 0);
+    }
+
+    #[test]
+    fn test_reserve_existing_avoids_reserved_ids_and_networks() {
+        let mut existing_generator = VlanGenerator::new_with_std_rng(Some(7));
+        let existing = existing_generator
+            .generate_configurations(50, None, None)
+            .unwrap();
+
+        // Same seed would otherwise reproduce the existing configurations
+        let mut generator = VlanGenerator::new_with_std_rng(Some(7));
+        generator.reserve_existing(&existing);
+        let appended = generator.generate_configurations(50, None, None).unwrap();
+
+        let existing_ids: HashSet<u16> = existing.iter().map(|c| c.vlan_id).collect();
+        let existing_networks: HashSet<&str> =
+            existing.iter().map(|c| c.ip_network.as_str()).collect();
+        for config in &appended {
+            assert!(!existing_ids.contains(&config.vlan_id));
+            assert!(!existing_networks.contains(config.ip_network.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_unique_ip_network_output_unchanged_by_packed_keys() {
+        // Values recorded with the previous string-keyed uniqueness set
+        let mut generator = VlanGenerator::new(Some(42));
+        let networks: Vec<String> = (0..10_000)
+            .map(|_| generator.generate_unique_ip_network(100).unwrap())
+            .collect();
+        assert_eq!(
+            networks[..3],
+            ["10.57.174.x", "10.38.242.x", "10.197.109.x"]
+        );
+        assert_eq!(
+            networks[9997..],
+            ["10.29.138.x", "10.53.84.x", "10.192.70.x"]
+        );
+        assert_eq!(networks.iter().collect::<HashSet<_>>().len(), 10_000);
+
+        let mut generator = VlanGenerator::new(Some(42)).with_prefix_len(26).unwrap();
+        let networks: Vec<String> = (0..3)
+            .map(|_| generator.generate_unique_ip_network(100).unwrap())
+            .collect();
+        assert_eq!(
+            networks,
+            ["10.57.174.0/26", "10.242.197.64/26", "10.88.160.128/26"]
+        );
+
+        let mut generator = VlanGenerator::new(Some(7))
+            .with_supernet("10.50.0.0/22".parse().unwrap())
+            .unwrap();
+        let networks: Vec<String> = (0..4)
+            .map(|_| generator.generate_unique_ip_network(100).unwrap())
+            .collect();
+        assert_eq!(
+            networks,
+            ["10.50.0.x", "10.50.1.x", "10.50.2.x", "10.50.3.x"]
+        );
+        assert!(generator.generate_unique_ip_network(100).is_err());
+    }
+
+    #[test]
+    fn test_generate_configurations_after_returns_tail() {
+        let full = VlanGenerator::new(Some(42))
+            .generate_configurations(10, None, None)
+            .unwrap();
+        let tail = VlanGenerator::new(Some(42))
+            .generate_configurations_after(4, 10, None, None)
+            .unwrap();
+        assert_eq!(tail, full[4..]);
+
+        assert!(
+            VlanGenerator::new(Some(42))
+                .generate_configurations_after(11, 10, None, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_generate_into_callback() {
+        let mut collected = Vec::new();
+        let delivered = VlanGenerator::new(Some(42))
+            .generate_into(25, |config| {
+                collected.push(config);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(delivered, 25);
+        assert_eq!(collected.len(), 25);
+
+        let vlan_ids: HashSet<u16> = collected.iter().map(|c| c.vlan_id).collect();
+        let networks: HashSet<&str> = collected.iter().map(|c| c.ip_network.as_str()).collect();
+        assert_eq!(vlan_ids.len(), 25);
+        assert_eq!(networks.len(), 25);
+        assert_eq!(
+            collected,
+            VlanGenerator::new(Some(42))
+                .generate_configurations(25, None, None)
+                .unwrap()
+        );
+
+        let mut seen = 0;
+        let result = VlanGenerator::new(Some(42)).generate_into(25, |_| {
+            seen += 1;
+            if seen == 3 {
+                Err(ConfigError::validation("sink full"))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_err());
+        assert_eq!(seen, 3);
+    }
+
+    #[test]
+    fn test_network_pool_assigns_each_network_once() {
+        let pool: Vec<Ipv4Network> = ["10.20.0.0/24", "172.16.5.64/26", "192.168.9.0/24"]
+            .iter()
+            .map(|cidr| cidr.parse().unwrap())
+            .collect();
+
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.use_network_pool(pool.clone()).unwrap();
+        let configs = generator.generate_batch(3).unwrap();
+        let networks: Vec<&str> = configs.iter().map(|c| c.ip_network.as_str()).collect();
+        assert_eq!(networks, ["10.20.0.x", "172.16.5.64/26", "192.168.9.x"]);
+        assert_eq!(configs[1].prefix_len, 26);
+
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.use_network_pool(pool.clone()).unwrap();
+        generator.shuffle_network_pool();
+        let shuffled: HashSet<String> = generator
+            .generate_batch(3)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.ip_network)
+            .collect();
+        assert_eq!(shuffled.len(), 3);
+        assert!(networks.iter().all(|network| shuffled.contains(*network)));
+
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.use_network_pool(pool.clone()).unwrap();
+        let error = generator.generate_batch(4).unwrap_err().to_string();
+        assert!(error.contains("network pool only holds 3"), "{error}");
+
+        let mut duplicated = pool.clone();
+        duplicated.push(pool[0]);
+        assert!(
+            VlanGenerator::new(Some(42))
+                .use_network_pool(duplicated)
+                .is_err()
+        );
+        let public = vec!["8.8.8.0/24".parse().unwrap()];
+        assert!(
+            VlanGenerator::new(Some(42))
+                .use_network_pool(public)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_network_pool_rejects_nested_networks() {
+        let pool: Vec<Ipv4Network> = ["10.1.2.0/24", "192.168.1.0/24", "10.1.0.0/16"]
+            .iter()
+            .map(|cidr| cidr.parse().unwrap())
+            .collect();
+        let error = VlanGenerator::new(Some(42))
+            .use_network_pool(pool)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("10.1.2.0/24 overlaps 10.1.0.0/16"),
+            "{error}"
+        );
+
+        let adjacent: Vec<Ipv4Network> = ["10.1.0.0/16", "10.2.0.0/24"]
+            .iter()
+            .map(|cidr| cidr.parse().unwrap())
+            .collect();
+        assert!(
+            VlanGenerator::new(Some(42))
+                .use_network_pool(adjacent)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reserve_existing_blocks_networks_inside_wider_prefix() {
+        let mut existing_generator = VlanGenerator::new(Some(7));
+        existing_generator
+            .use_network_pool(vec!["10.1.0.0/16".parse().unwrap()])
+            .unwrap();
+        let wide = existing_generator.generate_batch(1).unwrap().remove(0);
+        assert_eq!(wide.ip_network, "10.1.0.0/16");
+
+        let pool: Vec<Ipv4Network> = ["10.1.2.0/24", "10.2.0.0/24"]
+            .iter()
+            .map(|cidr| cidr.parse().unwrap())
+            .collect();
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.use_network_pool(pool).unwrap();
+        generator.reserve_existing(std::slice::from_ref(&wide));
+        let configs = generator.generate_batch(1).unwrap();
+        assert_eq!(configs[0].ip_network, "10.2.0.x");
+        assert!(generator.generate_batch(1).is_err());
+
+        let mut within = VlanGenerator::new(Some(42))
+            .with_supernet("10.1.0.0/15".parse().unwrap())
+            .unwrap();
+        within.reserve_existing(&[wide]);
+        assert_eq!(within.remaining_networks_estimate(), 256);
+        for config in within.generate_batch(256).unwrap() {
+            assert!(
+                config.ip_network.starts_with("10.0."),
+                "{}",
+                config.ip_network
+            );
+        }
+    }
+
+    #[test]
+    fn test_sort_key_network_orders_numerically() {
+        let config = |id, network: &str| {
+            VlanConfig::new(id, network.to_string(), format!("VLAN {id}"), 1).unwrap()
+        };
+        let mut configs = vec![
+            config(300, "10.10.0.x"),
+            config(100, "10.2.0.x"),
+            config(200, "10.2.1.x"),
+        ];
+
+        SortKey::Network.sort(&mut configs);
+        let ids: Vec<u16> = configs.iter().map(|c| c.vlan_id).collect();
+        assert_eq!(ids, [100, 200, 300]);
+
+        configs.reverse();
+        SortKey::VlanId.sort(&mut configs);
+        let ids: Vec<u16> = configs.iter().map(|c| c.vlan_id).collect();
+        assert_eq!(ids, [100, 200, 300]);
+    }
+
+    #[test]
+    fn test_reserve_existing_prunes_allowed_pool() {
+        let existing = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+        ];
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.set_allowed_vlan_ids(&[(100, 104)]).unwrap();
+        generator.reserve_existing(&existing);
+
+        assert_eq!(generator.remaining_allowed_vlan_ids(), Some(4));
+    }
+
+    #[test]
+    fn test_generate_by_department_exact_counts() {
+        let counts = HashMap::from([("IT".to_string(), 2), ("Sales".to_string(), 3)]);
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42));
+        let configs = generator
+            .generate_by_department(counts.clone(), None)
+            .unwrap();
+
+        assert_eq!(configs.len(), 5);
+        let count_of = |department: &str| {
+            configs
+                .iter()
+                .filter(|c| c.description.starts_with(&format!("{department} VLAN ")))
+                .count()
+        };
+        assert_eq!(count_of("IT"), 2);
+        assert_eq!(count_of("Sales"), 3);
+
+        let ids: HashSet<u16> = configs.iter().map(|c| c.vlan_id).collect();
+        let networks: HashSet<&str> = configs.iter().map(|c| c.ip_network.as_str()).collect();
+        assert_eq!(ids.len(), 5);
+        assert_eq!(networks.len(), 5);
+
+        // Same seed gives the same result regardless of map iteration order
+        let mut again = VlanGenerator::new_with_std_rng(Some(42));
+        assert_eq!(again.generate_by_department(counts, None).unwrap(), configs);
+    }
+
+    #[test]
+    fn test_voice_department_gets_higher_pcp_than_guest() {
+        use crate::xml::generator::XMLGenerator;
+
+        let counts = HashMap::from([("Voice".to_string(), 1), ("Guest".to_string(), 1)]);
+        let configs = VlanGenerator::new_with_std_rng(Some(42))
+            .generate_by_department(counts, None)
+            .unwrap();
+        let pcp_of = |department: &str| {
+            configs
+                .iter()
+                .find(|c| c.description.starts_with(department))
+                .map(|c| c.pcp)
+                .unwrap()
+        };
+        assert!(pcp_of("Voice") > pcp_of("Guest"));
+
+        let voice = configs
+            .iter()
+            .find(|c| c.description.starts_with("Voice"))
+            .unwrap();
+        let events = crate::xml::generator::VlanGenerator::new(voice.clone())
+            .generate_events()
+            .unwrap();
+        let xml = crate::xml::XMLEngine::new().process_events(events).unwrap();
+        assert!(xml.contains(&format!("<pcp>{}</pcp>", voice.pcp)), "{xml}");
+
+        let mut invalid = voice.clone();
+        invalid.pcp = 8;
+        assert!(invalid.validate().is_err());
+        assert!(voice.clone().with_pcp(8).is_err());
+    }
+
+    #[test]
+    fn test_reserve_vlan_ids_avoids_base_config_ids() {
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42));
+        generator.reserve_vlan_ids(&[100]);
+        let configs = generator.generate_configurations(50, None, None).unwrap();
+        assert!(configs.iter().all(|c| c.vlan_id != 100));
+
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42));
+        generator.reserve_vlan_ids(&[100]);
+        let configs = generator
+            .generate_configurations_from_ranges(&[(99, 101)], None, None)
+            .unwrap();
+        let ids: Vec<u16> = configs.iter().map(|c| c.vlan_id).collect();
+        assert_eq!(ids, [99, 101]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_generate_batch_parallel_unique_and_deterministic() {
+        let configs = VlanGenerator::new_with_std_rng(Some(42))
+            .generate_batch_parallel(1000)
+            .unwrap();
+        assert_eq!(configs.len(), 1000);
+
+        let ids: HashSet<u16> = configs.iter().map(|c| c.vlan_id).collect();
+        let networks: HashSet<&str> = configs.iter().map(|c| c.ip_network.as_str()).collect();
+        assert_eq!(ids.len(), 1000);
+        assert_eq!(networks.len(), 1000);
+        for config in &configs {
+            assert!(
+                config
+                    .description
+                    .ends_with(&format!(" VLAN {}", config.vlan_id))
+            );
+        }
+
+        let again = VlanGenerator::new_with_std_rng(Some(42))
+            .generate_batch_parallel(1000)
+            .unwrap();
+        assert_eq!(configs, again);
+    }
+
+    #[test]
+    fn test_excluded_networks_never_generated() {
+        let lower_half: Ipv4Network = "10.0.0.0/9".parse().unwrap();
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42));
+        generator.add_exclusions(&[lower_half]);
+
+        let configs = generator.generate_configurations(200, None, None).unwrap();
+        for config in &configs {
+            let network = config.as_ipv4_network().unwrap();
+            assert!(
+                network.network().octets()[1] >= 128,
+                "{}",
+                config.ip_network
+            );
+        }
+
+        let mut generator = VlanGenerator::new(Some(42));
+        generator.add_exclusions(&[lower_half]);
+        for config in generator.generate_batch(200).unwrap() {
+            let network = config.as_ipv4_network().unwrap();
+            assert!(!lower_half.overlaps(network), "{}", config.ip_network);
+        }
+    }
+
+    #[test]
+    fn test_excluding_whole_class_exhausts_networks() {
+        let mut generator = VlanGenerator::new_with_std_rng(Some(42));
+        generator.add_exclusions(&["10.0.0.0/8".parse().unwrap()]);
+
+        let err = generator.generate_unique_ip_network(100).unwrap_err();
+        assert!(matches!(err, ConfigError::ResourceExhausted { .. }));
+        assert!(err.to_string().contains("outside excluded ranges"));
+    }
+
+    #[test]
+    fn test_set_departments_rejects_empty_list() {
+        let mut generator = VlanGenerator::new(Some(42));
+        assert!(generator.set_departments(Vec::new()).is_err());
+        assert!(generator.set_departments(vec!["  ".to_string()]).is_err());
+    }
+
     #[test]
     fn test_dhcp_server_config_complete() {
         let config = VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
-        let dhcp_config = config.dhcp_server_config().unwrap();
+        let dhcp_config = config.dhcp_server_config(&DhcpOptions::default()).unwrap();
 
         assert!(dhcp_config.enabled);
         assert_eq!(dhcp_config.range_start, "10.1.2.100");
@@ -1649,4 +4319,30 @@ fn test_dhcp_server_config_complete() {
         assert!(dhcp_config.ntp_servers.len() >= 3);
         assert!(dhcp_config.static_reservations.len() >= 2);
     }
+
+    #[test]
+    fn test_effective_seed_reproduces_unseeded_run() {
+        let mut unseeded = VlanGenerator::new(None);
+        let first = unseeded.generate_batch(10).unwrap();
+
+        let mut reseeded = VlanGenerator::new(Some(unseeded.effective_seed()));
+        assert_eq!(reseeded.effective_seed(), unseeded.effective_seed());
+        assert_eq!(reseeded.generate_batch(10).unwrap(), first);
+        assert_eq!(VlanGenerator::new_with_std_rng(Some(7)).effective_seed(), 7);
+    }
+
+    #[test]
+    fn test_dhcp_validate_rejects_colliding_reservations() {
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
+        let mut dhcp_config = config.dhcp_server_config(&DhcpOptions::default()).unwrap();
+        assert!(dhcp_config.validate().is_ok());
+
+        dhcp_config.static_reservations[0].ip_addr = "10.1.2.150".to_string();
+        let err = dhcp_config.validate().unwrap_err();
+        assert!(err.to_string().contains("inside the DHCP pool"), "{err}");
+
+        dhcp_config.static_reservations[0].ip_addr = "10.1.2.1".to_string();
+        let err = dhcp_config.validate().unwrap_err();
+        assert!(err.to_string().contains("gateway"), "{err}");
+    }
 }
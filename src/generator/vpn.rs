@@ -4,10 +4,12 @@
 //! including OpenVPN, WireGuard, and IPSec tunnels for testing purposes.
 
 use crate::model::ConfigError;
+use crate::utils::seed::{random_short_id, random_uuid};
+use ipnetwork::Ipv4Network;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use uuid::Uuid;
+use std::net::Ipv4Addr;
 
 /// Result type for VPN generation operations
 pub type VpnResult<T> = Result<T, ConfigError>;
@@ -47,12 +49,27 @@ pub struct VpnConfig {
     pub dns_servers: Vec<String>,
     /// Whether the VPN is enabled
     pub enabled: bool,
+    /// WireGuard peers; empty for other VPN types
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peers: Vec<WireGuardPeer>,
+}
+
+/// A WireGuard peer allowed to connect to a tunnel
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WireGuardPeer {
+    /// Base64 public key of the peer
+    pub public_key: String,
+    /// Tunnel addresses routed to the peer, e.g. `10.8.0.2/32`
+    pub allowed_ips: String,
+    /// Address and port the peer is reached at, e.g. `vpn.example.com:51820`
+    pub endpoint: String,
 }
 
 impl VpnConfig {
     /// Create a new VPN configuration with validation
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        id: String,
         vpn_type: VpnType,
         name: String,
         server: String,
@@ -66,7 +83,7 @@ pub fn new(
         enabled: bool,
     ) -> VpnResult<Self> {
         let config = Self {
-            id: Uuid::new_v4().to_string(),
+            id,
             vpn_type,
             name,
             server,
@@ -78,6 +95,7 @@ pub fn new(
             client_subnet,
             dns_servers,
             enabled,
+            peers: Vec::new(),
         };
 
         config.validate()?;
@@ -182,7 +200,9 @@ pub fn generate_single(&mut self, vpn_type: Option<VpnType>) -> VpnResult<VpnCon
         let dns_servers = self.generate_dns_servers();
         let enabled = self.rng.random_bool(0.85); // 85% chance of being enabled
 
-        VpnConfig::new(
+        let id = random_uuid(&mut self.rng).to_string();
+        let config = VpnConfig::new(
+            id,
             vpn_type,
             name,
             server,
@@ -194,7 +214,53 @@ pub fn generate_single(&mut self, vpn_type: Option<VpnType>) -> VpnResult<VpnCon
             client_subnet,
             dns_servers,
             enabled,
-        )
+        )?;
+        Ok(config)
+    }
+
+    /// Generate a WireGuard configuration with `peer_count` peers
+    ///
+    /// Each peer gets its own key and a /32 from the tunnel's `client_subnet`,
+    /// starting after the first host address, which the server keeps.
+    pub fn generate_wireguard_with_peers(&mut self, peer_count: u16) -> VpnResult<VpnConfig> {
+        let mut config = self.generate_single(Some(VpnType::WireGuard))?;
+        let subnet: Ipv4Network = config.client_subnet.parse().map_err(|e| {
+            ConfigError::validation(format!(
+                "Invalid WireGuard client subnet '{}': {e}",
+                config.client_subnet
+            ))
+        })?;
+
+        // Skip the network address and the server's first host address
+        let hosts = subnet.size().saturating_sub(3);
+        if u32::from(peer_count) > hosts {
+            return Err(ConfigError::resource_exhausted(format!(
+                "WireGuard peer addresses in {subnet} ({hosts} available, {peer_count} requested)"
+            )));
+        }
+
+        let mut keys = HashSet::with_capacity(usize::from(peer_count));
+        for offset in 0..u32::from(peer_count) {
+            let public_key = loop {
+                let key = self.generate_key_identifier(&VpnType::WireGuard);
+                if keys.insert(key.clone()) {
+                    break key;
+                }
+            };
+            let address = Ipv4Addr::from(u32::from(subnet.network()) + 2 + offset);
+            let endpoint = format!(
+                "{}:{}",
+                self.generate_server_address(),
+                self.rng.random_range(51820..=51899)
+            );
+            config.peers.push(WireGuardPeer {
+                public_key,
+                allowed_ips: format!("{address}/32"),
+                endpoint,
+            });
+        }
+
+        Ok(config)
     }
 
     /// Generate multiple VPN configurations
@@ -271,7 +337,7 @@ fn generate_unique_name(&mut self, vpn_type: &VpnType) -> String {
                 VpnType::WireGuard => "WireGuard",
                 VpnType::IPSec => "IPSec",
             },
-            Uuid::new_v4().to_string().split('-').next().unwrap()
+            random_short_id(&mut self.rng)
         )
     }
 
@@ -402,10 +468,7 @@ fn get_auth_method_for_type(&mut self, vpn_type: &VpnType) -> String {
     /// Generate key identifier
     fn generate_key_identifier(&mut self, vpn_type: &VpnType) -> String {
         match vpn_type {
-            VpnType::OpenVPN => format!(
-                "openvpn-cert-{}",
-                Uuid::new_v4().to_string().split('-').next().unwrap()
-            ),
+            VpnType::OpenVPN => format!("openvpn-cert-{}", random_short_id(&mut self.rng)),
             VpnType::WireGuard => {
                 // Generate realistic WireGuard public key format (base64, 44 chars)
                 let chars: Vec<char> =
@@ -420,12 +483,9 @@ fn generate_key_identifier(&mut self, vpn_type: &VpnType) -> String {
             VpnType::IPSec => {
                 // Generate PSK or certificate identifier
                 if self.rng.random_bool(0.6) {
-                    format!("psk-{}", Uuid::new_v4())
+                    format!("psk-{}", random_uuid(&mut self.rng))
                 } else {
-                    format!(
-                        "ipsec-cert-{}",
-                        Uuid::new_v4().to_string().split('-').next().unwrap()
-                    )
+                    format!("ipsec-cert-{}", random_short_id(&mut self.rng))
                 }
             }
         }
@@ -506,7 +566,7 @@ fn default() -> Self {
 pub fn generate_vpn_configurations(
     count: u16,
     seed: Option<u64>,
-    progress_bar: Option<&indicatif::ProgressBar>,
+    progress_bar: Option<&dyn crate::utils::progress::ProgressSink>,
 ) -> VpnResult<Vec<VpnConfig>> {
     let mut generator = VpnGenerator::new_with_seed(seed);
     let mut configs = Vec::with_capacity(count as usize);
@@ -530,6 +590,7 @@ mod tests {
     #[test]
     fn test_vpn_config_creation() {
         let config = VpnConfig::new(
+            "vpn-1".to_string(),
             VpnType::OpenVPN,
             "Test-VPN".to_string(),
             "vpn.example.com".to_string(),
@@ -553,6 +614,7 @@ fn test_vpn_config_creation() {
     #[test]
     fn test_vpn_config_validation_invalid_port() {
         let config = VpnConfig::new(
+            "vpn-1".to_string(),
             VpnType::OpenVPN,
             "Test-VPN".to_string(),
             "vpn.example.com".to_string(),
@@ -578,6 +640,7 @@ fn test_vpn_config_validation_invalid_port() {
     #[test]
     fn test_vpn_config_validation_invalid_protocol() {
         let config = VpnConfig::new(
+            "vpn-1".to_string(),
             VpnType::WireGuard,
             "Test-VPN".to_string(),
             "vpn.example.com".to_string(),
@@ -613,6 +676,34 @@ fn test_vpn_generator_single() {
         assert!(config.port > 0);
     }
 
+    #[test]
+    fn test_wireguard_peers_have_distinct_keys_and_addresses() {
+        let mut generator = VpnGenerator::new_with_seed(Some(42));
+        let config = generator.generate_wireguard_with_peers(3).unwrap();
+        assert_eq!(config.vpn_type, VpnType::WireGuard);
+        assert_eq!(config.peers.len(), 3);
+
+        let subnet: Ipv4Network = config.client_subnet.parse().unwrap();
+        let allowed: Vec<Ipv4Network> = config
+            .peers
+            .iter()
+            .map(|peer| peer.allowed_ips.parse().unwrap())
+            .collect();
+        for (i, network) in allowed.iter().enumerate() {
+            assert!(
+                subnet.is_supernet_of(*network),
+                "{network} outside {subnet}"
+            );
+            for other in &allowed[i + 1..] {
+                assert!(!network.overlaps(*other), "{network} overlaps {other}");
+            }
+        }
+
+        let keys: HashSet<_> = config.peers.iter().map(|peer| &peer.public_key).collect();
+        assert_eq!(keys.len(), 3);
+        assert!(config.peers.iter().all(|peer| peer.public_key.len() == 44));
+    }
+
     #[test]
     fn test_vpn_generator_batch() {
         let mut generator = VpnGenerator::new_with_seed(Some(42));
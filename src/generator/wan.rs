@@ -0,0 +1,223 @@
+//! WAN interface generation for multi-WAN configurations
+//!
+//! Each generated WAN gets a point-to-point /30 uplink carved out of the
+//! RFC 5737 documentation blocks, so addresses look public without ever
+//! colliding with RFC 1918 space or real hosts.
+
+use crate::Result;
+use crate::model::ConfigError;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// RFC 5737 documentation networks (TEST-NET-1, -2 and -3)
+const DOCUMENTATION_BLOCKS: [Ipv4Addr; 3] = [
+    Ipv4Addr::new(192, 0, 2, 0),
+    Ipv4Addr::new(198, 51, 100, 0),
+    Ipv4Addr::new(203, 0, 113, 0),
+];
+
+/// Number of /30 uplinks that fit in one /24 documentation block
+const UPLINKS_PER_BLOCK: u32 = 64;
+
+/// Maximum number of WAN interfaces that can be generated
+pub const MAX_WAN_INTERFACES: usize = DOCUMENTATION_BLOCKS.len() * UPLINKS_PER_BLOCK as usize;
+
+/// Well-known public resolvers used as upstream monitor targets
+const PUBLIC_MONITOR_IPS: [&str; 6] = [
+    "8.8.8.8",
+    "8.8.4.4",
+    "1.1.1.1",
+    "1.0.0.1",
+    "9.9.9.9",
+    "149.112.112.112",
+];
+
+/// Priority step between consecutive WANs (lower priority is preferred)
+const PRIORITY_STEP: u8 = 10;
+
+/// Gateway monitoring and failover settings for a WAN uplink
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WanMonitor {
+    /// Address probed to decide whether the uplink is up
+    pub monitor_ip: String,
+    /// Load-balancing weight within a gateway group (1-5)
+    pub weight: u8,
+    /// Failover priority (1-255, lower is preferred)
+    pub priority: u8,
+}
+
+/// A WAN uplink that `VlanConfig::wan_assignment` values refer to by `id`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WanInterface {
+    /// WAN number matching `VlanConfig::wan_assignment` (1-based)
+    pub id: u8,
+    /// Public address assigned to the interface
+    pub public_ip: String,
+    /// Upstream gateway address
+    pub gateway: String,
+    /// OPNsense interface name (`wan`, `wan2`, ...)
+    pub interface_name: String,
+    /// Gateway monitoring settings, if the uplink is monitored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<WanMonitor>,
+}
+
+impl WanInterface {
+    /// Build the interface name for a WAN number
+    pub fn interface_name_for(id: u8) -> String {
+        if id == 1 {
+            "wan".to_string()
+        } else {
+            format!("wan{id}")
+        }
+    }
+
+    /// OPNsense gateway name for this uplink (`WAN_GW`, `WAN2_GW`, ...)
+    pub fn gateway_name(&self) -> String {
+        format!("{}_GW", self.interface_name.to_uppercase())
+    }
+}
+
+/// Generate `count` WAN interfaces with distinct public uplinks
+///
+/// Each uplink is monitored: the first ones probe well-known public resolvers
+/// and the rest fall back to their own gateway, so monitor IPs never repeat.
+/// Priorities rise with the WAN number so `wan` is the preferred uplink.
+pub fn generate_wan_interfaces(count: u8, seed: Option<u64>) -> Result<Vec<WanInterface>> {
+    if usize::from(count) > MAX_WAN_INTERFACES {
+        return Err(ConfigError::resource_exhausted(format!(
+            "WAN uplinks (requested {count}, maximum {MAX_WAN_INTERFACES})"
+        )));
+    }
+
+    let mut rng: Box<dyn RngCore> = if let Some(seed) = seed {
+        Box::new(StdRng::seed_from_u64(seed))
+    } else {
+        Box::new(StdRng::from_rng(&mut rand::rng()))
+    };
+
+    let uplinks = (0..MAX_WAN_INTERFACES as u32).choose_multiple(&mut rng, usize::from(count));
+
+    let mut wans: Vec<WanInterface> = uplinks
+        .into_iter()
+        .zip(1..=count)
+        .map(|(uplink, id)| {
+            let block = u32::from(DOCUMENTATION_BLOCKS[(uplink / UPLINKS_PER_BLOCK) as usize]);
+            let network = block + (uplink % UPLINKS_PER_BLOCK) * 4;
+            WanInterface {
+                id,
+                public_ip: Ipv4Addr::from(network + 2).to_string(),
+                gateway: Ipv4Addr::from(network + 1).to_string(),
+                interface_name: WanInterface::interface_name_for(id),
+                monitor: None,
+            }
+        })
+        .collect();
+
+    // Monitors are drawn after the uplinks so the addressing does not depend on them
+    let mut monitor_ips = PUBLIC_MONITOR_IPS.to_vec();
+    monitor_ips.shuffle(&mut rng);
+    let mut monitor_ips = monitor_ips.into_iter();
+    for wan in &mut wans {
+        let monitor_ip = monitor_ips
+            .next()
+            .map_or_else(|| wan.gateway.clone(), str::to_string);
+        wan.monitor = Some(WanMonitor {
+            monitor_ip,
+            weight: rng.random_range(1..=5),
+            priority: wan.id.saturating_mul(PRIORITY_STEP),
+        });
+    }
+
+    Ok(wans)
+}
+
+/// Find the WAN interface a VLAN's `wan_assignment` refers to
+pub fn find_wan_interface(wans: &[WanInterface], wan_assignment: u8) -> Option<&WanInterface> {
+    wans.iter().find(|wan| wan.id == wan_assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rfc1918::is_rfc1918_addr;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_three_wans_have_distinct_public_ips() {
+        let wans = generate_wan_interfaces(3, Some(42)).unwrap();
+        assert_eq!(wans.len(), 3);
+
+        let ips: HashSet<&str> = wans.iter().map(|wan| wan.public_ip.as_str()).collect();
+        assert_eq!(ips.len(), 3);
+
+        for wan in &wans {
+            let ip: Ipv4Addr = wan.public_ip.parse().unwrap();
+            let gateway: Ipv4Addr = wan.gateway.parse().unwrap();
+            assert!(!is_rfc1918_addr(ip));
+            assert!(!is_rfc1918_addr(gateway));
+            // Gateway and address share the same /30
+            assert_eq!(u32::from(ip) & !3, u32::from(gateway) & !3);
+        }
+        assert_eq!(
+            wans.iter()
+                .map(|wan| wan.interface_name.as_str())
+                .collect::<Vec<_>>(),
+            ["wan", "wan2", "wan3"]
+        );
+    }
+
+    #[test]
+    fn test_generate_wan_interfaces_deterministic() {
+        assert_eq!(
+            generate_wan_interfaces(3, Some(7)).unwrap(),
+            generate_wan_interfaces(3, Some(7)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_wan_interfaces_capacity() {
+        assert_eq!(
+            generate_wan_interfaces(192, Some(1)).unwrap().len(),
+            MAX_WAN_INTERFACES
+        );
+        assert!(generate_wan_interfaces(193, Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_each_wan_gateway_is_monitored() {
+        let wans = generate_wan_interfaces(10, Some(42)).unwrap();
+
+        let monitor_ips: HashSet<&str> = wans
+            .iter()
+            .map(|wan| {
+                let monitor = wan.monitor.as_ref().expect("monitor settings");
+                assert!(monitor.monitor_ip.parse::<Ipv4Addr>().is_ok());
+                assert!(
+                    PUBLIC_MONITOR_IPS.contains(&monitor.monitor_ip.as_str())
+                        || monitor.monitor_ip == wan.gateway
+                );
+                assert!((1..=255).contains(&monitor.priority));
+                assert!((1..=5).contains(&monitor.weight));
+                monitor.monitor_ip.as_str()
+            })
+            .collect();
+        assert_eq!(monitor_ips.len(), wans.len());
+
+        // The first WAN is the preferred uplink
+        let priorities: Vec<u8> = wans
+            .iter()
+            .map(|wan| wan.monitor.as_ref().unwrap().priority)
+            .collect();
+        assert!(priorities.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(wans[1].gateway_name(), "WAN2_GW");
+    }
+
+    #[test]
+    fn test_find_wan_interface() {
+        let wans = generate_wan_interfaces(3, Some(42)).unwrap();
+        assert_eq!(find_wan_interface(&wans, 2).unwrap().interface_name, "wan2");
+        assert!(find_wan_interface(&wans, 4).is_none());
+    }
+}
@@ -1,17 +1,30 @@
 //! CSV input/output operations
 
 use crate::Result;
-use crate::generator::{FirewallRule, VlanConfig};
-use csv::{Reader, Writer, WriterBuilder};
+use crate::generator::vlan::infer_prefix_len;
+use crate::generator::{FirewallRule, Host, IpVersion, PointToPointLink, VlanConfig};
+use csv::{Position, Reader, ReaderBuilder, StringRecord, Writer, WriterBuilder};
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
+/// Field delimiter used unless another is requested
+pub const DEFAULT_DELIMITER: u8 = b',';
+
+/// First character of comment lines written before the CSV header
+///
+/// RFC 4180 has no comments, so they are only allowed at the top of a file,
+/// where readers skip them.
+pub const COMMENT_PREFIX: char = '#';
+
 // CSV header field name constants
-#[allow(dead_code)]
 const FIELD_VLAN: &str = "VLAN";
 
 // Lazy static validation sets for O(1) membership tests
@@ -34,15 +47,14 @@
         set.insert("tcp");
         set.insert("udp");
         set.insert("icmp");
+        set.insert("icmpv6");
         set.insert("any");
         set
     };
 }
-#[allow(dead_code)]
 const FIELD_IP_RANGE: &str = "IP Range";
-#[allow(dead_code)]
 const FIELD_BESCHREIBUNG: &str = "Beschreibung";
-#[allow(dead_code)]
+const FIELD_ENGLISH_DESCRIPTION: &str = "Description";
 const FIELD_WAN: &str = "WAN";
 #[allow(dead_code)]
 const FIELD_RULE_ID: &str = "rule_id";
@@ -78,6 +90,39 @@ fn vlan_csv_header() -> String {
     )
 }
 
+/// Column header set used for VLAN CSV files
+///
+/// Readers accept either set; the schema only affects the header written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvSchema {
+    /// `VLAN,IP Range,Beschreibung,WAN`, as written by the Python original
+    #[default]
+    Legacy,
+    /// `VLAN,IP Range,Description,WAN`
+    English,
+}
+
+impl CsvSchema {
+    /// Header row for this schema
+    pub fn headers(self) -> [&'static str; 4] {
+        let description = match self {
+            CsvSchema::Legacy => FIELD_BESCHREIBUNG,
+            CsvSchema::English => FIELD_ENGLISH_DESCRIPTION,
+        };
+        [FIELD_VLAN, FIELD_IP_RANGE, description, FIELD_WAN]
+    }
+}
+
+/// Character encoding of a VLAN CSV file being read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// UTF-8; anything else is rejected with the offending byte offset
+    #[default]
+    Utf8,
+    /// ISO-8859-1, as exported by legacy spreadsheet tooling
+    Latin1,
+}
+
 /// CSV record structure matching Python implementation format
 #[derive(Debug, Serialize, Deserialize)]
 struct CsvRecord {
@@ -87,7 +132,7 @@ struct CsvRecord {
     #[serde(rename = "IP Range")]
     ip_range: String,
 
-    #[serde(rename = "Beschreibung")]
+    #[serde(rename = "Beschreibung", alias = "Description")]
     description: String,
 
     #[serde(rename = "WAN")]
@@ -112,6 +157,10 @@ fn from(record: CsvRecord) -> Self {
         // strictly conform to our validation rules
         Self {
             vlan_id: record.vlan_id,
+            prefix_len: infer_prefix_len(&record.ip_range),
+            dhcp_policy: None,
+            outer_vlan_id: None,
+            pcp: 0,
             ip_network: record.ip_range,
             description: record.description,
             wan_assignment: record.wan_assignment,
@@ -119,12 +168,196 @@ fn from(record: CsvRecord) -> Self {
     }
 }
 
+/// Create a CSV writer that writes the schema's header row, if requested
+fn vlan_csv_writer<W: Write>(
+    writer: W,
+    schema: CsvSchema,
+    delimiter: u8,
+    header: bool,
+) -> Result<Writer<W>> {
+    let mut writer = WriterBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .from_writer(writer);
+    if header {
+        writer.write_record(schema.headers())?;
+    }
+    Ok(writer)
+}
+
+/// Whether a path names a gzip-compressed file, e.g. `vlans.csv.gz`
+pub fn is_gzip_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"))
+}
+
+/// Open a VLAN CSV file, decompressing `.gz` files on the fly
+fn open_csv_input(path: &Path) -> Result<Box<dyn Read>> {
+    let file = BufReader::new(File::open(path)?);
+    Ok(if is_gzip_path(path) {
+        Box::new(MultiGzDecoder::new(file))
+    } else {
+        Box::new(file)
+    })
+}
+
+/// Read a whole CSV input file as text in the given encoding
+///
+/// Invalid UTF-8 is reported with its byte offset instead of surfacing later
+/// as an opaque deserialization error.
+fn read_csv_text(path: &Path, encoding: InputEncoding) -> Result<String> {
+    let mut bytes = Vec::new();
+    open_csv_input(path)?.read_to_end(&mut bytes)?;
+    match encoding {
+        InputEncoding::Utf8 => String::from_utf8(bytes).map_err(|e| {
+            crate::model::ConfigError::validation(format!(
+                "CSV file '{}' is not valid UTF-8 at byte {}; convert it to UTF-8 or use --input-encoding latin1",
+                path.display(),
+                e.utf8_error().valid_up_to()
+            ))
+        }),
+        // Every Latin-1 byte is the Unicode code point of the same value
+        InputEncoding::Latin1 => Ok(bytes.into_iter().map(char::from).collect()),
+    }
+}
+
+/// Split leading comment lines off CSV text
+///
+/// Returns the text from the header on and how many lines were skipped, so
+/// row line numbers still match the file.
+fn skip_leading_comments(text: &str) -> (&str, usize) {
+    let mut rest = text;
+    let mut skipped = 0;
+    while rest.starts_with(COMMENT_PREFIX) {
+        rest = rest.split_once('\n').map_or("", |(_, after)| after);
+        skipped += 1;
+    }
+    (rest, skipped)
+}
+
+/// Write `comment` as `#`-prefixed lines, to go before the CSV header
+pub fn write_csv_comment<W: Write>(mut writer: W, comment: &str) -> io::Result<()> {
+    for line in comment.lines() {
+        writeln!(writer, "{COMMENT_PREFIX} {line}")?;
+    }
+    Ok(())
+}
+
+/// VLAN CSV output file, gzip-compressed when the path ends in `.gz`
+///
+/// Call [`CsvOutput::finish`] once writing is done so the gzip trailer is
+/// written and write errors are not lost on drop.
+enum CsvOutput<W: Write = BufWriter<File>> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+}
+
+impl CsvOutput {
+    /// Create or truncate the file at `path`
+    fn create(path: &Path) -> Result<Self> {
+        Ok(Self::wrap(path, File::create(path)?))
+    }
+
+    /// Open the file at `path` for appending; gzip output gets a new member,
+    /// which readers decode as one continuous stream
+    fn append(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::wrap(path, file))
+    }
+
+    fn wrap(path: &Path, file: File) -> Self {
+        CsvOutput::new(BufWriter::new(file), is_gzip_path(path))
+    }
+}
+
+impl<W: Write> CsvOutput<W> {
+    fn new(writer: W, gzip: bool) -> Self {
+        if gzip {
+            Self::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            Self::Plain(writer)
+        }
+    }
+
+    /// Flush buffered data and complete the gzip stream
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Plain(mut file) => file.flush()?,
+            Self::Gzip(encoder) => encoder.finish()?.flush()?,
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for CsvOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
 /// Write VLAN configurations to a CSV file
+///
+/// Paths ending in `.gz` (e.g. `vlans.csv.gz`) are gzip-compressed.
 pub fn write_csv<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<()> {
-    let file = File::create(path)?;
-    let mut writer = Writer::from_writer(BufWriter::new(file));
+    write_csv_with_schema(configs, path, CsvSchema::Legacy)
+}
+
+/// Write VLAN configurations to a CSV file using the given header set
+pub fn write_csv_with_schema<P: AsRef<Path>>(
+    configs: &[VlanConfig],
+    path: P,
+    schema: CsvSchema,
+) -> Result<()> {
+    write_csv_with_delimiter(configs, path, schema, DEFAULT_DELIMITER)
+}
+
+/// Write VLAN configurations to a CSV file using the given header set and
+/// field delimiter
+pub fn write_csv_with_delimiter<P: AsRef<Path>>(
+    configs: &[VlanConfig],
+    path: P,
+    schema: CsvSchema,
+    delimiter: u8,
+) -> Result<()> {
+    let mut output = CsvOutput::create(path.as_ref())?;
+    write_csv_to_with_delimiter(configs, &mut output, schema, delimiter)?;
+    output.finish()
+}
+
+/// Write VLAN configurations as CSV to any writer
+pub fn write_csv_to<W: Write>(configs: &[VlanConfig], writer: W) -> Result<()> {
+    write_csv_to_with_schema(configs, writer, CsvSchema::Legacy)
+}
+
+/// Write VLAN configurations as CSV to any writer using the given header set
+pub fn write_csv_to_with_schema<W: Write>(
+    configs: &[VlanConfig],
+    writer: W,
+    schema: CsvSchema,
+) -> Result<()> {
+    write_csv_to_with_delimiter(configs, writer, schema, DEFAULT_DELIMITER)
+}
+
+/// Write VLAN configurations as CSV to any writer using the given header set
+/// and field delimiter
+pub fn write_csv_to_with_delimiter<W: Write>(
+    configs: &[VlanConfig],
+    writer: W,
+    schema: CsvSchema,
+    delimiter: u8,
+) -> Result<()> {
+    let mut writer = vlan_csv_writer(writer, schema, delimiter, true)?;
 
-    // Write header and records
     for config in configs {
         let record = CsvRecord::from(config);
         writer.serialize(record)?;
@@ -134,10 +367,71 @@ pub fn write_csv<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<()>
     Ok(())
 }
 
+/// Append VLAN configurations to a CSV file
+///
+/// Rows are written without a header unless the file is missing or empty,
+/// in which case one is written first.
+pub fn append_csv<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<()> {
+    append_csv_with_schema(configs, path, CsvSchema::Legacy)
+}
+
+/// Append VLAN configurations to a CSV file, using the given header set if
+/// the file needs a header
+pub fn append_csv_with_schema<P: AsRef<Path>>(
+    configs: &[VlanConfig],
+    path: P,
+    schema: CsvSchema,
+) -> Result<()> {
+    append_csv_with_delimiter(configs, path, schema, DEFAULT_DELIMITER)
+}
+
+/// Append VLAN configurations to a CSV file with the given field delimiter,
+/// using the given header set if the file needs a header
+pub fn append_csv_with_delimiter<P: AsRef<Path>>(
+    configs: &[VlanConfig],
+    path: P,
+    schema: CsvSchema,
+    delimiter: u8,
+) -> Result<()> {
+    let path = path.as_ref();
+    let needs_header = fs::metadata(path).map_or(true, |metadata| metadata.len() == 0);
+    let mut output = CsvOutput::append(path)?;
+    let mut writer = vlan_csv_writer(&mut output, schema, delimiter, needs_header)?;
+
+    for config in configs {
+        writer.serialize(CsvRecord::from(config))?;
+    }
+
+    writer.flush()?;
+    drop(writer);
+    output.finish()
+}
+
 /// Read VLAN configurations from a CSV file
+///
+/// Paths ending in `.gz` are decompressed while reading.
 pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
-    let file = File::open(path)?;
-    let mut reader = Reader::from_reader(BufReader::new(file));
+    read_csv_with_delimiter(path, DEFAULT_DELIMITER)
+}
+
+/// Read VLAN configurations from a CSV file whose fields are separated by
+/// `delimiter`, e.g. `b';'`
+pub fn read_csv_with_delimiter<P: AsRef<Path>>(path: P, delimiter: u8) -> Result<Vec<VlanConfig>> {
+    read_csv_with_encoding(path, delimiter, InputEncoding::Utf8)
+}
+
+/// Read VLAN configurations from a CSV file separated by `delimiter` and
+/// stored in `encoding`
+pub fn read_csv_with_encoding<P: AsRef<Path>>(
+    path: P,
+    delimiter: u8,
+    encoding: InputEncoding,
+) -> Result<Vec<VlanConfig>> {
+    let text = read_csv_text(path.as_ref(), encoding)?;
+    let (text, _) = skip_leading_comments(&text);
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
     let mut configs = Vec::new();
 
     for result in reader.deserialize() {
@@ -150,10 +444,11 @@ pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
 
 /// Read VLAN configurations from a CSV file with enhanced validation
 pub fn read_csv_validated<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
-    let file = File::open(path)?;
-    let mut reader = Reader::from_reader(BufReader::new(file));
+    let text = read_csv_text(path.as_ref(), InputEncoding::Utf8)?;
+    let (text, comment_lines) = skip_leading_comments(&text);
+    let mut reader = Reader::from_reader(text.as_bytes());
     let mut configs = Vec::new();
-    let mut line_number = 1; // Start at 1 for header
+    let mut line_number = 1 + comment_lines; // The header follows any comments
 
     for result in reader.deserialize() {
         line_number += 1;
@@ -165,34 +460,134 @@ pub fn read_csv_validated<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
 
         // Validate the converted VlanConfig
         let config = VlanConfig::from(record);
-
-        // Additional validation for CSV-loaded data
-        if config.vlan_id < 10 || config.vlan_id > 4094 {
+        if let Some((problem, rule)) = vlan_row_problem(&config) {
             return Err(crate::model::ConfigError::validation(format!(
-                "Invalid VLAN ID '{}' at line {}: must be between 10 and 4094",
-                config.vlan_id, line_number
+                "{problem} at line {line_number}: {rule}"
             )));
         }
 
-        if config.wan_assignment < 1 || config.wan_assignment > 3 {
-            return Err(crate::model::ConfigError::validation(format!(
-                "Invalid WAN assignment '{}' at line {}: must be between 1 and 3",
-                config.wan_assignment, line_number
-            )));
-        }
+        configs.push(config);
+    }
 
-        // Validate IP network format
-        if !config.ip_network.ends_with(".x") && !config.ip_network.contains('/') {
-            return Err(crate::model::ConfigError::validation(format!(
-                "Invalid IP network format '{}' at line {}: must end with '.x' or contain '/'",
-                config.ip_network, line_number
-            )));
+    Ok(configs)
+}
+
+/// A CSV row that could not be loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Line number in the file, counting the header as line 1
+    pub line: usize,
+    /// What is wrong with the row
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Configurations paired with the file line they start on, and the rows
+/// that could not be loaded
+pub type CollectedRows = (Vec<(usize, VlanConfig)>, Vec<RowError>);
+
+/// Read VLAN configurations, collecting every bad row instead of stopping
+///
+/// Applies the same checks as [`read_csv_validated`]. Each configuration is
+/// returned with the file line its record starts on, counting the header as
+/// line 1. Rows that fail are left out and reported as [`RowError`]s in
+/// file order. Only failing to open or decode the file is an error.
+pub fn read_csv_collect_errors<P: AsRef<Path>>(path: P) -> Result<CollectedRows> {
+    read_csv_collect_errors_with_encoding(path, InputEncoding::Utf8)
+}
+
+/// [`read_csv_collect_errors`] for a file stored in `encoding`
+pub fn read_csv_collect_errors_with_encoding<P: AsRef<Path>>(
+    path: P,
+    encoding: InputEncoding,
+) -> Result<CollectedRows> {
+    read_csv_collect_errors_with_delimiter(path, DEFAULT_DELIMITER, encoding)
+}
+
+/// [`read_csv_collect_errors`] for a file separated by `delimiter` and
+/// stored in `encoding`
+pub fn read_csv_collect_errors_with_delimiter<P: AsRef<Path>>(
+    path: P,
+    delimiter: u8,
+    encoding: InputEncoding,
+) -> Result<CollectedRows> {
+    let text = read_csv_text(path.as_ref(), encoding)?;
+    let (text, comment_lines) = skip_leading_comments(&text);
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let mut configs = Vec::new();
+    let mut errors = Vec::new();
+
+    // Records may span lines inside quotes, so lines come from the reader
+    let mut row = StringRecord::new();
+    loop {
+        let parsed = match reader.read_record(&mut row) {
+            Ok(false) => break,
+            Ok(true) => row.deserialize::<CsvRecord>(Some(&headers)),
+            Err(e) if e.is_io_error() => return Err(e.into()),
+            Err(e) => Err(e),
+        };
+        let position = match &parsed {
+            Ok(_) => row.position(),
+            Err(e) => e.position(),
+        };
+        let line =
+            comment_lines + position.map_or(reader.position().line(), Position::line) as usize;
+
+        let config = match parsed {
+            Ok(record) => VlanConfig::from(record),
+            Err(e) => {
+                errors.push(RowError {
+                    line,
+                    message: format!("CSV parsing error: {e}"),
+                });
+                continue;
+            }
+        };
+        match vlan_row_problem(&config) {
+            Some((problem, rule)) => errors.push(RowError {
+                line,
+                message: format!("{problem}: {rule}"),
+            }),
+            None => configs.push((line, config)),
         }
+    }
 
-        configs.push(config);
+    Ok((configs, errors))
+}
+
+/// First check a CSV-loaded VLAN fails, as the problem and the rule it breaks
+fn vlan_row_problem(config: &VlanConfig) -> Option<(String, &'static str)> {
+    if config.vlan_id < 10 || config.vlan_id > 4094 {
+        return Some((
+            format!("Invalid VLAN ID '{}'", config.vlan_id),
+            "must be between 10 and 4094",
+        ));
     }
 
-    Ok(configs)
+    if config.wan_assignment < 1 || config.wan_assignment > 3 {
+        return Some((
+            format!("Invalid WAN assignment '{}'", config.wan_assignment),
+            "must be between 1 and 3",
+        ));
+    }
+
+    // Validate IP network format
+    if !config.ip_network.ends_with(".x") && !config.ip_network.contains('/') {
+        return Some((
+            format!("Invalid IP network format '{}'", config.ip_network),
+            "must end with '.x' or contain '/'",
+        ));
+    }
+
+    None
 }
 
 /// CSV record structure for firewall rules
@@ -233,6 +628,13 @@ struct FirewallRuleCsvRecord {
 
     #[serde(rename = "interface")]
     interface: String,
+
+    // Older files predate this column and hold IPv4 rules only
+    #[serde(rename = "ip_version", default)]
+    ip_version: IpVersion,
+
+    #[serde(rename = "icmp_type", default)]
+    icmp_type: Option<String>,
 }
 
 impl From<&FirewallRule> for FirewallRuleCsvRecord {
@@ -250,6 +652,8 @@ fn from(rule: &FirewallRule) -> Self {
             vlan_id: rule.vlan_id,
             priority: rule.priority,
             interface: rule.interface.clone(),
+            ip_version: rule.ip_version,
+            icmp_type: rule.icmp_type.clone(),
         }
     }
 }
@@ -270,6 +674,8 @@ fn from(record: FirewallRuleCsvRecord) -> Self {
             vlan_id: record.vlan_id,
             priority: record.priority,
             interface: record.interface,
+            ip_version: record.ip_version,
+            icmp_type: record.icmp_type,
         }
     }
 }
@@ -295,6 +701,8 @@ pub fn write_firewall_rules_csv<P: AsRef<Path>>(rules: &[FirewallRule], path: P)
         "vlan_id",
         "priority",
         "interface",
+        "ip_version",
+        "icmp_type",
     ])?;
 
     // Write records
@@ -307,6 +715,36 @@ pub fn write_firewall_rules_csv<P: AsRef<Path>>(rules: &[FirewallRule], path: P)
     Ok(())
 }
 
+/// Write a host inventory to a CSV file
+///
+/// Columns are hostname, mac, ip, os_type and role.
+pub fn write_hosts_csv<P: AsRef<Path>>(hosts: &[Host], path: P) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = Writer::from_writer(BufWriter::new(file));
+
+    for host in hosts {
+        writer.serialize(host)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write point-to-point links to a CSV file
+///
+/// Columns are local_ip, peer_ip and prefix.
+pub fn write_p2p_links_csv<P: AsRef<Path>>(links: &[PointToPointLink], path: P) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = Writer::from_writer(BufWriter::new(file));
+
+    for link in links {
+        writer.serialize(link)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
 /// Read firewall rules from a CSV file
 pub fn read_firewall_rules_csv<P: AsRef<Path>>(path: P) -> Result<Vec<FirewallRule>> {
     let file = File::open(path)?;
@@ -391,6 +829,14 @@ pub fn read_firewall_rules_csv_validated<P: AsRef<Path>>(path: P) -> Result<Vec<
             )));
         }
 
+        if let Some(icmp_type) = &rule.icmp_type
+            && protocol_lower != "icmp"
+        {
+            return Err(crate::model::ConfigError::validation(format!(
+                "ICMP type '{icmp_type}' at line {line_number}: only ICMP rules can have an ICMP type"
+            )));
+        }
+
         rules.push(rule);
     }
 
@@ -402,8 +848,10 @@ pub fn read_csv_streaming<P: AsRef<Path>, F>(path: P, mut callback: F) -> Result
 where
     F: FnMut(VlanConfig) -> Result<()>,
 {
-    let file = File::open(path)?;
-    let mut reader = Reader::from_reader(BufReader::new(file));
+    // Data rows never start with '#', so only a leading comment block is skipped
+    let mut reader = ReaderBuilder::new()
+        .comment(Some(COMMENT_PREFIX as u8))
+        .from_reader(open_csv_input(path.as_ref())?);
     let mut count = 0;
 
     for result in reader.deserialize() {
@@ -422,17 +870,90 @@ pub fn write_csv_streaming<P, I>(configs: I, path: P) -> Result<usize>
     I: Iterator<Item = VlanConfig>,
     P: AsRef<Path>,
 {
-    let file = File::create(path)?;
-    let mut writer = Writer::from_writer(BufWriter::new(file));
+    try_write_csv_streaming(configs.map(Ok), path)
+}
+
+/// Write fallible VLAN configurations to CSV, stopping at the first error
+///
+/// Pairs with [`VlanGeneratorIter`](crate::generator::vlan::VlanGeneratorIter)
+/// so configurations are generated and written one at a time.
+pub fn try_write_csv_streaming<P, I>(configs: I, path: P) -> Result<usize>
+where
+    I: Iterator<Item = Result<VlanConfig>>,
+    P: AsRef<Path>,
+{
+    try_write_csv_streaming_with_schema(configs, path, CsvSchema::Legacy)
+}
+
+/// Stream fallible VLAN configurations to CSV using the given header set
+pub fn try_write_csv_streaming_with_schema<P, I>(
+    configs: I,
+    path: P,
+    schema: CsvSchema,
+) -> Result<usize>
+where
+    I: Iterator<Item = Result<VlanConfig>>,
+    P: AsRef<Path>,
+{
+    try_write_csv_streaming_with_delimiter(configs, path, schema, DEFAULT_DELIMITER)
+}
+
+/// Stream fallible VLAN configurations to CSV using the given header set and
+/// field delimiter
+pub fn try_write_csv_streaming_with_delimiter<P, I>(
+    configs: I,
+    path: P,
+    schema: CsvSchema,
+    delimiter: u8,
+) -> Result<usize>
+where
+    I: Iterator<Item = Result<VlanConfig>>,
+    P: AsRef<Path>,
+{
+    let file = BufWriter::new(File::create(path.as_ref())?);
+    try_write_csv_streaming_to(
+        configs,
+        file,
+        is_gzip_path(path.as_ref()),
+        schema,
+        delimiter,
+        None,
+    )
+}
+
+/// Stream fallible VLAN configurations as CSV to any writer, gzip-compressed
+/// when `gzip` is set
+///
+/// A `comment` is written as `#` lines before the header; see
+/// [`write_csv_comment`].
+pub fn try_write_csv_streaming_to<W, I>(
+    configs: I,
+    writer: W,
+    gzip: bool,
+    schema: CsvSchema,
+    delimiter: u8,
+    comment: Option<&str>,
+) -> Result<usize>
+where
+    I: Iterator<Item = Result<VlanConfig>>,
+    W: Write,
+{
+    let mut output = CsvOutput::new(writer, gzip);
+    if let Some(comment) = comment {
+        write_csv_comment(&mut output, comment)?;
+    }
+    let mut writer = vlan_csv_writer(&mut output, schema, delimiter, true)?;
     let mut count = 0;
 
     for config in configs {
-        let record = CsvRecord::from(&config);
+        let record = CsvRecord::from(&config?);
         writer.serialize(record)?;
         count += 1;
     }
 
     writer.flush()?;
+    drop(writer);
+    output.finish()?;
     Ok(count)
 }
 
@@ -441,6 +962,177 @@ mod tests {
     use super::*;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_non_utf8_input_is_reported_or_transcoded() {
+        let temp_file = NamedTempFile::new().unwrap();
+        // "München" with the Latin-1 encoded u-umlaut (0xFC)
+        let content = b"VLAN,IP Range,Beschreibung,WAN\n100,10.1.2.x,M\xfcnchen VLAN 100,1\n";
+        fs::write(temp_file.path(), content).unwrap();
+        let offset = content.iter().position(|&b| b == 0xfc).unwrap();
+
+        let err = read_csv(temp_file.path()).unwrap_err().to_string();
+        assert!(
+            err.contains(&format!("is not valid UTF-8 at byte {offset}")),
+            "{err}"
+        );
+        assert!(read_csv_validated(temp_file.path()).is_err());
+
+        let configs =
+            read_csv_with_encoding(temp_file.path(), DEFAULT_DELIMITER, InputEncoding::Latin1)
+                .unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].description, "München VLAN 100");
+    }
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Sales VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "IT VLAN 200".to_string(), 2).unwrap(),
+        ];
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("vlans.csv.gz");
+
+        write_csv(&configs, &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(&bytes[..2], [0x1f, 0x8b], "output is not gzip");
+        assert_eq!(read_csv(&path).unwrap(), configs);
+
+        // Appending adds a gzip member that reads back as one stream
+        let extra =
+            VlanConfig::new(300, "10.5.6.x".to_string(), "HR VLAN 300".to_string(), 3).unwrap();
+        append_csv(std::slice::from_ref(&extra), &path).unwrap();
+        let read = read_csv_validated(&path).unwrap();
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[2], extra);
+
+        // Plain paths stay uncompressed
+        let plain = temp_dir.path().join("vlans.csv");
+        write_csv(&configs, &plain).unwrap();
+        assert!(fs::read_to_string(&plain).unwrap().starts_with("VLAN,"));
+    }
+
+    #[test]
+    fn test_header_comment_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Sales VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "IT VLAN 200".to_string(), 2).unwrap(),
+        ];
+        let temp_file = NamedTempFile::new().unwrap();
+        let file = File::create(temp_file.path()).unwrap();
+        try_write_csv_streaming_to(
+            configs.iter().cloned().map(Ok),
+            file,
+            false,
+            CsvSchema::Legacy,
+            DEFAULT_DELIMITER,
+            Some("Generated for testing\nseed=42"),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(
+            content.starts_with("# Generated for testing\n# seed=42\nVLAN,"),
+            "{content}"
+        );
+        assert_eq!(read_csv(temp_file.path()).unwrap(), configs);
+        let mut streamed = Vec::new();
+        read_csv_streaming(temp_file.path(), |config| {
+            streamed.push(config);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(streamed, configs);
+
+        // Row line numbers still count the comment lines
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(temp_file.path())
+            .unwrap();
+        writeln!(file, "300,not-a-network,HR VLAN 300,1").unwrap();
+        let (valid, errors) = read_csv_collect_errors(temp_file.path()).unwrap();
+        let valid: Vec<_> = valid.into_iter().map(|(_, config)| config).collect();
+        assert_eq!(valid, configs);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 6);
+    }
+
+    #[test]
+    fn test_english_schema_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Sales VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "IT VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_csv_with_schema(&configs, temp_file.path(), CsvSchema::English).unwrap();
+
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.starts_with("VLAN,IP Range,Description,WAN\n"));
+
+        let read_configs = read_csv(temp_file.path()).unwrap();
+        assert_eq!(read_configs, configs);
+    }
+
+    #[test]
+    fn test_semicolon_delimiter_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Sales, EMEA".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "IT VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_csv_with_delimiter(&configs, temp_file.path(), CsvSchema::Legacy, b';').unwrap();
+
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        let mut lines = content.lines();
+        assert_eq!(lines.next(), Some("VLAN;IP Range;Beschreibung;WAN"));
+        assert_eq!(lines.next(), Some("100;10.1.2.x;Sales, EMEA;1"));
+
+        let read_configs = read_csv_with_delimiter(temp_file.path(), b';').unwrap();
+        assert_eq!(read_configs, configs);
+    }
+
+    #[test]
+    fn test_write_hosts_csv() {
+        use crate::generator::generate_hosts_for_vlan;
+        use rand::SeedableRng;
+
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        let hosts = generate_hosts_for_vlan(&config, 3, &mut rng).unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_hosts_csv(&hosts, temp_file.path()).unwrap();
+
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        let mut lines = content.lines();
+        assert_eq!(lines.next(), Some("hostname,mac,ip,os_type,role"));
+        let first = lines.next().unwrap();
+        assert!(first.starts_with(&format!(
+            "{},{},{},",
+            hosts[0].hostname, hosts[0].mac, hosts[0].ip
+        )));
+        assert_eq!(lines.count(), 2);
+    }
+
+    #[test]
+    fn test_try_write_csv_streaming_stops_at_error() {
+        let configs = vec![
+            Ok(VlanConfig::new(100, "10.1.2.x".to_string(), "Test 1".to_string(), 1).unwrap()),
+            Err(crate::model::ConfigError::resource_exhausted("VLAN IDs")),
+            Ok(VlanConfig::new(200, "10.3.4.x".to_string(), "Test 2".to_string(), 2).unwrap()),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let result = try_write_csv_streaming(configs.into_iter(), temp_file.path());
+        assert!(matches!(
+            result,
+            Err(crate::model::ConfigError::ResourceExhausted { .. })
+        ));
+    }
+
     #[test]
     fn test_csv_round_trip() {
         let original_configs = vec![
@@ -464,6 +1156,28 @@ fn test_csv_round_trip() {
         }
     }
 
+    #[test]
+    fn test_append_csv_keeps_single_header() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let first =
+            vec![VlanConfig::new(100, "10.1.2.x".to_string(), "Test 1".to_string(), 1).unwrap()];
+        let second =
+            vec![VlanConfig::new(200, "10.3.4.x".to_string(), "Test 2".to_string(), 2).unwrap()];
+
+        // An empty file gets a header on first append
+        append_csv(&first, temp_file.path()).unwrap();
+        append_csv(&second, temp_file.path()).unwrap();
+
+        let contents = fs::read_to_string(temp_file.path()).unwrap();
+        assert_eq!(contents.matches("VLAN,").count(), 1);
+
+        let configs = read_csv(temp_file.path()).unwrap();
+        assert_eq!(
+            configs.iter().map(|c| c.vlan_id).collect::<Vec<_>>(),
+            [100, 200]
+        );
+    }
+
     #[test]
     fn test_csv_record_conversion() {
         let config =
@@ -567,6 +1281,36 @@ fn test_csv_validated_reading_invalid_ip_format() {
         assert!(error_msg.contains("line 2"));
     }
 
+    #[test]
+    fn test_read_csv_collect_errors_reports_every_bad_row() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_file.path(),
+            format!(
+                "{}\n5,10.1.2.x,Low ID,1\n100,10.1.3.x,Valid,1\n200,10.1.4.x,Bad WAN,9\n300,10.1.5.1,Bad Net,2\n400,10.1.6.x,\"Two\nLines\",1\nx,10.1.7.x,Bad ID,1\n500,10.1.8.x,Valid,1\n",
+                vlan_csv_header()
+            ),
+        )
+        .unwrap();
+
+        let (configs, errors) = read_csv_collect_errors(temp_file.path()).unwrap();
+        let found: Vec<_> = configs
+            .iter()
+            .map(|(line, config)| (*line, config.vlan_id))
+            .collect();
+        assert_eq!(found, [(3, 100), (6, 400), (9, 500)]);
+
+        let lines: Vec<_> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [2, 4, 5, 8]);
+        assert!(errors[0].message.contains("Invalid VLAN ID '5'"));
+        assert!(errors[1].message.contains("Invalid WAN assignment '9'"));
+        assert!(errors[2].message.contains("Invalid IP network format"));
+        assert_eq!(
+            errors[1].to_string(),
+            "line 4: Invalid WAN assignment '9': must be between 1 and 3"
+        );
+    }
+
     #[test]
     fn test_csv_streaming_read() {
         let configs = vec![
@@ -755,6 +1499,23 @@ fn test_firewall_rule_validation_performance() {
         assert_eq!(result.unwrap().len(), 1000);
     }
 
+    #[test]
+    fn test_firewall_rules_csv_roundtrip_keeps_ip_version() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let rules = crate::generator::FirewallGenerator::new(Some(42))
+            .generate_ipv6_basic_rules(100, "fd00:0:0:64::/64", "IT")
+            .unwrap();
+
+        write_firewall_rules_csv(&rules, temp_file.path()).unwrap();
+        let read_back = read_firewall_rules_csv_validated(temp_file.path()).unwrap();
+        assert_eq!(read_back, rules);
+        assert!(
+            read_back
+                .iter()
+                .all(|rule| rule.ip_version == IpVersion::V6)
+        );
+    }
+
     #[test]
     fn test_firewall_rule_validation_invalid_values() {
         // Test that invalid values are caught efficiently
@@ -0,0 +1,116 @@
+//! BIND zone file output for generated DNS records
+
+use crate::Result;
+use crate::generator::dns::{DEFAULT_TTL, DnsRecord, DnsRecordType};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Write the records that belong to `origin` as a BIND-format zone file
+///
+/// `origin` is a fully qualified zone name such as `company.local.` or
+/// `in-addr.arpa.`; records outside it are skipped so the same record set
+/// can be split into forward and reverse zones. Owner names are written
+/// relative to the origin. The primary name server is the first A record
+/// in `records` (a VLAN gateway), so the forward and reverse zones share a
+/// server with an address; `ns1.<origin>` is used only without any A record.
+pub fn write_bind_zone<P: AsRef<Path>>(records: &[DnsRecord], origin: &str, path: P) -> Result<()> {
+    let origin = if origin.ends_with('.') {
+        origin.to_string()
+    } else {
+        format!("{origin}.")
+    };
+    let in_zone: Vec<&DnsRecord> = records
+        .iter()
+        .filter(|record| record.name == origin || record.name.ends_with(&format!(".{origin}")))
+        .collect();
+    let name_server = records
+        .iter()
+        .find(|record| record.record_type == DnsRecordType::A)
+        .map_or_else(|| format!("ns1.{origin}"), |record| record.name.clone());
+
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    writeln!(writer, "$ORIGIN {origin}")?;
+    writeln!(writer, "$TTL {DEFAULT_TTL}")?;
+    writeln!(
+        writer,
+        "@\tIN\tSOA\t{name_server} hostmaster.{origin} (1 3600 900 604800 {DEFAULT_TTL})"
+    )?;
+    writeln!(writer, "@\tIN\tNS\t{name_server}")?;
+    writeln!(writer)?;
+
+    for record in in_zone {
+        let name = relative_name(&record.name, &origin);
+        writeln!(
+            writer,
+            "{name}\t{}\tIN\t{}\t{}",
+            record.ttl, record.record_type, record.value
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Strip the origin from a fully qualified name, using `@` for the apex
+fn relative_name<'a>(name: &'a str, origin: &str) -> &'a str {
+    match name.strip_suffix(origin) {
+        Some("") => "@",
+        Some(relative) => relative.trim_end_matches('.'),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::VlanConfig;
+    use crate::generator::dns::{REVERSE_ZONE_ORIGIN, generate_dns_records};
+    use tempfile::NamedTempFile;
+
+    fn sample_records() -> Vec<DnsRecord> {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        generate_dns_records(&[config]).unwrap()
+    }
+
+    #[test]
+    fn test_write_forward_zone() {
+        let temp_file = NamedTempFile::new().unwrap();
+        write_bind_zone(&sample_records(), "company.local", temp_file.path()).unwrap();
+
+        let zone = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert!(zone.starts_with("$ORIGIN company.local.\n$TTL 3600\n"));
+        assert!(zone.contains("@\tIN\tNS\tgw.vlan100.it.company.local."));
+        assert!(zone.contains("gw.vlan100.it\t3600\tIN\tA\t10.1.2.1"));
+        assert!(zone.contains("server-it-01.vlan100.it\t3600\tIN\tA\t10.1.2.10"));
+        assert!(!zone.contains("PTR"));
+    }
+
+    #[test]
+    fn test_write_reverse_zone() {
+        let temp_file = NamedTempFile::new().unwrap();
+        write_bind_zone(&sample_records(), REVERSE_ZONE_ORIGIN, temp_file.path()).unwrap();
+
+        let zone = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert!(zone.contains("1.2.1.10\t3600\tIN\tPTR\tgw.vlan100.it.company.local."));
+        assert!(!zone.contains("\tA\t"));
+        // The forward zone's gateway serves the reverse zone too
+        assert!(zone.contains(&format!(
+            "@\tIN\tSOA\tgw.vlan100.it.company.local. hostmaster.{REVERSE_ZONE_ORIGIN} "
+        )));
+        assert!(zone.contains("@\tIN\tNS\tgw.vlan100.it.company.local.\n"));
+    }
+
+    #[test]
+    fn test_relative_name() {
+        assert_eq!(relative_name("company.local.", "company.local."), "@");
+        assert_eq!(relative_name("gw.company.local.", "company.local."), "gw");
+        assert_eq!(
+            relative_name("other.example.", "company.local."),
+            "other.example."
+        );
+    }
+}
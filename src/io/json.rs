@@ -0,0 +1,76 @@
+//! JSON input/output operations
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Top-level JSON document holding VLAN configurations under a `vlans` key
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonDocument {
+    vlans: Vec<VlanConfig>,
+}
+
+/// Write VLAN configurations to a JSON file
+pub fn write_json<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<()> {
+    let file = File::create(path)?;
+    write_json_to(configs, BufWriter::new(file))
+}
+
+/// Write VLAN configurations as pretty-printed JSON to any writer
+pub fn write_json_to<W: Write>(configs: &[VlanConfig], mut writer: W) -> Result<()> {
+    let document = JsonDocument {
+        vlans: configs.to_vec(),
+    };
+    serde_json::to_writer_pretty(&mut writer, &document)?;
+    writeln!(writer)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read VLAN configurations from a JSON file
+pub fn read_json<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
+    let file = File::open(path)?;
+    let document: JsonDocument = serde_json::from_reader(BufReader::new(file))?;
+    Ok(document.vlans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ConfigError;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_json_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Sales VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new_with_prefix(
+                200,
+                "10.3.4.64/26".to_string(),
+                "IT VLAN 200".to_string(),
+                2,
+                26,
+            )
+            .unwrap(),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_json(&configs, temp_file.path()).unwrap();
+        let read_configs = read_json(temp_file.path()).unwrap();
+
+        assert_eq!(configs, read_configs);
+    }
+
+    #[test]
+    fn test_read_json_invalid_document() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[{{\"vlan_id\": 100}}]").unwrap();
+
+        let result = read_json(temp_file.path());
+        assert!(matches!(result, Err(ConfigError::Json(_))));
+    }
+}
@@ -0,0 +1,82 @@
+//! JSON Lines (newline-delimited JSON) input/output operations
+//!
+//! Each line holds one serialized `VlanConfig`, so consumers can process a
+//! file or pipe incrementally without parsing a whole document first.
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Write VLAN configurations to a JSONL file
+pub fn write_jsonl<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<usize> {
+    let file = File::create(path)?;
+    write_jsonl_streaming(configs.iter().cloned(), BufWriter::new(file))
+}
+
+/// Write configurations one per line, flushing after every line
+///
+/// Returns the number of lines written. Flushing per line lets a reader on
+/// the other end of a pipe see each configuration as soon as it is produced.
+pub fn write_jsonl_streaming<I, W>(configs: I, writer: W) -> Result<usize>
+where
+    I: Iterator<Item = VlanConfig>,
+    W: Write,
+{
+    try_write_jsonl_streaming(configs.map(Ok), writer)
+}
+
+/// Write fallible configurations one per line, stopping at the first error
+pub fn try_write_jsonl_streaming<I, W>(configs: I, mut writer: W) -> Result<usize>
+where
+    I: Iterator<Item = Result<VlanConfig>>,
+    W: Write,
+{
+    let mut count = 0;
+    for config in configs {
+        serde_json::to_writer(&mut writer, &config?)?;
+        writeln!(writer)?;
+        writer.flush()?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Read VLAN configurations from a JSONL file, skipping blank lines
+pub fn read_jsonl<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut configs = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            configs.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(configs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::VlanGenerator;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_jsonl_one_config_per_line() {
+        let configs = VlanGenerator::new(Some(42)).generate_batch(5).unwrap();
+        let temp_file = NamedTempFile::new().unwrap();
+        let written = write_jsonl(&configs, temp_file.path()).unwrap();
+        assert_eq!(written, 5);
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 5);
+        for (line, config) in lines.iter().zip(&configs) {
+            let parsed: VlanConfig = serde_json::from_str(line).unwrap();
+            assert_eq!(&parsed, config);
+        }
+
+        assert_eq!(read_jsonl(temp_file.path()).unwrap(), configs);
+    }
+}
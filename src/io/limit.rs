@@ -0,0 +1,159 @@
+//! Output size limits
+//!
+//! [`OutputBudget`] caps the total number of bytes a run writes so a
+//! misconfigured generation cannot fill the disk.
+
+use crate::model::ConfigError;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Writer that refuses any write taking it past `limit` bytes
+pub struct LimitedWriter<W> {
+    inner: W,
+    written: u64,
+    limit: u64,
+    exceeded: bool,
+}
+
+impl<W: Write> LimitedWriter<W> {
+    /// Wrap `inner`, allowing at most `limit` bytes through
+    pub fn new(inner: W, limit: u64) -> Self {
+        Self {
+            inner,
+            written: 0,
+            limit,
+            exceeded: false,
+        }
+    }
+
+    /// Bytes passed to the inner writer so far
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Whether a write was refused for exceeding the limit
+    pub fn exceeded(&self) -> bool {
+        self.exceeded
+    }
+}
+
+impl<W: Write> Write for LimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written.saturating_add(buf.len() as u64) > self.limit {
+            self.exceeded = true;
+            return Err(io::Error::other(format!(
+                "output limit of {} bytes exceeded",
+                self.limit
+            )));
+        }
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Byte allowance shared by every file a run writes
+#[derive(Debug, Default)]
+pub struct OutputBudget {
+    max_bytes: Option<u64>,
+    written: u64,
+}
+
+impl OutputBudget {
+    /// Budget of `max_bytes` in total, or unlimited for `None`
+    pub fn new(max_bytes: Option<u64>) -> Self {
+        Self {
+            max_bytes,
+            written: 0,
+        }
+    }
+
+    /// Bytes written through this budget so far
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Create `path` and let `write` fill it from the remaining budget
+    ///
+    /// If the budget runs out, the partial file is removed and a
+    /// [`ConfigError::ResourceExhausted`] error is returned instead of
+    /// whatever `write` failed with.
+    pub fn write_file<T, E, F>(&mut self, path: &Path, write: F) -> Result<T, E>
+    where
+        E: From<io::Error> + From<ConfigError>,
+        F: FnOnce(&mut dyn Write) -> Result<T, E>,
+    {
+        let remaining = self
+            .max_bytes
+            .map_or(u64::MAX, |max| max.saturating_sub(self.written));
+        let mut writer = BufWriter::new(LimitedWriter::new(File::create(path)?, remaining));
+        let result = write(&mut writer).and_then(|value| {
+            writer.flush()?;
+            Ok(value)
+        });
+
+        self.written += writer.get_ref().written();
+        if writer.get_ref().exceeded() {
+            // Drop without flushing further; the file is removed either way
+            drop(writer.into_parts());
+            fs::remove_file(path)?;
+            return Err(ConfigError::resource_exhausted(format!(
+                "output limit of {} bytes reached while writing '{}'; the partial file was removed",
+                self.max_bytes.unwrap_or(u64::MAX),
+                path.display()
+            ))
+            .into());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_is_shared_across_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let first = temp_dir.path().join("first.txt");
+        let second = temp_dir.path().join("second.txt");
+        let mut budget = OutputBudget::new(Some(10));
+
+        budget
+            .write_file(&first, |writer| {
+                Ok::<_, ConfigError>(writer.write_all(b"123456")?)
+            })
+            .unwrap();
+        assert_eq!(budget.written(), 6);
+
+        let err = budget
+            .write_file(&second, |writer| {
+                Ok::<_, ConfigError>(writer.write_all(b"123456")?)
+            })
+            .unwrap_err();
+        assert!(
+            matches!(err, ConfigError::ResourceExhausted { .. }),
+            "{err}"
+        );
+        assert!(first.exists());
+        assert!(!second.exists());
+    }
+
+    #[test]
+    fn test_unlimited_budget() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("out.txt");
+        let mut budget = OutputBudget::new(None);
+        budget
+            .write_file(&path, |writer| {
+                Ok::<_, ConfigError>(writer.write_all(&[b'x'; 100_000])?)
+            })
+            .unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), 100_000);
+    }
+}
@@ -1,3 +1,10 @@
 //! Input/output handling for CSV and other formats
 
 pub mod csv;
+pub mod dns;
+pub mod json;
+pub mod jsonl;
+pub mod limit;
+pub mod ovpn;
+pub mod toml;
+pub mod yaml;
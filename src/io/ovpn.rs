@@ -0,0 +1,110 @@
+//! OpenVPN client profile (`.ovpn`) output for generated VPN configurations
+
+use crate::Result;
+use crate::generator::vpn::{VpnConfig, VpnType};
+use crate::model::ConfigError;
+use std::fs;
+use std::path::Path;
+
+/// Inline certificate blocks written with placeholder PEM bodies
+const PLACEHOLDER_BLOCKS: &[(&str, &str)] = &[
+    ("ca", "CERTIFICATE"),
+    ("cert", "CERTIFICATE"),
+    ("key", "PRIVATE KEY"),
+];
+
+/// Write an OpenVPN client profile for an OpenVPN-typed configuration
+pub fn write_ovpn<P: AsRef<Path>>(config: &VpnConfig, path: P) -> Result<()> {
+    fs::write(path, render_ovpn(config)?)?;
+    Ok(())
+}
+
+/// Render the client profile for an OpenVPN-typed configuration
+///
+/// The profile connects to the configuration's server, port and protocol
+/// with its cipher, pushes its DNS servers and embeds `<ca>`, `<cert>` and
+/// `<key>` blocks whose PEM bodies are placeholders named after the key
+/// identifier. Username/password configurations also get `auth-user-pass`.
+pub fn render_ovpn(config: &VpnConfig) -> Result<String> {
+    if config.vpn_type != VpnType::OpenVPN {
+        return Err(ConfigError::invalid_parameter(
+            "vpn_type",
+            format!(
+                "'{}' is a {:?} configuration; .ovpn profiles need an OpenVPN configuration",
+                config.name, config.vpn_type
+            ),
+        ));
+    }
+
+    let mut lines = vec![
+        format!("# {} ({})", config.name, config.id),
+        "client".to_string(),
+        "dev tun".to_string(),
+        format!("proto {}", config.protocol.to_ascii_lowercase()),
+        format!("remote {} {}", config.server, config.port),
+        "resolv-retry infinite".to_string(),
+        "nobind".to_string(),
+        "persist-key".to_string(),
+        "persist-tun".to_string(),
+        "remote-cert-tls server".to_string(),
+        format!("cipher {}", config.cipher),
+        format!("data-ciphers {}", config.cipher),
+        "auth SHA256".to_string(),
+    ];
+    if config.auth_method.contains("Password") {
+        lines.push("auth-user-pass".to_string());
+    }
+    for dns_server in &config.dns_servers {
+        lines.push(format!("dhcp-option DNS {dns_server}"));
+    }
+    lines.push("verb 3".to_string());
+
+    for (block, label) in PLACEHOLDER_BLOCKS {
+        lines.extend([
+            format!("<{block}>"),
+            format!("-----BEGIN {label}-----"),
+            format!("PLACEHOLDER-{}-{block}", config.key_identifier),
+            format!("-----END {label}-----"),
+            format!("</{block}>"),
+        ]);
+    }
+
+    let mut profile = lines.join("\n");
+    profile.push('\n');
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::vpn::VpnGenerator;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_write_ovpn_client_profile() {
+        let mut config = VpnGenerator::new_with_seed(Some(42))
+            .generate_single(Some(VpnType::OpenVPN))
+            .unwrap();
+        config.protocol = "UDP".to_string();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_ovpn(&config, temp_file.path()).unwrap();
+
+        let profile = std::fs::read_to_string(temp_file.path()).unwrap();
+        let lines: Vec<&str> = profile.lines().collect();
+        assert!(lines.contains(&format!("remote {} {}", config.server, config.port).as_str()));
+        assert!(lines.contains(&"proto udp"));
+        assert!(lines.contains(&format!("cipher {}", config.cipher).as_str()));
+        assert!(profile.contains("<ca>\n-----BEGIN CERTIFICATE-----\n"));
+        assert!(profile.contains("-----END PRIVATE KEY-----\n</key>\n"));
+    }
+
+    #[test]
+    fn test_write_ovpn_rejects_other_vpn_types() {
+        let config = VpnGenerator::new_with_seed(Some(42))
+            .generate_single(Some(VpnType::WireGuard))
+            .unwrap();
+        let temp_file = NamedTempFile::new().unwrap();
+        assert!(write_ovpn(&config, temp_file.path()).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! TOML input/output operations
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Top-level TOML document holding VLAN configurations as `[[vlan]]` tables
+#[derive(Debug, Serialize, Deserialize)]
+struct TomlDocument {
+    #[serde(rename = "vlan", default)]
+    vlans: Vec<VlanConfig>,
+}
+
+/// Write VLAN configurations to a TOML file
+pub fn write_toml<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<()> {
+    fs::write(path, to_toml_string(configs)?)?;
+    Ok(())
+}
+
+/// Serialize VLAN configurations to a TOML document
+pub fn to_toml_string(configs: &[VlanConfig]) -> Result<String> {
+    let document = TomlDocument {
+        vlans: configs.to_vec(),
+    };
+    Ok(toml::to_string(&document)?)
+}
+
+/// Read VLAN configurations from a TOML file
+pub fn read_toml<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
+    let content = fs::read_to_string(path)?;
+    let document: TomlDocument = toml::from_str(&content)?;
+    Ok(document.vlans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ConfigError;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_toml_array_of_tables() {
+        let configs =
+            vec![VlanConfig::new(100, "10.1.2.x".to_string(), "Test".to_string(), 1).unwrap()];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_toml(&configs, temp_file.path()).unwrap();
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+
+        assert!(content.starts_with("[[vlan]]\n"));
+        assert!(content.contains("vlan_id = 100\n"));
+        assert!(content.contains("wan_assignment = 1\n"));
+        assert!(content.contains("ip_network = \"10.1.2.x\"\n"));
+    }
+
+    #[test]
+    fn test_toml_utf8_descriptions_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(
+                100,
+                "10.1.2.x".to_string(),
+                "営業部 VLAN 100".to_string(),
+                1,
+            )
+            .unwrap(),
+            VlanConfig::new_with_prefix(
+                200,
+                "10.3.4.64/26".to_string(),
+                "Geschäftsführung Büro".to_string(),
+                2,
+                26,
+            )
+            .unwrap(),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_toml(&configs, temp_file.path()).unwrap();
+        let read_configs = read_toml(temp_file.path()).unwrap();
+
+        assert_eq!(configs, read_configs);
+    }
+
+    #[test]
+    fn test_read_toml_invalid_document() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "[[vlan]]\nvlan_id = \"one hundred\"").unwrap();
+
+        let result = read_toml(temp_file.path());
+        assert!(matches!(result, Err(ConfigError::TomlParse(_))));
+    }
+}
@@ -0,0 +1,130 @@
+//! YAML input/output operations
+
+use crate::Result;
+use crate::generator::VlanConfig;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Top-level YAML document holding VLAN configurations under a `vlans:` key
+#[derive(Debug, Serialize, Deserialize)]
+struct YamlDocument {
+    vlans: Vec<VlanConfig>,
+}
+
+/// Write VLAN configurations to a YAML file
+pub fn write_yaml<P: AsRef<Path>>(configs: &[VlanConfig], path: P) -> Result<()> {
+    let file = File::create(path)?;
+    write_yaml_to(configs, BufWriter::new(file))
+}
+
+/// Write VLAN configurations as YAML to any writer
+pub fn write_yaml_to<W: Write>(configs: &[VlanConfig], mut writer: W) -> Result<()> {
+    let document = YamlDocument {
+        vlans: configs.to_vec(),
+    };
+    serde_yaml::to_writer(&mut writer, &document)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read VLAN configurations from a YAML file
+pub fn read_yaml<P: AsRef<Path>>(path: P) -> Result<Vec<VlanConfig>> {
+    let file = File::open(path)?;
+    let document: YamlDocument = serde_yaml::from_reader(BufReader::new(file))?;
+    Ok(document.vlans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ConfigError;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_yaml_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Sales VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new_with_prefix(
+                200,
+                "10.3.4.64/26".to_string(),
+                "IT VLAN 200".to_string(),
+                2,
+                26,
+            )
+            .unwrap(),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_yaml(&configs, temp_file.path()).unwrap();
+        let read_configs = read_yaml(temp_file.path()).unwrap();
+
+        assert_eq!(configs, read_configs);
+    }
+
+    #[test]
+    fn test_yaml_top_level_vlans_key() {
+        let configs =
+            vec![VlanConfig::new(100, "10.1.2.x".to_string(), "Test".to_string(), 1).unwrap()];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_yaml(&configs, temp_file.path()).unwrap();
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+
+        assert!(content.starts_with("vlans:\n"));
+        assert!(content.contains("vlan_id: 100"));
+        assert!(content.contains("ip_network: 10.1.2.x"));
+    }
+
+    #[test]
+    fn test_yaml_utf8_descriptions_roundtrip() {
+        let configs = vec![
+            VlanConfig::new(
+                100,
+                "10.1.2.x".to_string(),
+                "営業部 VLAN 100".to_string(),
+                1,
+            )
+            .unwrap(),
+            VlanConfig::new(
+                200,
+                "10.3.4.x".to_string(),
+                "Geschäftsführung Büro".to_string(),
+                2,
+            )
+            .unwrap(),
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        write_yaml(&configs, temp_file.path()).unwrap();
+        let read_configs = read_yaml(temp_file.path()).unwrap();
+
+        assert_eq!(read_configs[0].description, "営業部 VLAN 100");
+        assert_eq!(read_configs[1].description, "Geschäftsführung Büro");
+    }
+
+    #[test]
+    fn test_read_yaml_defaults_prefix_len() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "vlans:\n- vlan_id: 100\n  ip_network: 10.1.2.x\n  description: Test\n  wan_assignment: 1"
+        )
+        .unwrap();
+
+        let configs = read_yaml(temp_file.path()).unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].prefix_len, 24);
+    }
+
+    #[test]
+    fn test_read_yaml_invalid_document() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "- not: a vlan document").unwrap();
+
+        let result = read_yaml(temp_file.path());
+        assert!(matches!(result, Err(ConfigError::Yaml(_))));
+    }
+}
@@ -6,15 +6,71 @@
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use opnsense_config_faker::cli::{Cli, Commands};
+use opnsense_config_faker::cli::error::{CliError, ErrorReport};
+use opnsense_config_faker::cli::{Cli, Commands, GlobalArgs};
+use std::ffi::OsString;
+use std::process::ExitCode;
+use tracing_subscriber::EnvFilter;
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn main() -> Result<ExitCode> {
+    let mut cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // Help and version output are not errors
+        Err(error) if error.use_stderr() && requests_json_errors(std::env::args_os()) => {
+            print_json_report(&CliError::from_clap(&error).into());
+            return Ok(ExitCode::from(u8::try_from(error.exit_code()).unwrap_or(1)));
+        }
+        Err(error) => error.exit(),
+    };
+    // Keep stderr for the JSON report alone
+    cli.global.quiet |= cli.global.json_errors();
+    setup_environment(&cli.global);
+
+    match run(cli.command, &cli.global) {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        Err(error) if cli.global.json_errors() => {
+            print_json_report(&error);
+            Ok(ExitCode::FAILURE)
+        }
+        Err(error) => Err(error),
+    }
+}
 
+/// Whether the command line selects `--error-format json`
+///
+/// Clap reports argument errors before it hands over the parsed flags, so
+/// the raw arguments are scanned instead.
+fn requests_json_errors(args: impl IntoIterator<Item = OsString>) -> bool {
+    let mut json = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--error-format" {
+            json = args.next().is_some_and(|value| value == "json");
+        } else if arg == "--error-format=json" {
+            json = true;
+        } else if arg.to_string_lossy().starts_with("--error-format=") {
+            json = false;
+        }
+    }
+    json
+}
+
+/// Write the `--error-format json` report for a failed command to stderr
+fn print_json_report(error: &anyhow::Error) {
+    match serde_json::to_string(&ErrorReport::from_error(error)) {
+        Ok(json) => eprintln!("{json}"),
+        Err(_) => eprintln!("Error: {error:?}"),
+    }
+}
+
+/// Run the chosen command, adding context to any error it returns
+fn run(command: Commands, global: &GlobalArgs) -> Result<()> {
     // Execute command with rich context
-    match cli.command {
+    match command {
         Commands::Generate(args) => {
-            opnsense_config_faker::cli::commands::generate::execute_with_global(args, &cli.global)
+            opnsense_config_faker::cli::commands::generate::execute_with_global(*args, global)
                 .context("Failed to generate configurations")?
         }
         Commands::Completions { shell } => {
@@ -22,9 +78,15 @@ fn main() -> Result<()> {
                 .context("Failed to generate shell completions")?
         }
         Commands::Validate(args) => {
-            opnsense_config_faker::cli::commands::validate::execute_with_global(args, &cli.global)
+            opnsense_config_faker::cli::commands::validate::execute_with_global(args, global)
                 .context("Failed to validate configurations")?
         }
+        Commands::Diff(args) => opnsense_config_faker::cli::commands::diff::execute(args, global)
+            .context("Failed to compare configurations")?,
+        Commands::Verify(args) => {
+            opnsense_config_faker::cli::commands::verify::execute(args, global)
+                .context("Failed to verify generated files")?
+        }
         Commands::Csv(args) => {
             opnsense_config_faker::cli::commands::deprecated::handle_deprecated_csv(args)
                 .context("Failed to process CSV command")?
@@ -37,3 +99,21 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Send tracing output to stderr at the level chosen by `-v` or `RUST_LOG`
+fn setup_environment(global: &GlobalArgs) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        EnvFilter::new(match global.verbose {
+            0 => "warn",
+            1 => "info",
+            2 => "debug",
+            _ => "trace",
+        })
+    });
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(!global.no_color)
+        .without_time()
+        .try_init();
+}
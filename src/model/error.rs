@@ -21,6 +21,18 @@ pub enum ConfigError {
     #[error("JSON operation failed: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// YAML serialization/deserialization failed
+    #[error("YAML operation failed: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    /// TOML serialization failed
+    #[error("TOML serialization failed: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    /// TOML parsing failed
+    #[error("TOML parsing failed: {0}")]
+    TomlParse(#[from] toml::de::Error),
+
     /// VLAN configuration generation failed
     #[error("VLAN generation failed: {message}")]
     VlanGeneration { message: String },
@@ -57,6 +69,10 @@ pub enum ConfigError {
     #[error("Configuration file not found: {path}")]
     ConfigNotFound { path: String },
 
+    /// Output file exists and overwriting was not requested
+    #[error("Output file already exists. Use --force to overwrite: {path}")]
+    FileExists { path: String },
+
     /// Invalid configuration parameter
     #[error("Invalid parameter '{parameter}': {reason}")]
     InvalidParameter { parameter: String, reason: String },
@@ -99,6 +115,13 @@ pub fn config<S: Into<String>>(message: S) -> Self {
         }
     }
 
+    /// Create a new file exists error for an output path
+    pub fn file_exists<P: AsRef<std::path::Path>>(path: P) -> Self {
+        Self::FileExists {
+            path: path.as_ref().display().to_string(),
+        }
+    }
+
     /// Create a new invalid parameter error
     pub fn invalid_parameter<S: Into<String>, R: Into<String>>(parameter: S, reason: R) -> Self {
         Self::InvalidParameter {
@@ -144,4 +167,30 @@ pub fn xml_namespace<S: Into<String>>(message: S) -> Self {
             message: message.into(),
         }
     }
+
+    /// Name of the variant, e.g. `Validation`, for machine-readable reports
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "Io",
+            Self::Csv(_) => "Csv",
+            Self::Xml(_) => "Xml",
+            Self::Json(_) => "Json",
+            Self::Yaml(_) => "Yaml",
+            Self::TomlSerialize(_) => "TomlSerialize",
+            Self::TomlParse(_) => "TomlParse",
+            Self::VlanGeneration { .. } => "VlanGeneration",
+            Self::Validation { .. } => "Validation",
+            Self::XmlTemplate { .. } => "XmlTemplate",
+            Self::XmlEventParsing { .. } => "XmlEventParsing",
+            Self::XmlInjectionPointNotFound { .. } => "XmlInjectionPointNotFound",
+            Self::XmlSchemaValidation { .. } => "XmlSchemaValidation",
+            Self::XmlMemoryLimitExceeded { .. } => "XmlMemoryLimitExceeded",
+            Self::XmlNamespace { .. } => "XmlNamespace",
+            Self::ConfigNotFound { .. } => "ConfigNotFound",
+            Self::FileExists { .. } => "FileExists",
+            Self::InvalidParameter { .. } => "InvalidParameter",
+            Self::ResourceExhausted { .. } => "ResourceExhausted",
+            Self::Config { .. } => "Config",
+        }
+    }
 }
@@ -29,6 +29,10 @@ pub enum VlanError {
     #[error("WAN assignment {0} is outside valid range 1-3")]
     InvalidWanAssignment(u8),
 
+    /// CIDR prefix length is outside valid range (8-30)
+    #[error("Prefix length /{0} is outside valid range /8-/30")]
+    InvalidPrefixLength(u8),
+
     /// Department name is invalid or empty
     #[error("Department name is invalid: {0}")]
     InvalidDepartment(String),
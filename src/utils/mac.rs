@@ -0,0 +1,187 @@
+//! MAC address generation utilities
+//!
+//! Static reservation MACs are not drawn from the VLAN generator's RNG: the
+//! RNG is seeded per VLAN by [`mac_seed`], because configurations read back
+//! from a file have no generator state but must reproduce the same MACs.
+
+use crate::utils::seed;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Bit marking a MAC address as locally administered (first octet)
+const LOCALLY_ADMINISTERED_BIT: u8 = 0b0000_0010;
+
+/// Bit marking a MAC address as multicast (first octet)
+const MULTICAST_BIT: u8 = 0b0000_0001;
+
+/// Generate a random unicast MAC address in `aa:bb:cc:dd:ee:ff` form
+///
+/// With an `oui` the first three octets are used verbatim and only the NIC
+/// portion is random. Without one, the whole address is random with the
+/// locally-administered bit set, so it cannot clash with vendor-assigned
+/// hardware.
+pub fn generate_mac<R: Rng + ?Sized>(rng: &mut R, oui: Option<[u8; 3]>) -> String {
+    let prefix = oui.unwrap_or_else(|| {
+        let mut prefix: [u8; 3] = rng.random();
+        prefix[0] = (prefix[0] | LOCALLY_ADMINISTERED_BIT) & !MULTICAST_BIT;
+        prefix
+    });
+    let nic: [u8; 3] = rng.random();
+
+    format_mac(&[prefix[0], prefix[1], prefix[2], nic[0], nic[1], nic[2]])
+}
+
+/// Hardware vendor whose registered OUIs can prefix generated MACs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MacVendor {
+    /// Cisco Systems
+    Cisco,
+    /// Dell
+    Dell,
+    /// Apple
+    Apple,
+    /// Hewlett Packard
+    Hp,
+    /// Intel
+    Intel,
+    /// VMware virtual NICs
+    Vmware,
+}
+
+impl MacVendor {
+    /// A few of the vendor's IEEE-registered OUIs
+    pub fn ouis(self) -> &'static [[u8; 3]] {
+        match self {
+            Self::Cisco => &[[0x00, 0x00, 0x0c], [0x00, 0x1b, 0x54]],
+            Self::Dell => &[[0x00, 0x14, 0x22], [0xf8, 0xbc, 0x12]],
+            Self::Apple => &[[0x00, 0x03, 0x93], [0x3c, 0x07, 0x54]],
+            Self::Hp => &[[0x00, 0x1b, 0x78], [0x3c, 0xd9, 0x2b]],
+            Self::Intel => &[[0x00, 0x1b, 0x21], [0x3c, 0xfd, 0xfe]],
+            Self::Vmware => &[[0x00, 0x50, 0x56], [0x00, 0x0c, 0x29]],
+        }
+    }
+}
+
+/// OUIs to draw MAC prefixes from, one per generated device
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OuiPool {
+    ouis: Vec<[u8; 3]>,
+}
+
+impl OuiPool {
+    /// Pool of every OUI of the given vendors
+    pub fn from_vendors(vendors: &[MacVendor]) -> Self {
+        Self {
+            ouis: vendors
+                .iter()
+                .flat_map(|vendor| vendor.ouis().iter().copied())
+                .collect(),
+        }
+    }
+
+    /// Whether the pool has no OUIs
+    pub fn is_empty(&self) -> bool {
+        self.ouis.is_empty()
+    }
+
+    /// Pick an OUI at random; `None` for an empty pool, without consuming
+    /// randomness, so `generate_mac` falls back to a locally administered
+    /// prefix exactly as before
+    pub fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<[u8; 3]> {
+        if self.ouis.is_empty() {
+            return None;
+        }
+        Some(self.ouis[rng.random_range(0..self.ouis.len())])
+    }
+
+    /// Generate a MAC with an OUI from the pool, or a locally administered
+    /// one when the pool is empty
+    pub fn generate_mac<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let oui = self.pick(rng);
+        generate_mac(rng, oui)
+    }
+}
+
+/// Derive a stable RNG seed from a VLAN's identity
+///
+/// Uses FNV-1a so the seed is identical across builds and platforms, letting
+/// configurations loaded from CSV or YAML reproduce the same MAC addresses.
+/// The generator's `--seed` does not enter into it; VLANs with the same ID
+/// and network get the same MACs in every run.
+pub fn mac_seed(vlan_id: u16, network: &str) -> u64 {
+    seed::fnv1a(vlan_id.to_be_bytes().iter().chain(network.as_bytes()))
+}
+
+fn format_mac(octets: &[u8; 6]) -> String {
+    octets
+        .iter()
+        .map(|octet| format!("{octet:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn first_octet(mac: &str) -> u8 {
+        u8::from_str_radix(&mac[..2], 16).unwrap()
+    }
+
+    #[test]
+    fn test_generate_mac_is_locally_administered_unicast() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mac = generate_mac(&mut rng, None);
+            assert_eq!(mac.len(), 17);
+            assert_eq!(mac.split(':').count(), 6);
+            assert_ne!(first_octet(&mac) & LOCALLY_ADMINISTERED_BIT, 0);
+            assert_eq!(first_octet(&mac) & MULTICAST_BIT, 0);
+        }
+    }
+
+    #[test]
+    fn test_generate_mac_with_oui() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let mac = generate_mac(&mut rng, Some([0x00, 0x50, 0x56]));
+        assert!(mac.starts_with("00:50:56:"));
+    }
+
+    #[test]
+    fn test_generate_mac_is_reproducible() {
+        let mut rng1 = ChaCha8Rng::seed_from_u64(mac_seed(100, "10.1.2.x"));
+        let mut rng2 = ChaCha8Rng::seed_from_u64(mac_seed(100, "10.1.2.x"));
+        assert_eq!(generate_mac(&mut rng1, None), generate_mac(&mut rng2, None));
+    }
+
+    #[test]
+    fn test_oui_pool_uses_selected_vendors() {
+        let pool = OuiPool::from_vendors(&[MacVendor::Cisco, MacVendor::Apple]);
+        let prefixes: Vec<String> = [MacVendor::Cisco, MacVendor::Apple]
+            .iter()
+            .flat_map(|vendor| vendor.ouis())
+            .map(|oui| format!("{:02x}:{:02x}:{:02x}:", oui[0], oui[1], oui[2]))
+            .collect();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mac = pool.generate_mac(&mut rng);
+            assert!(
+                prefixes.iter().any(|prefix| mac.starts_with(prefix)),
+                "{mac}"
+            );
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let mac = OuiPool::default().generate_mac(&mut rng);
+        assert_ne!(first_octet(&mac) & LOCALLY_ADMINISTERED_BIT, 0);
+    }
+
+    #[test]
+    fn test_mac_seed_differs_by_vlan_id() {
+        assert_ne!(mac_seed(100, "10.1.2.x"), mac_seed(356, "10.1.2.x"));
+    }
+}
@@ -1,3 +1,8 @@
 //! Utility functions for network operations
 
+pub mod mac;
+pub mod placeholder;
+pub mod progress;
+pub mod rate;
 pub mod rfc1918;
+pub mod seed;
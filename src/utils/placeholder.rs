@@ -0,0 +1,157 @@
+//! `{name}` placeholder templates for user-supplied naming patterns
+
+use std::fmt;
+
+/// A piece of a parsed template
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// A template such as `fw-{firewall_nr}-vlan-{vlan_id}.xml`
+///
+/// Placeholders are checked against an allowed list when parsing, so
+/// rendering never fails. Write `{{` or `}}` for a literal brace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceholderTemplate {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl PlaceholderTemplate {
+    /// Parse a template, rejecting placeholders not listed in `allowed`
+    pub fn parse(template: &str, allowed: &[&str]) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(format!("Unclosed placeholder in '{template}'"));
+                            }
+                        }
+                    }
+                    if !allowed.contains(&name.as_str()) {
+                        let allowed: Vec<String> =
+                            allowed.iter().map(|name| format!("{{{name}}}")).collect();
+                        return Err(format!(
+                            "Unknown placeholder '{{{name}}}' in '{template}' (expected one of {})",
+                            allowed.join(", ")
+                        ));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(name));
+                }
+                '}' => return Err(format!("Unmatched '}}' in '{template}'")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self {
+            source: template.to_string(),
+            segments,
+        })
+    }
+
+    /// Whether the template uses the named placeholder
+    pub fn uses(&self, name: &str) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Placeholder(used) if used == name))
+    }
+
+    /// Substitute every placeholder with the value returned for its name
+    pub fn render(&self, value: impl Fn(&str) -> String) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Placeholder(name) => value(name),
+            })
+            .collect()
+    }
+
+    /// Whether `text` could have been rendered from this template
+    ///
+    /// `accept` decides whether a candidate substring is a valid value for
+    /// the named placeholder; placeholder values are never empty.
+    pub fn matches(&self, text: &str, accept: impl Fn(&str, &str) -> bool) -> bool {
+        matches_segments(&self.segments, text, &accept)
+    }
+}
+
+/// Match `text` against `segments`, trying every split for placeholders
+fn matches_segments(segments: &[Segment], text: &str, accept: &dyn Fn(&str, &str) -> bool) -> bool {
+    match segments.split_first() {
+        None => text.is_empty(),
+        Some((Segment::Literal(literal), rest)) => text
+            .strip_prefix(literal.as_str())
+            .is_some_and(|remaining| matches_segments(rest, remaining, accept)),
+        Some((Segment::Placeholder(name), rest)) => text
+            .char_indices()
+            .skip(1)
+            .map(|(end, _)| end)
+            .chain(std::iter::once(text.len()))
+            .any(|end| accept(name, &text[..end]) && matches_segments(rest, &text[end..], accept)),
+    }
+}
+
+impl fmt::Display for PlaceholderTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let template = PlaceholderTemplate::parse("{a}-{{x}}-{b}", &["a", "b"]).unwrap();
+        assert!(template.uses("a"));
+        assert!(!template.uses("c"));
+        assert_eq!(template.render(str::to_uppercase), "A-{x}-B");
+        assert_eq!(template.to_string(), "{a}-{{x}}-{b}");
+    }
+
+    #[test]
+    fn test_matches_rendered_text() {
+        let template = PlaceholderTemplate::parse("fw-{a}-{b}.xml", &["a", "b"]).unwrap();
+        let digits = |_: &str, value: &str| value.bytes().all(|b| b.is_ascii_digit());
+        assert!(template.matches("fw-1-200.xml", digits));
+        assert!(!template.matches("fw-1-.xml", digits));
+        assert!(!template.matches("fw-1-2x.xml", digits));
+        assert!(!template.matches("fw-1-200.xml.bak", digits));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_templates() {
+        let error = PlaceholderTemplate::parse("{a}-{c}", &["a", "b"]).unwrap_err();
+        assert!(error.contains("'{c}'"), "{error}");
+        assert!(error.contains("{a}, {b}"), "{error}");
+        assert!(PlaceholderTemplate::parse("{a", &["a"]).is_err());
+        assert!(PlaceholderTemplate::parse("a}", &["a"]).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Progress reporting decoupled from any particular progress bar library
+//!
+//! Generator functions report progress through [`ProgressSink`] so library
+//! users can plug in their own reporting while the CLI keeps using indicatif.
+
+use indicatif::ProgressBar;
+
+/// Receiver for progress updates from long-running generation
+pub trait ProgressSink {
+    /// Set the absolute number of completed items
+    fn set_position(&self, pos: u64);
+
+    /// Advance the number of completed items by `n`
+    fn inc(&self, n: u64);
+
+    /// Describe the item currently being worked on; ignored by default
+    fn set_message(&self, _message: &str) {}
+}
+
+impl ProgressSink for ProgressBar {
+    fn set_position(&self, pos: u64) {
+        ProgressBar::set_position(self, pos);
+    }
+
+    fn inc(&self, n: u64) {
+        ProgressBar::inc(self, n);
+    }
+
+    fn set_message(&self, message: &str) {
+        ProgressBar::set_message(self, message.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::vlan::{VlanGenerator, generate_vlan_configurations};
+    use crate::generator::{FirewallComplexity, generate_firewall_rules};
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct CountingSink {
+        positions: Cell<u64>,
+        increments: Cell<u64>,
+        last_position: Cell<u64>,
+    }
+
+    impl ProgressSink for CountingSink {
+        fn set_position(&self, pos: u64) {
+            self.positions.set(self.positions.get() + 1);
+            self.last_position.set(pos);
+        }
+
+        fn inc(&self, n: u64) {
+            self.increments.set(self.increments.get() + n);
+        }
+    }
+
+    #[test]
+    fn test_generators_report_to_custom_sink() {
+        let sink = CountingSink::default();
+        let configs = generate_vlan_configurations(5, Some(42), Some(&sink)).unwrap();
+        assert_eq!(sink.positions.get(), 5);
+        assert_eq!(sink.last_position.get(), 5);
+
+        generate_firewall_rules(
+            &configs,
+            FirewallComplexity::Basic,
+            Some(42),
+            Some(&sink),
+            None,
+        )
+        .unwrap();
+        assert_eq!(sink.increments.get(), 5);
+
+        let sink = CountingSink::default();
+        let counts = HashMap::from([("IT".to_string(), 2), ("Sales".to_string(), 3)]);
+        VlanGenerator::new_with_std_rng(Some(42))
+            .generate_by_department(counts, Some(&sink))
+            .unwrap();
+        assert_eq!(sink.positions.get(), 5);
+        assert_eq!(sink.last_position.get(), 5);
+    }
+}
@@ -0,0 +1,99 @@
+//! Throttling of generation speed for live demos
+//!
+//! Generation is usually far faster than a progress bar can show. A
+//! [`RateLimiter`] spaces out items so a run of a few hundred VLANs visibly
+//! animates; [`RateLimitedSink`] applies it at each progress update, which
+//! generator loops already make once per item.
+
+use crate::utils::progress::ProgressSink;
+use std::cell::Cell;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Paces a loop to at most a fixed number of iterations per second
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Cell<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Allow `rate` iterations per second; a rate of 0 is treated as 1
+    pub fn per_second(rate: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / rate.max(1),
+            next: Cell::new(None),
+        }
+    }
+
+    /// Sleep until the next iteration is due
+    ///
+    /// The first call returns at once; each later call returns no earlier
+    /// than one interval after the previous one.
+    pub fn wait(&self) {
+        let now = Instant::now();
+        let due = match self.next.get() {
+            Some(next) if next > now => {
+                thread::sleep(next - now);
+                next
+            }
+            _ => now,
+        };
+        self.next.set(Some(due + self.interval));
+    }
+}
+
+/// Progress sink that waits on a rate limiter before each update
+pub struct RateLimitedSink<'a, S: ProgressSink + ?Sized> {
+    inner: &'a S,
+    limiter: Option<RateLimiter>,
+}
+
+impl<'a, S: ProgressSink + ?Sized> RateLimitedSink<'a, S> {
+    /// Wrap a sink; without a limiter updates pass straight through
+    pub fn new(inner: &'a S, limiter: Option<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    fn wait(&self) {
+        if let Some(limiter) = &self.limiter {
+            limiter.wait();
+        }
+    }
+}
+
+impl<S: ProgressSink + ?Sized> ProgressSink for RateLimitedSink<'_, S> {
+    fn set_position(&self, pos: u64) {
+        self.wait();
+        self.inner.set_position(pos);
+    }
+
+    fn inc(&self, n: u64) {
+        self.wait();
+        self.inner.inc(n);
+    }
+
+    fn set_message(&self, message: &str) {
+        self.inner.set_message(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::vlan::generate_vlan_configurations;
+    use indicatif::ProgressBar;
+
+    #[test]
+    fn test_rate_limited_generation_takes_minimum_time() {
+        let pb = ProgressBar::hidden();
+        let sink = RateLimitedSink::new(&pb, Some(RateLimiter::per_second(20)));
+
+        let started = Instant::now();
+        let configs = generate_vlan_configurations(5, Some(42), Some(&sink)).unwrap();
+        assert_eq!(configs.len(), 5);
+        // Five updates at 20 per second are four 50 ms intervals apart
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(pb.position(), 5);
+    }
+}
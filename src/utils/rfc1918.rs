@@ -30,6 +30,69 @@ fn default() -> Self {
     }
 }
 
+/// RFC 1918 address block to draw generated networks from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rfc1918Class {
+    /// 10.0.0.0/8
+    ClassA,
+    /// 172.16.0.0/12
+    ClassB,
+    /// 192.168.0.0/16
+    ClassC,
+    /// Weighted mix favouring Class A, then Class B, then Class C
+    Mixed,
+}
+
+impl Rfc1918Class {
+    /// Shortest prefix length whose networks still fit inside the class block
+    pub fn min_prefix_len(self) -> u8 {
+        match self {
+            Self::ClassA => 8,
+            Self::ClassB => 12,
+            Self::ClassC | Self::Mixed => 16,
+        }
+    }
+
+    /// Class block containing a network, if it lies within one
+    ///
+    /// Never returns [`Rfc1918Class::Mixed`].
+    pub fn of_network(network: &Ipv4Network) -> Option<Self> {
+        [Self::ClassA, Self::ClassB, Self::ClassC]
+            .into_iter()
+            .find(|class| class.block().is_supernet_of(*network))
+    }
+
+    /// Address block of the class; Mixed spans the Class C block
+    fn block(self) -> Ipv4Network {
+        let (address, prefix) = match self {
+            Self::ClassA => (Ipv4Addr::new(10, 0, 0, 0), 8),
+            Self::ClassB => (Ipv4Addr::new(172, 16, 0, 0), 12),
+            Self::ClassC | Self::Mixed => (Ipv4Addr::new(192, 168, 0, 0), 16),
+        };
+        Ipv4Network::new(address, prefix).expect("RFC 1918 blocks are valid networks")
+    }
+
+    /// Generate a random /24 network from this class
+    pub fn generate_network<R: rand::Rng>(self, rng: &mut R) -> Ipv4Network {
+        match self {
+            Self::ClassA => generate_random_class_a_network(rng),
+            Self::ClassB => generate_random_class_b_network(rng),
+            Self::ClassC => generate_random_class_c_network(rng),
+            Self::Mixed => {
+                // Prefer Class A networks for larger address space
+                if rng.random_bool(0.8) {
+                    generate_random_class_a_network(rng)
+                } else if rng.random_bool(0.6) {
+                    generate_random_class_b_network(rng)
+                } else {
+                    generate_random_class_c_network(rng)
+                }
+            }
+        }
+    }
+}
+
 /// Check if an IPv4 address is within RFC 1918 private address space
 pub fn is_rfc1918_addr(addr: Ipv4Addr) -> bool {
     let ranges = Rfc1918Ranges::default();
@@ -126,6 +189,30 @@ mod tests {
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
+    #[test]
+    fn test_rfc1918_class_generate_network() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..50 {
+            let network = Rfc1918Class::ClassB.generate_network(&mut rng);
+            let octets = network.network().octets();
+            assert_eq!(octets[0], 172);
+            assert!((16..=31).contains(&octets[1]));
+
+            let network = Rfc1918Class::Mixed.generate_network(&mut rng);
+            assert!(is_rfc1918_network(&network));
+        }
+    }
+
+    #[test]
+    fn test_rfc1918_class_of_network() {
+        let class_of = |network: &str| Rfc1918Class::of_network(&network.parse().unwrap());
+        assert_eq!(class_of("10.20.30.0/24"), Some(Rfc1918Class::ClassA));
+        assert_eq!(class_of("172.31.0.0/16"), Some(Rfc1918Class::ClassB));
+        assert_eq!(class_of("192.168.7.64/26"), Some(Rfc1918Class::ClassC));
+        assert_eq!(class_of("172.32.0.0/24"), None);
+        assert_eq!(class_of("8.8.8.0/24"), None);
+    }
+
     #[test]
     fn test_rfc1918_addr_validation() {
         // Valid RFC 1918 addresses
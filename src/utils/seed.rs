@@ -0,0 +1,109 @@
+//! Stable seed derivation for reproducible generation
+
+/// FNV-1a 64-bit offset basis
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a 64-bit prime
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hash bytes with 64-bit FNV-1a
+///
+/// Unlike `std::hash`, the result is identical across builds, platforms and
+/// Rust versions, so it is safe to derive persisted seeds from.
+pub fn fnv1a<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u64 {
+    bytes.into_iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Derive an RNG seed from a human-readable label such as "staging"
+///
+/// The same label always yields the same seed, so `--seed-label ci-run-7`
+/// reproduces its data without having to remember a number.
+pub fn seed_from_label(label: &str) -> u64 {
+    fnv1a(label.as_bytes())
+}
+
+/// Pick a fresh seed from the thread-local RNG for runs without `--seed`
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+/// Build a version 4 UUID from the given RNG's bytes
+///
+/// With a seeded RNG the same sequence of UUIDs comes out on every run, which
+/// `Uuid::new_v4` cannot offer.
+pub fn random_uuid<R: rand::Rng + ?Sized>(rng: &mut R) -> uuid::Uuid {
+    uuid::Builder::from_random_bytes(rng.random()).into_uuid()
+}
+
+/// First group of a [`random_uuid`], eight hex digits for name suffixes
+pub fn random_short_id<R: rand::Rng + ?Sized>(rng: &mut R) -> String {
+    let mut id = random_uuid(rng).simple().to_string();
+    id.truncate(8);
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_known_vectors() {
+        assert_eq!(fnv1a(b""), FNV_OFFSET_BASIS);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_seed_from_label() {
+        assert_eq!(seed_from_label("staging"), seed_from_label("staging"));
+        assert_ne!(seed_from_label("staging"), seed_from_label("ci-run-7"));
+    }
+
+    #[test]
+    fn test_random_uuid_is_v4_and_seeded() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let first = random_uuid(&mut StdRng::seed_from_u64(42));
+        assert_eq!(first.get_version_num(), 4);
+        assert_eq!(first.get_variant(), uuid::Variant::RFC4122);
+        assert_eq!(first, random_uuid(&mut StdRng::seed_from_u64(42)));
+        assert_ne!(first, random_uuid(&mut StdRng::seed_from_u64(43)));
+
+        let short = random_short_id(&mut StdRng::seed_from_u64(42));
+        assert_eq!(short, first.to_string().split('-').next().unwrap());
+    }
+
+    #[test]
+    fn test_seeded_generators_have_stable_ids() {
+        use crate::generator::nat::NatGenerator;
+        use crate::generator::vpn::VpnGenerator;
+        use std::collections::HashSet;
+
+        let nat_ids = |seed| {
+            NatGenerator::new_with_seed(Some(seed))
+                .generate_batch(10)
+                .unwrap()
+                .into_iter()
+                .map(|mapping| mapping.id)
+                .collect::<Vec<_>>()
+        };
+        let vpn_ids = |seed| {
+            VpnGenerator::new_with_seed(Some(seed))
+                .generate_batch(10)
+                .unwrap()
+                .into_iter()
+                .map(|config| config.id)
+                .collect::<Vec<_>>()
+        };
+
+        for ids in [nat_ids, vpn_ids] {
+            let first = ids(42);
+            assert_eq!(first, ids(42));
+            assert_ne!(first, ids(43));
+            assert_eq!(first.iter().collect::<HashSet<_>>().len(), first.len());
+        }
+    }
+}
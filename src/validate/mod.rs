@@ -2,13 +2,57 @@
 
 use crate::Result;
 use crate::generator::VlanConfig;
+use crate::generator::vlan::{DEFAULT_MAX_DESCRIPTION_LEN, PREFIX_LEN_RANGE};
 use crate::model::ConfigError;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Kind of value shared between two configuration sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both sets use the same VLAN ID
+    VlanId,
+    /// The sets contain overlapping IP networks
+    Network,
+}
+
+/// A VLAN ID or network collision between two configuration sets
+///
+/// Indices are zero-based positions within each input slice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossConflict {
+    /// What collided
+    pub kind: ConflictKind,
+    /// Value from the first set (VLAN ID or network)
+    pub value: String,
+    /// Value from the second set (equal to `value` for VLAN IDs)
+    pub other_value: String,
+    /// Index of the configuration in the first set
+    pub index: usize,
+    /// Index of the configuration in the second set
+    pub other_index: usize,
+}
+
+impl fmt::Display for CrossConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ConflictKind::VlanId => write!(f, "VLAN ID {} is used by both files", self.value),
+            ConflictKind::Network if self.value == self.other_value => {
+                write!(f, "IP network {} is used by both files", self.value)
+            }
+            ConflictKind::Network => {
+                write!(f, "IP network {} overlaps {}", self.value, self.other_value)
+            }
+        }
+    }
+}
 
 /// Validation engine for cross-component consistency
 pub struct ValidationEngine {
-    unique_vlan_ids: HashSet<u16>,
+    /// (outer, inner) tag pairs; the outer tag is `None` for single-tagged VLANs
+    unique_vlan_ids: HashSet<(Option<u16>, u16)>,
     unique_networks: HashSet<String>,
+    max_description_len: usize,
 }
 
 impl ValidationEngine {
@@ -17,17 +61,31 @@ pub fn new() -> Self {
         Self {
             unique_vlan_ids: HashSet::new(),
             unique_networks: HashSet::new(),
+            max_description_len: DEFAULT_MAX_DESCRIPTION_LEN,
         }
     }
 
+    /// Accept descriptions up to `max_len` characters instead of the default
+    pub fn with_max_description_len(mut self, max_len: usize) -> Self {
+        self.max_description_len = max_len;
+        self
+    }
+
     /// Validate a single VLAN configuration
     pub fn validate_config(&mut self, config: &VlanConfig) -> Result<()> {
-        // Check VLAN ID uniqueness
-        if !self.unique_vlan_ids.insert(config.vlan_id) {
-            return Err(ConfigError::validation(format!(
-                "Duplicate VLAN ID: {}",
-                config.vlan_id
-            )));
+        // Check VLAN ID uniqueness; QinQ VLANs may reuse an inner tag under
+        // a different service tag
+        if !self
+            .unique_vlan_ids
+            .insert((config.outer_vlan_id, config.vlan_id))
+        {
+            return Err(ConfigError::validation(match config.outer_vlan_id {
+                Some(outer) => format!(
+                    "Duplicate QinQ tag pair: outer {outer}, inner {}",
+                    config.vlan_id
+                ),
+                None => format!("Duplicate VLAN ID: {}", config.vlan_id),
+            }));
         }
 
         // Check network uniqueness
@@ -46,6 +104,11 @@ pub fn validate_config(&mut self, config: &VlanConfig) -> Result<()> {
             )));
         }
 
+        // Validate QinQ service tag range
+        if let Some(outer) = config.outer_vlan_id {
+            VlanConfig::validate_outer_vlan_id(outer)?;
+        }
+
         // Validate WAN assignment
         if !(1..=3).contains(&config.wan_assignment) {
             return Err(ConfigError::validation(format!(
@@ -54,20 +117,153 @@ pub fn validate_config(&mut self, config: &VlanConfig) -> Result<()> {
             )));
         }
 
-        // Validate IP network format
-        self.validate_ip_network(&config.ip_network)?;
+        VlanConfig::validate_description(&config.description, self.max_description_len)?;
+
+        // Validate prefix length
+        if !PREFIX_LEN_RANGE.contains(&config.prefix_len) {
+            return Err(ConfigError::validation(format!(
+                "Prefix length /{} is outside valid range /8-/30",
+                config.prefix_len
+            )));
+        }
+
+        // Validate IP network format; non-/24 networks are checked as CIDR
+        if config.prefix_len == 24 {
+            self.validate_ip_network(&config.ip_network)?;
+        } else {
+            config
+                .validate_rfc1918()
+                .map_err(|e| ConfigError::validation(e.to_string()))?;
+        }
 
         Ok(())
     }
 
     /// Validate multiple configurations
+    ///
+    /// Each configuration is checked individually, then the set is checked
+    /// for overlapping networks.
     pub fn validate_configs(&mut self, configs: &[VlanConfig]) -> Result<()> {
         for config in configs {
             self.validate_config(config)?;
         }
+        Self::validate_no_overlaps(configs)
+    }
+
+    /// Check every configuration and collect all problems instead of
+    /// stopping at the first
+    ///
+    /// Runs the same checks as `validate_configs`, so an empty result means
+    /// `validate_configs` would succeed on a fresh engine.
+    pub fn collect_issues(&mut self, configs: &[VlanConfig]) -> Vec<ConfigError> {
+        let mut issues: Vec<ConfigError> = configs
+            .iter()
+            .filter_map(|config| self.validate_config(config).err())
+            .collect();
+        issues.extend(Self::validate_no_overlaps(configs).err());
+        issues
+    }
+
+    /// Reject configurations whose networks share any addresses
+    ///
+    /// Catches nesting such as `10.1.0.0/16` containing `10.1.2.x`, and the
+    /// same network written in different forms, which exact string
+    /// comparison misses. Networks that cannot be parsed are left to
+    /// `validate_config`.
+    pub fn validate_no_overlaps(configs: &[VlanConfig]) -> Result<()> {
+        let mut networks: Vec<_> = configs
+            .iter()
+            .filter_map(|config| {
+                let network = config.as_ipv4_network().ok()?;
+                Some((
+                    u32::from(network.network()),
+                    u32::from(network.broadcast()),
+                    config,
+                ))
+            })
+            .collect();
+        networks.sort_unstable_by_key(|&(start, end, _)| (start, std::cmp::Reverse(end)));
+
+        // Sorted by start address, a network overlaps an earlier one exactly
+        // when it starts before the furthest end seen so far
+        let mut widest: Option<(u32, &VlanConfig)> = None;
+        for (start, end, config) in networks {
+            match widest {
+                Some((widest_end, widest_config)) if start <= widest_end => {
+                    return Err(ConfigError::validation(format!(
+                        "IP network {} (VLAN {}) overlaps {} (VLAN {})",
+                        config.ip_network,
+                        config.vlan_id,
+                        widest_config.ip_network,
+                        widest_config.vlan_id
+                    )));
+                }
+                Some((widest_end, _)) if widest_end >= end => {}
+                _ => widest = Some((end, config)),
+            }
+        }
+
         Ok(())
     }
 
+    /// Find VLAN IDs and networks shared between two configuration sets
+    ///
+    /// Networks conflict when their address ranges overlap, so `10.1.2.x`
+    /// collides with `10.1.0.0/16`. Networks that cannot be parsed are
+    /// compared as strings.
+    pub fn find_cross_conflicts(
+        configs: &[VlanConfig],
+        other: &[VlanConfig],
+    ) -> Vec<CrossConflict> {
+        let mut conflicts = Vec::new();
+
+        let mut other_ids: HashMap<u16, usize> = HashMap::with_capacity(other.len());
+        for (other_index, config) in other.iter().enumerate() {
+            other_ids.entry(config.vlan_id).or_insert(other_index);
+        }
+        let other_networks: Vec<_> = other
+            .iter()
+            .map(|config| config.as_ipv4_network().ok())
+            .collect();
+
+        for (index, config) in configs.iter().enumerate() {
+            if let Some(&other_index) = other_ids.get(&config.vlan_id) {
+                conflicts.push(CrossConflict {
+                    kind: ConflictKind::VlanId,
+                    value: config.vlan_id.to_string(),
+                    other_value: config.vlan_id.to_string(),
+                    index,
+                    other_index,
+                });
+            }
+
+            let network = config.as_ipv4_network().ok();
+            let overlapping =
+                other
+                    .iter()
+                    .zip(&other_networks)
+                    .position(
+                        |(other_config, other_network)| match (network, other_network) {
+                            (Some(network), Some(other_network)) => {
+                                network.overlaps(*other_network)
+                            }
+                            _ => config.ip_network == other_config.ip_network,
+                        },
+                    );
+            if let Some(other_index) = overlapping {
+                conflicts.push(CrossConflict {
+                    kind: ConflictKind::Network,
+                    value: config.ip_network.clone(),
+                    other_value: other[other_index].ip_network.clone(),
+                    index,
+                    other_index,
+                });
+            }
+        }
+
+        conflicts
+    }
+
     /// Validate IP network format and RFC 1918 compliance
     fn validate_ip_network(&self, network: &str) -> Result<()> {
         // Check for expected format patterns
@@ -163,6 +359,7 @@ fn default() -> Self {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::VlanGenerator;
 
     #[test]
     fn test_validation_engine() {
@@ -178,6 +375,18 @@ fn test_validation_engine() {
         assert_eq!(engine.config_count(), 2);
     }
 
+    #[test]
+    fn test_max_description_len() {
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "x".repeat(80), 1).unwrap();
+        assert!(ValidationEngine::new().validate_config(&config).is_err());
+        assert!(
+            ValidationEngine::new()
+                .with_max_description_len(80)
+                .validate_config(&config)
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_duplicate_vlan_id() {
         let mut engine = ValidationEngine::new();
@@ -191,6 +400,44 @@ fn test_duplicate_vlan_id() {
         assert!(engine.validate_config(&config2).is_err());
     }
 
+    #[test]
+    fn test_qinq_tag_pair_uniqueness() {
+        let mut engine = ValidationEngine::new();
+        let stacked = |outer: u16, inner: u16, network: &str| {
+            VlanConfig::new(inner, network.to_string(), "Test".to_string(), 1)
+                .unwrap()
+                .with_outer_vlan_id(outer)
+                .unwrap()
+        };
+
+        // The same inner tag is allowed untagged and under different outer tags
+        let plain = VlanConfig::new(100, "10.1.1.x".to_string(), "Test".to_string(), 1).unwrap();
+        assert!(engine.validate_config(&plain).is_ok());
+        assert!(
+            engine
+                .validate_config(&stacked(200, 100, "10.1.2.x"))
+                .is_ok()
+        );
+        assert!(
+            engine
+                .validate_config(&stacked(300, 100, "10.1.3.x"))
+                .is_ok()
+        );
+
+        let error = engine
+            .validate_config(&stacked(200, 100, "10.1.4.x"))
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Duplicate QinQ tag pair: outer 200, inner 100")
+        );
+
+        let mut out_of_range = stacked(200, 101, "10.1.5.x");
+        out_of_range.outer_vlan_id = Some(5);
+        assert!(engine.validate_config(&out_of_range).is_err());
+    }
+
     #[test]
     fn test_duplicate_network() {
         let mut engine = ValidationEngine::new();
@@ -204,6 +451,74 @@ fn test_duplicate_network() {
         assert!(engine.validate_config(&config2).is_err());
     }
 
+    #[test]
+    fn test_validate_no_overlaps_rejects_nested_networks() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Test 1".to_string(), 1).unwrap(),
+            VlanConfig::new_with_prefix(
+                200,
+                "10.1.0.0/16".to_string(),
+                "Test 2".to_string(),
+                2,
+                16,
+            )
+            .unwrap(),
+        ];
+
+        let error = ValidationEngine::validate_no_overlaps(&configs).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("IP network 10.1.2.x (VLAN 100) overlaps 10.1.0.0/16 (VLAN 200)")
+        );
+        assert!(ValidationEngine::new().validate_configs(&configs).is_err());
+    }
+
+    #[test]
+    fn test_validate_no_overlaps_same_network_in_different_forms() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Test 1".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.1.2.0/24".to_string(), "Test 2".to_string(), 2).unwrap(),
+        ];
+        assert!(ValidationEngine::validate_no_overlaps(&configs).is_err());
+    }
+
+    #[test]
+    fn test_collect_issues_reports_each_problem() {
+        let mut configs = VlanGenerator::new(Some(42)).generate_batch(5).unwrap();
+        assert!(ValidationEngine::new().collect_issues(&configs).is_empty());
+
+        configs.push(configs[0].clone());
+        let issues = ValidationEngine::new().collect_issues(&configs);
+        // The duplicate repeats both the VLAN ID and the network
+        assert_eq!(issues.len(), 2, "{issues:?}");
+    }
+
+    #[test]
+    fn test_validate_no_overlaps_accepts_disjoint_networks() {
+        let configs = vec![
+            VlanConfig::new_with_prefix(
+                100,
+                "10.1.2.0/26".to_string(),
+                "Test 1".to_string(),
+                1,
+                26,
+            )
+            .unwrap(),
+            VlanConfig::new_with_prefix(
+                200,
+                "10.1.2.64/26".to_string(),
+                "Test 2".to_string(),
+                2,
+                26,
+            )
+            .unwrap(),
+            VlanConfig::new(300, "10.1.3.x".to_string(), "Test 3".to_string(), 3).unwrap(),
+        ];
+        assert!(ValidationEngine::validate_no_overlaps(&configs).is_ok());
+        assert!(ValidationEngine::new().validate_configs(&configs).is_ok());
+    }
+
     #[test]
     fn test_rfc1918_validation() {
         let engine = ValidationEngine::new();
@@ -221,4 +536,51 @@ fn test_rfc1918_validation() {
         assert!(engine.validate_network_prefix("192.167.1").is_err());
         assert!(engine.validate_network_prefix("10.0.0").is_err()); // Reserved
     }
+
+    #[test]
+    fn test_find_cross_conflicts() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Test 1".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Test 2".to_string(), 2).unwrap(),
+            VlanConfig::new(300, "10.5.6.x".to_string(), "Test 3".to_string(), 3).unwrap(),
+        ];
+        let other = vec![
+            VlanConfig::new(400, "10.9.9.x".to_string(), "Other 1".to_string(), 1).unwrap(),
+            VlanConfig::new(100, "10.7.8.x".to_string(), "Other 2".to_string(), 1).unwrap(),
+            VlanConfig::new_with_prefix(
+                500,
+                "10.3.0.0/16".to_string(),
+                "Other 3".to_string(),
+                1,
+                16,
+            )
+            .unwrap(),
+        ];
+
+        let conflicts = ValidationEngine::find_cross_conflicts(&configs, &other);
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(conflicts[0].kind, ConflictKind::VlanId);
+        assert_eq!((conflicts[0].index, conflicts[0].other_index), (0, 1));
+        assert_eq!(
+            conflicts[0].to_string(),
+            "VLAN ID 100 is used by both files"
+        );
+
+        assert_eq!(conflicts[1].kind, ConflictKind::Network);
+        assert_eq!((conflicts[1].index, conflicts[1].other_index), (1, 2));
+        assert_eq!(
+            conflicts[1].to_string(),
+            "IP network 10.3.4.x overlaps 10.3.0.0/16"
+        );
+    }
+
+    #[test]
+    fn test_find_cross_conflicts_none() {
+        let configs =
+            vec![VlanConfig::new(100, "10.1.2.x".to_string(), "Test 1".to_string(), 1).unwrap()];
+        let other =
+            vec![VlanConfig::new(200, "10.3.4.x".to_string(), "Test 2".to_string(), 2).unwrap()];
+        assert!(ValidationEngine::find_cross_conflicts(&configs, &other).is_empty());
+    }
 }
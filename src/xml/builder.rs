@@ -1,18 +1,31 @@
 //! OPNsense configuration builder for complete XML generation
 
+use crate::generator::{Alias, DhcpOptions, FirewallRule, NatMapping, VlanConfig};
 use crate::xml::engine::{XMLEngine, XMLTemplate};
 use crate::xml::error::{XMLError, XMLResult};
-use crate::xml::generator::XMLGenerator;
+use crate::xml::generator::{
+    AliasGenerator, DhcpGenerator, FilterRuleGenerator, NatRuleGenerator, VlanGenerator,
+    XMLGenerator,
+};
 use crate::xml::injection::XMLInjector;
+use crate::xml::registry::{Component, ComponentGenerator, ComponentRegistry, RegisteredComponent};
+use crate::xml::schema::SchemaVersion;
 use quick_xml::events::Event;
 use std::io::Write;
 use std::path::PathBuf;
 
+/// Physical NICs VLANs are spread across unless the builder is told otherwise
+pub const DEFAULT_PARENT_INTERFACES: &[&str] = &["em0", "em1", "em2", "em3", "igb0", "igb1"];
+
 /// Configuration builder for OPNsense XML generation
 pub struct OPNsenseConfigBuilder {
     template_path: Option<PathBuf>,
     template_content: Option<String>,
     components: Vec<Box<dyn XMLGenerator>>,
+    registry: ComponentRegistry,
+    parent_interfaces: Vec<String>,
+    schema_version: SchemaVersion,
+    dhcp_options: DhcpOptions,
     validation_rules: Vec<ValidationRule>,
     output_config: OutputConfig,
     xml_engine: XMLEngine,
@@ -46,6 +59,13 @@ fn default() -> Self {
     }
 }
 
+fn default_parent_interfaces() -> Vec<String> {
+    DEFAULT_PARENT_INTERFACES
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
 impl OPNsenseConfigBuilder {
     /// Create a new OPNsense configuration builder
     pub fn new() -> Self {
@@ -53,6 +73,10 @@ pub fn new() -> Self {
             template_path: None,
             template_content: None,
             components: Vec::new(),
+            registry: ComponentRegistry::new(),
+            parent_interfaces: default_parent_interfaces(),
+            schema_version: SchemaVersion::default(),
+            dhcp_options: DhcpOptions::default(),
             validation_rules: Vec::new(),
             output_config: OutputConfig::default(),
             xml_engine: XMLEngine::new(),
@@ -65,6 +89,10 @@ pub fn with_template_file(template_path: PathBuf) -> Self {
             template_path: Some(template_path),
             template_content: None,
             components: Vec::new(),
+            registry: ComponentRegistry::new(),
+            parent_interfaces: default_parent_interfaces(),
+            schema_version: SchemaVersion::default(),
+            dhcp_options: DhcpOptions::default(),
             validation_rules: Vec::new(),
             output_config: OutputConfig::default(),
             xml_engine: XMLEngine::new(),
@@ -77,6 +105,10 @@ pub fn with_template_content(content: String) -> Self {
             template_path: None,
             template_content: Some(content),
             components: Vec::new(),
+            registry: ComponentRegistry::new(),
+            parent_interfaces: default_parent_interfaces(),
+            schema_version: SchemaVersion::default(),
+            dhcp_options: DhcpOptions::default(),
             validation_rules: Vec::new(),
             output_config: OutputConfig::default(),
             xml_engine: XMLEngine::new(),
@@ -95,6 +127,95 @@ pub fn add_components(mut self, components: Vec<Box<dyn XMLGenerator>>) -> Self
         self
     }
 
+    /// Replace the per-VLAN components that later `add_vlans` calls emit
+    ///
+    /// Defaults to [`ComponentRegistry::new`], which holds only the built-in
+    /// `vlan` component.
+    pub fn with_registry(mut self, registry: ComponentRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Register a per-VLAN component for later `add_vlans` calls
+    ///
+    /// A component named like an existing one (e.g. `vlan`) replaces it.
+    pub fn register_component<G: ComponentGenerator + 'static>(mut self, generator: G) -> Self {
+        self.registry.register(generator);
+        self
+    }
+
+    /// Set the parent NICs that later `add_vlans` calls assign round-robin
+    ///
+    /// Defaults to [`DEFAULT_PARENT_INTERFACES`]; an empty list leaves VLANs
+    /// without a parent.
+    pub fn with_parent_interfaces(mut self, interfaces: Vec<String>) -> Self {
+        self.parent_interfaces = interfaces;
+        self
+    }
+
+    /// Target a specific OPNsense release in later `add_vlans` calls
+    ///
+    /// Defaults to the latest supported release.
+    pub fn with_schema_version(mut self, version: SchemaVersion) -> Self {
+        self.schema_version = version;
+        self
+    }
+
+    /// Set the DHCP DNS servers and reservation MAC vendors used by later
+    /// `add_vlans` and `add_vlan_dhcp_servers` calls
+    pub fn with_dhcp_options(mut self, options: DhcpOptions) -> Self {
+        self.dhcp_options = options;
+        self
+    }
+
+    /// Add each registered component for each VLAN
+    ///
+    /// By default that is a `<vlan>` entry injected under the template's
+    /// `<vlans>`. VLANs are tagged on the parent interfaces in turn: the
+    /// first VLAN on the first parent, the second on the second, wrapping
+    /// around. Components added with [`Self::register_component`] follow in
+    /// registration order.
+    pub fn add_vlans(mut self, configs: &[VlanConfig]) -> Self {
+        let mut parents = self.parent_interfaces.iter().cycle();
+        for config in configs {
+            let parent = parents.next();
+            for component in self.registry.components() {
+                let generator: Box<dyn XMLGenerator> = match component {
+                    Component::BuiltinVlan => {
+                        let mut generator = VlanGenerator::new(config.clone())
+                            .with_injection_target("vlans")
+                            .with_schema_version(self.schema_version)
+                            .with_dhcp_options(self.dhcp_options.clone());
+                        if let Some(parent) = parent {
+                            generator = generator.with_parent_interface(parent.clone());
+                        }
+                        Box::new(generator)
+                    }
+                    Component::Custom(generator) => {
+                        Box::new(RegisteredComponent::new(generator.clone(), config.clone()))
+                    }
+                };
+                self.components.push(generator);
+            }
+        }
+        self
+    }
+
+    /// Add a DHCP server for each VLAN, injected under the template's `<dhcpd>`
+    ///
+    /// VLANs are served on `opt{first_opt}`, `opt{first_opt + 1}`, ... in order,
+    /// matching the interface numbering used for the VLAN interfaces.
+    pub fn add_vlan_dhcp_servers(mut self, configs: &[VlanConfig], first_opt: u16) -> Self {
+        for (offset, config) in (0u16..).zip(configs) {
+            let interface = format!("opt{}", first_opt + offset);
+            self.components.push(Box::new(
+                DhcpGenerator::new(config.clone(), interface)
+                    .with_dhcp_options(self.dhcp_options.clone()),
+            ));
+        }
+        self
+    }
+
     /// Set output configuration
     pub fn with_output_config(mut self, config: OutputConfig) -> Self {
         let memory_limit = config.memory_limit_mb;
@@ -145,6 +266,32 @@ pub fn build(mut self) -> XMLResult<String> {
         Ok(result)
     }
 
+    /// Build one OPNsense document holding VLANs, firewall rules, NAT and aliases
+    ///
+    /// VLANs (with their DHCP settings) go under `<vlans>`, rules under
+    /// `<filter>`, NAT entries under `<nat>` and aliases under
+    /// `<OPNsense><Firewall><Alias>`. Empty inputs add nothing, so a template
+    /// only needs the sections that are actually filled.
+    pub fn build_complete(
+        self,
+        vlans: &[VlanConfig],
+        rules: &[FirewallRule],
+        nat: &[NatMapping],
+        aliases: &[Alias],
+    ) -> XMLResult<String> {
+        let mut builder = self.add_vlans(vlans);
+        if !rules.is_empty() {
+            builder = builder.add_component(FilterRuleGenerator::new(rules.to_vec()));
+        }
+        if !nat.is_empty() {
+            builder = builder.add_component(NatRuleGenerator::new(nat.to_vec()));
+        }
+        if !aliases.is_empty() {
+            builder = builder.add_component(AliasGenerator::new(aliases.to_vec()));
+        }
+        builder.build()
+    }
+
     /// Build with streaming output for large configurations
     pub fn build_streaming<W: Write>(mut self, writer: W) -> XMLResult<()> {
         // Load template
@@ -284,6 +431,9 @@ fn default_opnsense_template(&self) -> String {
     <vlans>
         <!-- VLAN configurations will be injected here -->
     </vlans>
+    <bridges>
+        <!-- Bridge interfaces will be injected here -->
+    </bridges>
     <dhcpd>
         <lan>
             <enable>1</enable>
@@ -307,6 +457,22 @@ fn default_opnsense_template(&self) -> String {
     <nat>
         <!-- NAT rules will be injected here -->
     </nat>
+    <OPNsense>
+        <Firewall>
+            <Alias version="1.0.0">
+                <!-- Firewall aliases will be injected here -->
+            </Alias>
+        </Firewall>
+        <captiveportal version="1.0.0">
+            <!-- Captive portal zones will be injected here -->
+        </captiveportal>
+        <TrafficShaper version="1.0.3">
+            <!-- Traffic shaper pipes and queues will be injected here -->
+        </TrafficShaper>
+        <Syslog version="1.0.1">
+            <!-- Remote syslog destinations will be injected here -->
+        </Syslog>
+    </OPNsense>
 </opnsense>"#.to_string()
     }
 
@@ -332,7 +498,6 @@ fn default() -> Self {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::generator::VlanConfig;
     use crate::xml::generator::VlanGenerator;
 
     #[test]
@@ -389,6 +554,514 @@ fn test_build_with_default_template() {
         assert!(!xml.is_empty());
     }
 
+    #[test]
+    fn test_build_injects_vlan_dhcp_server() {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        let reservations = config.static_reservations(&[]).unwrap();
+
+        let xml = OPNsenseConfigBuilder::new()
+            .add_vlan_dhcp_servers(std::slice::from_ref(&config), 6)
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let dhcpd = doc
+            .descendants()
+            .find(|node| node.has_tag_name("dhcpd"))
+            .expect("dhcpd element");
+        fn child<'a, 'input>(
+            node: roxmltree::Node<'a, 'input>,
+            name: &str,
+        ) -> roxmltree::Node<'a, 'input> {
+            node.children()
+                .find(|c| c.has_tag_name(name))
+                .unwrap_or_else(|| panic!("missing <{name}>"))
+        }
+
+        // Template's own LAN DHCP server is preserved
+        let lan = child(dhcpd, "lan");
+        assert_eq!(
+            child(child(lan, "range"), "from").text(),
+            Some("192.168.1.100")
+        );
+
+        let opt = child(dhcpd, "opt6");
+        let range = child(opt, "range");
+        assert_eq!(child(range, "from").text(), Some("10.1.2.100"));
+        assert_eq!(child(range, "to").text(), Some("10.1.2.200"));
+        assert_eq!(child(opt, "domain").text(), Some("it.company.local"));
+
+        let staticmaps: Vec<_> = opt
+            .children()
+            .filter(|c| c.has_tag_name("staticmap"))
+            .collect();
+        assert_eq!(staticmaps.len(), reservations.len());
+        for (map, reservation) in staticmaps.iter().zip(&reservations) {
+            assert_eq!(child(*map, "mac").text(), Some(reservation.mac.as_str()));
+            assert_eq!(
+                child(*map, "ipaddr").text(),
+                Some(reservation.ip_addr.as_str())
+            );
+        }
+        assert!(xml.contains("<ipaddr>10.1.2.10</ipaddr>"));
+    }
+
+    #[test]
+    fn test_build_injects_vlans_into_vlans_section() {
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "HR VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let xml = OPNsenseConfigBuilder::new()
+            .add_vlans(&configs)
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let vlan_ids: Vec<_> = doc
+            .descendants()
+            .filter(|node| node.has_tag_name("vlan"))
+            .inspect(|vlan| assert!(vlan.parent().unwrap().has_tag_name("vlans")))
+            .filter_map(|vlan| vlan.children().find(|c| c.has_tag_name("vlanid")))
+            .filter_map(|id| id.text())
+            .collect();
+        assert_eq!(vlan_ids, ["100", "200"]);
+    }
+
+    #[test]
+    fn test_build_emits_registered_components() {
+        struct SnmpComponent;
+
+        impl ComponentGenerator for SnmpComponent {
+            fn component_type(&self) -> &str {
+                "snmp"
+            }
+
+            fn generate_xml(&self, config: &VlanConfig) -> XMLResult<String> {
+                Ok(format!(
+                    "<snmp><community>vlan{}</community></snmp>",
+                    config.vlan_id
+                ))
+            }
+        }
+
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "HR VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let xml = OPNsenseConfigBuilder::new()
+            .register_component(SnmpComponent)
+            .add_vlans(&configs)
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let root = doc.root_element();
+        let communities: Vec<_> = root
+            .children()
+            .filter(|node| node.has_tag_name("snmp"))
+            .filter_map(|snmp| snmp.first_element_child()?.text())
+            .collect();
+        assert_eq!(communities, ["vlan100", "vlan200"]);
+        let vlans = doc
+            .descendants()
+            .filter(|node| node.has_tag_name("vlan"))
+            .count();
+        assert_eq!(vlans, 2);
+
+        // A registry without the built-in component replaces the <vlan> entries
+        let mut registry = ComponentRegistry::empty();
+        registry.register(SnmpComponent);
+        let xml = OPNsenseConfigBuilder::new()
+            .with_registry(registry)
+            .add_vlans(&configs)
+            .build()
+            .unwrap();
+        assert!(!xml.contains("<vlan>"), "{xml}");
+        assert!(xml.contains("<community>vlan200</community>"), "{xml}");
+    }
+
+    #[test]
+    fn test_add_vlans_cycles_parent_interfaces() {
+        let configs: Vec<_> = (0..5u16)
+            .map(|i| {
+                VlanConfig::new(100 + i, format!("10.1.{i}.x"), format!("VLAN {i}"), 1).unwrap()
+            })
+            .collect();
+
+        let xml = OPNsenseConfigBuilder::new()
+            .with_parent_interfaces(vec!["em0".to_string(), "igb1".to_string()])
+            .add_vlans(&configs)
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let vlans: Vec<_> = doc
+            .descendants()
+            .filter(|node| node.has_tag_name("vlan"))
+            .collect();
+        let child = |node: roxmltree::Node, name: &str| {
+            node.children()
+                .find(|c| c.has_tag_name(name))
+                .and_then(|c| c.text())
+                .map(str::to_string)
+        };
+        let parents: Vec<_> = vlans.iter().filter_map(|v| child(*v, "if")).collect();
+        assert_eq!(parents, ["em0", "igb1", "em0", "igb1", "em0"]);
+        assert_eq!(child(vlans[1], "tag").as_deref(), Some("101"));
+        assert_eq!(child(vlans[1], "pcp").as_deref(), Some("0"));
+        assert_eq!(child(vlans[1], "vlanif").as_deref(), Some("vlan101"));
+
+        let xml = OPNsenseConfigBuilder::new()
+            .with_parent_interfaces(Vec::new())
+            .add_vlans(&configs)
+            .build()
+            .unwrap();
+        assert!(!xml.contains("<vlanif>"));
+    }
+
+    #[test]
+    fn test_add_vlans_uses_schema_version_element_names() {
+        let configs =
+            vec![VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap()];
+        let build = |version| {
+            OPNsenseConfigBuilder::new()
+                .with_schema_version(version)
+                .add_vlans(&configs)
+                .build()
+                .unwrap()
+        };
+
+        let legacy = build(SchemaVersion::V23_7);
+        assert!(legacy.contains("<priority>0</priority>"), "{legacy}");
+        assert!(!legacy.contains("<pcp>"));
+
+        let current = build(SchemaVersion::V24_1);
+        assert!(current.contains("<pcp>0</pcp>"), "{current}");
+        assert!(!current.contains("<priority>"));
+    }
+
+    #[test]
+    fn test_build_complete_merges_sections() {
+        use crate::generator::{
+            FirewallComplexity, NatGenerator, generate_aliases, generate_firewall_rules,
+        };
+
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Sales VLAN 200".to_string(), 2).unwrap(),
+        ];
+        let rules =
+            generate_firewall_rules(&configs, FirewallComplexity::Basic, Some(42), None, None)
+                .unwrap();
+        let nat = NatGenerator::new_with_seed(Some(42))
+            .generate_for_vlans(&configs)
+            .unwrap();
+        let aliases = generate_aliases(&configs).unwrap();
+
+        let xml = OPNsenseConfigBuilder::new()
+            .build_complete(&configs, &rules, &nat, &aliases)
+            .unwrap();
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let section = |name: &str| {
+            doc.descendants()
+                .find(|node| node.has_tag_name(name))
+                .unwrap_or_else(|| panic!("missing <{name}>"))
+        };
+        let text = |node: roxmltree::Node, name: &str| {
+            node.children()
+                .find(|c| c.has_tag_name(name))
+                .and_then(|c| c.text())
+                .unwrap_or_else(|| panic!("missing <{name}>"))
+                .to_string()
+        };
+
+        let vlan_ids: Vec<u16> = section("vlans")
+            .children()
+            .filter(|node| node.has_tag_name("vlan"))
+            .map(|vlan| text(vlan, "vlanid").parse().unwrap())
+            .collect();
+        assert_eq!(vlan_ids, [100, 200]);
+
+        let filter_rules: Vec<_> = section("filter")
+            .children()
+            .filter(|node| node.has_tag_name("rule"))
+            .collect();
+        assert_eq!(filter_rules.len(), rules.len());
+        for rule in filter_rules {
+            let interface = text(rule, "interface");
+            assert!(
+                vlan_ids.iter().any(|id| interface == format!("vlan{id}")),
+                "filter rule on unknown interface {interface}"
+            );
+        }
+
+        let nat_rules: Vec<_> = section("nat")
+            .children()
+            .filter(|node| node.has_tag_name("rule"))
+            .collect();
+        assert_eq!(nat_rules.len(), nat.len());
+        for (rule, mapping) in nat_rules.iter().zip(&nat) {
+            let target: std::net::Ipv4Addr = text(*rule, "target").parse().unwrap();
+            let config = configs
+                .iter()
+                .find(|config| Some(config.vlan_id) == mapping.vlan_id)
+                .unwrap();
+            assert!(vlan_ids.contains(&config.vlan_id));
+            assert!(config.as_ipv4_network().unwrap().contains(target));
+        }
+
+        let alias_count = section("aliases")
+            .children()
+            .filter(|node| node.has_tag_name("alias"))
+            .count();
+        assert_eq!(alias_count, aliases.len());
+    }
+
+    #[test]
+    fn test_build_injects_captive_portal_zones() {
+        use crate::generator::generate_captive_portal_zones;
+        use crate::xml::generator::CaptivePortalGenerator;
+
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Guest VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let xml = OPNsenseConfigBuilder::new()
+            .add_component(CaptivePortalGenerator::new(
+                generate_captive_portal_zones(&configs).unwrap(),
+            ))
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let zones: Vec<_> = doc
+            .descendants()
+            .filter(|node| node.has_tag_name("zone"))
+            .collect();
+        assert_eq!(zones.len(), 1);
+        assert!(
+            zones[0]
+                .ancestors()
+                .any(|node| node.has_tag_name("captiveportal"))
+        );
+        let interface = zones[0]
+            .children()
+            .find(|c| c.has_tag_name("interfaces"))
+            .and_then(|c| c.text());
+        assert_eq!(interface, Some("vlan200"));
+    }
+
+    #[test]
+    fn test_build_injects_bridges() {
+        use crate::generator::generate_bridges;
+        use crate::xml::generator::BridgeGenerator;
+
+        let configs: Vec<_> = (1..=6u16)
+            .map(|i| {
+                VlanConfig::new(i * 100, format!("10.1.{i}.x"), format!("IT VLAN {i}"), 1).unwrap()
+            })
+            .collect();
+
+        let xml = OPNsenseConfigBuilder::new()
+            .add_component(BridgeGenerator::new(generate_bridges(&configs, 3).unwrap()))
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let bridges = doc
+            .descendants()
+            .find(|node| node.has_tag_name("bridges"))
+            .expect("bridges element");
+        let members: Vec<_> = bridges
+            .children()
+            .filter(|node| node.has_tag_name("bridged"))
+            .filter_map(|bridged| bridged.children().find(|c| c.has_tag_name("members")))
+            .filter_map(|members| members.text())
+            .collect();
+        assert_eq!(
+            members,
+            ["vlan100,vlan200,vlan300", "vlan400,vlan500,vlan600"]
+        );
+    }
+
+    #[test]
+    fn test_build_injects_syslog_destinations() {
+        use crate::generator::generate_syslog_targets;
+        use crate::xml::generator::SyslogGenerator;
+
+        let targets = generate_syslog_targets(2, Some(42)).unwrap();
+        let xml = OPNsenseConfigBuilder::new()
+            .add_component(SyslogGenerator::new(targets.clone()))
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let syslog = doc
+            .descendants()
+            .find(|node| node.has_tag_name("Syslog"))
+            .expect("Syslog element");
+        assert!(syslog.parent().unwrap().has_tag_name("OPNsense"));
+
+        let hostnames: Vec<_> = syslog
+            .descendants()
+            .filter(|node| node.has_tag_name("hostname"))
+            .filter_map(|hostname| hostname.text())
+            .collect();
+        let expected: Vec<_> = targets.iter().map(|target| target.host.as_str()).collect();
+        assert_eq!(hostnames, expected);
+    }
+
+    #[test]
+    fn test_build_injects_traffic_shaper() {
+        use crate::generator::generate_shapers;
+        use crate::xml::generator::TrafficShaperGenerator;
+
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "Guest VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let xml = OPNsenseConfigBuilder::new()
+            .add_component(TrafficShaperGenerator::new(generate_shapers(&configs)))
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let shaper = doc
+            .descendants()
+            .find(|node| node.has_tag_name("TrafficShaper"))
+            .expect("TrafficShaper element");
+        assert!(shaper.parent().unwrap().has_tag_name("OPNsense"));
+
+        let bandwidths: Vec<_> = shaper
+            .descendants()
+            .filter(|node| {
+                node.has_tag_name("pipe") && node.parent().unwrap().has_tag_name("pipes")
+            })
+            .filter_map(|pipe| pipe.children().find(|c| c.has_tag_name("bandwidth")))
+            .filter_map(|bandwidth| bandwidth.text())
+            .collect();
+        assert_eq!(bandwidths, ["1000", "50"]);
+        assert_eq!(
+            shaper
+                .descendants()
+                .filter(|node| node.has_tag_name("queue"))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_build_injects_users_and_groups() {
+        use crate::generator::generate_users;
+        use crate::xml::generator::UserGenerator;
+
+        let accounts = generate_users(5, Some(42));
+        let xml = OPNsenseConfigBuilder::new()
+            .add_component(UserGenerator::new(accounts.clone()))
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let system = doc
+            .descendants()
+            .find(|node| node.has_tag_name("system"))
+            .expect("system element");
+        let names = |tag: &str| -> Vec<String> {
+            system
+                .children()
+                .filter(|node| node.has_tag_name(tag))
+                .filter_map(|node| node.children().find(|c| c.has_tag_name("name")))
+                .filter_map(|name| name.text().map(str::to_string))
+                .collect()
+        };
+
+        // The template's root user and admins group stay in place
+        let users = names("user");
+        assert_eq!(users[0], "root");
+        assert_eq!(
+            &users[1..],
+            accounts
+                .users
+                .iter()
+                .map(|u| u.username.clone())
+                .collect::<Vec<_>>()
+        );
+        let groups = names("group");
+        assert_eq!(groups.len(), 1 + accounts.groups.len());
+        let members = system
+            .descendants()
+            .filter(|node| node.has_tag_name("member"))
+            .count();
+        let memberships: usize = accounts.users.iter().map(|u| u.groups.len()).sum();
+        assert_eq!(members, 1 + memberships);
+    }
+
+    #[test]
+    fn test_build_injects_firewall_aliases() {
+        use crate::generator::generate_aliases;
+        use crate::xml::generator::AliasGenerator;
+
+        let configs = vec![
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap(),
+            VlanConfig::new(200, "10.3.4.x".to_string(), "HR VLAN 200".to_string(), 2).unwrap(),
+        ];
+
+        let xml = OPNsenseConfigBuilder::new()
+            .add_component(AliasGenerator::new(generate_aliases(&configs).unwrap()))
+            .build()
+            .unwrap();
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let alias_model = doc
+            .descendants()
+            .find(|node| node.has_tag_name("Alias"))
+            .expect("Alias element");
+        assert!(alias_model.parent().unwrap().has_tag_name("Firewall"));
+
+        let content_of = |name: &str| {
+            alias_model
+                .descendants()
+                .filter(|node| node.has_tag_name("alias"))
+                .find(|alias| {
+                    alias
+                        .children()
+                        .any(|c| c.has_tag_name("name") && c.text() == Some(name))
+                })
+                .and_then(|alias| alias.children().find(|c| c.has_tag_name("content")))
+                .and_then(|content| content.text())
+                .map(str::to_string)
+        };
+        assert_eq!(content_of("VLAN100_NET").as_deref(), Some("10.1.2.0/24"));
+        assert_eq!(content_of("VLAN200_NET").as_deref(), Some("10.3.4.0/24"));
+        assert_eq!(
+            content_of("VLAN_GATEWAYS").as_deref(),
+            Some("10.1.2.1\n10.3.4.1")
+        );
+    }
+
+    #[test]
+    fn test_build_missing_injection_point() {
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+
+        let result = OPNsenseConfigBuilder::with_template_content(
+            "<?xml version=\"1.0\"?><opnsense></opnsense>".to_string(),
+        )
+        .add_vlan_dhcp_servers(&[config], 6)
+        .build();
+
+        assert!(matches!(
+            result,
+            Err(XMLError::InjectionPointNotFound { .. })
+        ));
+    }
+
     #[test]
     fn test_output_config() {
         let config = OutputConfig {
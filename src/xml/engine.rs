@@ -1,11 +1,18 @@
 //! Core XML processing engine with quick-xml event-based processing
 
+use crate::generator::VlanConfig;
+use crate::generator::vlan::DEFAULT_PREFIX_LEN;
 use crate::xml::error::{XMLError, XMLResult};
 use quick_xml::events::Event;
 use quick_xml::{Reader, Writer};
 use std::collections::HashMap;
 use std::io::{BufRead, Cursor, Write};
 use std::path::Path;
+use tracing::debug;
+
+/// Sections a base configuration must define under `<opnsense>` before VLANs
+/// and their interfaces can be injected
+pub const REQUIRED_BASE_SECTIONS: &[&str] = &["interfaces", "vlans"];
 
 /// Core XML processing engine using quick-xml events
 pub struct XMLEngine {
@@ -43,6 +50,7 @@ pub fn add_namespace<K: Into<String>, V: Into<String>>(&mut self, prefix: K, uri
 
     /// Load and parse an XML template from file
     pub fn load_template<P: AsRef<Path>>(&mut self, path: P) -> XMLResult<XMLTemplate> {
+        debug!(path = %path.as_ref().display(), "loading XML template");
         let content = std::fs::read_to_string(path)?;
         self.parse_template(content)
     }
@@ -55,6 +63,7 @@ pub fn parse_template(&mut self, content: String) -> XMLResult<XMLTemplate> {
         let events = Vec::new();
         let mut injection_points = HashMap::new();
         let mut depth = 0;
+        debug!(bytes = content.len(), "parsing XML template");
         let _current_path: Vec<String> = Vec::new();
 
         // Use a different approach to avoid lifetime issues
@@ -82,8 +91,73 @@ pub fn parse_template(&mut self, content: String) -> XMLResult<XMLTemplate> {
         ))
     }
 
+    /// Check that a base configuration has the structure injection relies on
+    ///
+    /// The root element must be `<opnsense>` and every section in
+    /// [`REQUIRED_BASE_SECTIONS`] must appear directly beneath it. Empty
+    /// sections such as `<vlans/>` are accepted.
+    pub fn validate_base_structure(content: &str) -> XMLResult<()> {
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(true);
+
+        let mut depth = 0usize;
+        let mut root = None;
+        let mut sections = Vec::new();
+
+        loop {
+            let (name, is_start) = match reader.read_event()? {
+                Event::Eof => break,
+                Event::Start(start) => (start.name().as_ref().to_vec(), true),
+                Event::Empty(empty) => (empty.name().as_ref().to_vec(), false),
+                Event::End(_) => {
+                    depth = depth.saturating_sub(1);
+                    continue;
+                }
+                _ => continue,
+            };
+            let name = String::from_utf8_lossy(&name).into_owned();
+            match depth {
+                0 => root = Some(name),
+                1 => sections.push(name),
+                _ => {}
+            }
+            if is_start {
+                depth += 1;
+            }
+        }
+
+        match root.as_deref() {
+            Some("opnsense") => {}
+            Some(other) => {
+                return Err(XMLError::invalid_structure(format!(
+                    "base configuration root element is <{other}>, expected <opnsense>"
+                )));
+            }
+            None => {
+                return Err(XMLError::invalid_structure(
+                    "base configuration has no root element",
+                ));
+            }
+        }
+
+        let missing: Vec<String> = REQUIRED_BASE_SECTIONS
+            .iter()
+            .filter(|section| !sections.iter().any(|name| name == *section))
+            .map(|section| format!("<{section}>"))
+            .collect();
+        if !missing.is_empty() {
+            return Err(XMLError::invalid_structure(format!(
+                "base configuration is missing required section {} under <opnsense>",
+                missing.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Process a series of XML events and generate output
     pub fn process_events(&mut self, events: Vec<Event>) -> XMLResult<String> {
+        debug!(events = events.len(), "writing XML events");
         let mut output = Cursor::new(Vec::new());
         let mut writer = Writer::new(&mut output);
 
@@ -210,6 +284,40 @@ pub fn metadata(&self) -> &TemplateMetadata {
         &self.metadata
     }
 
+    /// VLAN IDs already defined by `<vlans><vlan><tag>` in the template
+    ///
+    /// Tags that are not numbers, such as `{{VLAN_ID}}` placeholders, are skipped.
+    pub fn existing_vlan_ids(&self) -> XMLResult<Vec<u16>> {
+        let mut reader = Reader::from_str(&self.metadata.original_content);
+        reader.config_mut().trim_text(true);
+
+        let mut path: Vec<String> = Vec::new();
+        let mut vlan_ids = Vec::new();
+
+        loop {
+            match reader.read_event()? {
+                Event::Eof => break,
+                Event::Start(start) => {
+                    path.push(String::from_utf8_lossy(start.name().as_ref()).into_owned());
+                }
+                Event::End(_) => {
+                    path.pop();
+                }
+                Event::Text(text)
+                    if path.ends_with(&["vlans", "vlan", "tag"].map(String::from)) =>
+                {
+                    // Non-numeric tags are placeholders such as {{VLAN_ID}}
+                    if let Ok(vlan_id) = String::from_utf8_lossy(&text).trim().parse() {
+                        vlan_ids.push(vlan_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(vlan_ids)
+    }
+
     /// Find injection points matching selectors
     pub fn find_injection_points(&self, selectors: &[String]) -> Vec<InjectionPoint> {
         selectors
@@ -274,6 +382,112 @@ pub fn validate_structure(&self) -> ValidationResult {
     }
 }
 
+/// Read the VLANs back out of generated OPNsense XML
+///
+/// Each `<vlans><vlan>` entry needs a `<vlanid>` (or `<tag>`) and a
+/// `<subnet>`; `<descr>` and a QinQ `<qinq><tag>` are read when present.
+/// The WAN assignment and DHCP pool are not stored in the `<vlan>` element,
+/// so extracted configs use WAN 1 and the default pool.
+pub fn extract_vlans(xml: &str) -> XMLResult<Vec<VlanConfig>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut path: Vec<String> = Vec::new();
+    let mut fields: HashMap<String, String> = HashMap::new();
+    let mut text = String::new();
+    let mut vlans = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Eof => break,
+            Event::Start(start) => {
+                path.push(String::from_utf8_lossy(start.name().as_ref()).into_owned());
+                text.clear();
+                if path.ends_with(&["vlans", "vlan"].map(String::from)) {
+                    fields.clear();
+                }
+            }
+            Event::Text(raw) => {
+                text.push_str(&raw.decode().map_err(quick_xml::Error::from)?);
+            }
+            // Entities are reported separately; keep them escaped until the
+            // element ends so the whole text is unescaped in one go
+            Event::GeneralRef(entity) => {
+                text.push('&');
+                text.push_str(&entity.decode().map_err(quick_xml::Error::from)?);
+                text.push(';');
+            }
+            Event::End(_) => {
+                if let Some(vlan_depth) = path
+                    .windows(2)
+                    .position(|pair| pair[0] == "vlans" && pair[1] == "vlan")
+                    .map(|index| index + 2)
+                {
+                    if path.len() > vlan_depth {
+                        let value = quick_xml::escape::unescape(&text)
+                            .map_err(quick_xml::Error::from)?
+                            .into_owned();
+                        fields.insert(path[vlan_depth..].join("/"), value);
+                    } else {
+                        vlans.push(vlan_from_fields(&fields)?);
+                    }
+                }
+                text.clear();
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(vlans)
+}
+
+/// Build a `VlanConfig` from the child elements of one `<vlan>` entry
+fn vlan_from_fields(fields: &HashMap<String, String>) -> XMLResult<VlanConfig> {
+    let id_text = fields
+        .get("vlanid")
+        .or_else(|| fields.get("tag"))
+        .ok_or_else(|| XMLError::invalid_structure("<vlan> entry has no <vlanid> or <tag>"))?;
+    let vlan_id: u16 = id_text
+        .trim()
+        .parse()
+        .map_err(|_| XMLError::invalid_structure(format!("VLAN ID '{id_text}' is not a number")))?;
+
+    let network = fields
+        .get("subnet")
+        .ok_or_else(|| XMLError::invalid_structure(format!("VLAN {vlan_id} has no <subnet>")))?;
+    let prefix_len = match network.split_once('/') {
+        Some((_, prefix)) => prefix.parse().map_err(|_| {
+            XMLError::invalid_structure(format!(
+                "VLAN {vlan_id} subnet '{network}' has an invalid prefix"
+            ))
+        })?,
+        None => DEFAULT_PREFIX_LEN,
+    };
+    let description = fields.get("descr").cloned().unwrap_or_default();
+
+    let mut config =
+        VlanConfig::new_with_prefix(vlan_id, network.clone(), description, 1, prefix_len)
+            .map_err(|e| XMLError::invalid_structure(format!("VLAN {vlan_id}: {e}")))?;
+    if let Some(outer) = fields.get("qinq/tag") {
+        config.outer_vlan_id = Some(outer.trim().parse().map_err(|_| {
+            XMLError::invalid_structure(format!(
+                "VLAN {vlan_id} QinQ tag '{outer}' is not a number"
+            ))
+        })?);
+    }
+    if let Some(pcp) = fields.get("pcp").or_else(|| fields.get("priority")) {
+        let pcp = pcp.trim().parse().map_err(|_| {
+            XMLError::invalid_structure(format!("VLAN {vlan_id} PCP '{pcp}' is not a number"))
+        })?;
+        config = config
+            .with_pcp(pcp)
+            .map_err(|e| XMLError::invalid_structure(format!("VLAN {vlan_id}: {e}")))?;
+    }
+
+    Ok(config)
+}
+
 /// Injection point in XML template
 #[derive(Debug, Clone)]
 pub struct InjectionPoint {
@@ -305,6 +519,76 @@ mod tests {
     use super::*;
     use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText};
 
+    #[test]
+    fn test_existing_vlan_ids() {
+        let mut engine = XMLEngine::new();
+        let template = engine
+            .parse_template(
+                "<opnsense><vlans><vlan><if>igb0</if><tag>100</tag></vlan>\
+                 <vlan><tag>200</tag></vlan></vlans><tag>5</tag></opnsense>"
+                    .to_string(),
+            )
+            .unwrap();
+        assert_eq!(template.existing_vlan_ids().unwrap(), [100, 200]);
+
+        let template = engine
+            .parse_template(
+                "<opnsense><vlans><vlan><tag>{{VLAN_ID}}</tag></vlan></vlans></opnsense>".into(),
+            )
+            .unwrap();
+        assert!(template.existing_vlan_ids().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_extract_vlans() {
+        let xml = "<opnsense><vlans>\
+                   <vlan><vlanid>100</vlanid><qinq><tag>20</tag></qinq>\
+                   <descr>R&amp;D VLAN 100</descr><subnet>10.1.2.x</subnet></vlan>\
+                   <vlan><vlanid>200</vlanid><descr>HR</descr><subnet>10.2.0.0/23</subnet></vlan>\
+                   </vlans></opnsense>";
+        let vlans = extract_vlans(xml).unwrap();
+        assert_eq!(vlans.len(), 2);
+        assert_eq!(vlans[0].vlan_id, 100);
+        assert_eq!(vlans[0].outer_vlan_id, Some(20));
+        assert_eq!(vlans[0].description, "R&D VLAN 100");
+        assert_eq!(vlans[0].ip_network, "10.1.2.x");
+        assert_eq!(vlans[1].prefix_len, 23);
+
+        let error =
+            extract_vlans("<opnsense><vlans><vlan><vlanid>100</vlanid></vlan></vlans></opnsense>")
+                .unwrap_err();
+        assert!(error.to_string().contains("<subnet>"), "{error}");
+    }
+
+    #[test]
+    fn test_validate_base_structure() {
+        XMLEngine::validate_base_structure(
+            "<?xml version=\"1.0\"?><opnsense><interfaces><lan/></interfaces><vlans/></opnsense>",
+        )
+        .unwrap();
+        XMLEngine::validate_base_structure(include_str!("../../test_xml/firewall_vlan_base.xml"))
+            .unwrap();
+
+        let error =
+            XMLEngine::validate_base_structure("<opnsense><system/><vlans></vlans></opnsense>")
+                .unwrap_err();
+        assert!(error.to_string().contains("<interfaces>"), "{error}");
+
+        // Nested elements do not count as top-level sections
+        let error = XMLEngine::validate_base_structure(
+            "<opnsense><system><interfaces/></system></opnsense>",
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("<interfaces>, <vlans>"),
+            "{error}"
+        );
+
+        let error = XMLEngine::validate_base_structure("<pfsense><interfaces/><vlans/></pfsense>")
+            .unwrap_err();
+        assert!(error.to_string().contains("<pfsense>"), "{error}");
+    }
+
     #[test]
     fn test_xml_engine_creation() {
         let engine = XMLEngine::new();
@@ -10,11 +10,8 @@ pub enum XMLError {
     Parsing(#[from] quick_xml::Error),
 
     /// Template loading error
-    #[error("Template loading failed: {source}")]
-    TemplateLoading {
-        #[from]
-        source: std::io::Error,
-    },
+    #[error("Template loading failed: {0}")]
+    TemplateLoading(#[from] std::io::Error),
 
     /// Injection point not found in template
     #[error("Injection point not found: {selector}")]
@@ -0,0 +1,200 @@
+//! Consistent indentation for generated XML
+//!
+//! Templates and generators emit XML with whatever whitespace they happen to
+//! carry. [`pretty_print`] reindents a document so fixtures read well and
+//! diffs stay clean.
+
+use crate::Result;
+use crate::model::ConfigError;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+
+/// Spaces added per nesting level
+const INDENT_WIDTH: usize = 2;
+
+/// Reindent `xml` with two spaces per level, one element per line
+///
+/// Only whitespace between elements is rewritten. Elements holding text,
+/// CDATA or entity references keep their content byte for byte, including
+/// any child elements mixed into it, so no text value changes. Comments,
+/// processing instructions and the declaration are kept and indented like
+/// elements.
+pub fn pretty_print(xml: &str) -> Result<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut events = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Eof => break,
+            event => events.push(event.into_owned()),
+        }
+    }
+    let inline = inline_elements(&events);
+
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut depth = 0usize;
+    // Open elements inside the element currently written verbatim
+    let mut verbatim_depth = 0usize;
+    for (index, event) in events.into_iter().enumerate() {
+        if verbatim_depth > 0 {
+            match event {
+                Event::Start(_) => verbatim_depth += 1,
+                Event::End(_) => verbatim_depth -= 1,
+                _ => {}
+            }
+            writer.write_event(event)?;
+            continue;
+        }
+
+        match &event {
+            Event::Text(text) if is_whitespace(text) => continue,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        let output = writer.get_mut();
+        if !output.is_empty() {
+            output.push(b'\n');
+        }
+        output.resize(output.len() + depth * INDENT_WIDTH, b' ');
+
+        if let Event::Start(_) = event {
+            if inline[index] {
+                verbatim_depth = 1;
+            } else {
+                depth += 1;
+            }
+        }
+        writer.write_event(event)?;
+    }
+
+    let mut output = writer.into_inner();
+    output.push(b'\n');
+    String::from_utf8(output).map_err(|e| ConfigError::xml_template(e.to_string()))
+}
+
+/// Whether the element opened at each index must be written verbatim
+///
+/// That is the case for leaf elements, whose whitespace may be their value,
+/// and for elements with text, CDATA or entity references next to child
+/// elements, where whitespace between the children is part of the text.
+fn inline_elements(events: &[Event<'_>]) -> Vec<bool> {
+    let mut inline = vec![false; events.len()];
+    // (start index, has child elements, has text) per open element
+    let mut open: Vec<(usize, bool, bool)> = Vec::new();
+    for (index, event) in events.iter().enumerate() {
+        match event {
+            Event::Start(_) => {
+                if let Some(parent) = open.last_mut() {
+                    parent.1 = true;
+                }
+                open.push((index, false, false));
+            }
+            Event::Empty(_) => {
+                if let Some(parent) = open.last_mut() {
+                    parent.1 = true;
+                }
+            }
+            Event::Text(text) if !is_whitespace(text) => {
+                if let Some(parent) = open.last_mut() {
+                    parent.2 = true;
+                }
+            }
+            Event::CData(_) | Event::GeneralRef(_) => {
+                if let Some(parent) = open.last_mut() {
+                    parent.2 = true;
+                }
+            }
+            Event::End(_) => {
+                if let Some((start, has_children, has_text)) = open.pop() {
+                    inline[start] = !has_children || has_text;
+                }
+            }
+            _ => {}
+        }
+    }
+    inline
+}
+
+fn is_whitespace(text: &[u8]) -> bool {
+    text.iter().all(u8::is_ascii_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Element tree with whitespace-only text between elements dropped
+    #[derive(Debug, PartialEq)]
+    enum Node {
+        Element {
+            name: String,
+            attributes: Vec<(String, String)>,
+            children: Vec<Node>,
+        },
+        Text(String),
+        Comment(String),
+    }
+
+    fn dom(xml: &str) -> Vec<Node> {
+        fn convert(node: roxmltree::Node) -> Option<Node> {
+            if node.is_element() {
+                Some(Node::Element {
+                    name: node.tag_name().name().to_string(),
+                    attributes: node
+                        .attributes()
+                        .map(|a| (a.name().to_string(), a.value().to_string()))
+                        .collect(),
+                    children: node.children().filter_map(convert).collect(),
+                })
+            } else if node.is_comment() {
+                Some(Node::Comment(node.text()?.to_string()))
+            } else if node.is_text() {
+                let text = node.text()?;
+                let significant = !text.trim().is_empty()
+                    || node
+                        .parent()
+                        .is_some_and(|p| !p.children().any(|c| c.is_element()));
+                significant.then(|| Node::Text(text.to_string()))
+            } else {
+                None
+            }
+        }
+        let document = roxmltree::Document::parse(xml).unwrap();
+        document.root().children().filter_map(convert).collect()
+    }
+
+    const COMPACT: &str = concat!(
+        r#"<?xml version="1.0"?><!-- generated --><opnsense><system><hostname>fw01</hostname>"#,
+        r#"<domain>example.com</domain></system><interfaces><lan><if>em0</if><enable/>"#,
+        r#"<descr><![CDATA[LAN <trusted> & internal]]></descr></lan></interfaces>"#,
+        r#"<!-- keep --><filter><rule type="pass"><descr>Allow &amp; log</descr>"#,
+        r#"<note>Mixed <b>bold</b> text</note><padded>  </padded></rule></filter></opnsense>"#
+    );
+
+    #[test]
+    fn test_pretty_print_preserves_dom() {
+        let pretty = pretty_print(COMPACT).unwrap();
+        assert_eq!(dom(&pretty), dom(COMPACT));
+        assert!(
+            pretty.contains("\n    <hostname>fw01</hostname>\n"),
+            "{pretty}"
+        );
+        assert!(
+            pretty.contains("<descr><![CDATA[LAN <trusted> & internal]]></descr>"),
+            "{pretty}"
+        );
+        assert!(pretty.contains("\n  <!-- keep -->\n"), "{pretty}");
+        assert!(
+            pretty.contains("<note>Mixed <b>bold</b> text</note>"),
+            "{pretty}"
+        );
+        assert!(pretty.contains("<padded>  </padded>"), "{pretty}");
+
+        // Reindenting is idempotent
+        assert_eq!(pretty_print(&pretty).unwrap(), pretty);
+    }
+
+    #[test]
+    fn test_pretty_print_rejects_malformed_xml() {
+        assert!(pretty_print("<opnsense><system></opnsense>").is_err());
+    }
+}
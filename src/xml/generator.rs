@@ -1,7 +1,20 @@
 //! XML component generators for structured XML generation
 
 use crate::generator::VlanConfig;
-use crate::xml::error::XMLResult;
+use crate::generator::alias::Alias;
+use crate::generator::bridge::Bridge;
+use crate::generator::captive_portal::CaptivePortalZone;
+use crate::generator::firewall::{FirewallRule, icmp_type_pf_name};
+use crate::generator::gateway_group::{GatewayGroup, MAX_GATEWAY_TIER};
+use crate::generator::logging::{SYSLOG_FACILITIES, SyslogTarget};
+use crate::generator::nat::{NatMapping, NatRuleType};
+use crate::generator::shaper::TrafficShaper;
+use crate::generator::users::UserAccounts;
+use crate::generator::vlan::{DhcpOptions, DhcpServerConfig};
+use crate::generator::wan::{WanInterface, find_wan_interface};
+use crate::model::ConfigError;
+use crate::xml::error::{XMLError, XMLResult};
+use crate::xml::schema::SchemaVersion;
 use crate::xml::template::escape_xml_string;
 use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use serde::{Deserialize, Serialize};
@@ -24,6 +37,20 @@ pub enum ComponentType {
     Carp,
     /// RADIUS authentication component
     Radius,
+    /// Gateway (WAN uplink monitoring) component
+    Gateway,
+    /// Firewall alias component
+    Alias,
+    /// Captive portal zone component
+    CaptivePortal,
+    /// Traffic shaper pipes and queues component
+    TrafficShaper,
+    /// Local user and group accounts component
+    Users,
+    /// Bridge interface component
+    Bridge,
+    /// Remote syslog destination component
+    Syslog,
     /// Custom component type
     Custom(String),
 }
@@ -38,6 +65,13 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             ComponentType::Firewall => write!(f, "Firewall"),
             ComponentType::Carp => write!(f, "CARP"),
             ComponentType::Radius => write!(f, "RADIUS"),
+            ComponentType::Gateway => write!(f, "Gateway"),
+            ComponentType::Alias => write!(f, "Alias"),
+            ComponentType::CaptivePortal => write!(f, "CaptivePortal"),
+            ComponentType::TrafficShaper => write!(f, "TrafficShaper"),
+            ComponentType::Users => write!(f, "Users"),
+            ComponentType::Bridge => write!(f, "Bridge"),
+            ComponentType::Syslog => write!(f, "Syslog"),
             ComponentType::Custom(name) => write!(f, "Custom({name})"),
         }
     }
@@ -57,6 +91,13 @@ pub trait XMLGenerator: Send + Sync {
     /// Estimate memory usage for this component (in bytes)
     fn memory_estimate(&self) -> usize;
 
+    /// Template element the events are injected into (e.g. `dhcpd`)
+    ///
+    /// `None` places the events directly under the document root.
+    fn injection_target(&self) -> Option<&str> {
+        None
+    }
+
     /// Get component identifier for debugging
     fn component_id(&self) -> String {
         format!("{}_{}", self.component_type(), uuid::Uuid::new_v4())
@@ -131,6 +172,7 @@ pub struct VlanGenerator {
     config: VlanConfig,
     template_fragment: Option<String>,
     options: VlanGeneratorOptions,
+    target: Option<String>,
 }
 
 /// Options for VLAN XML generation
@@ -138,6 +180,9 @@ pub struct VlanGenerator {
 pub struct VlanGeneratorOptions {
     /// Include DHCP configuration
     pub include_dhcp: bool,
+    /// DNS servers and reservation MAC vendors shared by every VLAN's DHCP
+    /// server
+    pub dhcp: DhcpOptions,
     /// Include firewall rules
     pub include_firewall_rules: bool,
     /// Include NAT rules
@@ -146,16 +191,26 @@ pub struct VlanGeneratorOptions {
     pub firewall_number: u16,
     /// OPT interface counter
     pub opt_counter: u16,
+    /// Generated WAN uplinks that `wan_assignment` is resolved against
+    pub wan_interfaces: Vec<WanInterface>,
+    /// Physical NIC (e.g. `em0`) the VLAN is tagged on
+    pub parent_interface: Option<String>,
+    /// OPNsense release whose element names are emitted
+    pub schema_version: SchemaVersion,
 }
 
 impl Default for VlanGeneratorOptions {
     fn default() -> Self {
         Self {
             include_dhcp: true,
+            dhcp: DhcpOptions::default(),
             include_firewall_rules: false,
             include_nat_rules: false,
             firewall_number: 1,
             opt_counter: 1,
+            wan_interfaces: Vec::new(),
+            parent_interface: None,
+            schema_version: SchemaVersion::default(),
         }
     }
 }
@@ -167,6 +222,7 @@ pub fn new(config: VlanConfig) -> Self {
             config,
             template_fragment: None,
             options: VlanGeneratorOptions::default(),
+            target: None,
         }
     }
 
@@ -176,6 +232,7 @@ pub fn with_options(config: VlanConfig, options: VlanGeneratorOptions) -> Self {
             config,
             template_fragment: None,
             options,
+            target: None,
         }
     }
 
@@ -185,6 +242,45 @@ pub fn with_template_fragment(mut self, fragment: String) -> Self {
         self
     }
 
+    /// Inject the VLAN under a template element such as `vlans`
+    /// instead of directly under the document root
+    pub fn with_injection_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Resolve the VLAN's `wan_assignment` against generated WAN interfaces
+    pub fn with_wan_interfaces(mut self, wans: Vec<WanInterface>) -> Self {
+        self.options.wan_interfaces = wans;
+        self
+    }
+
+    /// Tag the VLAN on a physical parent NIC such as `em0`
+    ///
+    /// Adds OPNsense's `<if>`, `<tag>` and `<vlanif>` elements around the
+    /// `<pcp>` priority (`<priority>` for 23.x schemas) every VLAN carries.
+    pub fn with_parent_interface(mut self, interface: impl Into<String>) -> Self {
+        self.options.parent_interface = Some(interface.into());
+        self
+    }
+
+    /// Emit element names for a specific OPNsense release
+    pub fn with_schema_version(mut self, version: SchemaVersion) -> Self {
+        self.options.schema_version = version;
+        self
+    }
+
+    /// Configure the DNS servers and reservation MACs of the VLAN's DHCP server
+    pub fn with_dhcp_options(mut self, options: DhcpOptions) -> Self {
+        self.options.dhcp = options;
+        self
+    }
+
+    /// The WAN interface this VLAN is assigned to, if WANs were provided
+    fn assigned_wan(&self) -> Option<&WanInterface> {
+        find_wan_interface(&self.options.wan_interfaces, self.config.wan_assignment)
+    }
+
     /// Generate VLAN XML section events
     fn generate_vlan_events(&self) -> XMLResult<Vec<Event<'static>>> {
         let mut events = Vec::new();
@@ -199,10 +295,36 @@ fn generate_vlan_events(&self) -> XMLResult<Vec<Event<'static>>> {
         events.push(Event::Text(BytesText::new(&vlan_id_text).into_owned()));
         events.push(Event::End(BytesEnd::new("vlanid")));
 
+        // Parent NIC and tag, the 802.1p priority and the resulting vlanXX device
+        let parent = self.options.parent_interface.as_ref();
+        if let Some(parent) = parent {
+            push_text_element(&mut events, "if", parent);
+            push_text_element(&mut events, "tag", &vlan_id_text);
+        }
+        push_text_element(
+            &mut events,
+            self.options.schema_version.vlan_priority_element(),
+            &self.config.pcp.to_string(),
+        );
+        if parent.is_some() {
+            push_text_element(&mut events, "vlanif", &format!("vlan{vlan_id_text}"));
+        }
+
+        // 802.1ad service tag for stacked (QinQ) VLANs
+        if let Some(outer) = self.config.outer_vlan_id {
+            events.push(Event::Start(BytesStart::new("qinq")));
+            push_text_element(&mut events, "tag", &outer.to_string());
+            push_text_element(&mut events, "proto", "802.1ad");
+            events.push(Event::End(BytesEnd::new("qinq")));
+        }
+
         // Description
         events.push(Event::Start(BytesStart::new("descr")));
+        // Already escaped, so it must not go through `BytesText::new` again
         let description_text = escape_xml_string(&self.config.description);
-        events.push(Event::Text(BytesText::new(&description_text).into_owned()));
+        events.push(Event::Text(
+            BytesText::from_escaped(description_text).into_owned(),
+        ));
         events.push(Event::End(BytesEnd::new("descr")));
 
         // Network configuration
@@ -212,8 +334,12 @@ fn generate_vlan_events(&self) -> XMLResult<Vec<Event<'static>>> {
         ));
         events.push(Event::End(BytesEnd::new("subnet")));
 
-        // Gateway IP if available
-        if let Ok(gateway) = self.config.gateway_ip() {
+        // Gateway: the assigned WAN's gateway by name, the way OPNsense
+        // interfaces reference a <gateway_item>, or else the VLAN's own
+        // gateway IP if available
+        if let Some(wan) = self.assigned_wan() {
+            push_text_element(&mut events, "gateway", &wan.gateway_name());
+        } else if let Ok(gateway) = self.config.gateway_ip() {
             events.push(Event::Start(BytesStart::new("gateway")));
             events.push(Event::Text(BytesText::new(&gateway).into_owned()));
             events.push(Event::End(BytesEnd::new("gateway")));
@@ -233,7 +359,7 @@ fn generate_vlan_events(&self) -> XMLResult<Vec<Event<'static>>> {
     /// Generate DHCP server configuration events
     fn generate_dhcp_events(&self) -> XMLResult<Vec<Event<'static>>> {
         // Get enhanced DHCP configuration
-        let dhcp_config = match self.config.dhcp_server_config() {
+        let dhcp_config = match self.config.dhcp_server_config(&self.options.dhcp) {
             Ok(config) => config,
             Err(_) => {
                 // Fallback to basic configuration if enhanced config fails
@@ -241,99 +367,7 @@ fn generate_dhcp_events(&self) -> XMLResult<Vec<Event<'static>>> {
             }
         };
 
-        let mut events = vec![
-            // Start DHCP element
-            Event::Start(BytesStart::new("dhcp")),
-            // Enable DHCP
-            Event::Start(BytesStart::new("enable")),
-            Event::Text(BytesText::new(if dhcp_config.enabled { "1" } else { "0" }).into_owned()),
-            Event::End(BytesEnd::new("enable")),
-        ];
-
-        // DHCP range
-        events.push(Event::Start(BytesStart::new("range")));
-        events.push(Event::Start(BytesStart::new("from")));
-        events.push(Event::Text(
-            BytesText::new(&dhcp_config.range_start).into_owned(),
-        ));
-        events.push(Event::End(BytesEnd::new("from")));
-
-        events.push(Event::Start(BytesStart::new("to")));
-        events.push(Event::Text(
-            BytesText::new(&dhcp_config.range_end).into_owned(),
-        ));
-        events.push(Event::End(BytesEnd::new("to")));
-        events.push(Event::End(BytesEnd::new("range")));
-
-        // Default lease time
-        events.push(Event::Start(BytesStart::new("defaultleasetime")));
-        events.push(Event::Text(
-            BytesText::new(&dhcp_config.lease_time.to_string()).into_owned(),
-        ));
-        events.push(Event::End(BytesEnd::new("defaultleasetime")));
-
-        // Maximum lease time
-        events.push(Event::Start(BytesStart::new("maxleasetime")));
-        events.push(Event::Text(
-            BytesText::new(&dhcp_config.max_lease_time.to_string()).into_owned(),
-        ));
-        events.push(Event::End(BytesEnd::new("maxleasetime")));
-
-        // Gateway
-        events.push(Event::Start(BytesStart::new("gateway")));
-        events.push(Event::Text(
-            BytesText::new(&dhcp_config.gateway).into_owned(),
-        ));
-        events.push(Event::End(BytesEnd::new("gateway")));
-
-        // Domain name
-        events.push(Event::Start(BytesStart::new("domain")));
-        events.push(Event::Text(
-            BytesText::new(&dhcp_config.domain_name).into_owned(),
-        ));
-        events.push(Event::End(BytesEnd::new("domain")));
-
-        // DNS servers (multiple entries)
-        for dns_server in &dhcp_config.dns_servers {
-            events.push(Event::Start(BytesStart::new("dnsserver")));
-            events.push(Event::Text(BytesText::new(dns_server).into_owned()));
-            events.push(Event::End(BytesEnd::new("dnsserver")));
-        }
-
-        // NTP servers
-        for ntp_server in &dhcp_config.ntp_servers {
-            events.push(Event::Start(BytesStart::new("ntpserver")));
-            events.push(Event::Text(BytesText::new(ntp_server).into_owned()));
-            events.push(Event::End(BytesEnd::new("ntpserver")));
-        }
-
-        // Static reservations
-        for reservation in &dhcp_config.static_reservations {
-            events.push(Event::Start(BytesStart::new("staticmap")));
-
-            events.push(Event::Start(BytesStart::new("mac")));
-            events.push(Event::Text(BytesText::new(&reservation.mac).into_owned()));
-            events.push(Event::End(BytesEnd::new("mac")));
-
-            events.push(Event::Start(BytesStart::new("ipaddr")));
-            events.push(Event::Text(
-                BytesText::new(&reservation.ip_addr).into_owned(),
-            ));
-            events.push(Event::End(BytesEnd::new("ipaddr")));
-
-            events.push(Event::Start(BytesStart::new("hostname")));
-            events.push(Event::Text(
-                BytesText::new(&reservation.hostname).into_owned(),
-            ));
-            events.push(Event::End(BytesEnd::new("hostname")));
-
-            events.push(Event::End(BytesEnd::new("staticmap")));
-        }
-
-        // End DHCP element
-        events.push(Event::End(BytesEnd::new("dhcp")));
-
-        Ok(events)
+        Ok(dhcp_server_events("dhcp", &dhcp_config))
     }
 
     /// Generate basic DHCP configuration events (fallback)
@@ -382,6 +416,106 @@ fn generate_basic_dhcp_events(&self) -> XMLResult<Vec<Event<'static>>> {
     }
 }
 
+/// Build the DHCP server events for one interface, wrapped in `<element>`
+///
+/// Covers the range, lease times, gateway, domain, DNS/NTP servers and the
+/// static reservations of `dhcp_config`.
+fn dhcp_server_events(element: &str, dhcp_config: &DhcpServerConfig) -> Vec<Event<'static>> {
+    let mut events = vec![
+        // Start DHCP element
+        Event::Start(BytesStart::new(element.to_string())),
+        // Enable DHCP
+        Event::Start(BytesStart::new("enable")),
+        Event::Text(BytesText::new(if dhcp_config.enabled { "1" } else { "0" }).into_owned()),
+        Event::End(BytesEnd::new("enable")),
+    ];
+
+    // DHCP range
+    events.push(Event::Start(BytesStart::new("range")));
+    events.push(Event::Start(BytesStart::new("from")));
+    events.push(Event::Text(
+        BytesText::new(&dhcp_config.range_start).into_owned(),
+    ));
+    events.push(Event::End(BytesEnd::new("from")));
+
+    events.push(Event::Start(BytesStart::new("to")));
+    events.push(Event::Text(
+        BytesText::new(&dhcp_config.range_end).into_owned(),
+    ));
+    events.push(Event::End(BytesEnd::new("to")));
+    events.push(Event::End(BytesEnd::new("range")));
+
+    // Default lease time
+    events.push(Event::Start(BytesStart::new("defaultleasetime")));
+    events.push(Event::Text(
+        BytesText::new(&dhcp_config.lease_time.to_string()).into_owned(),
+    ));
+    events.push(Event::End(BytesEnd::new("defaultleasetime")));
+
+    // Maximum lease time
+    events.push(Event::Start(BytesStart::new("maxleasetime")));
+    events.push(Event::Text(
+        BytesText::new(&dhcp_config.max_lease_time.to_string()).into_owned(),
+    ));
+    events.push(Event::End(BytesEnd::new("maxleasetime")));
+
+    // Gateway
+    events.push(Event::Start(BytesStart::new("gateway")));
+    events.push(Event::Text(
+        BytesText::new(&dhcp_config.gateway).into_owned(),
+    ));
+    events.push(Event::End(BytesEnd::new("gateway")));
+
+    // Domain name
+    events.push(Event::Start(BytesStart::new("domain")));
+    events.push(Event::Text(
+        BytesText::new(&dhcp_config.domain_name).into_owned(),
+    ));
+    events.push(Event::End(BytesEnd::new("domain")));
+
+    // DNS servers (multiple entries)
+    for dns_server in &dhcp_config.dns_servers {
+        events.push(Event::Start(BytesStart::new("dnsserver")));
+        events.push(Event::Text(BytesText::new(dns_server).into_owned()));
+        events.push(Event::End(BytesEnd::new("dnsserver")));
+    }
+
+    // NTP servers
+    for ntp_server in &dhcp_config.ntp_servers {
+        events.push(Event::Start(BytesStart::new("ntpserver")));
+        events.push(Event::Text(BytesText::new(ntp_server).into_owned()));
+        events.push(Event::End(BytesEnd::new("ntpserver")));
+    }
+
+    // Static reservations
+    for reservation in &dhcp_config.static_reservations {
+        events.push(Event::Start(BytesStart::new("staticmap")));
+
+        events.push(Event::Start(BytesStart::new("mac")));
+        events.push(Event::Text(BytesText::new(&reservation.mac).into_owned()));
+        events.push(Event::End(BytesEnd::new("mac")));
+
+        events.push(Event::Start(BytesStart::new("ipaddr")));
+        events.push(Event::Text(
+            BytesText::new(&reservation.ip_addr).into_owned(),
+        ));
+        events.push(Event::End(BytesEnd::new("ipaddr")));
+
+        events.push(Event::Start(BytesStart::new("hostname")));
+        events.push(Event::Text(
+            BytesText::new(&reservation.hostname).into_owned(),
+        ));
+        events.push(Event::End(BytesEnd::new("hostname")));
+
+        events.push(Event::End(BytesEnd::new("staticmap")));
+    }
+
+    // End DHCP element
+    events.push(Event::End(BytesEnd::new(element.to_string())));
+
+    events
+}
+
 impl XMLGenerator for VlanGenerator {
     fn component_type(&self) -> ComponentType {
         ComponentType::Vlan
@@ -409,6 +543,15 @@ fn validate_requirements(&self) -> ValidationResult {
             ));
         }
 
+        // Validate QinQ service tag range
+        if let Some(outer) = self.config.outer_vlan_id {
+            if let Err(ConfigError::Validation { message }) =
+                VlanConfig::validate_outer_vlan_id(outer)
+            {
+                errors.push(message);
+            }
+        }
+
         // Validate WAN assignment
         if !(1..=3).contains(&self.config.wan_assignment) {
             errors.push(format!(
@@ -417,6 +560,14 @@ fn validate_requirements(&self) -> ValidationResult {
             ));
         }
 
+        // Assigned WAN must exist when WAN interfaces are provided
+        if !self.options.wan_interfaces.is_empty() && self.assigned_wan().is_none() {
+            errors.push(format!(
+                "WAN assignment {} has no matching WAN interface",
+                self.config.wan_assignment
+            ));
+        }
+
         // Validate IP network format
         if !self.config.ip_network.contains('.') {
             errors.push("IP network format is invalid".to_string());
@@ -465,6 +616,10 @@ fn memory_estimate(&self) -> usize {
         base_size + dhcp_size + firewall_size + nat_size
     }
 
+    fn injection_target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
     fn supports_streaming(&self) -> bool {
         true
     }
@@ -481,6 +636,938 @@ fn generate_streaming_events(
     }
 }
 
+/// Generator for a VLAN's DHCP server, injected under the template's `<dhcpd>`
+pub struct DhcpGenerator {
+    config: VlanConfig,
+    interface: String,
+    options: DhcpOptions,
+}
+
+impl DhcpGenerator {
+    /// Create a DHCP generator serving `config` on the named interface
+    pub fn new(config: VlanConfig, interface: impl Into<String>) -> Self {
+        Self {
+            config,
+            interface: interface.into(),
+            options: DhcpOptions::default(),
+        }
+    }
+
+    /// Configure the DNS servers and reservation MACs of the DHCP server
+    pub fn with_dhcp_options(mut self, options: DhcpOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Interface element the DHCP server is configured under
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+}
+
+impl XMLGenerator for DhcpGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Dhcp
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let dhcp_config = self
+            .config
+            .dhcp_server_config(&self.options)
+            .map_err(|e| XMLError::generation("DHCPGenerator", e.to_string()))?;
+        Ok(dhcp_server_events(&self.interface, &dhcp_config))
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        if self.interface.is_empty() {
+            errors.push("DHCP interface name is empty".to_string());
+        }
+
+        if let Err(e) = self.config.dhcp_server_config(&self.options) {
+            errors.push(format!(
+                "Cannot build DHCP server config for VLAN {}: {e}",
+                self.config.vlan_id
+            ));
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // Base DHCP server configuration: ~256 bytes, plus ~128 bytes per static mapping
+        let reservations = self
+            .config
+            .static_reservations(&self.options.mac_vendors)
+            .map_or(0, |reservations| reservations.len());
+        256 + 128 * reservations
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("dhcpd")
+    }
+}
+
+/// Default OPNsense gateway priority and weight for unmonitored uplinks
+const DEFAULT_GATEWAY_PRIORITY: u8 = 255;
+const DEFAULT_GATEWAY_WEIGHT: u8 = 1;
+
+/// Generator for the `<gateways>` section describing WAN uplinks
+pub struct GatewayGenerator {
+    wans: Vec<WanInterface>,
+    groups: Vec<GatewayGroup>,
+}
+
+impl GatewayGenerator {
+    /// Create a gateway generator for the given WAN uplinks
+    pub fn new(wans: Vec<WanInterface>) -> Self {
+        Self {
+            wans,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Also emit these groups as `<gateway_group>` entries
+    pub fn with_groups(mut self, groups: Vec<GatewayGroup>) -> Self {
+        self.groups = groups;
+        self
+    }
+}
+
+/// Push `<name>value</name>` onto an event list
+fn push_text_element(events: &mut Vec<Event<'static>>, name: &'static str, value: &str) {
+    events.push(Event::Start(BytesStart::new(name)));
+    events.push(Event::Text(BytesText::new(value).into_owned()));
+    events.push(Event::End(BytesEnd::new(name)));
+}
+
+impl XMLGenerator for GatewayGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Gateway
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = vec![Event::Start(BytesStart::new("gateways"))];
+
+        for wan in &self.wans {
+            let (weight, priority) = wan.monitor.as_ref().map_or(
+                (DEFAULT_GATEWAY_WEIGHT, DEFAULT_GATEWAY_PRIORITY),
+                |monitor| (monitor.weight, monitor.priority),
+            );
+
+            events.push(Event::Start(BytesStart::new("gateway_item")));
+            push_text_element(&mut events, "interface", &wan.interface_name);
+            push_text_element(&mut events, "gateway", &wan.gateway);
+            push_text_element(&mut events, "name", &wan.gateway_name());
+            push_text_element(&mut events, "weight", &weight.to_string());
+            push_text_element(&mut events, "ipprotocol", "inet");
+            push_text_element(&mut events, "descr", &format!("WAN {} uplink", wan.id));
+            match &wan.monitor {
+                Some(monitor) => push_text_element(&mut events, "monitor", &monitor.monitor_ip),
+                None => push_text_element(&mut events, "monitor_disable", "1"),
+            }
+            push_text_element(&mut events, "priority", &priority.to_string());
+            events.push(Event::End(BytesEnd::new("gateway_item")));
+        }
+
+        for group in &self.groups {
+            events.push(Event::Start(BytesStart::new("gateway_group")));
+            push_text_element(&mut events, "name", &group.name);
+            // Items are `gateway|tier|virtual IP`; `address` is the interface address
+            for (gateway, tier) in &group.members {
+                push_text_element(&mut events, "item", &format!("{gateway}|{tier}|address"));
+            }
+            push_text_element(&mut events, "trigger", group.trigger.as_str());
+            push_text_element(
+                &mut events,
+                "descr",
+                &format!("{} gateway group", group.name),
+            );
+            events.push(Event::End(BytesEnd::new("gateway_group")));
+        }
+
+        events.push(Event::End(BytesEnd::new("gateways")));
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        if self.wans.is_empty() {
+            errors.push("No WAN interfaces to describe as gateways".to_string());
+        }
+
+        let mut monitor_ips = std::collections::HashSet::new();
+        for wan in &self.wans {
+            if let Some(monitor) = &wan.monitor {
+                if !monitor_ips.insert(monitor.monitor_ip.as_str()) {
+                    errors.push(format!(
+                        "Monitor IP {} is used by more than one gateway",
+                        monitor.monitor_ip
+                    ));
+                }
+            }
+        }
+
+        let gateway_names: std::collections::HashSet<String> =
+            self.wans.iter().map(WanInterface::gateway_name).collect();
+        for group in &self.groups {
+            for (gateway, tier) in &group.members {
+                if !gateway_names.contains(gateway) {
+                    errors.push(format!(
+                        "Gateway group {} refers to unknown gateway {gateway}",
+                        group.name
+                    ));
+                }
+                if !(1..=MAX_GATEWAY_TIER).contains(tier) {
+                    errors.push(format!(
+                        "Gateway group {} puts {gateway} on tier {tier} (1-{MAX_GATEWAY_TIER})",
+                        group.name
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~384 bytes per gateway item, ~64 per group member
+        let members: usize = self.groups.iter().map(|group| group.members.len()).sum();
+        64 + 384 * self.wans.len() + 128 * self.groups.len() + 64 * members
+    }
+}
+
+/// Firewall alias generator, injected under the template's
+/// `<OPNsense><Firewall><Alias>` model
+pub struct AliasGenerator {
+    aliases: Vec<Alias>,
+}
+
+impl AliasGenerator {
+    /// Create an alias generator for the given aliases
+    pub fn new(aliases: Vec<Alias>) -> Self {
+        Self { aliases }
+    }
+}
+
+impl XMLGenerator for AliasGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Alias
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = vec![Event::Start(BytesStart::new("aliases"))];
+
+        for alias in &self.aliases {
+            events.push(Event::Start(BytesStart::new("alias")));
+            push_text_element(&mut events, "enabled", "1");
+            push_text_element(&mut events, "name", &alias.name);
+            push_text_element(&mut events, "type", &alias.alias_type.to_string());
+            push_text_element(&mut events, "counters", "0");
+            // OPNsense stores alias entries newline-separated
+            push_text_element(&mut events, "content", &alias.entries.join("\n"));
+            push_text_element(&mut events, "description", &alias.description);
+            events.push(Event::End(BytesEnd::new("alias")));
+        }
+
+        events.push(Event::End(BytesEnd::new("aliases")));
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        let mut names = std::collections::HashSet::new();
+        for alias in &self.aliases {
+            if alias.entries.is_empty() {
+                errors.push(format!("Alias {} has no entries", alias.name));
+            }
+            if !names.insert(alias.name.as_str()) {
+                errors.push(format!("Alias name {} is used more than once", alias.name));
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~256 bytes per alias plus its entries
+        let entries: usize = self
+            .aliases
+            .iter()
+            .map(|alias| {
+                alias
+                    .entries
+                    .iter()
+                    .map(|entry| entry.len() + 1)
+                    .sum::<usize>()
+            })
+            .sum();
+        64 + 256 * self.aliases.len() + entries
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("Alias")
+    }
+}
+
+/// Captive portal generator, injected under the template's `<captiveportal>`
+pub struct CaptivePortalGenerator {
+    zones: Vec<CaptivePortalZone>,
+}
+
+impl CaptivePortalGenerator {
+    /// Create a captive portal generator for the given zones
+    pub fn new(zones: Vec<CaptivePortalZone>) -> Self {
+        Self { zones }
+    }
+}
+
+impl XMLGenerator for CaptivePortalGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::CaptivePortal
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = vec![Event::Start(BytesStart::new("zones"))];
+
+        for zone in &self.zones {
+            events.push(Event::Start(BytesStart::new("zone")));
+            push_text_element(&mut events, "enabled", "1");
+            push_text_element(&mut events, "zoneid", &zone.zone_id.to_string());
+            push_text_element(&mut events, "interfaces", &zone.interface);
+            push_text_element(&mut events, "maxclients", &zone.max_clients.to_string());
+            push_text_element(&mut events, "idletimeout", &zone.idle_timeout.to_string());
+            push_text_element(&mut events, "hardtimeout", &zone.hard_timeout.to_string());
+            push_text_element(&mut events, "concurrentlogins", "1");
+            push_text_element(
+                &mut events,
+                "description",
+                &format!("Captive portal for {}", zone.interface),
+            );
+            events.push(Event::End(BytesEnd::new("zone")));
+        }
+
+        events.push(Event::End(BytesEnd::new("zones")));
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        let mut zone_ids = std::collections::HashSet::new();
+        for zone in &self.zones {
+            if !zone_ids.insert(zone.zone_id) {
+                errors.push(format!(
+                    "Captive portal zone {} is used more than once",
+                    zone.zone_id
+                ));
+            }
+            if zone.hard_timeout != 0 && zone.idle_timeout > zone.hard_timeout {
+                errors.push(format!(
+                    "Captive portal zone {} idle timeout exceeds its hard timeout",
+                    zone.zone_id
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~384 bytes per zone
+        64 + 384 * self.zones.len()
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("captiveportal")
+    }
+}
+
+/// Bridge interface generator, injected under the template's `<bridges>`
+pub struct BridgeGenerator {
+    bridges: Vec<Bridge>,
+}
+
+impl BridgeGenerator {
+    /// Create a bridge generator for the given bridges
+    pub fn new(bridges: Vec<Bridge>) -> Self {
+        Self { bridges }
+    }
+}
+
+impl XMLGenerator for BridgeGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Bridge
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = Vec::new();
+
+        for bridge in &self.bridges {
+            let members = bridge.members.join(",");
+            events.push(Event::Start(BytesStart::new("bridged")));
+            push_text_element(&mut events, "bridgeif", &bridge.name);
+            push_text_element(&mut events, "members", &members);
+            push_text_element(
+                &mut events,
+                "descr",
+                &format!("Bridge of {}", bridge.members.join(", ")),
+            );
+            if bridge.stp_enabled {
+                push_text_element(&mut events, "proto", "rstp");
+                push_text_element(&mut events, "stp", &members);
+            }
+            events.push(Event::End(BytesEnd::new("bridged")));
+        }
+
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        let mut names = std::collections::HashSet::new();
+        let mut members = std::collections::HashSet::new();
+        for bridge in &self.bridges {
+            if !names.insert(bridge.name.as_str()) {
+                errors.push(format!("Bridge {} is defined more than once", bridge.name));
+            }
+            if bridge.members.is_empty() {
+                errors.push(format!("Bridge {} has no members", bridge.name));
+            }
+            for member in &bridge.members {
+                if !members.insert(member.as_str()) {
+                    errors.push(format!(
+                        "Interface {member} is a member of more than one bridge"
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~256 bytes per bridge
+        256 * self.bridges.len()
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("bridges")
+    }
+}
+
+/// Severities forwarded to every remote syslog destination
+const SYSLOG_LEVELS: &str = "info,notice,warn,err,crit,alert,emerg";
+
+/// Remote syslog generator, injected under `<OPNsense><Syslog>`
+///
+/// Remote destinations live in the `Syslog` model; the top-level `<syslog>`
+/// element only holds local logging settings.
+pub struct SyslogGenerator {
+    targets: Vec<SyslogTarget>,
+}
+
+impl SyslogGenerator {
+    /// Create a syslog generator for the given remote targets
+    pub fn new(targets: Vec<SyslogTarget>) -> Self {
+        Self { targets }
+    }
+}
+
+impl XMLGenerator for SyslogGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Syslog
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = vec![Event::Start(BytesStart::new("destinations"))];
+        for target in &self.targets {
+            events.push(Event::Start(BytesStart::new("destination")));
+            push_text_element(&mut events, "enabled", "1");
+            push_text_element(&mut events, "transport", target.transport.as_str());
+            push_text_element(&mut events, "level", SYSLOG_LEVELS);
+            push_text_element(&mut events, "facility", &target.facilities.join(","));
+            push_text_element(&mut events, "hostname", &target.host);
+            push_text_element(&mut events, "port", &target.port.to_string());
+            push_text_element(&mut events, "rfc5424", "0");
+            push_text_element(
+                &mut events,
+                "description",
+                &format!("Forward logs to {}", target.host),
+            );
+            events.push(Event::End(BytesEnd::new("destination")));
+        }
+        events.push(Event::End(BytesEnd::new("destinations")));
+
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        let mut destinations = std::collections::HashSet::new();
+        for target in &self.targets {
+            if target.host.is_empty() {
+                errors.push("Syslog target has no host".to_string());
+            }
+            if target.port == 0 {
+                errors.push(format!("Syslog target {} has no port", target.host));
+            }
+            if !destinations.insert((target.host.as_str(), target.port)) {
+                errors.push(format!(
+                    "Syslog target {}:{} is defined more than once",
+                    target.host, target.port
+                ));
+            }
+            for facility in &target.facilities {
+                if !SYSLOG_FACILITIES.contains(&facility.as_str()) {
+                    errors.push(format!(
+                        "Syslog target {} uses unknown facility {facility}",
+                        target.host
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~384 bytes per destination
+        64 + 384 * self.targets.len()
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("Syslog")
+    }
+}
+
+/// Traffic shaper generator, injected under `<OPNsense><TrafficShaper>`
+pub struct TrafficShaperGenerator {
+    shaper: TrafficShaper,
+}
+
+impl TrafficShaperGenerator {
+    /// Create a traffic shaper generator for the given pipes and queues
+    pub fn new(shaper: TrafficShaper) -> Self {
+        Self { shaper }
+    }
+}
+
+impl XMLGenerator for TrafficShaperGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::TrafficShaper
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = vec![Event::Start(BytesStart::new("pipes"))];
+        for pipe in &self.shaper.pipes {
+            events.push(Event::Start(BytesStart::new("pipe")));
+            push_text_element(&mut events, "number", &pipe.pipe_id.to_string());
+            push_text_element(&mut events, "enabled", "1");
+            push_text_element(&mut events, "bandwidth", &pipe.bandwidth_mbit.to_string());
+            push_text_element(&mut events, "bandwidthMetric", "Mbit");
+            push_text_element(&mut events, "scheduler", &pipe.scheduler.to_string());
+            push_text_element(&mut events, "description", &pipe.description);
+            events.push(Event::End(BytesEnd::new("pipe")));
+        }
+        events.push(Event::End(BytesEnd::new("pipes")));
+
+        events.push(Event::Start(BytesStart::new("queues")));
+        for (queue, number) in self.shaper.queues.iter().zip(1u16..) {
+            events.push(Event::Start(BytesStart::new("queue")));
+            push_text_element(&mut events, "number", &number.to_string());
+            push_text_element(&mut events, "enabled", "1");
+            push_text_element(&mut events, "pipe", &queue.pipe_id.to_string());
+            push_text_element(&mut events, "weight", &queue.weight.to_string());
+            push_text_element(&mut events, "priority", &queue.priority.to_string());
+            push_text_element(&mut events, "description", &queue.description);
+            events.push(Event::End(BytesEnd::new("queue")));
+        }
+        events.push(Event::End(BytesEnd::new("queues")));
+
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        let pipe_ids: std::collections::HashSet<_> =
+            self.shaper.pipes.iter().map(|pipe| pipe.pipe_id).collect();
+        if pipe_ids.len() != self.shaper.pipes.len() {
+            errors.push("Shaper pipe numbers must be unique".to_string());
+        }
+        for pipe in &self.shaper.pipes {
+            if pipe.bandwidth_mbit == 0 {
+                errors.push(format!("Shaper pipe {} has no bandwidth", pipe.pipe_id));
+            }
+        }
+        for queue in &self.shaper.queues {
+            if !pipe_ids.contains(&queue.pipe_id) {
+                errors.push(format!(
+                    "Shaper queue references unknown pipe {}",
+                    queue.pipe_id
+                ));
+            }
+            if !(1..=100).contains(&queue.weight) {
+                errors.push(format!(
+                    "Shaper queue weight {} is outside valid range 1-100",
+                    queue.weight
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~320 bytes per pipe or queue
+        64 + 320 * (self.shaper.pipes.len() + self.shaper.queues.len())
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("TrafficShaper")
+    }
+}
+
+/// User and group generator, injected under the template's `<system>`
+///
+/// Groups list their members by UID, as OPNsense stores membership on the
+/// group rather than the user.
+pub struct UserGenerator {
+    accounts: UserAccounts,
+}
+
+impl UserGenerator {
+    /// Create a generator for the given users and groups
+    pub fn new(accounts: UserAccounts) -> Self {
+        Self { accounts }
+    }
+}
+
+impl XMLGenerator for UserGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Users
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = Vec::new();
+
+        for group in &self.accounts.groups {
+            events.push(Event::Start(BytesStart::new("group")));
+            push_text_element(&mut events, "name", &group.name);
+            push_text_element(&mut events, "description", &group.description);
+            push_text_element(&mut events, "scope", "local");
+            push_text_element(&mut events, "gid", &group.gid.to_string());
+            for uid in self.accounts.member_uids(&group.name) {
+                push_text_element(&mut events, "member", &uid.to_string());
+            }
+            for privilege in &group.privileges {
+                push_text_element(&mut events, "priv", privilege);
+            }
+            events.push(Event::End(BytesEnd::new("group")));
+        }
+
+        for user in &self.accounts.users {
+            events.push(Event::Start(BytesStart::new("user")));
+            push_text_element(&mut events, "name", &user.username);
+            push_text_element(&mut events, "descr", &user.full_name);
+            push_text_element(&mut events, "scope", &user.scope.to_string());
+            push_text_element(&mut events, "uid", &user.uid.to_string());
+            events.push(Event::End(BytesEnd::new("user")));
+        }
+
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+
+        let groups: std::collections::HashSet<_> = self
+            .accounts
+            .groups
+            .iter()
+            .map(|group| group.name.as_str())
+            .collect();
+        let mut usernames = std::collections::HashSet::new();
+        for user in &self.accounts.users {
+            if !usernames.insert(user.username.as_str()) {
+                errors.push(format!("Username {} is used more than once", user.username));
+            }
+            for group in &user.groups {
+                if !groups.contains(group.as_str()) {
+                    errors.push(format!(
+                        "User {} references unknown group {group}",
+                        user.username
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~256 bytes per user or group
+        64 + 256 * (self.accounts.users.len() + self.accounts.groups.len())
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("system")
+    }
+}
+
+/// Push an OPNsense `<source>`/`<destination>` block for an address and port
+///
+/// `any` becomes `<any>1</any>`, CIDR blocks `<network>` and anything else
+/// `<address>`. An `any` or empty port is left out.
+fn push_endpoint(events: &mut Vec<Event<'static>>, name: &'static str, address: &str, port: &str) {
+    events.push(Event::Start(BytesStart::new(name)));
+    match address {
+        "any" | "" => push_text_element(events, "any", "1"),
+        network if network.contains('/') => push_text_element(events, "network", network),
+        address => push_text_element(events, "address", address),
+    }
+    if !matches!(port, "any" | "") {
+        push_text_element(events, "port", port);
+    }
+    events.push(Event::End(BytesEnd::new(name)));
+}
+
+/// Firewall rule generator, injected as `<rule>` entries under `<filter>`
+pub struct FilterRuleGenerator {
+    rules: Vec<FirewallRule>,
+}
+
+impl FilterRuleGenerator {
+    /// Create a filter rule generator for the given rules
+    pub fn new(rules: Vec<FirewallRule>) -> Self {
+        Self { rules }
+    }
+}
+
+impl XMLGenerator for FilterRuleGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Firewall
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = Vec::new();
+
+        for rule in &self.rules {
+            events.push(Event::Start(BytesStart::new("rule")));
+            push_text_element(&mut events, "type", &rule.action.to_lowercase());
+            push_text_element(&mut events, "interface", &rule.interface);
+            push_text_element(&mut events, "ipprotocol", &rule.ip_version.to_string());
+            push_text_element(&mut events, "direction", &rule.direction.to_lowercase());
+            if !rule.protocol.eq_ignore_ascii_case("any") {
+                push_text_element(&mut events, "protocol", &rule.protocol.to_lowercase());
+            }
+            if let Some(pf_name) = rule.icmp_type.as_deref().and_then(icmp_type_pf_name) {
+                push_text_element(&mut events, "icmptype", pf_name);
+            }
+            push_endpoint(&mut events, "source", &rule.source, "any");
+            push_endpoint(&mut events, "destination", &rule.destination, &rule.ports);
+            push_text_element(&mut events, "descr", &rule.description);
+            if rule.log {
+                push_text_element(&mut events, "log", "1");
+            }
+            events.push(Event::End(BytesEnd::new("rule")));
+        }
+
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let errors: Vec<String> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.validate().err())
+            .map(|e| e.to_string())
+            .collect();
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~512 bytes per rule
+        64 + 512 * self.rules.len()
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("filter")
+    }
+}
+
+/// NAT rule generator, injected under `<nat>`
+///
+/// Port-forwards and destination NAT become `<rule>` entries, 1:1 mappings
+/// `<onetoone>` entries and source/outbound NAT a hybrid `<outbound>` block.
+pub struct NatRuleGenerator {
+    mappings: Vec<NatMapping>,
+}
+
+impl NatRuleGenerator {
+    /// Create a NAT rule generator for the given mappings
+    pub fn new(mappings: Vec<NatMapping>) -> Self {
+        Self { mappings }
+    }
+}
+
+/// OPNsense protocol value for a NAT mapping protocol
+fn nat_protocol(protocol: &str) -> String {
+    match protocol {
+        "Both" => "tcp/udp".to_string(),
+        other => other.to_lowercase(),
+    }
+}
+
+/// Push the fields shared by every kind of NAT entry
+fn push_nat_common(events: &mut Vec<Event<'static>>, mapping: &NatMapping) {
+    push_text_element(events, "interface", &mapping.interface.to_lowercase());
+    push_text_element(events, "descr", &mapping.name);
+    if !mapping.enabled {
+        push_text_element(events, "disabled", "1");
+    }
+    if mapping.log {
+        push_text_element(events, "log", "1");
+    }
+}
+
+impl XMLGenerator for NatRuleGenerator {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Nat
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let mut events = Vec::new();
+        let mut outbound = Vec::new();
+
+        for mapping in &self.mappings {
+            match mapping.rule_type {
+                NatRuleType::PortForward | NatRuleType::DestinationNat => {
+                    events.push(Event::Start(BytesStart::new("rule")));
+                    push_text_element(&mut events, "protocol", &nat_protocol(&mapping.protocol));
+                    push_text_element(&mut events, "ipprotocol", "inet");
+                    push_text_element(&mut events, "target", &mapping.target_ip);
+                    if !matches!(mapping.target_port.as_str(), "any" | "") {
+                        push_text_element(&mut events, "local-port", &mapping.target_port);
+                    }
+                    push_endpoint(&mut events, "source", &mapping.source, &mapping.source_port);
+                    push_endpoint(
+                        &mut events,
+                        "destination",
+                        &mapping.destination,
+                        &mapping.destination_port,
+                    );
+                    push_nat_common(&mut events, mapping);
+                    events.push(Event::End(BytesEnd::new("rule")));
+                }
+                NatRuleType::OneToOneNat => {
+                    events.push(Event::Start(BytesStart::new("onetoone")));
+                    push_text_element(&mut events, "external", &mapping.target_ip);
+                    push_endpoint(&mut events, "source", &mapping.source, "any");
+                    push_endpoint(&mut events, "destination", &mapping.destination, "any");
+                    push_nat_common(&mut events, mapping);
+                    events.push(Event::End(BytesEnd::new("onetoone")));
+                }
+                NatRuleType::SourceNat | NatRuleType::OutboundNat => {
+                    outbound.push(Event::Start(BytesStart::new("rule")));
+                    push_text_element(&mut outbound, "protocol", &nat_protocol(&mapping.protocol));
+                    push_text_element(&mut outbound, "target", &mapping.target_ip);
+                    push_endpoint(
+                        &mut outbound,
+                        "source",
+                        &mapping.source,
+                        &mapping.source_port,
+                    );
+                    push_endpoint(
+                        &mut outbound,
+                        "destination",
+                        &mapping.destination,
+                        &mapping.destination_port,
+                    );
+                    push_nat_common(&mut outbound, mapping);
+                    outbound.push(Event::End(BytesEnd::new("rule")));
+                }
+            }
+        }
+
+        if !outbound.is_empty() {
+            events.push(Event::Start(BytesStart::new("outbound")));
+            push_text_element(&mut events, "mode", "hybrid");
+            events.extend(outbound);
+            events.push(Event::End(BytesEnd::new("outbound")));
+        }
+
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        let errors: Vec<String> = self
+            .mappings
+            .iter()
+            .filter_map(|mapping| mapping.validate().err())
+            .map(|e| e.to_string())
+            .collect();
+
+        if errors.is_empty() {
+            ValidationResult::valid()
+        } else {
+            ValidationResult::invalid(errors)
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // ~512 bytes per NAT entry
+        64 + 512 * self.mappings.len()
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("nat")
+    }
+}
+
 impl VlanGenerator {
     /// Generate events from template fragment
     fn generate_from_template_fragment(&self, _fragment: &str) -> XMLResult<Vec<Event<'static>>> {
@@ -559,6 +1646,35 @@ fn test_vlan_generator_memory_estimate() {
         assert!(estimate < 10000); // Reasonable upper bound
     }
 
+    #[test]
+    fn test_vlan_generator_emits_qinq_for_stacked_vlan() {
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "Test VLAN".to_string(), 1)
+            .unwrap()
+            .with_outer_vlan_id(200)
+            .unwrap();
+        let events = VlanGenerator::new(config).generate_events().unwrap();
+        let xml = crate::xml::XMLEngine::new().process_events(events).unwrap();
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+
+        let qinq = doc
+            .descendants()
+            .find(|node| node.has_tag_name("qinq"))
+            .expect("qinq element");
+        let text = |name: &str| {
+            qinq.children()
+                .find(|node| node.has_tag_name(name))
+                .and_then(|node| node.text())
+        };
+        assert_eq!(text("tag"), Some("200"));
+        assert_eq!(text("proto"), Some("802.1ad"));
+
+        let plain =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Test VLAN".to_string(), 1).unwrap();
+        let events = VlanGenerator::new(plain).generate_events().unwrap();
+        let xml = crate::xml::XMLEngine::new().process_events(events).unwrap();
+        assert!(!xml.contains("<qinq>"));
+    }
+
     #[test]
     fn test_vlan_generator_events_generation() {
         let config =
@@ -607,4 +1723,103 @@ fn test_vlan_generator_supports_streaming() {
         let generator = VlanGenerator::new(config);
         assert!(generator.supports_streaming());
     }
+
+    #[test]
+    fn test_vlan_generator_maps_wan_assignment() {
+        let wans = crate::generator::wan::generate_wan_interfaces(3, Some(42)).unwrap();
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Test VLAN".to_string(), 2).unwrap();
+        let generator = VlanGenerator::new(config).with_wan_interfaces(wans.clone());
+        assert!(generator.validate_requirements().is_valid);
+
+        let texts: Vec<String> = generator
+            .generate_events()
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Text(text) => Some(String::from_utf8(text.to_vec()).unwrap()),
+                _ => None,
+            })
+            .collect();
+        assert!(texts.contains(&wans[1].gateway_name()));
+        assert!(!texts.contains(&wans[1].public_ip));
+    }
+
+    #[test]
+    fn test_gateway_generator_emits_monitored_gateway_items() {
+        let wans = crate::generator::wan::generate_wan_interfaces(3, Some(42)).unwrap();
+        let generator = GatewayGenerator::new(wans.clone());
+        assert!(generator.validate_requirements().is_valid);
+
+        let events = generator.generate_events().unwrap();
+        let xml = crate::xml::XMLEngine::new().process_events(events).unwrap();
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        assert!(doc.root_element().has_tag_name("gateways"));
+
+        let items: Vec<_> = doc
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("gateway_item"))
+            .collect();
+        assert_eq!(items.len(), 3);
+        for (item, wan) in items.iter().zip(&wans) {
+            let text = |name: &str| {
+                item.children()
+                    .find(|node| node.has_tag_name(name))
+                    .and_then(|node| node.text())
+                    .map(str::to_string)
+            };
+            let monitor = wan.monitor.as_ref().unwrap();
+            assert_eq!(text("interface"), Some(wan.interface_name.clone()));
+            assert_eq!(text("gateway"), Some(wan.gateway.clone()));
+            assert_eq!(text("monitor"), Some(monitor.monitor_ip.clone()));
+            assert_eq!(text("priority"), Some(monitor.priority.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_gateway_generator_emits_gateway_groups() {
+        use crate::generator::gateway_group::generate_gateway_groups;
+
+        let wans = crate::generator::wan::generate_wan_interfaces(3, Some(42)).unwrap();
+        let generator =
+            GatewayGenerator::new(wans.clone()).with_groups(generate_gateway_groups(&wans));
+        assert!(generator.validate_requirements().is_valid);
+
+        let events = generator.generate_events().unwrap();
+        let xml = crate::xml::XMLEngine::new().process_events(events).unwrap();
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let groups: Vec<_> = doc
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("gateway_group"))
+            .collect();
+        assert_eq!(groups.len(), 2);
+
+        let items: Vec<_> = groups[0]
+            .children()
+            .filter(|node| node.has_tag_name("item"))
+            .filter_map(|node| node.text())
+            .collect();
+        assert_eq!(
+            items,
+            ["WAN_GW|1|address", "WAN2_GW|2|address", "WAN3_GW|3|address"]
+        );
+
+        let unknown =
+            GatewayGenerator::new(wans[..1].to_vec()).with_groups(generate_gateway_groups(&wans));
+        assert!(!unknown.validate_requirements().is_valid);
+    }
+
+    #[test]
+    fn test_vlan_generator_rejects_unknown_wan() {
+        let wans = crate::generator::wan::generate_wan_interfaces(1, Some(42)).unwrap();
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "Test VLAN".to_string(), 3).unwrap();
+        let generator = VlanGenerator::new(config).with_wan_interfaces(wans);
+
+        let validation = generator.validate_requirements();
+        assert!(!validation.is_valid);
+        assert!(validation.errors[0].contains("no matching WAN interface"));
+    }
 }
@@ -1,14 +1,14 @@
 //! XML injection mechanisms for structured event injection
 
 use crate::xml::engine::XMLTemplate;
-use crate::xml::error::XMLResult;
+use crate::xml::error::{XMLError, XMLResult};
 use crate::xml::generator::XMLGenerator;
-use quick_xml::events::Event;
-use std::collections::HashMap;
+use quick_xml::Reader;
+use quick_xml::events::{BytesEnd, Event};
+use std::collections::{HashMap, HashSet};
 
 /// XML injector for combining templates with generated components
 pub struct XMLInjector {
-    #[allow(dead_code)]
     template: XMLTemplate,
     generators: Vec<Box<dyn XMLGenerator>>,
     injection_mappings: HashMap<String, String>,
@@ -36,18 +36,109 @@ pub fn add_mapping<K: Into<String>, V: Into<String>>(&mut self, selector: K, tar
     }
 
     /// Inject components into template
+    ///
+    /// Components with an injection target are placed inside each matching
+    /// template element, just before it closes; the remaining components go
+    /// before the root element closes. Without any targeted components the
+    /// generated events are returned on their own.
     pub fn inject_components(&mut self) -> XMLResult<Vec<Event<'static>>> {
+        if self
+            .generators
+            .iter()
+            .all(|generator| generator.injection_target().is_none())
+        {
+            let mut result_events = Vec::new();
+            for generator in &self.generators {
+                let events = generator.generate_events()?;
+                result_events.extend(events);
+            }
+            return Ok(result_events);
+        }
+
+        self.inject_into_template()
+    }
+
+    /// Walk the template and splice component events into their targets
+    fn inject_into_template(&self) -> XMLResult<Vec<Event<'static>>> {
+        let content = &self.template.metadata().original_content;
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(true);
+
         let mut result_events = Vec::new();
+        let mut found_targets = HashSet::new();
+        let mut depth = 0usize;
+
+        loop {
+            match reader.read_event()? {
+                Event::Eof => break,
+                Event::Start(start) => {
+                    depth += 1;
+                    result_events.push(Event::Start(start.into_owned()));
+                }
+                Event::End(end) => {
+                    let name = String::from_utf8_lossy(end.name().as_ref()).into_owned();
+                    if self.inject_for_element(&name, depth == 1, &mut result_events)? {
+                        found_targets.insert(name);
+                    }
+                    depth = depth.saturating_sub(1);
+                    result_events.push(Event::End(end.into_owned()));
+                }
+                Event::Empty(empty) => {
+                    let name = String::from_utf8_lossy(empty.name().as_ref()).into_owned();
+                    if self.is_target(&name) {
+                        // Expand <target/> so components can be placed inside it
+                        result_events.push(Event::Start(empty.into_owned()));
+                        self.inject_for_element(&name, false, &mut result_events)?;
+                        result_events.push(Event::End(BytesEnd::new(name.clone())));
+                        found_targets.insert(name);
+                    } else {
+                        result_events.push(Event::Empty(empty.into_owned()));
+                    }
+                }
+                event => result_events.push(event.into_owned()),
+            }
+        }
 
-        // For now, just combine events from all generators
         for generator in &self.generators {
-            let events = generator.generate_events()?;
-            result_events.extend(events);
+            if let Some(target) = generator.injection_target() {
+                if !found_targets.contains(target) {
+                    return Err(XMLError::injection_point_not_found(target));
+                }
+            }
         }
 
         Ok(result_events)
     }
 
+    /// Whether any component targets the named element
+    fn is_target(&self, name: &str) -> bool {
+        self.generators
+            .iter()
+            .any(|generator| generator.injection_target() == Some(name))
+    }
+
+    /// Append events of components targeting `name` (and untargeted ones at
+    /// the root); returns whether any targeted component matched
+    fn inject_for_element(
+        &self,
+        name: &str,
+        is_root: bool,
+        events: &mut Vec<Event<'static>>,
+    ) -> XMLResult<bool> {
+        let mut matched = false;
+        for generator in &self.generators {
+            match generator.injection_target() {
+                Some(target) if target == name => {
+                    events.extend(generator.generate_events()?);
+                    matched = true;
+                }
+                None if is_root => events.extend(generator.generate_events()?),
+                _ => {}
+            }
+        }
+        Ok(matched)
+    }
+
     /// Stream injection for large configurations
     pub fn stream_inject<W: std::io::Write>(&mut self, _writer: W) -> XMLResult<()> {
         // Placeholder implementation
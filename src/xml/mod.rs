@@ -3,8 +3,12 @@
 pub mod builder;
 pub mod engine;
 pub mod error;
+pub mod format;
 pub mod generator;
 pub mod injection;
+pub mod naming;
+pub mod registry;
+pub mod schema;
 pub mod streaming;
 pub mod template;
 
@@ -13,5 +17,8 @@
 pub use engine::XMLEngine;
 pub use generator::{ComponentType, XMLGenerator};
 pub use injection::XMLInjector;
+pub use naming::XmlNameTemplate;
+pub use registry::{ComponentGenerator, ComponentRegistry};
+pub use schema::SchemaVersion;
 pub use streaming::StreamingXmlGenerator;
 pub use template::{XmlTemplate, escape_xml_string};
@@ -0,0 +1,114 @@
+//! File naming for per-VLAN XML output
+
+use crate::utils::placeholder::PlaceholderTemplate;
+use std::fmt;
+use std::str::FromStr;
+
+/// Placeholders available in XML file name templates
+const PLACEHOLDERS: &[&str] = &["firewall_nr", "vlan_id", "opt_counter", "index"];
+
+/// Placeholders that differ between VLANs, one of which every template needs
+const PER_VLAN_PLACEHOLDERS: &[&str] = &["vlan_id", "opt_counter", "index"];
+
+/// Template for the names of generated XML files
+///
+/// Supports `{firewall_nr}`, `{vlan_id}`, `{opt_counter}` and `{index}`
+/// (0-based position in the batch). The default reproduces the historical
+/// `firewall_{firewall_nr}_vlan_{vlan_id}.xml` naming.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlNameTemplate(PlaceholderTemplate);
+
+impl XmlNameTemplate {
+    /// File name for the VLAN at position `index` of the batch
+    pub fn file_name(
+        &self,
+        firewall_nr: u16,
+        vlan_id: u16,
+        opt_counter: u16,
+        index: usize,
+    ) -> String {
+        self.0.render(|name| match name {
+            "firewall_nr" => firewall_nr.to_string(),
+            "vlan_id" => vlan_id.to_string(),
+            "opt_counter" => opt_counter.to_string(),
+            _ => index.to_string(),
+        })
+    }
+
+    /// Whether `file_name` is one this template produces for `firewall_nr`
+    pub fn matches(&self, file_name: &str, firewall_nr: u16) -> bool {
+        let firewall_nr = firewall_nr.to_string();
+        self.0.matches(file_name, |name, value| match name {
+            "firewall_nr" => value == firewall_nr,
+            _ => value.bytes().all(|b| b.is_ascii_digit()),
+        })
+    }
+}
+
+impl Default for XmlNameTemplate {
+    fn default() -> Self {
+        "firewall_{firewall_nr}_vlan_{vlan_id}.xml"
+            .parse()
+            .expect("default template is valid")
+    }
+}
+
+impl fmt::Display for XmlNameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for XmlNameTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains(['/', '\\']) {
+            return Err(format!(
+                "XML name template '{s}' must be a file name, not a path"
+            ));
+        }
+        let template = PlaceholderTemplate::parse(s, PLACEHOLDERS)?;
+        if !PER_VLAN_PLACEHOLDERS.iter().any(|name| template.uses(name)) {
+            return Err(format!(
+                "XML name template '{s}' must contain {{vlan_id}}, {{opt_counter}} or {{index}} so each VLAN gets its own file"
+            ));
+        }
+        Ok(Self(template))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_legacy_naming() {
+        let template = XmlNameTemplate::default();
+        assert_eq!(template.file_name(1, 100, 6, 0), "firewall_1_vlan_100.xml");
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let template: XmlNameTemplate =
+            "fw-{firewall_nr}-vlan-{vlan_id}-opt{opt_counter}-{index}.xml"
+                .parse()
+                .unwrap();
+        assert_eq!(template.file_name(2, 300, 8, 2), "fw-2-vlan-300-opt8-2.xml");
+    }
+
+    #[test]
+    fn test_matches_generated_names() {
+        let template = XmlNameTemplate::default();
+        assert!(template.matches("firewall_1_vlan_100.xml", 1));
+        assert!(!template.matches("firewall_2_vlan_100.xml", 1));
+        assert!(!template.matches("firewall_1_rules.csv", 1));
+    }
+
+    #[test]
+    fn test_rejects_invalid_templates() {
+        assert!("fw-{vlan}.xml".parse::<XmlNameTemplate>().is_err());
+        assert!("fw-{firewall_nr}.xml".parse::<XmlNameTemplate>().is_err());
+        assert!("out/{vlan_id}.xml".parse::<XmlNameTemplate>().is_err());
+    }
+}
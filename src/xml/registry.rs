@@ -0,0 +1,246 @@
+//! Registry of per-VLAN XML components, open to user-defined generators
+//!
+//! [`XMLGenerator`] implementations are bound to a fixed [`ComponentType`].
+//! A [`ComponentRegistry`] instead holds [`ComponentGenerator`]s keyed by
+//! name, so library users can add components of their own without touching
+//! this crate. [`OPNsenseConfigBuilder::add_vlans`] emits every registered
+//! component for each VLAN.
+//!
+//! [`OPNsenseConfigBuilder::add_vlans`]: crate::xml::builder::OPNsenseConfigBuilder::add_vlans
+
+use crate::generator::VlanConfig;
+use crate::xml::engine::XMLEngine;
+use crate::xml::error::XMLResult;
+use crate::xml::generator::{ComponentType, ValidationResult, VlanGenerator, XMLGenerator};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::sync::Arc;
+
+/// Generator for one kind of XML fragment derived from a VLAN
+pub trait ComponentGenerator: Send + Sync {
+    /// Name identifying the component, e.g. `vlan`
+    fn component_type(&self) -> &str;
+
+    /// XML fragment for the VLAN
+    fn generate_xml(&self, config: &VlanConfig) -> XMLResult<String>;
+
+    /// Template element the fragment is injected into (e.g. `vlans`)
+    ///
+    /// `None` places the fragment directly under the document root.
+    fn injection_target(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Built-in `vlan` component: the `<vlan>` element with its DHCP settings
+struct BuiltinVlanComponent;
+
+impl ComponentGenerator for BuiltinVlanComponent {
+    fn component_type(&self) -> &str {
+        "vlan"
+    }
+
+    fn generate_xml(&self, config: &VlanConfig) -> XMLResult<String> {
+        let events = VlanGenerator::new(config.clone()).generate_events()?;
+        XMLEngine::new().process_events(events)
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        Some("vlans")
+    }
+}
+
+/// A registered component
+///
+/// The built-in VLAN component is kept apart so the config builder can
+/// emit it with its parent interface and schema version.
+#[derive(Clone)]
+pub(crate) enum Component {
+    BuiltinVlan,
+    Custom(Arc<dyn ComponentGenerator>),
+}
+
+impl Component {
+    fn generator(&self) -> &dyn ComponentGenerator {
+        match self {
+            Self::BuiltinVlan => &BuiltinVlanComponent,
+            Self::Custom(generator) => generator.as_ref(),
+        }
+    }
+}
+
+/// Ordered set of component generators
+///
+/// Fragments are emitted in registration order. Registering a generator
+/// under a name already in use replaces the earlier one in place, which
+/// lets users override built-in components.
+#[derive(Clone)]
+pub struct ComponentRegistry {
+    generators: Vec<Component>,
+}
+
+impl ComponentRegistry {
+    /// Create a registry holding the built-in components
+    pub fn new() -> Self {
+        Self {
+            generators: vec![Component::BuiltinVlan],
+        }
+    }
+
+    /// Create a registry without any components
+    pub fn empty() -> Self {
+        Self {
+            generators: Vec::new(),
+        }
+    }
+
+    /// Add a generator, replacing any registered under the same name
+    pub fn register<G: ComponentGenerator + 'static>(&mut self, generator: G) {
+        let generator = Component::Custom(Arc::new(generator));
+        match self.generators.iter_mut().find(|existing| {
+            existing.generator().component_type() == generator.generator().component_type()
+        }) {
+            Some(existing) => *existing = generator,
+            None => self.generators.push(generator),
+        }
+    }
+
+    /// Names of the registered components in output order
+    pub fn component_types(&self) -> Vec<&str> {
+        self.generators
+            .iter()
+            .map(|generator| generator.generator().component_type())
+            .collect()
+    }
+
+    /// Look up a generator by name
+    pub fn get(&self, component_type: &str) -> Option<&dyn ComponentGenerator> {
+        self.generators
+            .iter()
+            .map(Component::generator)
+            .find(|generator| generator.component_type() == component_type)
+    }
+
+    /// Concatenate every component's fragment for the VLAN
+    pub fn generate_xml(&self, config: &VlanConfig) -> XMLResult<String> {
+        let mut xml = String::new();
+        for generator in &self.generators {
+            xml.push_str(&generator.generator().generate_xml(config)?);
+        }
+        Ok(xml)
+    }
+
+    /// Registered components in output order
+    pub(crate) fn components(&self) -> &[Component] {
+        &self.generators
+    }
+}
+
+/// [`XMLGenerator`] emitting a registered component's fragment for one VLAN
+pub(crate) struct RegisteredComponent {
+    generator: Arc<dyn ComponentGenerator>,
+    config: VlanConfig,
+}
+
+impl RegisteredComponent {
+    pub(crate) fn new(generator: Arc<dyn ComponentGenerator>, config: VlanConfig) -> Self {
+        Self { generator, config }
+    }
+}
+
+impl XMLGenerator for RegisteredComponent {
+    fn component_type(&self) -> ComponentType {
+        ComponentType::Custom(self.generator.component_type().to_string())
+    }
+
+    fn generate_events(&self) -> XMLResult<Vec<Event<'static>>> {
+        let xml = self.generator.generate_xml(&self.config)?;
+        let mut reader = Reader::from_str(&xml);
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event()? {
+                Event::Eof => break,
+                event => events.push(event.into_owned()),
+            }
+        }
+        Ok(events)
+    }
+
+    fn validate_requirements(&self) -> ValidationResult {
+        ValidationResult::valid()
+    }
+
+    fn memory_estimate(&self) -> usize {
+        // Fragment size is unknown until generated: ~512 bytes, like a VLAN
+        512
+    }
+
+    fn injection_target(&self) -> Option<&str> {
+        self.generator.injection_target()
+    }
+}
+
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SnmpComponent;
+
+    impl ComponentGenerator for SnmpComponent {
+        fn component_type(&self) -> &str {
+            "snmp"
+        }
+
+        fn generate_xml(&self, config: &VlanConfig) -> XMLResult<String> {
+            Ok(format!(
+                "<snmp><community>vlan{}</community></snmp>",
+                config.vlan_id
+            ))
+        }
+    }
+
+    #[test]
+    fn test_custom_generator_appears_in_output() {
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
+        let mut registry = ComponentRegistry::new();
+        assert_eq!(registry.component_types(), ["vlan"]);
+
+        registry.register(SnmpComponent);
+        assert_eq!(registry.component_types(), ["vlan", "snmp"]);
+        assert!(registry.get("snmp").is_some());
+
+        let xml = registry.generate_xml(&config).unwrap();
+        assert!(xml.starts_with("<vlan><vlanid>100</vlanid>"), "{xml}");
+        assert!(
+            xml.ends_with("<snmp><community>vlan100</community></snmp>"),
+            "{xml}"
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_same_component_type() {
+        struct QuietVlan;
+
+        impl ComponentGenerator for QuietVlan {
+            fn component_type(&self) -> &str {
+                "vlan"
+            }
+
+            fn generate_xml(&self, _config: &VlanConfig) -> XMLResult<String> {
+                Ok(String::new())
+            }
+        }
+
+        let config = VlanConfig::new(100, "10.1.2.x".to_string(), "IT 100".to_string(), 1).unwrap();
+        let mut registry = ComponentRegistry::new();
+        registry.register(QuietVlan);
+        assert_eq!(registry.component_types(), ["vlan"]);
+        assert_eq!(registry.generate_xml(&config).unwrap(), "");
+    }
+}
@@ -0,0 +1,99 @@
+//! OPNsense release targeting for generated XML
+//!
+//! Element names in `config.xml` occasionally change between OPNsense
+//! releases. [`SchemaVersion`] selects which release's names the XML
+//! generators emit; it defaults to the latest supported release.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// OPNsense release whose configuration schema generated XML follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SchemaVersion {
+    /// OPNsense 23.7
+    V23_7,
+    /// OPNsense 24.1
+    V24_1,
+    /// OPNsense 24.7
+    #[default]
+    V24_7,
+}
+
+impl SchemaVersion {
+    /// Every supported release, oldest first
+    pub const ALL: &'static [SchemaVersion] = &[
+        SchemaVersion::V23_7,
+        SchemaVersion::V24_1,
+        SchemaVersion::V24_7,
+    ];
+
+    /// The newest supported release
+    pub fn latest() -> Self {
+        Self::default()
+    }
+
+    /// Release string as written in `<version>`, e.g. `24.1`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaVersion::V23_7 => "23.7",
+            SchemaVersion::V24_1 => "24.1",
+            SchemaVersion::V24_7 => "24.7",
+        }
+    }
+
+    /// Element holding a VLAN's 802.1p priority
+    ///
+    /// 23.x stores it as `<priority>`; 24.1 renamed it to `<pcp>`.
+    pub fn vlan_priority_element(&self) -> &'static str {
+        match self {
+            SchemaVersion::V23_7 => "priority",
+            SchemaVersion::V24_1 | SchemaVersion::V24_7 => "pcp",
+        }
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = String;
+
+    /// Parse a release such as `24.1`; point releases like `24.1.3` select
+    /// their major release
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|version| {
+                trimmed == version.as_str()
+                    || trimmed
+                        .strip_prefix(version.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .ok_or_else(|| {
+                let supported: Vec<&str> = Self::ALL.iter().map(|v| v.as_str()).collect();
+                format!(
+                    "Unsupported OPNsense version '{s}' (supported: {})",
+                    supported.join(", ")
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!("24.1".parse(), Ok(SchemaVersion::V24_1));
+        assert_eq!("23.7.12".parse(), Ok(SchemaVersion::V23_7));
+        assert_eq!(SchemaVersion::latest().to_string(), "24.7");
+        assert!("24.10".parse::<SchemaVersion>().is_err());
+        assert!("22.1".parse::<SchemaVersion>().is_err());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::Result;
 use crate::generator::VlanConfig;
+use crate::generator::wan::WanInterface;
 use crate::model::ConfigError;
 
 /// XML template processor for OPNsense configurations
@@ -25,6 +26,23 @@ pub fn new(base_content: String) -> Result<Self> {
     }
 
     /// Apply a VLAN configuration to generate an XML configuration
+    ///
+    /// Every occurrence of these placeholders is replaced:
+    ///
+    /// - `{{VLAN_ID}}`: VLAN tag, e.g. `100`
+    /// - `{{DESCRIPTION}}`: VLAN description
+    /// - `{{IP_NETWORK}}`: network as stored, e.g. `10.1.2.x`
+    /// - `{{NETWORK}}`: network in CIDR notation, e.g. `10.1.2.0/24`
+    /// - `{{GATEWAY}}` / `{{GATEWAY_IP}}`: first host address, e.g. `10.1.2.1`
+    /// - `{{DHCP_START}}` / `{{DHCP_END}}`: DHCP pool bounds
+    /// - `{{DOMAIN}}`: department DHCP domain, e.g. `it.company.local`
+    /// - `{{WAN}}`: WAN interface name, e.g. `wan2`
+    /// - `{{WAN_ASSIGNMENT}}`: WAN number, e.g. `2`
+    /// - `{{FIREWALL_NR}}` / `{{OPT_COUNTER}}`: the given arguments
+    ///
+    /// A network-derived placeholder that the template uses but the
+    /// configuration's network cannot fill (e.g. an unparsable network) is an
+    /// error, rather than being left in the output.
     pub fn apply_configuration(
         &self,
         config: &VlanConfig,
@@ -43,27 +61,61 @@ pub fn apply_configuration(
         result = result.replace("{{IP_NETWORK}}", &escape_xml_string(&config.ip_network));
         result = result.replace("{{DESCRIPTION}}", &escape_xml_string(&config.description));
         result = result.replace("{{WAN_ASSIGNMENT}}", &config.wan_assignment.to_string());
+        result = result.replace(
+            "{{WAN}}",
+            &WanInterface::interface_name_for(config.wan_assignment),
+        );
+        result = result.replace("{{DOMAIN}}", &escape_xml_string(&config.dhcp_domain_name()));
         result = result.replace("{{FIREWALL_NR}}", &firewall_nr.to_string());
         result = result.replace("{{OPT_COUNTER}}", &opt_counter.to_string());
 
-        // Add gateway IP if possible
-        if let Ok(gateway) = config.gateway_ip() {
-            result = result.replace("{{GATEWAY_IP}}", &escape_xml_string(&gateway));
-        }
-
-        // Add DHCP range if possible
-        if let Ok(dhcp_start) = config.dhcp_range_start() {
-            result = result.replace("{{DHCP_START}}", &escape_xml_string(&dhcp_start));
-        }
-
-        if let Ok(dhcp_end) = config.dhcp_range_end() {
-            result = result.replace("{{DHCP_END}}", &escape_xml_string(&dhcp_end));
-        }
+        fill_derived(&mut result, &["{{NETWORK}}"], config, || {
+            config
+                .as_ipv4_network()
+                .map(|network| network.to_string())
+                .map_err(|e| ConfigError::validation(e.to_string()))
+        })?;
+        fill_derived(
+            &mut result,
+            &["{{GATEWAY_IP}}", "{{GATEWAY}}"],
+            config,
+            || config.gateway_ip(),
+        )?;
+        fill_derived(&mut result, &["{{DHCP_START}}"], config, || {
+            config.dhcp_range_start()
+        })?;
+        fill_derived(&mut result, &["{{DHCP_END}}"], config, || {
+            config.dhcp_range_end()
+        })?;
 
         Ok(result)
     }
 }
 
+/// Replace network-derived `placeholders` with `value`, which is only
+/// computed when the template uses one of them
+fn fill_derived(
+    result: &mut String,
+    placeholders: &[&str],
+    config: &VlanConfig,
+    value: impl FnOnce() -> Result<String>,
+) -> Result<()> {
+    let Some(first) = placeholders.iter().find(|p| result.contains(**p)) else {
+        return Ok(());
+    };
+    let value = value().map_err(|e| {
+        ConfigError::xml_template(format!(
+            "Cannot fill {first} for VLAN {}: {e}",
+            config.vlan_id
+        ))
+    })?;
+    let value = escape_xml_string(&value);
+    for placeholder in placeholders {
+        *result = result.replace(placeholder, &value);
+    }
+    Ok(())
+}
+
 /// Escape XML special characters in a string
 ///
 /// Single-pass implementation: iterates the input once, avoiding 12 intermediate
@@ -133,6 +185,72 @@ fn test_apply_configuration() {
         assert!(result.contains("<gateway>10.1.2.1</gateway>"));
     }
 
+    #[test]
+    fn test_apply_configuration_replaces_every_placeholder() {
+        let xml_content = r#"<vlan>
+    <tag>{{VLAN_ID}}</tag>
+    <descr>{{DESCRIPTION}}</descr>
+    <range>{{IP_NETWORK}}</range>
+    <network>{{NETWORK}}</network>
+    <gateway>{{GATEWAY}}</gateway>
+    <gatewayip>{{GATEWAY_IP}}</gatewayip>
+    <from>{{DHCP_START}}</from>
+    <to>{{DHCP_END}}</to>
+    <domain>{{DOMAIN}}</domain>
+    <wan>{{WAN}}</wan>
+    <wannr>{{WAN_ASSIGNMENT}}</wannr>
+    <fw>{{FIREWALL_NR}}</fw>
+    <opt>{{OPT_COUNTER}}</opt>
+</vlan>"#;
+
+        let template = XmlTemplate::new(xml_content.to_string()).unwrap();
+        let config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 2).unwrap();
+
+        let result = template.apply_configuration(&config, 3, 7).unwrap();
+
+        assert!(!result.contains("{{"), "{result}");
+        for expected in [
+            "<tag>100</tag>",
+            "<descr>IT VLAN 100</descr>",
+            "<range>10.1.2.x</range>",
+            "<network>10.1.2.0/24</network>",
+            "<gateway>10.1.2.1</gateway>",
+            "<gatewayip>10.1.2.1</gatewayip>",
+            "<from>10.1.2.100</from>",
+            "<to>10.1.2.200</to>",
+            "<domain>it.company.local</domain>",
+            "<wan>wan2</wan>",
+            "<wannr>2</wannr>",
+            "<fw>3</fw>",
+            "<opt>7</opt>",
+        ] {
+            assert!(result.contains(expected), "missing {expected} in {result}");
+        }
+    }
+
+    #[test]
+    fn test_unfillable_placeholder_is_an_error() {
+        let template = XmlTemplate::new("<gateway>{{GATEWAY}}</gateway>".to_string()).unwrap();
+        let mut config =
+            VlanConfig::new(100, "10.1.2.x".to_string(), "IT VLAN 100".to_string(), 1).unwrap();
+        config.ip_network = "not-a-network".to_string();
+
+        let err = template.apply_configuration(&config, 1, 6).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Cannot fill {{GATEWAY}} for VLAN 100"),
+            "{err}"
+        );
+
+        // Templates without network-derived placeholders still render
+        let template = XmlTemplate::new("<tag>{{VLAN_ID}}</tag>".to_string()).unwrap();
+        assert_eq!(
+            template.apply_configuration(&config, 1, 6).unwrap(),
+            "<tag>100</tag>"
+        );
+    }
+
     #[test]
     fn test_escape_xml_string() {
         assert_eq!(escape_xml_string("Hello & World"), "Hello &amp; World");
@@ -5,6 +5,7 @@
 
 use assert_cmd::Command;
 use assert_fs::TempDir as AssertTempDir;
+use opnsense_config_faker::generator::VlanConfig;
 use regex::Regex;
 use std::collections::HashMap;
 use std::ffi::OsStr;
@@ -94,6 +95,13 @@ pub fn env<K, V>(mut self, key: K, val: V) -> Self
         self
     }
 
+    /// Remove a variable inherited from the test environment
+    #[allow(dead_code)]
+    pub fn env_remove<K: AsRef<OsStr>>(mut self, key: K) -> Self {
+        self.command.env_remove(key);
+        self
+    }
+
     /// Set the current working directory
     #[allow(dead_code)]
     pub fn current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
@@ -492,6 +500,30 @@ fn assert_vlan_generation_success(&self, count: u32) -> &Self {
     }
 }
 
+/// Assert that VLANs read back from XML match the originals
+///
+/// Compares every field the `<vlan>` element carries (ID, network, prefix,
+/// description and QinQ tag) in order. WAN assignment and DHCP pool are not
+/// written to the element, so they are ignored.
+#[allow(dead_code)]
+pub fn assert_vlans_round_trip(original: &[VlanConfig], extracted: &[VlanConfig]) {
+    let key = |config: &VlanConfig| {
+        (
+            config.vlan_id,
+            config.ip_network.clone(),
+            config.prefix_len,
+            config.description.clone(),
+            config.outer_vlan_id,
+        )
+    };
+    let original: Vec<_> = original.iter().map(key).collect();
+    let extracted: Vec<_> = extracted.iter().map(key).collect();
+    assert_eq!(
+        extracted, original,
+        "VLANs did not survive the XML round trip"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
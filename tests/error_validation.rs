@@ -32,7 +32,7 @@ fn test_resource_exhaustion_vlan_ids_exceed_maximum() {
     match result.unwrap_err() {
         ConfigError::ResourceExhausted { resource } => {
             assert_eq!(
-                resource, "VLAN IDs",
+                resource, "VLAN IDs (gave up after 1000 attempts)",
                 "Expected VLAN IDs resource exhaustion"
             );
         }
@@ -159,6 +159,52 @@ fn test_invalid_output_file_permissions_without_force() {
     );
 }
 
+#[test]
+fn test_existing_output_without_force_returns_file_exists() {
+    use clap::Parser;
+    use opnsense_config_faker::cli::{Cli, Commands};
+
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.child("existing.csv");
+    output_file.write_str("existing,content\n1,2").unwrap();
+
+    let cli = Cli::try_parse_from([
+        "opnsense-config-faker",
+        "generate",
+        "--format",
+        "csv",
+        "--count",
+        "5",
+        "--output",
+        output_file.path().to_str().unwrap(),
+    ])
+    .unwrap();
+    let Commands::Generate(args) = cli.command else {
+        panic!("expected the generate command");
+    };
+
+    let error =
+        opnsense_config_faker::cli::commands::generate::execute_with_global(*args, &cli.global)
+            .expect_err("writing over an existing file without --force must fail");
+    let config_error = error
+        .downcast_ref::<ConfigError>()
+        .expect("error is a ConfigError");
+
+    let expected_path = output_file.path().display().to_string();
+    assert!(
+        matches!(config_error, ConfigError::FileExists { path } if *path == expected_path),
+        "unexpected error: {config_error:?}"
+    );
+    assert_eq!(
+        config_error.to_string(),
+        format!("Output file already exists. Use --force to overwrite: {expected_path}")
+    );
+    assert_eq!(
+        fs::read_to_string(output_file.path()).unwrap(),
+        "existing,content\n1,2"
+    );
+}
+
 #[test]
 #[cfg(unix)]
 fn test_invalid_output_directory_permissions() {
@@ -259,6 +259,2104 @@ fn test_generate_csv_missing_output_fails() {
     assert_no_ansi_escapes(&output.stderr);
 }
 
+// ===== Generate command with YAML format tests =====
+
+#[test]
+fn test_generate_yaml_and_reingest_for_xml() {
+    let (temp_dir, base_config_path, _temp_file) = create_test_base_config();
+    let yaml_file = temp_dir.path().join("vlans.yaml");
+    let output_dir = temp_dir.path().join("yaml_xml_output");
+
+    let output = cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("yaml")
+        .arg("--count")
+        .arg("3")
+        .arg("--output")
+        .arg(&yaml_file)
+        .arg("--seed")
+        .arg("42")
+        .run_success();
+
+    let content = fs::read_to_string(&yaml_file).unwrap();
+    assert!(content.starts_with("vlans:"));
+    assert_eq!(content.matches("vlan_id:").count(), 3);
+
+    // Generated YAML can drive XML generation like --csv-file
+    let output_xml = cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("xml")
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--yaml-file")
+        .arg(&yaml_file)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .run_success();
+
+    let files: Vec<_> = fs::read_dir(&output_dir).unwrap().collect();
+    assert_eq!(files.len(), 3, "Expected one XML file per YAML VLAN");
+
+    // Verify no ANSI escapes
+    assert_no_ansi_escapes(&output.stdout);
+    assert_no_ansi_escapes(&output_xml.stdout);
+}
+
+#[test]
+fn test_generate_toml_and_reingest_for_xml() {
+    let (temp_dir, base_config_path, _temp_file) = create_test_base_config();
+    let toml_file = temp_dir.path().join("vlans.toml");
+    let output_dir = temp_dir.path().join("toml_xml_output");
+
+    cli_command()
+        .args([
+            "generate", "--format", "toml", "--count", "3", "--seed", "42",
+        ])
+        .arg("--output")
+        .arg(&toml_file)
+        .run_success();
+
+    let content = fs::read_to_string(&toml_file).unwrap();
+    assert!(content.starts_with("[[vlan]]"));
+    assert_eq!(content.matches("[[vlan]]").count(), 3);
+
+    cli_command()
+        .args(["generate", "--format", "xml"])
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--toml-file")
+        .arg(&toml_file)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .run_success();
+
+    let files: Vec<_> = fs::read_dir(&output_dir).unwrap().collect();
+    assert_eq!(files.len(), 3, "Expected one XML file per TOML VLAN");
+}
+
+#[test]
+fn test_generate_csv_stream_matches_batch_output() {
+    let temp_dir = create_temp_dir("csv_stream_test");
+    let batch_file = temp_dir.path().join("batch.csv");
+    let stream_file = temp_dir.path().join("stream.csv");
+
+    let base_args = [
+        "generate", "--format", "csv", "--count", "50", "--seed", "42",
+    ];
+    cli_command()
+        .args(base_args)
+        .arg("--output")
+        .arg(&batch_file)
+        .run_success();
+    cli_command()
+        .args(base_args)
+        .arg("--output")
+        .arg(&stream_file)
+        .arg("--stream")
+        .run_success();
+
+    assert_eq!(
+        fs::read_to_string(&batch_file).unwrap(),
+        fs::read_to_string(&stream_file).unwrap()
+    );
+}
+
+#[test]
+fn test_generate_csv_preview_prints_table_to_stderr() {
+    let temp_dir = create_temp_dir("preview_test");
+    let output_file = temp_dir.path().join("preview.csv");
+
+    let output = cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("csv")
+        .arg("--count")
+        .arg("5")
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--seed")
+        .arg("42")
+        .arg("--preview")
+        .arg("--no-color")
+        .run_success();
+
+    let stderr = output.normalized_stderr();
+    assert!(stderr.contains("| VLAN | Network"));
+    for config in opnsense_config_faker::io::csv::read_csv(&output_file).unwrap() {
+        assert!(stderr.contains(&config.vlan_id.to_string()));
+        assert!(stderr.contains(&config.ip_network));
+    }
+}
+
+#[test]
+fn test_generate_csv_with_departments_file() {
+    let temp_dir = create_temp_dir("departments_file_test");
+    let departments_file = temp_dir.path().join("departments.txt");
+    let output_file = temp_dir.path().join("departments.csv");
+    fs::write(&departments_file, "Platform\nFacilities\nStudio\n").unwrap();
+
+    cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("csv")
+        .arg("--count")
+        .arg("20")
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--seed")
+        .arg("42")
+        .arg("--departments-file")
+        .arg(&departments_file)
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 20);
+    for config in configs {
+        let department = config.description.split(" VLAN ").next().unwrap();
+        assert!(["Platform", "Facilities", "Studio"].contains(&department));
+    }
+}
+
+#[test]
+fn test_generate_csv_with_missing_departments_file() {
+    let temp_dir = create_temp_dir("departments_missing_test");
+
+    let output = cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("csv")
+        .arg("--output")
+        .arg(temp_dir.path().join("out.csv"))
+        .arg("--departments-file")
+        .arg(temp_dir.path().join("missing.txt"))
+        .run_failure();
+
+    assert!(
+        output
+            .normalized_stderr()
+            .contains("Configuration file not found")
+    );
+}
+
+#[test]
+fn test_generate_csv_append_keeps_vlan_ids_unique() {
+    let temp_dir = create_temp_dir("append_test");
+    let output_file = temp_dir.path().join("append.csv");
+
+    for (count, extra) in [("3", None), ("5", Some("--append"))] {
+        let mut cmd = cli_command()
+            .arg("generate")
+            .arg("--format")
+            .arg("csv")
+            .arg("--count")
+            .arg(count)
+            .arg("--output")
+            .arg(&output_file)
+            .arg("--seed")
+            .arg("42");
+        if let Some(flag) = extra {
+            cmd = cmd.arg(flag);
+        }
+        cmd.run_success();
+    }
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 8);
+    let unique_ids: std::collections::HashSet<u16> =
+        configs.iter().map(|config| config.vlan_id).collect();
+    assert_eq!(unique_ids.len(), 8);
+}
+
+#[test]
+fn test_validate_against_reports_shared_vlan_id() {
+    let temp_dir = create_temp_dir("validate_against_test");
+    let site_a = temp_dir.path().join("site_a.csv");
+    let site_b = temp_dir.path().join("site_b.csv");
+    fs::write(
+        &site_a,
+        "VLAN,IP Range,Beschreibung,WAN\n100,10.1.2.x,IT VLAN 100,1\n200,10.3.4.x,Sales VLAN 200,2\n",
+    )
+    .unwrap();
+    fs::write(
+        &site_b,
+        "VLAN,IP Range,Beschreibung,WAN\n300,10.9.9.x,HR VLAN 300,1\n100,10.7.8.x,QA VLAN 100,3\n",
+    )
+    .unwrap();
+
+    let output = cli_command()
+        .arg("validate")
+        .arg("--input")
+        .arg(&site_a)
+        .arg("--against")
+        .arg(&site_b)
+        .run_failure();
+
+    let stderr = output.normalized_stderr();
+    assert!(
+        stderr.contains("Conflict: VLAN ID 100 is used by both files"),
+        "unexpected stderr: {stderr}"
+    );
+    // Temp paths are normalized away; line numbers count the header as line 1
+    assert!(stderr.contains("line 2, <TEMP_FILE> line 3)"));
+    assert!(!stderr.contains("IP network"));
+}
+
+#[test]
+fn test_generate_csv_exclude_networks() {
+    let temp_dir = create_temp_dir("exclude_networks_test");
+    let output_file = temp_dir.path().join("excluded.csv");
+
+    cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("csv")
+        .arg("--count")
+        .arg("50")
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--seed")
+        .arg("42")
+        .arg("--exclude-networks")
+        .arg("10.0.0.0/9")
+        .run_success();
+
+    for config in opnsense_config_faker::io::csv::read_csv(&output_file).unwrap() {
+        let second_octet: u8 = config
+            .ip_network
+            .split('.')
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(second_octet >= 128, "{} is excluded", config.ip_network);
+    }
+}
+
+#[test]
+fn test_generate_csv_per_department_counts() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("departments.csv");
+
+    cli_command()
+        .args([
+            "generate",
+            "--per-department",
+            "IT=2,Sales=3",
+            "--format",
+            "csv",
+            "--output",
+            output_file.to_str().unwrap(),
+            "--seed",
+            "42",
+        ])
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 5);
+    let count_of = |department: &str| {
+        configs
+            .iter()
+            .filter(|c| c.description.starts_with(&format!("{department} VLAN ")))
+            .count()
+    };
+    assert_eq!(count_of("IT"), 2);
+    assert_eq!(count_of("Sales"), 3);
+}
+
+#[test]
+fn test_generate_csv_header_comment() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("commented.csv");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "5", "--seed", "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--csv-header-comment")
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    let first_line = content.lines().next().unwrap();
+    assert!(
+        first_line.starts_with("# Generated by opnsense-config-faker v"),
+        "{first_line}"
+    );
+    assert!(first_line.ends_with("seed=42, count=5"), "{first_line}");
+    assert!(content.lines().nth(1).unwrap().starts_with("VLAN,"));
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 5);
+}
+
+#[test]
+fn test_generate_p2p_links_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "3", "--seed", "42",
+        ])
+        .args(["--p2p-links", "5"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(temp_dir.path().join("vlans_p2p_links.csv")).unwrap();
+    let mut lines = content.lines();
+    assert_eq!(lines.next(), Some("local_ip,peer_ip,prefix"));
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(rows.len(), 5);
+    assert!(rows.iter().all(|row| row.ends_with(",31")), "{content}");
+}
+
+#[test]
+fn test_generate_p2p_links_avoid_vlan_networks() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    // The supernet is the transit block the links are carved from
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "200", "--seed", "1",
+        ])
+        .args([
+            "--supernet",
+            "10.0.0.0/16",
+            "--p2p-links",
+            "200",
+            "--output",
+        ])
+        .arg(&output_file)
+        .run_success();
+
+    let vlan_prefixes: std::collections::HashSet<String> =
+        opnsense_config_faker::io::csv::read_csv(&output_file)
+            .unwrap()
+            .iter()
+            .map(|config| config.ip_network.trim_end_matches('x').to_string())
+            .collect();
+    let content = fs::read_to_string(temp_dir.path().join("vlans_p2p_links.csv")).unwrap();
+    let links: Vec<&str> = content.lines().skip(1).collect();
+    assert_eq!(links.len(), 200);
+    for link in links {
+        let (prefix, _) = link.rsplit_once('.').unwrap();
+        assert!(
+            !vlan_prefixes.contains(&format!("{prefix}.")),
+            "{link} lies in a VLAN network"
+        );
+    }
+}
+
+#[test]
+fn test_generate_small_office_preset() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("small-office.csv");
+
+    cli_command()
+        .args(["generate", "--preset", "small-office", "--format", "csv"])
+        .arg("--output")
+        .arg(&output_file)
+        .args(["--seed", "42"])
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert!(
+        (1..=10).contains(&configs.len()),
+        "{} VLANs is not a small office",
+        configs.len()
+    );
+    for config in &configs {
+        assert!(
+            config.ip_network.starts_with("192.168."),
+            "{} is not a Class C network",
+            config.ip_network
+        );
+    }
+}
+
+#[test]
+fn test_generate_csv_manifest_records_invocation() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+    let manifest_file = temp_dir.path().join("vlans.manifest.json");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "7", "--seed", "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--manifest")
+        .arg(&manifest_file)
+        .run_success();
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_file).unwrap()).unwrap();
+    assert_eq!(manifest["count"], 7);
+    assert_eq!(manifest["seed"], 42);
+    assert_eq!(manifest["format"], "csv");
+    assert_eq!(manifest["tool_version"], opnsense_config_faker::VERSION);
+    assert_eq!(
+        manifest["outputs"][0]["sha256"],
+        opnsense_config_faker::cli::manifest::sha256_file(&output_file).unwrap()
+    );
+}
+
+#[test]
+fn test_generate_manifest_records_side_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+    let manifest_file = temp_dir.path().join("vlans.manifest.json");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "3", "--seed", "42",
+        ])
+        .args(["--include-firewall-rules", "--p2p-links", "2", "--output"])
+        .arg(&output_file)
+        .arg("--manifest")
+        .arg(&manifest_file)
+        .run_success();
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_file).unwrap()).unwrap();
+    let paths: Vec<&str> = manifest["outputs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|output| output["path"].as_str().unwrap())
+        .collect();
+    for file in [
+        "vlans.csv",
+        "vlans_firewall_rules.csv",
+        "vlans_p2p_links.csv",
+    ] {
+        let path = temp_dir.path().join(file);
+        assert!(path.exists(), "{file}");
+        assert!(
+            paths.contains(&path.to_str().unwrap()),
+            "{file} in {paths:?}"
+        );
+    }
+}
+
+#[test]
+fn test_generate_yaml_with_dhcp_range() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.yaml");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "yaml",
+            "--count",
+            "3",
+            "--dhcp-range",
+            "20-240",
+            "--seed",
+            "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let configs = opnsense_config_faker::io::yaml::read_yaml(&output_file).unwrap();
+    assert_eq!(configs.len(), 3);
+    for config in &configs {
+        assert!(config.dhcp_range_start().unwrap().ends_with(".20"));
+        assert!(config.dhcp_range_end().unwrap().ends_with(".240"));
+    }
+
+    cli_command()
+        .args(["generate", "--format", "yaml", "--dhcp-range", "240-20"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_failure();
+}
+
+#[test]
+fn test_generate_interactive_skips_confirmation_without_tty() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    // Piped stdin is not a terminal, so the "n" is never read as an answer
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "3",
+            "--interactive",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .into_command()
+        .write_stdin("n\n")
+        .assert()
+        .success();
+
+    assert!(output_file.exists());
+
+    // Streaming writes before anything could be confirmed
+    let output = cli_command()
+        .args(["generate", "--format", "csv", "--count", "3"])
+        .args(["--interactive", "--stream"])
+        .run_failure();
+    assert!(
+        output.stderr.contains("cannot be used with"),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn test_generate_seed_label_is_reproducible() {
+    let temp_dir = TempDir::new().unwrap();
+    let generate = |label: &str, name: &str| {
+        let output_file = temp_dir.path().join(name);
+        cli_command()
+            .args(["generate", "--format", "csv", "--count", "10"])
+            .args(["--seed-label", label])
+            .arg("--output")
+            .arg(&output_file)
+            .run_success();
+        fs::read_to_string(output_file).unwrap()
+    };
+
+    let first = generate("staging", "first.csv");
+    let second = generate("staging", "second.csv");
+    let other = generate("ci-run-7", "other.csv");
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn test_generate_csv_with_advanced_firewall_rules() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "3",
+            "--with-firewall",
+            "--firewall-complexity",
+            "advanced",
+            "--seed",
+            "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let firewall_file = temp_dir.path().join("vlans_firewall_rules.csv");
+    let content = fs::read_to_string(&firewall_file).unwrap();
+    let mut lines = content.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            "rule_id,source,destination,protocol,ports,action,direction,description,log,vlan_id,priority,interface,ip_version,icmp_type"
+        )
+    );
+    assert!(lines.count() >= 45);
+
+    let rules = opnsense_config_faker::io::csv::read_firewall_rules_csv(&firewall_file).unwrap();
+    let vlans = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    for vlan in &vlans {
+        let per_vlan = rules
+            .iter()
+            .filter(|rule| rule.vlan_id == Some(vlan.vlan_id))
+            .count();
+        assert!(per_vlan >= 15, "VLAN {} has {per_vlan} rules", vlan.vlan_id);
+    }
+}
+
+#[test]
+fn test_generate_dry_run_writes_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    let output = cli_command()
+        .args(["generate", "--format", "csv", "--count", "12", "--dry-run"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    assert!(!output_file.exists());
+    assert!(output.stdout.contains("Dry run: no files written"));
+    assert!(output.stdout.contains(&output_file.display().to_string()));
+    assert!(output.stdout.contains("Configurations: 12"));
+}
+
+#[test]
+fn test_generate_xml_dry_run_creates_no_output_dir() {
+    let (temp_dir, base_path, _temp_file) = create_test_base_config();
+    let output_dir = temp_dir.path().join("xml_out");
+
+    let output = cli_command()
+        .args(["generate", "--format", "xml", "--count", "3", "--dry-run"])
+        .arg("--base-config")
+        .arg(&base_path)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .run_success();
+
+    assert!(!output_dir.exists());
+    assert!(output.stdout.contains("Format: xml"));
+    assert!(output.stdout.contains(&output_dir.display().to_string()));
+}
+
+#[test]
+fn test_generate_emit_csv_and_json_share_vlan_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("data.csv");
+
+    cli_command()
+        .args([
+            "generate", "--emit", "csv,json", "--count", "8", "--seed", "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let csv_configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    let json_configs =
+        opnsense_config_faker::io::json::read_json(temp_dir.path().join("data.json")).unwrap();
+
+    let csv_ids: Vec<u16> = csv_configs.iter().map(|c| c.vlan_id).collect();
+    let json_ids: Vec<u16> = json_configs.iter().map(|c| c.vlan_id).collect();
+    assert_eq!(csv_ids.len(), 8);
+    assert_eq!(csv_ids, json_ids);
+}
+
+#[test]
+fn test_generate_emit_xml_validates_base_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("data.csv");
+    let (_base_file, base_path) =
+        create_temp_xml("base", "<opnsense><system/></opnsense>").unwrap();
+
+    let output = cli_command()
+        .args([
+            "generate", "--emit", "csv,xml", "--count", "4", "--seed", "42",
+        ])
+        .arg("--base-config")
+        .arg(&base_path)
+        .arg("--output")
+        .arg(&output_file)
+        .run_failure();
+
+    assert!(
+        output.stderr.contains("Invalid base config"),
+        "{}",
+        output.stderr
+    );
+    assert!(!output_file.exists());
+    assert!(!temp_dir.path().join("data.xml").exists());
+}
+
+#[test]
+fn test_generate_opnsense_version_requires_builder_output() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Per-file XML only fills template placeholders, so a version is refused
+    let output = cli_command()
+        .args([
+            "generate",
+            "--format",
+            "xml",
+            "--count",
+            "2",
+            "--base-config",
+            "test_xml/firewall_vlan_base.xml",
+            "--opnsense-version",
+            "23.7",
+        ])
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .run_failure();
+    assert!(output.stderr.contains("--monolithic"), "{}", output.stderr);
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+    let output_file = temp_dir.path().join("data.csv");
+    cli_command()
+        .args([
+            "generate",
+            "--emit",
+            "csv,xml",
+            "--count",
+            "2",
+            "--seed",
+            "42",
+            "--base-config",
+            "test_xml/firewall_vlan_base.xml",
+            "--opnsense-version",
+            "23.7",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    let xml = fs::read_to_string(temp_dir.path().join("data.xml")).unwrap();
+    assert!(xml.contains("<priority>"), "{xml}");
+    assert!(!xml.contains("<pcp>"), "{xml}");
+}
+
+#[test]
+fn test_generate_csv_with_balanced_classes() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "9",
+            "--seed",
+            "42",
+            "--balanced-classes",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 9);
+    for prefix in ["10.", "172.", "192.168."] {
+        let in_class = configs
+            .iter()
+            .filter(|config| config.ip_network.starts_with(prefix))
+            .count();
+        assert!(in_class >= 3, "only {in_class} networks in {prefix}");
+    }
+}
+
+#[test]
+fn test_generate_csv_within_supernet() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "20",
+            "--seed",
+            "42",
+            "--supernet",
+            "10.50.0.0/16",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 20);
+    assert!(
+        configs
+            .iter()
+            .all(|config| config.ip_network.starts_with("10.50."))
+    );
+
+    let output = cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "2",
+            "--supernet",
+            "10.50.0.0/24",
+        ])
+        .arg("--output")
+        .arg(temp_dir.path().join("small.csv"))
+        .run_failure();
+    assert!(
+        output
+            .normalized_combined()
+            .contains("supernet 10.50.0.0/24")
+    );
+}
+
+#[test]
+fn test_generate_xml_with_name_template() {
+    let (temp_dir, base_path, _temp_file) = create_test_base_config();
+    let output_dir = temp_dir.path().join("named");
+
+    cli_command()
+        .args([
+            "generate", "--format", "xml", "--count", "3", "--seed", "42",
+        ])
+        .args(["--firewall-nr", "7", "--xml-name-template"])
+        .arg("fw-{firewall_nr}-vlan-{vlan_id}-{index}.xml")
+        .arg("--base-config")
+        .arg(&base_path)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .run_success();
+
+    let mut names: Vec<String> = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 3);
+    for name in &names {
+        let rest = name.strip_prefix("fw-7-vlan-").unwrap();
+        let (vlan_id, index) = rest.strip_suffix(".xml").unwrap().split_once('-').unwrap();
+        assert!(vlan_id.parse::<u16>().is_ok(), "{name}");
+        assert!(index.parse::<usize>().unwrap() < 3, "{name}");
+    }
+
+    let rejected = temp_dir.path().join("rejected");
+    let output = cli_command()
+        .args(["generate", "--format", "xml", "--count", "3"])
+        .args(["--xml-name-template", "fw-{vlan}.xml"])
+        .arg("--base-config")
+        .arg(&base_path)
+        .arg("--output-dir")
+        .arg(&rejected)
+        .run_failure();
+    assert!(output.normalized_combined().contains("Unknown placeholder"));
+    assert!(!rejected.exists());
+}
+
+#[test]
+fn test_generate_xml_resume_writes_only_remaining_files() {
+    let (temp_dir, base_path, _temp_file) = create_test_base_config();
+    let full_dir = temp_dir.path().join("full");
+    let resumed_dir = temp_dir.path().join("resumed");
+    let run = |output_dir: &std::path::Path, resume: bool| {
+        cli_command()
+            .args([
+                "generate", "--format", "xml", "--count", "8", "--seed", "42",
+            ])
+            .args([
+                "--xml-name-template",
+                "fw-{firewall_nr}-{index}-vlan-{vlan_id}.xml",
+            ])
+            .arg("--base-config")
+            .arg(&base_path)
+            .arg("--output-dir")
+            .arg(output_dir)
+            .args(resume.then_some("--resume"))
+            .run_success()
+    };
+    let list = |dir: &std::path::Path| {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+
+    run(&full_dir, false);
+    let full_names = list(&full_dir);
+    assert_eq!(full_names.len(), 8);
+
+    // Five files left behind by an interrupted run
+    fs::create_dir_all(&resumed_dir).unwrap();
+    let interrupted: Vec<&String> = full_names
+        .iter()
+        .filter(|name| (0..5).any(|index| name.starts_with(&format!("fw-1-{index}-"))))
+        .collect();
+    assert_eq!(interrupted.len(), 5);
+    for name in &interrupted {
+        fs::write(resumed_dir.join(name), "partial").unwrap();
+    }
+
+    let output = run(&resumed_dir, true);
+    assert!(
+        output.stdout.contains("5 of 8 VLAN files already written"),
+        "{}",
+        output.stdout
+    );
+    assert_eq!(list(&resumed_dir), full_names);
+    for name in &interrupted {
+        assert_eq!(
+            fs::read_to_string(resumed_dir.join(name)).unwrap(),
+            "partial"
+        );
+    }
+}
+
+#[test]
+fn test_generate_csv_with_description_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "5", "--seed", "42",
+        ])
+        .args(["--description-template", "NYC {dept}_{id}"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 5);
+    for config in &configs {
+        let suffix = format!("_{}", config.vlan_id);
+        assert!(
+            config.description.starts_with("NYC "),
+            "{}",
+            config.description
+        );
+        assert!(
+            config.description.ends_with(&suffix),
+            "{}",
+            config.description
+        );
+    }
+}
+
+#[test]
+fn test_generate_firewall_rules_with_reject_deny_action() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "3",
+            "--with-firewall",
+            "--firewall-complexity",
+            "advanced",
+            "--deny-action",
+            "reject",
+            "--seed",
+            "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let rules = opnsense_config_faker::io::csv::read_firewall_rules_csv(
+        temp_dir.path().join("vlans_firewall_rules.csv"),
+    )
+    .unwrap();
+    assert!(rules.iter().any(|rule| rule.action == "reject"));
+    assert!(rules.iter().all(|rule| rule.action != "block"));
+}
+
+#[test]
+fn test_diff_flags_changed_description() {
+    let temp_dir = TempDir::new().unwrap();
+    let a = temp_dir.path().join("a.csv");
+    let b = temp_dir.path().join("b.csv");
+    fs::write(
+        &a,
+        "VLAN,IP Range,Beschreibung,WAN\n100,10.1.1.x,IT VLAN 100,1\n200,10.1.2.x,Sales VLAN 200,2\n",
+    )
+    .unwrap();
+    fs::write(
+        &b,
+        "VLAN,IP Range,Beschreibung,WAN\n100,10.1.1.x,IT VLAN 100,1\n200,10.1.2.x,Guest VLAN 200,2\n",
+    )
+    .unwrap();
+
+    let output = cli_command()
+        .args(["diff", "--json"])
+        .arg(&a)
+        .arg(&b)
+        .run_success();
+    let diff: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(diff["only_in_a"], serde_json::json!([]));
+    assert_eq!(diff["only_in_b"], serde_json::json!([]));
+    assert_eq!(
+        diff["changed"],
+        serde_json::json!([{
+            "vlan_id": 200,
+            "changes": [{"field": "description", "a": "Sales VLAN 200", "b": "Guest VLAN 200"}]
+        }])
+    );
+
+    let output = cli_command().arg("diff").arg(&a).arg(&b).run_success();
+    assert!(output.stdout.contains("Changed: 1"));
+    assert!(output.stdout.contains("VLAN 200 description"));
+}
+
+#[test]
+fn test_quiet_generate_produces_no_stdout() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("quiet.csv");
+
+    let output = cli_command()
+        .args(["--quiet", "generate", "--format", "csv", "--count", "5"])
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--include-firewall-rules")
+        .run_success();
+
+    assert!(output.stdout.is_empty(), "stdout: {}", output.stdout);
+    assert!(output.stderr.is_empty(), "stderr: {}", output.stderr);
+    assert!(output_file.exists());
+
+    let (_temp_dir, base_path, _temp_file) = create_test_base_config();
+    let output = cli_command()
+        .args(["--quiet", "generate", "--format", "xml", "--count", "2"])
+        .arg("--base-config")
+        .arg(&base_path)
+        .arg("--output-dir")
+        .arg(temp_dir.path().join("xml"))
+        .run_success();
+    assert!(output.stdout.is_empty(), "stdout: {}", output.stdout);
+
+    let output = cli_command()
+        .args(["--quiet", "validate", "--input"])
+        .arg(&output_file)
+        .run_success();
+    assert!(output.stdout.is_empty(), "stdout: {}", output.stdout);
+}
+
+#[test]
+fn test_generate_monolithic_xml() {
+    let xml_content = r#"<?xml version="1.0"?>
+<opnsense>
+  <interfaces>
+    <lan>
+      <if>em0</if>
+    </lan>
+  </interfaces>
+  <vlans/>
+  <filter/>
+  <nat/>
+  <OPNsense>
+    <Firewall>
+      <Alias version="1.0.0"/>
+    </Firewall>
+  </OPNsense>
+</opnsense>"#;
+    let (_temp_file, base_config_path) = create_temp_xml("base_full_", xml_content).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+
+    cli_command()
+        .args(["generate", "--format", "xml", "--count", "3", "--seed", "7"])
+        .args(["--monolithic", "--include-firewall-rules"])
+        .args(["--parent-interfaces", "igb0,igb1"])
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .run_success();
+
+    let xml_files: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".xml"))
+        .collect();
+    assert_eq!(xml_files, ["firewall_1_config.xml"]);
+
+    let xml = fs::read_to_string(temp_dir.path().join("firewall_1_config.xml")).unwrap();
+    assert_eq!(xml.matches("<vlanid>").count(), 3);
+    assert!(xml.contains("<filter><rule>"), "{xml}");
+    assert!(xml.contains("<nat><rule>"), "{xml}");
+    assert!(xml.contains("<aliases><alias>"), "{xml}");
+    assert_eq!(xml.matches("<if>igb0</if>").count(), 2);
+    assert_eq!(xml.matches("<if>igb1</if>").count(), 1);
+}
+
+#[test]
+fn test_generate_monolithic_xml_custom_dhcp_dns() {
+    let xml_content = r#"<?xml version="1.0"?>
+<opnsense>
+  <interfaces>
+    <lan>
+      <if>em0</if>
+    </lan>
+  </interfaces>
+  <vlans/>
+  <dhcpd/>
+  <filter/>
+  <nat/>
+  <OPNsense>
+    <Firewall>
+      <Alias version="1.0.0"/>
+    </Firewall>
+  </OPNsense>
+</opnsense>"#;
+    let (_temp_file, base_config_path) = create_temp_xml("base_dns_", xml_content).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+
+    cli_command()
+        .args(["generate", "--format", "xml", "--count", "2", "--seed", "7"])
+        .args(["--monolithic", "--dhcp-dns", "10.0.0.53,10.0.0.54"])
+        .args(["--mac-vendors", "cisco"])
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .run_success();
+
+    let xml = fs::read_to_string(temp_dir.path().join("firewall_1_config.xml")).unwrap();
+    assert_eq!(xml.matches("<dnsserver>10.0.0.53</dnsserver>").count(), 2);
+    assert_eq!(xml.matches("<dnsserver>10.0.0.54</dnsserver>").count(), 2);
+    assert!(!xml.contains("<dnsserver>8.8.8.8</dnsserver>"), "{xml}");
+    assert!(!xml.contains("<dnsserver>1.1.1.1</dnsserver>"), "{xml}");
+    let macs: Vec<&str> = xml.split("<mac>").skip(1).map(|rest| &rest[..17]).collect();
+    assert!(!macs.is_empty(), "{xml}");
+    for mac in macs {
+        assert!(
+            mac.starts_with("00:00:0c:") || mac.starts_with("00:1b:54:"),
+            "{mac}"
+        );
+    }
+
+    // DNS and MAC settings belong to the DHCP servers, not to the VLAN records
+    let json_path = temp_dir.path().join("vlans.json");
+    cli_command()
+        .args(["generate", "--format", "json", "--count", "2"])
+        .args(["--dhcp-dns", "10.0.0.53", "--no-gateway-dns"])
+        .args(["--mac-vendors", "cisco", "--output"])
+        .arg(&json_path)
+        .run_success();
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(!json.contains("dns_servers"), "{json}");
+    assert!(!json.contains("gateway_dns"), "{json}");
+    assert!(!json.contains("mac_vendors"), "{json}");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "xml",
+            "--dhcp-dns",
+            "dns.example.com",
+        ])
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .run_failure();
+}
+
+#[test]
+fn test_generate_xml_pretty() {
+    let xml_content = concat!(
+        r#"<?xml version="1.0"?><opnsense><interfaces><lan><if>em0</if>"#,
+        r#"<descr><![CDATA[VLAN {{VLAN_ID}}]]></descr></lan></interfaces>"#,
+        r#"<vlans><vlan><tag>{{VLAN_ID}}</tag></vlan></vlans></opnsense>"#
+    );
+    let (_temp_file, base_config_path) = create_temp_xml("base_compact_", xml_content).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+
+    cli_command()
+        .args([
+            "generate", "--format", "xml", "--count", "1", "--seed", "42",
+        ])
+        .arg("--pretty-xml")
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--output-dir")
+        .arg(temp_dir.path())
+        .run_success();
+
+    let xml_file = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "xml"))
+        .unwrap();
+    let xml = fs::read_to_string(xml_file).unwrap();
+    let lines: Vec<&str> = xml.lines().collect();
+    assert_eq!(lines[1], "<opnsense>", "{xml}");
+    assert_eq!(lines[2], "  <interfaces>", "{xml}");
+    assert!(lines[5].starts_with("      <descr><![CDATA[VLAN "), "{xml}");
+    assert!(lines[10].starts_with("      <tag>"), "{xml}");
+}
+
+#[test]
+fn test_generate_json_error_format() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = cli_command()
+        .args(["generate", "--format", "csv", "--count", "3", "--quiet"])
+        .args([
+            "--include-firewall-rules",
+            "--firewall-rule-complexity",
+            "bogus",
+        ])
+        .args(["--error-format", "json", "--output"])
+        .arg(temp_dir.path().join("vlans.csv"))
+        .run_failure();
+
+    let report: serde_json::Value = serde_json::from_str(output.stderr.trim()).unwrap();
+    assert_eq!(report["kind"], "Validation");
+    assert!(
+        report["error"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid complexity level 'bogus'"),
+        "{report}"
+    );
+    assert_eq!(
+        report["context"],
+        serde_json::json!(["Failed to generate configurations"])
+    );
+}
+
+#[test]
+fn test_json_error_format_owns_stderr() {
+    // Argument errors are reported as JSON too
+    let output = cli_command()
+        .args(["--error-format", "json", "generate", "--count", "0"])
+        .run_failure();
+    let report: serde_json::Value = serde_json::from_str(output.stderr.trim()).unwrap();
+    assert_eq!(report["kind"], "InvalidArgument");
+    assert!(
+        report["error"]
+            .as_str()
+            .unwrap()
+            .contains("invalid value '0' for '--count <COUNT>'"),
+        "{report}"
+    );
+
+    // Without --quiet, neither the seed line nor per-row errors reach stderr
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("vlans.csv");
+    fs::write(
+        &input,
+        "VLAN,IP Range,Beschreibung,WAN\n100,10.1.1.x,IT VLAN 100,1\n100,10.1.2.x,IT VLAN 100,1\n",
+    )
+    .unwrap();
+    let output = cli_command()
+        .args(["validate", "--error-format=json", "--input"])
+        .arg(&input)
+        .run_failure();
+    let report: serde_json::Value = serde_json::from_str(output.stderr.trim()).unwrap();
+    assert_eq!(report["context"][0], "Failed to validate configurations");
+
+    let output = cli_command()
+        .args(["--error-format", "json", "generate", "--format", "csv"])
+        .args(["--count", "3", "--include-firewall-rules"])
+        .args(["--firewall-rule-complexity", "bogus", "--output"])
+        .arg(temp_dir.path().join("out.csv"))
+        .run_failure();
+    let report: serde_json::Value = serde_json::from_str(output.stderr.trim()).unwrap();
+    assert_eq!(report["kind"], "Validation");
+}
+
+#[test]
+fn test_generate_networks_only() {
+    let output = cli_command()
+        .args([
+            "generate",
+            "--networks-only",
+            "--count",
+            "20",
+            "--network-class",
+            "class-c",
+            "--seed",
+            "7",
+        ])
+        .run_success();
+
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert_eq!(lines.len(), 20, "stdout: {}", output.stdout);
+    for line in &lines {
+        assert!(
+            line.starts_with("192.168.") && line.ends_with(".0/24"),
+            "{line}"
+        );
+    }
+    let unique: std::collections::HashSet<_> = lines.iter().collect();
+    assert_eq!(unique.len(), 20);
+}
+
+#[test]
+fn test_generate_without_seed_prints_reusable_seed() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.csv");
+    let output = cli_command()
+        .args(["generate", "--format", "csv", "--count", "15"])
+        .arg("--output")
+        .arg(&first)
+        .run_success();
+
+    let seed = output
+        .stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("Using random seed: "))
+        .unwrap_or_else(|| panic!("no seed reported: {}", output.stderr))
+        .trim()
+        .to_string();
+
+    let second = temp_dir.path().join("second.csv");
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "15", "--seed", &seed,
+        ])
+        .arg("--output")
+        .arg(&second)
+        .run_success();
+
+    assert_eq!(
+        fs::read_to_string(&first).unwrap(),
+        fs::read_to_string(&second).unwrap()
+    );
+}
+
+#[test]
+fn test_validate_reports_every_bad_csv_row() {
+    let temp_dir = TempDir::new().unwrap();
+    let csv_path = temp_dir.path().join("hand_edited.csv");
+    fs::write(
+        &csv_path,
+        "VLAN,IP Range,Beschreibung,WAN\n\
+         5,10.1.2.x,Low ID,1\n\
+         100,10.1.3.x,Valid,1\n\
+         200,10.1.4.x,Bad WAN,9\n\
+         300,10.1.5.1,Bad Net,2\n",
+    )
+    .unwrap();
+
+    let output = cli_command()
+        .args(["validate", "--input"])
+        .arg(&csv_path)
+        .run_failure();
+
+    let stderr = output.normalized_stderr();
+    assert!(stderr.contains("line 2: Invalid VLAN ID '5'"), "{stderr}");
+    assert!(
+        stderr.contains("line 4: Invalid WAN assignment '9'"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("line 5: Invalid IP network format"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("3 error(s) found"), "{stderr}");
+}
+
+#[test]
+fn test_validate_json_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let csv_path = temp_dir.path().join("hand_edited.csv");
+    fs::write(
+        &csv_path,
+        "VLAN,IP Range,Beschreibung,WAN\n\
+         5,10.1.2.x,Low ID,1\n\
+         100,10.1.3.x,Valid,1\n\
+         200,10.1.4.x,Bad WAN,9\n\
+         300,10.1.5.x,\"Quoted\nacross lines\",1\n\
+         400,10.1.6.x,Description past the limit,2\n",
+    )
+    .unwrap();
+
+    let output = cli_command()
+        .args([
+            "validate",
+            "--json",
+            "--max-description-len",
+            "20",
+            "--input",
+        ])
+        .arg(&csv_path)
+        .run_failure();
+
+    let report: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["count"], 3);
+    let errors = report["errors"].as_array().unwrap();
+    let lines: Vec<u64> = errors
+        .iter()
+        .map(|error| error["line"].as_u64().unwrap())
+        .collect();
+    // Lines stay right after dropped rows and multi-line records
+    assert_eq!(lines, [2, 4, 5, 7], "{report}");
+    assert!(
+        errors[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid VLAN ID '5'"),
+        "{report}"
+    );
+
+    // Rows that fail to parse count towards --max-errors too
+    let output = cli_command()
+        .args(["validate", "--json", "--max-description-len", "20"])
+        .args(["--max-errors", "1", "--input"])
+        .arg(&csv_path)
+        .run_failure();
+    let report: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(report["errors"].as_array().unwrap().len(), 1, "{report}");
+}
+
+#[test]
+fn test_validate_latin1_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    let csv_path = temp_dir.path().join("legacy.csv");
+    fs::write(
+        &csv_path,
+        b"VLAN,IP Range,Beschreibung,WAN\n100,10.1.2.x,B\xfcro VLAN 100,1\n",
+    )
+    .unwrap();
+
+    let output = cli_command()
+        .args(["validate", "--input"])
+        .arg(&csv_path)
+        .run_failure();
+    assert!(
+        output
+            .normalized_combined()
+            .contains("is not valid UTF-8 at byte 45"),
+        "{}",
+        output.normalized_combined()
+    );
+
+    cli_command()
+        .args(["validate", "--input-encoding", "latin1", "--input"])
+        .arg(&csv_path)
+        .run_success();
+
+    // The --against file is decoded the same way
+    let other_path = temp_dir.path().join("other.csv");
+    fs::write(
+        &other_path,
+        b"VLAN,IP Range,Beschreibung,WAN\n200,10.1.3.x,K\xfcche VLAN 200,1\n",
+    )
+    .unwrap();
+    cli_command()
+        .args(["validate", "--input-encoding", "latin1", "--input"])
+        .arg(&csv_path)
+        .arg("--against")
+        .arg(&other_path)
+        .run_success();
+}
+
+#[test]
+fn test_generate_streams_jsonl() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.jsonl");
+
+    cli_command()
+        .args([
+            "generate", "--format", "jsonl", "--count", "5", "--seed", "42", "--stream",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 5, "{content}");
+    for line in lines {
+        let config: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(config["vlan_id"].is_u64(), "{line}");
+    }
+}
+
+#[test]
+fn test_generate_validate_after() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+    let args = [
+        "generate",
+        "--format",
+        "csv",
+        "--count",
+        "20",
+        "--seed",
+        "42",
+        "--validate-after",
+    ];
+
+    let output = cli_command()
+        .args(args)
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    assert!(
+        output
+            .stdout
+            .contains("Validated 20 generated configurations")
+    );
+}
+
+#[test]
+fn test_generate_count_from_environment() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .env("OPNSENSE_FAKER_COUNT", "7")
+        .env("OPNSENSE_FAKER_SEED", "42")
+        .args(["generate", "--format", "csv"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    let content = fs::read_to_string(&output_file).unwrap();
+    assert_eq!(content.lines().count(), 8, "{content}");
+
+    // The flag wins over the environment
+    cli_command()
+        .env("OPNSENSE_FAKER_COUNT", "7")
+        .args(["generate", "--format", "csv", "--count", "3", "--force"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    let content = fs::read_to_string(&output_file).unwrap();
+    assert_eq!(content.lines().count(), 4, "{content}");
+}
+
+#[test]
+fn test_environment_defaults_yield_to_conflicting_flags() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+    let generate = |extra: &[&str]| {
+        cli_command()
+            .env("OPNSENSE_FAKER_COUNT", "5")
+            .env("OPNSENSE_FAKER_SEED", "42")
+            .args(["generate", "--format", "csv", "--force"])
+            .args(extra)
+            .arg("--output")
+            .arg(&output_file)
+            .run_success();
+        fs::read_to_string(&output_file).unwrap()
+    };
+
+    let content = generate(&["--per-department", "IT=2,Sales=1"]);
+    assert_eq!(content.lines().count(), 4, "{content}");
+
+    let content = generate(&["--supernet", "10.50.0.0/22", "--fill-supernet"]);
+    assert_eq!(content.lines().count(), 5, "{content}");
+
+    // --seed-label replaces the environment seed
+    let labelled = generate(&["--seed-label", "staging"]);
+    let from_env = generate(&[]);
+    assert_ne!(labelled, from_env);
+    let seed = opnsense_config_faker::utils::seed::seed_from_label("staging").to_string();
+    assert_eq!(generate(&["--seed", &seed]), labelled);
+}
+
+#[test]
+fn test_verify_detects_modified_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+    let manifest_file = temp_dir.path().join("vlans.manifest.json");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "5", "--seed", "42",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--manifest")
+        .arg(&manifest_file)
+        .run_success();
+
+    let output = cli_command()
+        .arg("verify")
+        .arg(&manifest_file)
+        .arg(&output_file)
+        .run_success();
+    assert!(
+        output.stdout.contains("All 1 files match"),
+        "{}",
+        output.stdout
+    );
+
+    let mut content = fs::read_to_string(&output_file).unwrap();
+    content.push_str("999,10.9.9.x,Tampered VLAN 999,1\n");
+    fs::write(&output_file, content).unwrap();
+
+    let output = cli_command()
+        .arg("verify")
+        .arg(&manifest_file)
+        .arg(&output_file)
+        .run_failure();
+    assert!(
+        output.stderr.contains("expected sha256"),
+        "{}",
+        output.stderr
+    );
+    assert!(
+        output.stderr.contains("1 of 1 files do not match"),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn test_generate_rate_throttles_generation() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    let started = std::time::Instant::now();
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "4", "--seed", "42", "--rate", "10",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    // Four configurations at 10 per second are three 100 ms intervals apart
+    assert!(started.elapsed() >= std::time::Duration::from_millis(300));
+}
+
+#[test]
+fn test_generate_verbose_logs_retries() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    // 200 of the 256 Class C /24 networks force repeated draws
+    let output = cli_command()
+        .env_remove("RUST_LOG")
+        .args([
+            "-vv", "generate", "--format", "csv", "--count", "200", "--seed", "42",
+        ])
+        .args(["--network-class", "class-c", "--no-color"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    assert!(output.stderr.contains("DEBUG"), "{}", output.stderr);
+    assert!(
+        output.stderr.contains("network already in use, retrying"),
+        "{}",
+        output.stderr
+    );
+
+    let output = cli_command()
+        .env_remove("RUST_LOG")
+        .args([
+            "generate", "--format", "csv", "--count", "5", "--seed", "42", "--force",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    assert!(!output.stderr.contains("DEBUG"), "{}", output.stderr);
+}
+
+#[test]
+fn test_generate_allow_duplicates() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+    let args = [
+        "generate",
+        "--format",
+        "csv",
+        "--count",
+        "5",
+        "--seed",
+        "42",
+        "--vlan-range",
+        "100-101",
+    ];
+
+    cli_command()
+        .args(args)
+        .arg("--output")
+        .arg(&output_file)
+        .run_failure();
+
+    cli_command()
+        .args(args)
+        .arg("--allow-duplicates")
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    let ids: Vec<&str> = content
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 5);
+    let unique: std::collections::HashSet<_> = ids.iter().collect();
+    assert!(unique.len() < ids.len(), "{content}");
+}
+
+#[test]
+fn test_generate_csv_semicolon_delimiter() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "3", "--seed", "42",
+        ])
+        .args(["--csv-delimiter", ";", "--output"])
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    assert!(
+        content.starts_with("VLAN;IP Range;Beschreibung;WAN\n"),
+        "{content}"
+    );
+    assert_eq!(content.lines().count(), 4);
+    assert!(content.lines().all(|line| !line.contains(',')), "{content}");
+
+    // The file reads back through validate and diff with the same delimiter
+    cli_command()
+        .args(["validate", "--csv-delimiter", ";", "--input"])
+        .arg(&output_file)
+        .run_success();
+    cli_command()
+        .args(["validate", "--input"])
+        .arg(&output_file)
+        .run_failure();
+    let output = cli_command()
+        .args(["diff", "--json", "--csv-delimiter", ";"])
+        .arg(&output_file)
+        .arg(&output_file)
+        .run_success();
+    let diff: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(diff["changed"], serde_json::json!([]));
+    assert_eq!(diff["only_in_a"], serde_json::json!([]));
+
+    cli_command()
+        .args(["generate", "--format", "csv", "--csv-delimiter", ";;"])
+        .arg("--output")
+        .arg(&output_file)
+        .run_failure();
+}
+
+#[test]
+fn test_generate_sort_by_vlan_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "50", "--seed", "42",
+        ])
+        .args(["--sort", "vlan-id", "--output"])
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    let ids: Vec<u16> = content
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap().parse().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 50);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]), "{ids:?}");
+}
+
+#[test]
+fn test_generate_shuffle_is_reproducible() {
+    let temp_dir = TempDir::new().unwrap();
+    let generate = |name: &str, shuffle: bool| {
+        let output_file = temp_dir.path().join(name);
+        cli_command()
+            .args([
+                "generate", "--format", "csv", "--count", "50", "--seed", "42",
+            ])
+            .args(shuffle.then_some("--shuffle"))
+            .arg("--output")
+            .arg(&output_file)
+            .run_success();
+        fs::read_to_string(&output_file)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap().parse().unwrap())
+            .collect::<Vec<u16>>()
+    };
+
+    let first = generate("first.csv", true);
+    let second = generate("second.csv", true);
+    let unshuffled = generate("unshuffled.csv", false);
+    assert_eq!(first, second);
+    assert_ne!(first, unshuffled);
+
+    let mut sorted = first.clone();
+    let mut unshuffled_sorted = unshuffled.clone();
+    sorted.sort();
+    unshuffled_sorted.sort();
+    assert_eq!(sorted, unshuffled_sorted);
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--shuffle",
+            "--sort",
+            "vlan-id",
+        ])
+        .arg("--output")
+        .arg(temp_dir.path().join("conflict.csv"))
+        .run_failure();
+}
+
+#[test]
+fn test_generate_networks_from_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let networks_file = temp_dir.path().join("ipam.txt");
+    fs::write(&networks_file, "10.20.0.0/24\n10.20.1.0/24\n10.20.2.0/24\n").unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "3", "--seed", "42",
+        ])
+        .arg("--networks-file")
+        .arg(&networks_file)
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    for network in ["10.20.0.x", "10.20.1.x", "10.20.2.x"] {
+        let uses = content
+            .lines()
+            .filter(|line| line.split(',').nth(1) == Some(network))
+            .count();
+        assert_eq!(uses, 1, "{network} in {content}");
+    }
+
+    cli_command()
+        .args(["generate", "--format", "csv", "--count", "4", "--force"])
+        .arg("--networks-file")
+        .arg(&networks_file)
+        .arg("--output")
+        .arg(&output_file)
+        .run_failure();
+}
+
+#[test]
+fn test_generate_rejects_nested_networks_in_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let networks_file = temp_dir.path().join("ipam.txt");
+    fs::write(&networks_file, "10.1.0.0/16\n10.1.2.0/24\n").unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    let output = cli_command()
+        .args(["generate", "--format", "csv", "--count", "2"])
+        .arg("--networks-file")
+        .arg(&networks_file)
+        .arg("--output")
+        .arg(&output_file)
+        .run_failure();
+
+    assert!(
+        output.stderr.contains("10.1.2.0/24 overlaps 10.1.0.0/16"),
+        "{}",
+        output.stderr
+    );
+    assert!(!output_file.exists());
+}
+
+#[test]
+fn test_generate_rejects_unknown_nat_protocol() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--count",
+            "2",
+            "--nat-mappings",
+            "3",
+        ])
+        .args(["--nat-protocols", "sctp", "--nat-ports", "443"])
+        .arg("--output")
+        .arg(temp_dir.path().join("vlans.csv"))
+        .run_failure();
+
+    assert!(
+        output.normalized_stderr().contains("'sctp' is not one of"),
+        "{}",
+        output.normalized_stderr()
+    );
+}
+
+#[test]
+fn test_generate_fill_supernet() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--seed",
+            "42",
+            "--fill-supernet",
+        ])
+        .args(["--supernet", "10.50.0.0/22", "--output"])
+        .arg(&output_file)
+        .run_success();
+
+    let content = fs::read_to_string(&output_file).unwrap();
+    let mut networks: Vec<&str> = content
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split(',').nth(1))
+        .collect();
+    networks.sort();
+    assert_eq!(
+        networks,
+        ["10.50.0.x", "10.50.1.x", "10.50.2.x", "10.50.3.x"],
+        "{content}"
+    );
+
+    // Excluded networks are not counted
+    cli_command()
+        .args([
+            "generate",
+            "--format",
+            "csv",
+            "--seed",
+            "42",
+            "--fill-supernet",
+            "--force",
+        ])
+        .args([
+            "--supernet",
+            "10.50.0.0/22",
+            "--exclude-networks",
+            "10.50.1.0/24",
+        ])
+        .arg("--output")
+        .arg(&output_file)
+        .run_success();
+    let content = fs::read_to_string(&output_file).unwrap();
+    let mut networks: Vec<&str> = content
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split(',').nth(1))
+        .collect();
+    networks.sort();
+    assert_eq!(
+        networks,
+        ["10.50.0.x", "10.50.2.x", "10.50.3.x"],
+        "{content}"
+    );
+}
+
+#[test]
+fn test_generate_stats_histogram() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_file = temp_dir.path().join("vlans.csv");
+
+    let output = cli_command()
+        .args([
+            "generate", "--format", "csv", "--count", "25", "--seed", "42",
+        ])
+        .args(["--stats", "--output"])
+        .arg(&output_file)
+        .run_success();
+
+    let histogram: Vec<&str> = output
+        .stdout
+        .lines()
+        .skip_while(|line| !line.contains("VLANs per department:"))
+        .skip(1)
+        .take_while(|line| line.contains('█'))
+        .collect();
+    assert!(!histogram.is_empty(), "{}", output.stdout);
+    let total: usize = histogram
+        .iter()
+        .map(|line| {
+            line.split_whitespace()
+                .rev()
+                .nth(1)
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        })
+        .sum();
+    assert_eq!(total, 25, "{}", output.stdout);
+}
+
+#[test]
+fn test_generate_max_output_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+
+    for stream in [false, true] {
+        let output_file = temp_dir.path().join(format!("vlans_{stream}.csv"));
+        let output = cli_command()
+            .args(["generate", "--format", "csv", "--count", "1000"])
+            .args(["--max-output-bytes", "512"])
+            .args(stream.then_some("--stream"))
+            .arg("--output")
+            .arg(&output_file)
+            .run_failure();
+
+        assert!(
+            output
+                .normalized_stderr()
+                .contains("output limit of 512 bytes"),
+            "{}",
+            output.normalized_stderr()
+        );
+        assert!(!output_file.exists());
+    }
+
+    let output_file = temp_dir.path().join("small.csv");
+    cli_command()
+        .args(["generate", "--format", "csv", "--count", "2"])
+        .args(["--max-output-bytes", "512", "--output"])
+        .arg(&output_file)
+        .run_success();
+    assert!(fs::metadata(&output_file).unwrap().len() <= 512);
+}
+
+// ===== Generate command with VLAN range tests =====
+
+#[test]
+fn test_generate_csv_vlan_range_with_count() {
+    let temp_dir = create_temp_dir("vlan_range_count_test");
+    let output_file = temp_dir.path().join("ranged.csv");
+
+    cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("csv")
+        .arg("--vlan-range")
+        .arg("100-105")
+        .arg("--count")
+        .arg("6")
+        .arg("--output")
+        .arg(&output_file)
+        .arg("--seed")
+        .arg("42")
+        .run_success();
+
+    let configs = opnsense_config_faker::io::csv::read_csv(&output_file).unwrap();
+    assert_eq!(configs.len(), 6);
+    assert!(configs.iter().all(|c| (100..=105).contains(&c.vlan_id)));
+
+    let output = cli_command()
+        .arg("generate")
+        .arg("--format")
+        .arg("csv")
+        .arg("--vlan-range")
+        .arg("100-105")
+        .arg("--count")
+        .arg("7")
+        .arg("--output")
+        .arg(temp_dir.path().join("exhausted.csv"))
+        .run_failure();
+
+    let combined_output = output.normalized_combined();
+    assert!(
+        combined_output.contains("Resource exhaustion"),
+        "Expected resource exhaustion error, got: {combined_output}"
+    );
+}
+
 // ===== Generate command with XML format tests =====
 
 #[test]
@@ -299,6 +2397,125 @@ fn test_generate_xml_with_base_config() {
     assert_no_ansi_escapes(&output.stderr);
 }
 
+#[test]
+fn test_generate_xml_skips_base_config_vlan_ids() {
+    let xml_content = r#"<?xml version="1.0"?>
+<opnsense>
+  <interfaces>
+    <lan>
+      <if>em0</if>
+    </lan>
+  </interfaces>
+  <vlans>
+    <vlan>
+      <if>em0</if>
+      <tag>100</tag>
+      <descr>Existing VLAN</descr>
+      <vlanif>em0_vlan100</vlanif>
+    </vlan>
+  </vlans>
+</opnsense>"#;
+    let (_temp_file, base_config_path) = create_temp_xml("base_vlan_", xml_content).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("xml_test");
+
+    // 51 IDs in the range, one of which is already taken by the base config
+    let output = cli_command()
+        .args(["generate", "--format", "xml", "--vlan-range", "90-140"])
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .args(["--seed", "42"])
+        .run_success();
+
+    assert!(
+        output
+            .normalized_stdout()
+            .contains("Base configuration defines 1 VLANs")
+    );
+    let files: Vec<String> = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(files.len(), 50);
+    assert!(!files.contains(&"firewall_1_vlan_100.xml".to_string()));
+}
+
+#[test]
+fn test_generate_xml_continue_on_error_skips_failing_vlan() {
+    let xml_content = r#"<?xml version="1.0"?>
+<opnsense>
+  <interfaces>
+    <lan>
+      <if>em0</if>
+      <gateway>{{GATEWAY}}</gateway>
+    </lan>
+  </interfaces>
+  <vlans>
+    <vlan>
+      <tag>{{VLAN_ID}}</tag>
+    </vlan>
+  </vlans>
+</opnsense>"#;
+    let (_temp_file, base_config_path) = create_temp_xml("base_gateway_", xml_content).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    // VLAN 200's network cannot be parsed, so {{GATEWAY}} cannot be filled
+    let csv_file = temp_dir.path().join("vlans.csv");
+    fs::write(
+        &csv_file,
+        "VLAN,IP Range,Beschreibung,WAN\n\
+         100,10.1.1.x,IT VLAN 100,1\n\
+         200,10.1.999.x,IT VLAN 200,1\n\
+         300,10.1.3.x,IT VLAN 300,1\n",
+    )
+    .unwrap();
+    let run = |output_dir: &std::path::Path, continue_on_error: bool| {
+        cli_command()
+            .args(["generate", "--format", "xml"])
+            .arg("--csv-file")
+            .arg(&csv_file)
+            .arg("--base-config")
+            .arg(&base_config_path)
+            .arg("--output-dir")
+            .arg(output_dir)
+            .args(continue_on_error.then_some("--continue-on-error"))
+            .run_failure()
+    };
+    let list = |dir: &std::path::Path| {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+
+    // Without the flag the first failure stops the run
+    let fail_fast_dir = temp_dir.path().join("fail_fast");
+    let output = run(&fail_fast_dir, false);
+    assert!(
+        output
+            .normalized_stderr()
+            .contains("Cannot fill {{GATEWAY}} for VLAN 200")
+    );
+    assert_eq!(list(&fail_fast_dir), ["firewall_1_vlan_100.xml"]);
+
+    let continued_dir = temp_dir.path().join("continued");
+    let output = run(&continued_dir, true);
+    assert_eq!(
+        list(&continued_dir),
+        ["firewall_1_vlan_100.xml", "firewall_1_vlan_300.xml"]
+    );
+    let combined = output.normalized_combined();
+    assert!(combined.contains("Failed: 1"), "{combined}");
+    assert!(
+        combined.contains("1 of 3 VLAN configurations failed"),
+        "{combined}"
+    );
+    assert!(combined.contains("VLAN 200: "), "{combined}");
+}
+
 #[test]
 fn test_generate_xml_missing_base_config_fails() {
     let output = cli_command()
@@ -323,6 +2540,35 @@ fn test_generate_xml_missing_base_config_fails() {
     assert_no_ansi_escapes(&output.stderr);
 }
 
+#[test]
+fn test_generate_xml_base_config_without_interfaces_fails() {
+    let xml_content = r#"<?xml version="1.0"?>
+<opnsense>
+  <system>
+    <hostname>OPNsense</hostname>
+  </system>
+  <vlans/>
+</opnsense>"#;
+    let (_temp_file, base_config_path) = create_temp_xml("base_no_if_", xml_content).unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("xml_test");
+
+    let output = cli_command()
+        .args(["generate", "--format", "xml", "--count", "2"])
+        .arg("--base-config")
+        .arg(&base_config_path)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .run_failure();
+
+    let combined_output = output.normalized_combined();
+    assert!(
+        combined_output.contains("missing required section <interfaces>"),
+        "Expected missing section error, got: {combined_output}"
+    );
+    assert!(!output_dir.exists() || fs::read_dir(&output_dir).unwrap().next().is_none());
+}
+
 #[test]
 fn test_generate_xml_nonexistent_base_config_fails() {
     let temp_dir = create_temp_dir("xml_nonexistent_test");
@@ -159,6 +159,7 @@ fn test_xml_generation_snapshots() {
       <ipaddr>dhcp</ipaddr>
     </wan>
   </interfaces>
+  <vlans/>
 </opnsense>"#;
 
     let (temp_xml_file, xml_path) = create_temp_xml("base_config_", base_xml_content).unwrap();
@@ -1,6 +1,11 @@
 //! Integration test demonstrating the new XML processing engine
 
+mod common;
+
+use common::assert_vlans_round_trip;
 use opnsense_config_faker::generator::VlanConfig;
+use opnsense_config_faker::generator::vlan::generate_vlan_configurations;
+use opnsense_config_faker::xml::engine::extract_vlans;
 use opnsense_config_faker::xml::generator::VlanGenerator;
 use opnsense_config_faker::xml::{OPNsenseConfigBuilder, XMLGenerator};
 
@@ -230,3 +235,17 @@ fn test_enhanced_dhcp_different_departments() {
         "Security should have security domain"
     );
 }
+
+#[test]
+fn test_generated_vlans_round_trip_through_xml() {
+    let configs = generate_vlan_configurations(10, Some(42), None).unwrap();
+
+    let xml = OPNsenseConfigBuilder::new()
+        .add_vlans(&configs)
+        .build()
+        .unwrap();
+    let extracted = extract_vlans(&xml).unwrap();
+
+    assert_eq!(extracted.len(), 10);
+    assert_vlans_round_trip(&configs, &extracted);
+}